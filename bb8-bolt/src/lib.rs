@@ -12,7 +12,7 @@ use bolt_proto::*;
 pub struct BoltConnectionManager {
     addr: SocketAddr,
     domain: Option<String>,
-    supported_versions: [u32; 4],
+    supported_versions: Vec<VersionRange>,
     metadata: HashMap<String, Value>,
 }
 
@@ -20,14 +20,14 @@ impl BoltConnectionManager {
     pub fn new(
         addr: impl ToSocketAddrs,
         domain: Option<String>,
-        supported_versions: [u32; 4],
+        supported_versions: Vec<VersionRange>,
         metadata: HashMap<impl Into<String>, impl Into<Value>>,
     ) -> Result<Self, Error> {
         Ok(Self {
             addr: addr
                 .to_socket_addrs()?
                 .next()
-                .ok_or_else(|| Error::InvalidAddress)?,
+                .ok_or(Error::InvalidAddress)?,
             domain,
             supported_versions,
             metadata: metadata
@@ -45,7 +45,7 @@ pub enum Error {
     #[error("Invalid host address.")]
     InvalidAddress,
     #[error("Invalid client version: {0}")]
-    InvalidClientVersion(u32),
+    InvalidClientVersion(Version),
     #[error("Initialization of client failed: {0}")]
     ClientInitFailed(String),
     #[error(transparent)]
@@ -62,7 +62,7 @@ impl ManageConnection for BoltConnectionManager {
     async fn connect(&self) -> Result<Self::Connection, Self::Error> {
         let mut client = Client::new(self.addr, self.domain.as_ref()).await?;
         let version = client.handshake(&self.supported_versions).await?;
-        let response = match version {
+        let response = match version.major() {
             1 | 2 => {
                 let mut metadata = self.metadata.clone();
                 let user_agent: String = metadata
@@ -73,7 +73,7 @@ impl ManageConnection for BoltConnectionManager {
                     .map(String::try_from)??;
                 client.init(user_agent, Metadata::from(metadata)).await?
             }
-            3 | 4 => {
+            3..=5 => {
                 client
                     .hello(Some(Metadata::from(self.metadata.clone())))
                     .await?
@@ -111,7 +111,7 @@ mod tests {
 
     use super::*;
 
-    fn get_connection_manager(supported_versions: [u32; 4]) -> BoltConnectionManager {
+    fn get_connection_manager(supported_versions: Vec<VersionRange>) -> BoltConnectionManager {
         BoltConnectionManager::new(
             env::var("BOLT_TEST_ADDR").unwrap(),
             env::var("BOLT_TEST_DOMAIN").ok(),
@@ -126,13 +126,16 @@ mod tests {
         .unwrap()
     }
 
-    async fn is_server_compatible(bolt_version: u32) -> Result<bool, Error> {
+    async fn is_server_compatible(bolt_version: u8) -> Result<bool, Error> {
         let mut client = Client::new(
             env::var("BOLT_TEST_ADDR").unwrap(),
             env::var("BOLT_TEST_DOMAIN").ok(),
         )
         .await?;
-        Ok(client.handshake(&[bolt_version, 0, 0, 0]).await.is_ok())
+        Ok(client
+            .handshake(&[Version::new(bolt_version, 0).into()])
+            .await
+            .is_ok())
     }
 
     #[tokio::test]
@@ -147,7 +150,7 @@ mod tests {
                 continue;
             }
 
-            let manager = get_connection_manager([bolt_version, 0, 0, 0]);
+            let manager = get_connection_manager(vec![Version::new(bolt_version, 0).into()]);
             let pool = Pool::builder().max_size(15).build(manager).await.unwrap();
 
             let mut tasks = Vec::with_capacity(50);
@@ -194,7 +197,12 @@ mod tests {
         let invalid_manager = BoltConnectionManager::new(
             "127.0.0.1:7687",
             None,
-            [4, 3, 2, 1],
+            vec![
+                Version::new(4, 0).into(),
+                Version::new(3, 0).into(),
+                Version::new(2, 0).into(),
+                Version::new(1, 0).into(),
+            ],
             HashMap::from_iter(vec![
                 ("user_agent", "bolt-client/X.Y.Z"),
                 ("scheme", "basic"),
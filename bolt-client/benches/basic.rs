@@ -5,6 +5,7 @@ use criterion::*;
 use tokio::runtime::Runtime;
 
 use bolt_client::*;
+use bolt_proto::Version;
 
 async fn get_initialized_client() -> Result<Client, Box<dyn std::error::Error>> {
     let mut client = Client::new(
@@ -12,7 +13,7 @@ async fn get_initialized_client() -> Result<Client, Box<dyn std::error::Error>>
         env::var("BOLT_TEST_DOMAIN").ok(),
     )
     .await?;
-    client.handshake(&[3, 0, 0, 0]).await?;
+    client.handshake(&[Version::new(3, 0).into()]).await?;
     client
         .hello(Some(Metadata::from_iter(vec![
             ("user_agent".to_string(), "bolt-client/X.Y.Z".to_string()),
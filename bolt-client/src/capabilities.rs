@@ -0,0 +1,96 @@
+/// The features available in a client's session, derived from the negotiated protocol version and the server agent
+/// (e.g. `Neo4j/4.1.0`) reported when initializing the session.
+///
+/// A client computes its capabilities after a successful [`handshake`](crate::Client::handshake), and refines them
+/// once the server agent is known, see [`Client::capabilities`](crate::Client::capabilities).
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Capabilities {
+    pub(crate) supports_explicit_tx: bool,
+    pub(crate) supports_multi_db: bool,
+    pub(crate) supports_element_id: bool,
+    pub(crate) supports_utc_datetime: bool,
+    pub(crate) telemetry_enabled: bool,
+}
+
+impl Capabilities {
+    /// Compute the capabilities for a protocol version. If no server agent is given, the server is assumed to support
+    /// everything the version allows.
+    pub fn new(version: u32, server_agent: Option<&str>) -> Self {
+        let is_neo4j = server_agent.is_none_or(|agent| agent.starts_with("Neo4j/"));
+        Self {
+            supports_explicit_tx: version >= 3,
+            // Other servers implementing Bolt v4 may not have multiple databases
+            supports_multi_db: version >= 4 && is_neo4j,
+            supports_element_id: version >= 5,
+            supports_utc_datetime: version >= 5,
+            telemetry_enabled: false,
+        }
+    }
+
+    /// Whether transactions can be managed with `BEGIN`, `COMMIT`, and `ROLLBACK` messages.
+    pub fn supports_explicit_tx(&self) -> bool {
+        self.supports_explicit_tx
+    }
+
+    /// Whether a database can be selected in the metadata of `BEGIN` and `RUN` messages.
+    pub fn supports_multi_db(&self) -> bool {
+        self.supports_multi_db
+    }
+
+    /// Whether graph structures include string element ids.
+    pub fn supports_element_id(&self) -> bool {
+        self.supports_element_id
+    }
+
+    /// Whether date-times with time zones are sent in UTC. This is the case for Bolt v5+, and for Bolt v4.4 servers that
+    /// acknowledge the `utc` patch requested in `HELLO` metadata (`"patch_bolt": ["utc"]`).
+    pub fn supports_utc_datetime(&self) -> bool {
+        self.supports_utc_datetime
+    }
+
+    /// Whether the server accepts `TELEMETRY` messages. This is only enabled if the server hints at it in its
+    /// response to `HELLO`.
+    pub fn telemetry_enabled(&self) -> bool {
+        self.telemetry_enabled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn v1() {
+        let capabilities = Capabilities::new(1, Some("Neo4j/3.4.0"));
+        assert!(!capabilities.supports_explicit_tx());
+        assert!(!capabilities.supports_multi_db());
+        assert!(!capabilities.supports_element_id());
+        assert!(!capabilities.supports_utc_datetime());
+        assert!(!capabilities.telemetry_enabled());
+    }
+
+    #[test]
+    fn v3() {
+        let capabilities = Capabilities::new(3, Some("Neo4j/3.5.0"));
+        assert!(capabilities.supports_explicit_tx());
+        assert!(!capabilities.supports_multi_db());
+        assert!(!capabilities.supports_element_id());
+        assert!(!capabilities.supports_utc_datetime());
+    }
+
+    #[test]
+    fn v4() {
+        assert!(Capabilities::new(4, None).supports_multi_db());
+        assert!(Capabilities::new(4, Some("Neo4j/4.1.0")).supports_multi_db());
+        assert!(!Capabilities::new(4, Some("Memgraph/1.0")).supports_multi_db());
+    }
+
+    #[test]
+    fn v5() {
+        let capabilities = Capabilities::new(5, None);
+        assert!(capabilities.supports_explicit_tx());
+        assert!(capabilities.supports_multi_db());
+        assert!(capabilities.supports_element_id());
+        assert!(capabilities.supports_utc_datetime());
+    }
+}
@@ -5,23 +5,38 @@
 // License. To view a copy of this license, visit http://creativecommons.org/licenses/by-sa/3.0/ or send a letter to
 // Creative Commons, PO Box 1866, Mountain View, CA 94042, USA.
 
-use std::convert::TryInto;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io;
 use std::sync::Arc;
+use std::time::Duration;
 
 use bytes::*;
 use tokio::io::BufStream;
-use tokio::net::{TcpStream, ToSocketAddrs};
+use tokio::net::{self, TcpStream, ToSocketAddrs};
 use tokio::prelude::*;
-use tokio_rustls::rustls::ClientConfig;
+use tokio::time;
+use tokio_rustls::rustls::{
+    Certificate, ClientConfig, RootCertStore, ServerCertVerified, ServerCertVerifier, TLSError,
+};
 use tokio_rustls::webpki::DNSNameRef;
 use tokio_rustls::{webpki, TlsConnector};
 use webpki_roots::TLS_SERVER_ROOTS;
 
-use bolt_proto::Message;
+use bolt_proto::compression::{Compressor, Decompressor, NoCompression};
+use bolt_proto::decoder::MessageDecoder;
+use bolt_proto::message::{Logon, Telemetry};
+use bolt_proto::value::ExtensionRegistry;
+use bolt_proto::{DeserializeConfig, Message, Value, Version, VersionRange};
 
 use crate::error::*;
-use crate::stream::Stream;
+use crate::{Capabilities, ConnectionUri, Encryption, Metadata, Params, Stream};
 
+pub use builder::ClientBuilder;
+pub use query::{RunResult, SelfTestCheck, SelfTestReport};
+
+mod builder;
+mod query;
 mod v1;
 mod v2;
 mod v3;
@@ -29,86 +44,652 @@ mod v4;
 
 const PREAMBLE: [u8; 4] = [0x60, 0x60, 0xB0, 0x17];
 
-/// A tokio-based client for Bolt servers, compatible with versions 1 through 4 of the protocol.
+/// The protocol versions offered in the handshake by [`ClientBuilder`] unless configured otherwise, in order of
+/// preference: Bolt v5.0, v4.4 down to v4.0, v4.0 on its own for servers that don't understand version ranges, and
+/// v3.0.
+pub const DEFAULT_VERSIONS: [VersionRange; 4] = [
+    VersionRange::new(Version::new(5, 0), 0),
+    VersionRange::new(Version::new(4, 4), 4),
+    VersionRange::new(Version::new(4, 0), 0),
+    VersionRange::new(Version::new(3, 0), 0),
+];
+
+/// The state of the server session, as tracked by a [`Client`] from the messages it sends and receives.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ClientState {
+    /// The server is ready to accept a new request.
+    Ready,
+    /// A result stream is available or being consumed.
+    Streaming,
+    /// A request failed, and the failure has not yet been acknowledged.
+    Failed,
+    /// A `RESET` has been sent, and the server has not yet responded to it.
+    Interrupted,
+}
+
+/// How a [`Client`] handles parameter values that its protocol version does not support, such as temporal values in
+/// Bolt v1.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum UnsupportedValuePolicy {
+    /// Return [`Error::UnsupportedValue`] instead of sending the message.
+    #[default]
+    Error,
+    /// Send temporal values as ISO 8601 strings (see [`Value::to_iso_8601`]), which can be parsed server-side with
+    /// Cypher's temporal functions. Other unsupported values, and temporal values that can't be formatted, still produce
+    /// an error.
+    StringifyTemporal,
+}
+
+/// A tokio-based client for Bolt servers, compatible with versions 1 through 5.0 of the protocol.
+///
+/// By default, a client communicates over a [`Stream`], which is a TCP stream optionally secured with TLS. Clients can
+/// also be created over any other transport implementing [`AsyncRead`] and [`AsyncWrite`] using
+/// [`Client::from_stream`].
 #[derive(Debug)]
-pub struct Client {
-    stream: BufStream<Stream>,
+pub struct Client<S = Stream> {
+    stream: BufStream<S>,
     version: Option<u32>,
+    // The full version agreed upon in the handshake, which messages are serialized for
+    protocol_version: Option<Version>,
+    handshake_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    capabilities: Option<Capabilities>,
+    database: Option<String>,
+    unsupported_value_policy: UnsupportedValuePolicy,
+    state: ClientState,
+    pending_responses: usize,
+    compressor: Box<dyn Compressor>,
+    decompressor: Box<dyn Decompressor>,
+    // Reused for every message sent, see Message::to_chunks_in
+    write_buffer: BytesMut,
+    // Holds partially read messages, so that reading can be cancelled without losing them
+    decoder: MessageDecoder,
+}
+
+// Accepts any certificate, for servers with self-signed certificates that aren't known in advance
+struct TrustAllCertificates;
+
+impl ServerCertVerifier for TrustAllCertificates {
+    fn verify_server_cert(
+        &self,
+        _roots: &RootCertStore,
+        _presented_certs: &[Certificate],
+        _dns_name: DNSNameRef,
+        _ocsp_response: &[u8],
+    ) -> std::result::Result<ServerCertVerified, TLSError> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+// Connects to each of the addresses in turn, like TcpStream::connect, but optionally with a timeout for each attempt
+async fn connect_any(
+    addr: impl ToSocketAddrs,
+    attempt_timeout: Option<Duration>,
+) -> io::Result<TcpStream> {
+    let mut last_error = None;
+    for addr in net::lookup_host(addr).await? {
+        let result = match attempt_timeout {
+            Some(duration) => time::timeout(duration, TcpStream::connect(addr))
+                .await
+                .unwrap_or_else(|_| {
+                    Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!("connecting to {} timed out", addr),
+                    ))
+                }),
+            None => TcpStream::connect(addr).await,
+        };
+        match result {
+            Ok(stream) => return Ok(stream),
+            Err(error) => last_error = Some(error),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "could not resolve to any addresses",
+        )
+    }))
 }
 
-impl Client {
+impl Client<Stream> {
     /// Create a new client pointing to the provided server address. If a server domain is provided, the client will
-    /// attempt to connect to the server over a connection secured with TLS.
+    /// attempt to connect to the server over a connection secured with TLS, trusting the Mozilla root certificates.
+    ///
+    /// The address can be a host name (e.g. `neo4j.default.svc:7687`), in which case each of the addresses it resolves
+    /// to is tried in order until a connection succeeds. If none does, the error of the last attempt is returned.
     pub async fn new(addr: impl ToSocketAddrs, domain: Option<impl Into<String>>) -> Result<Self> {
-        let stream = match domain {
-            Some(domain) => {
-                let domain = domain.into();
-                let tls_connector = Client::configure_tls_connector(&TLS_SERVER_ROOTS);
+        Client::connect(addr, Client::verified_tls(domain), None).await
+    }
+
+    /// Like [`new`](Client::new), but giving up on connecting to each address after `attempt_timeout`, moving on to
+    /// the next address the host name resolves to. Attempts that time out fail with [`io::ErrorKind::TimedOut`].
+    pub async fn new_with_attempt_timeout(
+        addr: impl ToSocketAddrs,
+        domain: Option<impl Into<String>>,
+        attempt_timeout: Duration,
+    ) -> Result<Self> {
+        Client::connect(addr, Client::verified_tls(domain), Some(attempt_timeout)).await
+    }
+
+    /// Create a new client for the server at the given URI, e.g. `neo4j+s://example.com:7687`, see [`ConnectionUri`]
+    /// for the supported schemes. Connections are secured with TLS for the `+s` and `+ssc` variants of the schemes.
+    ///
+    /// Note that the client doesn't route queries to the members of a cluster for `neo4j` URIs. Instead, the routing
+    /// context of the URI should be sent to the server when the session is initialized, which [`ClientBuilder`] does.
+    pub async fn connect_uri(uri: &str) -> Result<Self> {
+        Client::connect_parsed(&uri.parse()?, None).await
+    }
+
+    pub(crate) async fn connect_parsed(
+        uri: &ConnectionUri,
+        attempt_timeout: Option<Duration>,
+    ) -> Result<Self> {
+        let tls = match uri.encryption() {
+            Encryption::None => None,
+            Encryption::Verified => Client::verified_tls(Some(uri.host())),
+            Encryption::TrustAllCertificates => {
+                let mut config = ClientConfig::new();
+                config
+                    .dangerous()
+                    .set_certificate_verifier(Arc::new(TrustAllCertificates));
+                // The certificate isn't checked against the domain, but a DNS name is still required, so servers at IP
+                // addresses get a placeholder, which isn't sent to them
+                let domain = if uri.is_ip_address() {
+                    config.enable_sni = false;
+                    "localhost"
+                } else {
+                    uri.host()
+                };
+                Some((domain.to_string(), Arc::new(config)))
+            }
+        };
+        Client::connect((uri.host(), uri.port()), tls, attempt_timeout).await
+    }
+
+    /// Create a new client pointing to the provided server address, connecting over TLS with the provided
+    /// configuration. The given domain is used to verify the server's certificate.
+    ///
+    /// This is useful for servers using self-signed certificates, which are common in on-premise deployments. In this
+    /// case, add the server's certificate (or the certificate of the CA that signed it) to the configuration's
+    /// [`root_store`](crate::rustls::ClientConfig::root_store).
+    pub async fn new_tls(
+        addr: impl ToSocketAddrs,
+        domain: impl Into<String>,
+        tls_config: Arc<ClientConfig>,
+    ) -> Result<Self> {
+        Client::connect(addr, Some((domain.into(), tls_config)), None).await
+    }
+
+    // Connects over TLS if a domain to verify the server's certificate against is given, along with the configuration
+    pub(crate) async fn connect(
+        addr: impl ToSocketAddrs,
+        tls: Option<(String, Arc<ClientConfig>)>,
+        attempt_timeout: Option<Duration>,
+    ) -> Result<Self> {
+        match tls {
+            Some((domain, tls_config)) => {
+                // Checked first, so that an invalid domain doesn't cost a connection
                 let dns_name_ref = DNSNameRef::try_from_ascii_str(&domain)
                     .map_err(|_| Error::InvalidDNSName(domain.clone()))?;
-                let stream = TcpStream::connect(addr).await?;
-                Stream::SecureTcp(Box::new(tls_connector.connect(dns_name_ref, stream).await?))
+                let stream = connect_any(addr, attempt_timeout).await?;
+                let tls_stream = TlsConnector::from(tls_config)
+                    .connect(dns_name_ref, stream)
+                    .await?;
+                Ok(Client::from_stream(Stream::SecureTcp(Box::new(tls_stream))))
             }
-            None => Stream::Tcp(TcpStream::connect(addr).await?),
-        };
-        Ok(Client {
+            None => Ok(Client::from_stream(Stream::Tcp(
+                connect_any(addr, attempt_timeout).await?,
+            ))),
+        }
+    }
+
+    pub(crate) fn verified_tls(
+        domain: Option<impl Into<String>>,
+    ) -> Option<(String, Arc<ClientConfig>)> {
+        domain.map(|domain| (domain.into(), Client::default_tls_config(&TLS_SERVER_ROOTS)))
+    }
+
+    fn default_tls_config(root_certs: &webpki::TLSServerTrustAnchors) -> Arc<ClientConfig> {
+        let mut config = ClientConfig::new();
+        config.root_store.add_server_trust_anchors(root_certs);
+        Arc::new(config)
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
+    /// Create a new client that communicates over the provided stream, which should already be connected to the
+    /// server. No handshake is performed, see [`handshake`](Client::handshake).
+    pub fn from_stream(stream: S) -> Self {
+        Client {
             stream: BufStream::new(stream),
             version: None,
-        })
+            protocol_version: None,
+            handshake_timeout: None,
+            read_timeout: None,
+            capabilities: None,
+            database: None,
+            unsupported_value_policy: UnsupportedValuePolicy::default(),
+            state: ClientState::Ready,
+            pending_responses: 0,
+            compressor: Box::new(NoCompression),
+            decompressor: Box::new(NoCompression),
+            write_buffer: BytesMut::new(),
+            decoder: MessageDecoder::new(),
+        }
     }
 
-    /// Get the current version of this client.
+    /// Get a reference to the underlying stream of this client.
+    pub fn stream(&self) -> &S {
+        self.stream.get_ref()
+    }
+
+    /// Get the major version of the protocol agreed upon in the handshake.
     pub fn version(&self) -> &Option<u32> {
         &self.version
     }
 
-    fn configure_tls_connector(root_certs: &webpki::TLSServerTrustAnchors) -> TlsConnector {
-        let mut config = ClientConfig::new();
-        config.root_store.add_server_trust_anchors(root_certs);
-        TlsConnector::from(Arc::new(config))
+    /// Get the full version of the protocol agreed upon in the handshake, e.g. Bolt v4.4.
+    pub fn protocol_version(&self) -> Option<Version> {
+        self.protocol_version
+    }
+
+    /// Get the capabilities of this client's session, which are available after a successful handshake. They are
+    /// refined further once the session is initialized and the server agent is known.
+    pub fn capabilities(&self) -> Option<&Capabilities> {
+        self.capabilities.as_ref()
+    }
+
+    /// Get the database that queries run against, if one is set.
+    pub fn database(&self) -> Option<&str> {
+        self.database.as_deref()
+    }
+
+    /// Set the database that queries run against with [`run_query`](Client::run_query), for servers supporting
+    /// multiple databases. If not set, the server's default database is used.
+    pub fn set_database(&mut self, database: Option<String>) {
+        self.database = database;
+    }
+
+    /// Get the current state of the server session.
+    pub fn state(&self) -> ClientState {
+        self.state
+    }
+
+    /// Set how parameter values that are not supported by the client's protocol version are handled. By default, an
+    /// error is returned.
+    pub fn set_unsupported_value_policy(&mut self, policy: UnsupportedValuePolicy) {
+        self.unsupported_value_policy = policy;
+    }
+
+    /// Set how message bodies are compressed before being sent, and decompressed after being received. By default,
+    /// messages are not compressed.
+    ///
+    /// This is only useful when the server is fronted by something that understands the same compression, since Bolt
+    /// has no way to negotiate it.
+    pub fn set_compression(
+        &mut self,
+        compressor: impl Compressor + 'static,
+        decompressor: impl Decompressor + 'static,
+    ) {
+        self.compressor = Box::new(compressor);
+        self.decompressor = Box::new(decompressor);
     }
 
-    /// Perform a handshake with the Bolt server and agree upon a protocol version to use for the client. Returns the
+    /// Set the maximum amount of time to wait for a [`handshake`](Client::handshake) to complete. By default, there is
+    /// no timeout.
+    pub fn set_handshake_timeout(&mut self, timeout: Option<Duration>) {
+        self.handshake_timeout = timeout;
+    }
+
+    /// Set the maximum amount of time to wait for each message read from the server, after which
+    /// [`Error::TimedOut`] is returned, so that a stalled server can't block the client forever. By default, there is
+    /// no timeout.
+    ///
+    /// Whatever was read of a message before the timeout is kept, so the client can keep reading after a timeout,
+    /// e.g. to wait longer for a slow query.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.read_timeout = timeout;
+    }
+
+    /// Set the maximum size of a message read from the server, after which reading fails with a
+    /// [`LimitExceeded`](bolt_proto::error::DeserializationError::LimitExceeded) error, so that a misbehaving server
+    /// can't make the client buffer without bound. By default, messages can be any size.
+    ///
+    /// The connection can't be used after a message exceeds the limit, since the rest of the message is left unread.
+    pub fn set_max_message_size(&mut self, max_message_size: Option<usize>) {
+        self.decoder.set_max_message_size(max_message_size);
+    }
+
+    /// Set the limits on the values in messages read from the server, such as how deeply they can be nested, see
+    /// [`DeserializeConfig`]. Defaults to [`DeserializeConfig::new`].
+    pub fn set_deserialize_config(&mut self, config: DeserializeConfig) {
+        self.decoder.set_deserialize_config(config);
+    }
+
+    /// Set the decoders of custom structure types in messages read from the server, see
+    /// [`decode_with_extensions`](bolt_proto::decode_with_extensions). By default, none are registered.
+    pub fn set_extensions(&mut self, registry: ExtensionRegistry) {
+        self.decoder.set_extensions(registry);
+    }
+
+    /// Perform a handshake with the Bolt server and agree upon a protocol version to use for the client. Up to four
+    /// versions (or ranges of versions, see [`VersionRange`]) can be offered, in order of preference. Returns the
     /// version that was agreed upon.
-    pub async fn handshake(&mut self, supported_versions: &[u32; 4]) -> Result<u32> {
+    ///
+    /// Servers supporting Bolt v5.7+ can also negotiate the version with a manifest of all the versions they support,
+    /// if [`Version::HANDSHAKE_MANIFEST_V1`] is offered. The client then picks the first of the other offered versions
+    /// that the server supports.
+    ///
+    /// If a handshake timeout is set (see [`set_handshake_timeout`](Client::set_handshake_timeout)) and the server
+    /// does not respond in time, [`Error::TimedOut`] is returned.
+    pub async fn handshake(&mut self, supported_versions: &[VersionRange]) -> Result<Version> {
+        if supported_versions.len() > 4 {
+            return Err(Error::TooManyVersions(supported_versions.len()));
+        }
+        match self.handshake_timeout {
+            Some(duration) => time::timeout(duration, self.negotiate_version(supported_versions))
+                .await
+                .map_err(|_| Error::TimedOut(duration))?,
+            None => self.negotiate_version(supported_versions).await,
+        }
+    }
+
+    async fn negotiate_version(&mut self, supported_versions: &[VersionRange]) -> Result<Version> {
         let mut allowed_versions = BytesMut::with_capacity(16);
         supported_versions
             .iter()
-            .for_each(|&v| allowed_versions.put_u32(v));
-        self.stream.write(&PREAMBLE).await?;
+            .for_each(|range| allowed_versions.put_slice(&range.to_bytes()));
+        // Unused entries are left as zeros
+        allowed_versions.resize(16, 0);
+        self.stream.write_all(&PREAMBLE).await?;
         self.stream.write_buf(&mut allowed_versions).await?;
         self.stream.flush().await?;
 
-        let version: u32 = self.stream.read_u32().await?;
-        if supported_versions.contains(&version) && version > 0 {
-            self.version = Some(version);
-            Ok(version)
-        } else {
-            Err(Error::HandshakeFailed)
+        let mut response = [0; 4];
+        self.stream.read_exact(&mut response).await?;
+        let version = Version::from_bytes(response).filter(|&version| {
+            supported_versions
+                .iter()
+                .any(|range| range.contains(version))
+        });
+        let version = match version {
+            Some(Version::HANDSHAKE_MANIFEST_V1) => {
+                self.negotiate_manifest(supported_versions).await?
+            }
+            version => version,
+        };
+        match version {
+            Some(version) => {
+                let major = u32::from(version.major());
+                self.version = Some(major);
+                self.protocol_version = Some(version);
+                self.capabilities = Some(Capabilities::new(major, None));
+                Ok(version)
+            }
+            None => Err(Error::HandshakeFailed {
+                offered: supported_versions.to_vec(),
+                response,
+            }),
+        }
+    }
+
+    // The server lists the versions it supports, followed by its capability flags. The client picks the first version
+    // it offered that the server supports, and responds with it along with the capabilities it wants, of which none are
+    // defined yet. If there is no such version, the client responds with zeros instead.
+    async fn negotiate_manifest(
+        &mut self,
+        supported_versions: &[VersionRange],
+    ) -> Result<Option<Version>> {
+        let count = read_varint(&mut self.stream).await?;
+        let mut server_versions = Vec::new();
+        for _ in 0..count {
+            let mut entry = [0; 4];
+            self.stream.read_exact(&mut entry).await?;
+            server_versions.push(VersionRange::from_bytes(entry));
         }
+        let _server_capabilities = read_varint(&mut self.stream).await?;
+
+        let version = supported_versions.iter().find_map(|offered| {
+            server_versions
+                .iter()
+                .filter_map(|supported| offered.newest_common(supported))
+                .filter(|&version| version != Version::HANDSHAKE_MANIFEST_V1)
+                .max()
+        });
+        match version {
+            Some(version) => {
+                self.stream.write_all(&version.to_bytes()).await?;
+                self.stream.write_all(&[0]).await?;
+            }
+            None => self.stream.write_all(&[0; 4]).await?,
+        }
+        self.stream.flush().await?;
+        Ok(version)
     }
 
-    pub(crate) async fn read_message(&mut self) -> Result<Message> {
-        let message = Message::from_stream(&mut self.stream).await?;
+    /// Read the next message sent by the server.
+    ///
+    /// This is a low-level method, intended to be used with [`send_message`](Client::send_message) for consuming
+    /// responses incrementally.
+    ///
+    /// If a read timeout is set (see [`set_read_timeout`](Client::set_read_timeout)) and no whole message arrives in
+    /// time, [`Error::TimedOut`] is returned.
+    ///
+    /// This is cancel-safe: if the returned future is dropped before a whole message has been read, e.g. by a timeout,
+    /// the part that has been read is kept, and the next call continues reading the same message.
+    pub async fn read_message(&mut self) -> Result<Message> {
+        let read = self
+            .decoder
+            .read_from(&mut self.stream, self.decompressor.as_ref());
+        let message = match self.read_timeout {
+            Some(duration) => time::timeout(duration, read)
+                .await
+                .map_err(|_| Error::TimedOut(duration))??,
+            None => read.await?,
+        };
 
         #[cfg(test)]
         println!("<<< {:?}\n", message);
 
+        self.update_state(&message);
+        self.update_capabilities(&message);
         Ok(message)
     }
 
-    pub(crate) async fn send_message(&mut self, message: Message) -> Result<()> {
+    /// Send a message to the server without waiting for a response.
+    ///
+    /// This is a low-level method: each message sent this way has one or more responses that must be consumed with
+    /// [`read_message`](Client::read_message) (or skipped with [`interrupt`](Client::interrupt)) before other
+    /// client methods can be used.
+    pub async fn send_message(&mut self, message: Message) -> Result<()> {
+        self.write_message(message).await?;
+        self.stream.flush().await?;
+        Ok(())
+    }
+
+    async fn write_message(&mut self, message: Message) -> Result<()> {
         #[cfg(test)]
         println!(">>> {:?}", message);
 
-        let chunks: Vec<Bytes> = message.try_into()?;
-        for mut chunk in chunks {
-            self.stream.write_buf(&mut chunk).await?;
+        if let Message::Reset = message {
+            self.state = ClientState::Interrupted;
+        }
+        let expects_response = !matches!(message, Message::Goodbye);
+        let (write_buffer, compressor) = (&mut self.write_buffer, self.compressor.as_ref());
+        let utc = self
+            .capabilities
+            .is_some_and(|capabilities| capabilities.supports_utc_datetime());
+        let mut to_chunks = || {
+            if utc {
+                bolt_proto::encode_utc(|| message.to_chunks_in(write_buffer, compressor))
+            } else {
+                message.to_chunks_in(write_buffer, compressor)
+            }
+        };
+        let chunks = match self.protocol_version {
+            Some(version) => bolt_proto::encode_with_version(version, to_chunks)?,
+            None => to_chunks()?,
+        };
+        Message::write_chunks_to(chunks, &mut self.stream).await?;
+        // The server closes the connection without responding to GOODBYE
+        if expects_response {
+            self.pending_responses += 1;
         }
-        self.stream.flush().await?;
         Ok(())
     }
 
+    // Apply the unsupported value policy to query parameters. Temporal and spatial values are only supported in Bolt v2+.
+    pub(crate) fn encode_parameters(
+        &self,
+        parameters: Option<Params>,
+    ) -> Result<HashMap<String, Value>> {
+        let parameters = parameters.unwrap_or_default().value;
+        match self.version {
+            Some(1) => parameters
+                .into_iter()
+                .map(|(key, value)| Ok((key, self.encode_v1_value(value)?)))
+                .collect(),
+            _ => Ok(parameters),
+        }
+    }
+
+    fn encode_v1_value(&self, value: Value) -> Result<Value> {
+        match value {
+            Value::List(_) => Ok(Value::from(
+                Vec::<Value>::try_from(value)?
+                    .into_iter()
+                    .map(|value| self.encode_v1_value(value))
+                    .collect::<Result<Vec<Value>>>()?,
+            )),
+            Value::Map(_) => Ok(Value::from(
+                HashMap::<String, Value>::try_from(value)?
+                    .into_iter()
+                    .map(|(key, value)| Ok((key, self.encode_v1_value(value)?)))
+                    .collect::<Result<HashMap<String, Value>>>()?,
+            )),
+            value
+                if value.is_temporal()
+                    && self.unsupported_value_policy
+                        == UnsupportedValuePolicy::StringifyTemporal =>
+            {
+                match value.to_iso_8601() {
+                    Some(formatted) => Ok(Value::from(formatted)),
+                    None => Err(Error::UnsupportedValue(1, Box::new(value))),
+                }
+            }
+            value if value.is_temporal() || value.is_spatial() => {
+                Err(Error::UnsupportedValue(1, Box::new(value)))
+            }
+            value => Ok(value),
+        }
+    }
+
+    // Whether the version agreed upon in the handshake is at least the given one
+    fn supports(&self, version: Version) -> bool {
+        self.protocol_version >= Some(version)
+    }
+
+    fn update_capabilities(&mut self, message: &Message) {
+        // The server agent and any hints are included in the response to INIT/HELLO
+        if let (Some(version), Message::Success(success)) = (self.version, message) {
+            let metadata = success.metadata();
+            if metadata.contains_key("server") {
+                let agent = success.server().ok().flatten();
+                self.capabilities = Some(Capabilities::new(version, agent.as_deref()));
+            }
+            if let (Some(capabilities), Some(hints)) =
+                (&mut self.capabilities, metadata.get("hints"))
+            {
+                capabilities.telemetry_enabled = HashMap::<String, Value>::try_from(hints.clone())
+                    .ok()
+                    .and_then(|hints| hints.get("telemetry.enabled").cloned())
+                    .and_then(|enabled| bool::try_from(enabled).ok())
+                    .unwrap_or(false);
+            }
+            // Servers supporting Bolt v4.4 acknowledge the `utc` patch requested in HELLO
+            if let (Some(capabilities), Some(patches)) =
+                (&mut self.capabilities, metadata.get("patch_bolt"))
+            {
+                if Vec::<String>::try_from(patches.clone())
+                    .is_ok_and(|patches| patches.iter().any(|patch| patch == "utc"))
+                {
+                    capabilities.supports_utc_datetime = true;
+                }
+            }
+        }
+    }
+
+    fn update_state(&mut self, message: &Message) {
+        if let Message::Record(_) = message {
+            if self.state != ClientState::Interrupted {
+                self.state = ClientState::Streaming;
+            }
+            return;
+        }
+
+        // Every other message is the summary of a request
+        self.pending_responses = self.pending_responses.saturating_sub(1);
+        if self.state == ClientState::Interrupted && self.pending_responses > 0 {
+            // Responses to requests sent before the RESET
+            return;
+        }
+        self.state = match message {
+            Message::Success(success) => {
+                let metadata = success.metadata();
+                // A RUN response includes the result fields, and a PULL response indicates whether there are more
+                // records to consume
+                if metadata.contains_key("fields")
+                    || metadata.get("has_more") == Some(&Value::from(true))
+                {
+                    ClientState::Streaming
+                } else {
+                    ClientState::Ready
+                }
+            }
+            _ => ClientState::Failed,
+        };
+    }
+
+    /// Send a `LOGON` message to the server, authenticating the session with the given auth token.
+    ///
+    /// # Description
+    /// From Bolt v5.1 on, servers ignore the auth token in `HELLO`, and sessions are authenticated with this message
+    /// after `HELLO` instead. Returns [`Error::UnsupportedOperation`] before Bolt v5.1.
+    ///
+    /// # Response
+    /// - `SUCCESS {}` if authentication has completed successfully
+    /// - `FAILURE {"code": …​, "message": …​}` if the request was malformed, or if the authorization failed
+    pub async fn logon(&mut self, auth_token: Metadata) -> Result<Message> {
+        if !self.supports(Version::new(5, 1)) {
+            return Err(Error::UnsupportedOperation(self.version));
+        }
+        self.send_message(Message::Logon(Logon::new(auth_token.value)))
+            .await?;
+        self.read_message().await
+    }
+
+    /// Send a `TELEMETRY` message to the server, reporting which driver API was used. Returns `None` without sending
+    /// anything if the server has not enabled telemetry, see [`Capabilities::telemetry_enabled`], or
+    /// [`Error::UnsupportedOperation`] before Bolt v5.4.
+    ///
+    /// # Response
+    /// - `SUCCESS {}` if the telemetry was received
+    /// - `FAILURE {"code": …​, "message": …​}` if the request was malformed
+    pub async fn telemetry(&mut self, api: i64) -> Result<Option<Message>> {
+        if !self.supports(Version::new(5, 4)) {
+            return Err(Error::UnsupportedOperation(self.version));
+        }
+        match self.capabilities {
+            Some(capabilities) if capabilities.telemetry_enabled() => {
+                self.send_message(Message::Telemetry(Telemetry::new(api)))
+                    .await?;
+                Ok(Some(self.read_message().await?))
+            }
+            _ => Ok(None),
+        }
+    }
+
     /// Send multiple messages to the server without waiting for a response. Returns a [`Vec`] containing the server's
     /// response messages for each of the sent messages, in the order they were provided.
     ///
@@ -134,13 +715,7 @@ impl Client {
         let mut responses = Vec::with_capacity(messages.len());
 
         for message in messages {
-            #[cfg(test)]
-            println!(">>> {:?}", message);
-
-            let chunks: Vec<Bytes> = message.try_into()?;
-            for mut chunk in chunks {
-                self.stream.write_buf(&mut chunk).await?;
-            }
+            self.write_message(message).await?;
         }
         self.stream.flush().await?;
 
@@ -155,3 +730,660 @@ impl Client {
         Ok(responses)
     }
 }
+
+// Reads an unsigned integer encoded in base 128, least significant group first, as used in the handshake manifest
+async fn read_varint<R: AsyncRead + Unpin>(reader: &mut R) -> Result<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = reader.read_u8().await?;
+        value |= u64::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::InvalidData, "varint is too long").into())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::convert::TryInto;
+    use std::io;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use std::iter::FromIterator;
+
+    use bolt_proto::error::{ConversionError, DeserializationError};
+    use bolt_proto::message::*;
+    use bolt_proto::value::Structure;
+    use bolt_proto::Deserialize;
+
+    use crate::{Params, ReplayStream};
+
+    use super::*;
+
+    /// Serialize the given messages into the bytes a server would send for them.
+    pub(crate) fn server_bytes(messages: Vec<Message>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for message in messages {
+            let chunks: Vec<Bytes> = message.try_into().unwrap();
+            chunks
+                .iter()
+                .for_each(|chunk| bytes.extend_from_slice(chunk));
+        }
+        bytes
+    }
+
+    #[tokio::test]
+    async fn connect_to_any_address() {
+        let mut listener = net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let open = listener.local_addr().unwrap();
+        // Bound but not listening, so connecting to it is refused
+        let closed = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        tokio::spawn(async move { listener.accept().await });
+
+        let addrs = [closed, open];
+        let stream = connect_any(&addrs[..], Some(Duration::from_secs(5)))
+            .await
+            .unwrap();
+        assert_eq!(stream.peer_addr().unwrap(), open);
+        assert!(connect_any(closed, None).await.is_err());
+        let no_addrs: &[std::net::SocketAddr] = &[];
+        assert_eq!(
+            connect_any(no_addrs, None).await.unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[tokio::test]
+    async fn handshake_over_generic_stream() {
+        let mut client = Client::from_stream(ReplayStream::new(vec![0, 0, 0, 4]));
+        assert_eq!(
+            client
+                .handshake(&[Version::new(4, 0).into(), Version::new(3, 0).into()])
+                .await
+                .unwrap(),
+            Version::new(4, 0)
+        );
+        assert_eq!(client.version(), &Some(4));
+        assert!(client.capabilities().unwrap().supports_multi_db());
+        assert_eq!(
+            client.stream().client_bytes(),
+            vec![0x60, 0x60, 0xB0, 0x17, 0, 0, 0, 4, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[tokio::test]
+    async fn handshake_with_version_range() {
+        let mut client = Client::from_stream(ReplayStream::new(vec![0, 0, 3, 4]));
+        let offered = [
+            VersionRange::new(Version::new(4, 4), 2),
+            Version::new(3, 0).into(),
+        ];
+        assert_eq!(
+            client.handshake(&offered).await.unwrap(),
+            Version::new(4, 3)
+        );
+        assert_eq!(client.version(), &Some(4));
+        assert_eq!(client.protocol_version(), Some(Version::new(4, 3)));
+        assert_eq!(
+            client.stream().client_bytes(),
+            vec![0x60, 0x60, 0xB0, 0x17, 0, 2, 4, 4, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+
+        // The server must pick a version within the offered ranges
+        let mut client = Client::from_stream(ReplayStream::new(vec![0, 0, 1, 4]));
+        assert!(matches!(
+            client.handshake(&offered).await,
+            Err(Error::HandshakeFailed { .. })
+        ));
+
+        let mut client = Client::from_stream(ReplayStream::new(vec![]));
+        assert!(matches!(
+            client.handshake(&[Version::new(4, 0).into(); 5]).await,
+            Err(Error::TooManyVersions(5))
+        ));
+        assert!(client.stream().client_bytes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn handshake_with_default_versions() {
+        for (server, version) in [
+            ([0, 0, 0, 5], Version::new(5, 0)),
+            ([0, 0, 4, 4], Version::new(4, 4)),
+            ([0, 0, 3, 4], Version::new(4, 3)),
+            ([0, 0, 0, 4], Version::new(4, 0)),
+            ([0, 0, 0, 3], Version::new(3, 0)),
+        ] {
+            let mut client = Client::from_stream(ReplayStream::new(server.to_vec()));
+            assert_eq!(client.handshake(&DEFAULT_VERSIONS).await.unwrap(), version);
+            assert_eq!(client.protocol_version(), Some(version));
+            assert_eq!(client.version(), &Some(u32::from(version.major())));
+            assert_eq!(
+                client.stream().client_bytes(),
+                vec![0x60, 0x60, 0xB0, 0x17, 0, 0, 0, 5, 0, 4, 4, 4, 0, 0, 0, 4, 0, 0, 0, 3]
+            );
+        }
+        let mut client = Client::from_stream(ReplayStream::new(vec![0, 0, 0, 1]));
+        assert!(client.handshake(&DEFAULT_VERSIONS).await.is_err());
+        assert_eq!(client.protocol_version(), None);
+    }
+
+    #[tokio::test]
+    async fn handshake_manifest() {
+        // The server supports Bolt v5.2-5.4 and v4.0-4.4, with an unknown capability flag set
+        let server = vec![0, 0, 1, 0xFF, 2, 0, 2, 4, 5, 0, 4, 4, 4, 0x80, 0x01];
+        let offered = [
+            Version::HANDSHAKE_MANIFEST_V1.into(),
+            VersionRange::new(Version::new(5, 8), 8),
+            VersionRange::new(Version::new(4, 4), 4),
+        ];
+        let mut client = Client::from_stream(ReplayStream::new(server.clone()));
+        assert_eq!(
+            client.handshake(&offered).await.unwrap(),
+            Version::new(5, 4)
+        );
+        assert_eq!(client.version(), &Some(5));
+        assert_eq!(
+            &client.stream().client_bytes()[..8],
+            &[0x60, 0x60, 0xB0, 0x17, 0, 0, 1, 0xFF]
+        );
+        // The chosen version, along with no capabilities
+        assert_eq!(&client.stream().client_bytes()[20..], &[0, 0, 4, 5, 0]);
+
+        // Versions are picked in the order they were offered
+        let mut client = Client::from_stream(ReplayStream::new(server.clone()));
+        let offered = [
+            Version::HANDSHAKE_MANIFEST_V1.into(),
+            Version::new(4, 2).into(),
+            Version::new(5, 4).into(),
+        ];
+        assert_eq!(
+            client.handshake(&offered).await.unwrap(),
+            Version::new(4, 2)
+        );
+
+        let mut client = Client::from_stream(ReplayStream::new(server));
+        let offered = [
+            Version::HANDSHAKE_MANIFEST_V1.into(),
+            Version::new(3, 0).into(),
+        ];
+        assert!(matches!(
+            client.handshake(&offered).await,
+            Err(Error::HandshakeFailed {
+                response: [0, 0, 1, 0xFF],
+                ..
+            })
+        ));
+        assert_eq!(&client.stream().client_bytes()[20..], &[0, 0, 0, 0]);
+        assert_eq!(client.version(), &None);
+
+        // The manifest is only used if it was offered
+        let mut client = Client::from_stream(ReplayStream::new(vec![0, 0, 1, 0xFF]));
+        assert!(matches!(
+            client.handshake(&[Version::new(4, 4).into()]).await,
+            Err(Error::HandshakeFailed { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn handshake_over_generic_stream_fails() {
+        let mut client = Client::from_stream(ReplayStream::new(vec![0, 0, 0, 0]));
+        assert!(matches!(
+            client
+                .handshake(&[Version::new(4, 0).into(), Version::new(3, 0).into()])
+                .await,
+            Err(Error::HandshakeFailed { .. })
+        ));
+        assert_eq!(client.version(), &None);
+    }
+
+    #[tokio::test]
+    async fn read_timeout() {
+        let mut client = Client::from_stream(SilentStream);
+        client.set_read_timeout(Some(Duration::from_millis(50)));
+        assert!(matches!(
+            client.read_message().await,
+            Err(Error::TimedOut(duration)) if duration == Duration::from_millis(50)
+        ));
+    }
+
+    #[tokio::test]
+    async fn deserialize_config() {
+        // [[1]]
+        let record = Message::Record(Record::new(vec![Value::from(vec![vec![1]])]));
+        let mut client = Client::from_stream(ReplayStream::new(server_bytes(vec![record])));
+        client.set_deserialize_config(DeserializeConfig::new().with_max_depth(1));
+        assert!(matches!(
+            client.read_message().await,
+            Err(Error::ProtocolError(
+                bolt_proto::error::Error::DeserializationError(
+                    DeserializationError::LimitExceeded { limit: "depth", .. }
+                )
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn extensions() {
+        fn reject(_: Structure) -> bolt_proto::error::Result<Value> {
+            Err(ConversionError::FromValue(Value::Null).into())
+        }
+
+        let record = Message::Record(Record::new(vec![Value::from(Structure::new(
+            0x01,
+            vec![1],
+        ))]));
+        let mut registry = ExtensionRegistry::new();
+        registry.register(0x01, reject);
+        let mut client = Client::from_stream(ReplayStream::new(server_bytes(vec![record])));
+        client.set_extensions(registry);
+        assert!(matches!(
+            client.read_message().await,
+            Err(Error::ProtocolError(
+                bolt_proto::error::Error::ConversionError(ConversionError::FromValue(Value::Null))
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn handshake_failure_lists_offered_versions() {
+        let mut client = Client::from_stream(ReplayStream::new(vec![0, 0, 0, 0]));
+        let error = client
+            .handshake(&[
+                Version::new(4, 0).into(),
+                Version::new(3, 0).into(),
+                Version::new(2, 0).into(),
+                Version::new(1, 0).into(),
+            ])
+            .await
+            .unwrap_err();
+        match &error {
+            Error::HandshakeFailed { offered, response } => {
+                assert_eq!(
+                    offered,
+                    &[
+                        VersionRange::from(Version::new(4, 0)),
+                        Version::new(3, 0).into(),
+                        Version::new(2, 0).into(),
+                        Version::new(1, 0).into(),
+                    ]
+                );
+                assert_eq!(response, &[0, 0, 0, 0]);
+            }
+            other => panic!("expected a handshake failure, got {:?}", other),
+        }
+        assert_eq!(
+            error.to_string(),
+            "Handshake with server failed (offered versions: [4.0, 3.0, 2.0, 1.0], server response: [00, 00, 00, 00])"
+        );
+
+        // An unexpected version is reported as-is
+        let mut client = Client::from_stream(ReplayStream::new(vec![0, 0, 0, 5]));
+        assert!(matches!(
+            client
+                .handshake(&[
+                    Version::new(4, 0).into(),
+                    Version::new(3, 0).into(),
+                    Version::new(2, 0).into(),
+                    Version::new(1, 0).into()
+                ])
+                .await,
+            Err(Error::HandshakeFailed {
+                response: [0, 0, 0, 5],
+                ..
+            })
+        ));
+    }
+
+    /// A stream that accepts all writes but never has anything to read.
+    struct SilentStream;
+
+    impl AsyncRead for SilentStream {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            Poll::Pending
+        }
+    }
+
+    impl AsyncWrite for SilentStream {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn handshake_timeout() {
+        let mut client = Client::from_stream(SilentStream);
+        client.set_handshake_timeout(Some(Duration::from_millis(50)));
+        assert!(matches!(
+            client.handshake(&[Version::new(4, 0).into(), Version::new(3, 0).into()]).await,
+            Err(Error::TimedOut(duration)) if duration == Duration::from_millis(50)
+        ));
+        assert_eq!(client.version(), &None);
+    }
+
+    #[tokio::test]
+    async fn interrupt_mid_stream() {
+        let success = |metadata: Vec<(&str, Value)>| {
+            Message::Success(Success::new(
+                metadata
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v))
+                    .collect(),
+            ))
+        };
+        let record = |n: i64| Message::Record(Record::new(vec![Value::from(n)]));
+        let mut client = Client::from_stream(ReplayStream::new(server_bytes(vec![
+            success(vec![("fields", Value::from(vec!["n"]))]),
+            record(1),
+            record(2),
+            record(3),
+            Message::Ignored,
+            success(vec![]),
+        ])));
+        client.version = Some(4);
+        assert_eq!(client.state(), ClientState::Ready);
+
+        client
+            .send_message(Message::RunWithMetadata(RunWithMetadata::new(
+                "UNWIND range(1, 1000) AS n RETURN n;".to_string(),
+                Default::default(),
+                Default::default(),
+            )))
+            .await
+            .unwrap();
+        client
+            .send_message(Message::Pull(Pull::all()))
+            .await
+            .unwrap();
+        assert!(matches!(
+            client.read_message().await.unwrap(),
+            Message::Success(_)
+        ));
+        assert_eq!(client.state(), ClientState::Streaming);
+        assert_eq!(client.read_message().await.unwrap(), record(1));
+        assert_eq!(client.state(), ClientState::Streaming);
+
+        assert_eq!(client.interrupt().await.unwrap(), success(vec![]));
+        assert_eq!(client.state(), ClientState::Ready);
+        assert_eq!(client.pending_responses, 0);
+        // All responses were drained
+        assert!(client.read_message().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn state_after_failure() {
+        let mut client = Client::from_stream(ReplayStream::new(server_bytes(vec![
+            Message::Failure(Failure::new(Default::default())),
+            Message::Success(Success::new(Default::default())),
+        ])));
+        client.version = Some(4);
+        client
+            .send_message(Message::Begin(Begin::new(Default::default())))
+            .await
+            .unwrap();
+        client.read_message().await.unwrap();
+        assert_eq!(client.state(), ClientState::Failed);
+        client.reset().await.unwrap();
+        assert_eq!(client.state(), ClientState::Ready);
+    }
+
+    async fn telemetry_client(telemetry_enabled: bool) -> Client<ReplayStream> {
+        let hints = HashMap::from_iter(vec![("telemetry.enabled", Value::from(telemetry_enabled))]);
+        let hello_success = Success::new(HashMap::from_iter(vec![
+            ("server".to_string(), Value::from("Neo4j/5.13.0")),
+            ("hints".to_string(), Value::from(hints)),
+        ]));
+        let mut server = vec![0, 0, 4, 5];
+        server.extend(server_bytes(vec![
+            Message::Success(hello_success),
+            Message::Success(Success::new(Default::default())),
+            Message::Success(Success::new(Default::default())),
+        ]));
+        let mut client = Client::from_stream(ReplayStream::new(server));
+        client
+            .handshake(&[Version::new(5, 4).into()])
+            .await
+            .unwrap();
+        client
+            .send_message(Message::Hello(Hello::new(Default::default())))
+            .await
+            .unwrap();
+        client.read_message().await.unwrap();
+        client.logon(Metadata::default()).await.unwrap();
+        client
+    }
+
+    #[tokio::test]
+    async fn logon() {
+        let mut client = Client::from_stream(ReplayStream::new(vec![0, 0, 0, 5]));
+        client
+            .handshake(&[Version::new(5, 0).into()])
+            .await
+            .unwrap();
+        assert!(matches!(
+            client.logon(Metadata::default()).await,
+            Err(Error::UnsupportedOperation(Some(5)))
+        ));
+
+        let mut server = vec![0, 0, 1, 5];
+        server.extend(server_bytes(vec![Message::Success(Success::new(
+            Default::default(),
+        ))]));
+        let mut client = Client::from_stream(ReplayStream::new(server));
+        client
+            .handshake(&[Version::new(5, 1).into()])
+            .await
+            .unwrap();
+        let auth_token = HashMap::from_iter(vec![("scheme".to_string(), Value::from("none"))]);
+        assert!(matches!(
+            client.logon(Metadata::from(auth_token)).await,
+            Ok(Message::Success(_))
+        ));
+        assert_eq!(
+            &client.stream().client_bytes()[20..],
+            &[
+                0x00, 0x0F, 0xB1, 0x6A, 0xA1, 0x86, b's', b'c', b'h', b'e', b'm', b'e', 0x84, b'n',
+                b'o', b'n', b'e', 0x00, 0x00
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn telemetry_unsupported() {
+        let mut client = Client::from_stream(ReplayStream::new(vec![0, 0, 0, 5]));
+        client
+            .handshake(&[Version::new(5, 0).into()])
+            .await
+            .unwrap();
+        assert!(matches!(
+            client.telemetry(0).await,
+            Err(Error::UnsupportedOperation(Some(5)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn telemetry_disabled() {
+        let mut client = telemetry_client(false).await;
+        assert!(!client.capabilities().unwrap().telemetry_enabled());
+        let sent = client.stream().client_bytes().len();
+        assert_eq!(client.telemetry(0).await.unwrap(), None);
+        assert_eq!(client.stream().client_bytes().len(), sent);
+    }
+
+    #[tokio::test]
+    async fn telemetry_enabled() {
+        let mut client = telemetry_client(true).await;
+        assert!(client.capabilities().unwrap().telemetry_enabled());
+        let sent = client.stream().client_bytes().len();
+        assert!(matches!(
+            client.telemetry(0).await.unwrap(),
+            Some(Message::Success(_))
+        ));
+        assert_eq!(
+            &client.stream().client_bytes()[sent..],
+            &[0x00, 0x03, 0xB1, 0x54, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[tokio::test]
+    async fn utc_patch() {
+        for &utc in &[false, true] {
+            let patches: Vec<&str> = if utc { vec!["utc"] } else { vec![] };
+            let hello_success = Success::new(HashMap::from_iter(vec![
+                ("server".to_string(), Value::from("Neo4j/4.4.0")),
+                ("patch_bolt".to_string(), Value::from(patches)),
+            ]));
+            let mut server = vec![0, 0, 0, 4];
+            server.extend(server_bytes(vec![
+                Message::Success(hello_success),
+                Message::Success(Success::new(Default::default())),
+            ]));
+            let mut client = Client::from_stream(ReplayStream::new(server));
+            client
+                .handshake(&[Version::new(4, 0).into()])
+                .await
+                .unwrap();
+            client
+                .hello(Some(Metadata::from_iter(vec![(
+                    "patch_bolt",
+                    Value::from(vec!["utc"]),
+                )])))
+                .await
+                .unwrap();
+            assert_eq!(client.capabilities().unwrap().supports_utc_datetime(), utc);
+
+            let date_time = chrono::TimeZone::timestamp_opt(
+                &chrono::FixedOffset::east_opt(3600).unwrap(),
+                1_600_000_000,
+                0,
+            )
+            .unwrap();
+            let sent = client.stream().client_bytes().len();
+            client
+                .run_with_metadata(
+                    "RETURN $dt;",
+                    Some(Params::from_iter(vec![("dt", date_time)])),
+                    None,
+                )
+                .await
+                .unwrap();
+            let bytes = &client.stream().client_bytes()[sent..];
+            // An offset date-time structure, with the signature depending on the encoding
+            let signature = if utc { 0x49 } else { 0x46 };
+            assert!(bytes.windows(2).any(|window| window == [0xB3, signature]));
+        }
+    }
+
+    fn date_params() -> Params {
+        let date = chrono::NaiveDate::from_ymd_opt(2020, 12, 25).unwrap();
+        Params::from_iter(vec![
+            ("date", Value::from(date)),
+            ("dates", Value::from(vec![date])),
+        ])
+    }
+
+    #[tokio::test]
+    async fn unsupported_value_error() {
+        let mut client = Client::from_stream(ReplayStream::new(vec![]));
+        client.version = Some(1);
+        assert!(matches!(
+            client.run("RETURN $date;", Some(date_params())).await,
+            Err(Error::UnsupportedValue(1, _))
+        ));
+        assert!(client.stream().client_bytes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn unsupported_value_stringify_temporal() {
+        let mut client =
+            Client::from_stream(ReplayStream::new(server_bytes(vec![Message::Success(
+                Success::new(Default::default()),
+            )])));
+        client.version = Some(1);
+        client.set_unsupported_value_policy(UnsupportedValuePolicy::StringifyTemporal);
+        client
+            .run("RETURN date($date);", Some(date_params()))
+            .await
+            .unwrap();
+
+        let mut sent = BufStream::new(ReplayStream::new(client.stream().client_bytes().to_vec()));
+        let run = Run::try_from(Message::from_stream(&mut sent).await.unwrap()).unwrap();
+        assert_eq!(
+            run.parameters().get("date"),
+            Some(&Value::from("2020-12-25"))
+        );
+        assert_eq!(
+            run.parameters().get("dates"),
+            Some(&Value::from(vec!["2020-12-25"]))
+        );
+    }
+
+    #[tokio::test]
+    async fn unsupported_value_stringify_unformattable() {
+        let mut client = Client::from_stream(ReplayStream::new(vec![]));
+        client.version = Some(1);
+        client.set_unsupported_value_policy(UnsupportedValuePolicy::StringifyTemporal);
+        // A date-time with the unknown time zone "Nope", as it could be received from the server
+        let unknown_zone = Value::try_from_bytes_exact(Bytes::from_static(&[
+            0xB3, 0x66, 0xC9, 0x07, 0xD0, 0x00, 0x84, b'N', b'o', b'p', b'e',
+        ]))
+        .unwrap();
+        let params = Params::from_iter(vec![("dateTime", unknown_zone)]);
+        assert!(matches!(
+            client.run("RETURN $dateTime;", Some(params)).await,
+            Err(Error::UnsupportedValue(1, _))
+        ));
+        assert!(client.stream().client_bytes().is_empty());
+    }
+
+    #[tokio::test]
+    async fn new_tls_with_custom_config() {
+        let mut tls_config = ClientConfig::new();
+        tls_config
+            .root_store
+            .add_server_trust_anchors(&TLS_SERVER_ROOTS);
+        let result =
+            Client::new_tls("127.0.0.1:7687", "invalid domain", Arc::new(tls_config)).await;
+        assert!(matches!(result, Err(Error::InvalidDNSName(_))));
+    }
+
+    #[tokio::test]
+    async fn connect_uri() {
+        assert!(matches!(
+            Client::connect_uri("localhost:7687").await,
+            Err(Error::InvalidUri { .. })
+        ));
+        // IP addresses can't be verified against a certificate
+        assert!(matches!(
+            Client::connect_uri("neo4j+s://[::1]:7687").await,
+            Err(Error::InvalidUri { .. })
+        ));
+        // Without verification they can be used, so connecting gets as far as the (closed) port
+        for uri in &["bolt+ssc://127.0.0.1:1", "bolt+ssc://[::1]:1"] {
+            assert!(matches!(
+                Client::connect_uri(uri).await,
+                Err(Error::IOError(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,469 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::iter::FromIterator;
+use std::time::Duration;
+
+use tokio::prelude::*;
+use tokio::time;
+
+use bolt_proto::message::redact_auth_token;
+use bolt_proto::value::ExtensionRegistry;
+use bolt_proto::{DeserializeConfig, Value, Version, VersionRange};
+
+use crate::client::query::into_summary;
+use crate::error::*;
+use crate::{Client, ConnectionUri, Metadata, DEFAULT_VERSIONS};
+
+const DEFAULT_USER_AGENT: &str = concat!("bolt-client/", env!("CARGO_PKG_VERSION"));
+
+/// Assembles a [`Client`] that is connected, has completed its handshake, and has an initialized session, so it is
+/// ready to run queries.
+///
+/// # Example
+/// ```no_run
+/// # use bolt_client::ClientBuilder;
+/// # async fn connect() -> bolt_client::error::Result<()> {
+/// let mut client = ClientBuilder::new()
+///     .uri("bolt://localhost:7687")
+///     .auth("neo4j", "password")
+///     .database("movies")
+///     .connect()
+///     .await?;
+/// let result = client.run_query("MATCH (m:Movie) RETURN m;", None).await?;
+/// # Ok(())
+/// # }
+/// ```
+// Debug is implemented by hand to mask the credentials
+#[derive(Clone)]
+pub struct ClientBuilder {
+    uri: String,
+    auth_token: HashMap<String, Value>,
+    database: Option<String>,
+    user_agent: String,
+    domain: Option<String>,
+    connect_timeout: Option<Duration>,
+    attempt_timeout: Option<Duration>,
+    versions: Vec<VersionRange>,
+    deserialize_config: DeserializeConfig,
+    extensions: ExtensionRegistry,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self {
+            uri: "localhost:7687".to_string(),
+            auth_token: HashMap::from_iter(vec![("scheme".to_string(), Value::from("none"))]),
+            database: None,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            domain: None,
+            connect_timeout: None,
+            attempt_timeout: None,
+            versions: DEFAULT_VERSIONS.to_vec(),
+            deserialize_config: DeserializeConfig::new(),
+            extensions: ExtensionRegistry::new(),
+        }
+    }
+}
+
+impl fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientBuilder")
+            .field("uri", &self.uri)
+            .field("auth_token", &redact_auth_token(&self.auth_token))
+            .field("database", &self.database)
+            .field("user_agent", &self.user_agent)
+            .field("domain", &self.domain)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("attempt_timeout", &self.attempt_timeout)
+            .field("versions", &self.versions)
+            .field("deserialize_config", &self.deserialize_config)
+            .field("extensions", &self.extensions)
+            .finish()
+    }
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the address of the server, either as `host:port` or as a [`ConnectionUri`] such as
+    /// `neo4j+s://example.com?region=eu`, whose scheme determines whether to use TLS and whether to send a routing
+    /// context when initializing the session. Defaults to `localhost:7687`.
+    pub fn uri(mut self, uri: impl Into<String>) -> Self {
+        self.uri = uri.into();
+        self
+    }
+
+    /// Authenticate with the basic scheme, using the given username and password. Without this, the `none` scheme
+    /// is used.
+    pub fn auth(mut self, principal: impl Into<String>, credentials: impl Into<String>) -> Self {
+        self.auth_token = HashMap::from_iter(vec![
+            ("scheme".to_string(), Value::from("basic")),
+            ("principal".to_string(), Value::from(principal.into())),
+            ("credentials".to_string(), Value::from(credentials.into())),
+        ]);
+        self
+    }
+
+    /// Set the database that queries run against, see [`Client::set_database`].
+    pub fn database(mut self, database: impl Into<String>) -> Self {
+        self.database = Some(database.into());
+        self
+    }
+
+    /// Set the name and version the client identifies itself with. Defaults to `bolt-client/<version>`.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Connect over TLS, using the given domain to verify the server's certificate. This overrides the encryption
+    /// implied by the scheme of the URI.
+    pub fn tls(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Set the maximum amount of time to wait for the client to be ready, from connecting until the session is
+    /// initialized. By default, there is no timeout.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum amount of time to wait for a connection to each of the addresses the server's host name resolves
+    /// to, before trying the next one, see [`Client::new_with_attempt_timeout`]. By default, there is no timeout.
+    pub fn attempt_timeout(mut self, timeout: Duration) -> Self {
+        self.attempt_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the protocol versions to offer in the handshake, in order of preference, see [`Client::handshake`].
+    /// Defaults to [`DEFAULT_VERSIONS`].
+    pub fn versions(mut self, versions: impl IntoIterator<Item = VersionRange>) -> Self {
+        self.versions = versions.into_iter().collect();
+        self
+    }
+
+    /// Set the limits on the values in messages read from the server, see [`Client::set_deserialize_config`].
+    pub fn deserialize_config(mut self, config: DeserializeConfig) -> Self {
+        self.deserialize_config = config;
+        self
+    }
+
+    /// Set the decoders of custom structure types in messages read from the server, see [`Client::set_extensions`].
+    pub fn extensions(mut self, registry: ExtensionRegistry) -> Self {
+        self.extensions = registry;
+        self
+    }
+
+    /// Connect to the server and initialize a session.
+    pub async fn connect(&self) -> Result<Client> {
+        self.with_timeout(async {
+            let uri = self.parsed_uri()?;
+            let client = match &self.domain {
+                Some(domain) => {
+                    let tls = Client::verified_tls(Some(domain));
+                    Client::connect((uri.host(), uri.port()), tls, self.attempt_timeout).await?
+                }
+                None => Client::connect_parsed(&uri, self.attempt_timeout).await?,
+            };
+            self.initialize(client).await
+        })
+        .await
+    }
+
+    /// Initialize a session over the given stream, which should already be connected to the server. This is like
+    /// [`connect`](ClientBuilder::connect), but for transports other than [`Stream`](crate::Stream).
+    pub async fn connect_stream<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        stream: S,
+    ) -> Result<Client<S>> {
+        self.with_timeout(self.initialize(Client::from_stream(stream)))
+            .await
+    }
+
+    async fn initialize<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        mut client: Client<S>,
+    ) -> Result<Client<S>> {
+        let uri = self.parsed_uri()?;
+        client.set_deserialize_config(self.deserialize_config);
+        client.set_extensions(self.extensions.clone());
+        let response = match client.handshake(&self.versions).await?.major() {
+            1 | 2 => {
+                client
+                    .init(
+                        self.user_agent.clone(),
+                        Metadata::from(self.auth_token.clone()),
+                    )
+                    .await?
+            }
+            3..=5 => {
+                // From Bolt v5.1, the auth token is sent in a LOGON message after HELLO instead
+                let logon = client.protocol_version() >= Some(Version::new(5, 1));
+                let mut metadata = if logon {
+                    HashMap::new()
+                } else {
+                    self.auth_token.clone()
+                };
+                metadata.insert(
+                    "user_agent".to_string(),
+                    Value::from(self.user_agent.clone()),
+                );
+                // Routing contexts were introduced in Bolt v4.1
+                match uri.routing_context() {
+                    Some(routing) if client.protocol_version() >= Some(Version::new(4, 1)) => {
+                        metadata.insert("routing".to_string(), Value::from(routing));
+                    }
+                    _ => {}
+                }
+                let response = client.hello(Some(Metadata::from(metadata))).await?;
+                if logon {
+                    into_summary(Some(response))?;
+                    client
+                        .logon(Metadata::from(self.auth_token.clone()))
+                        .await?
+                } else {
+                    response
+                }
+            }
+            _ => return Err(Error::UnsupportedOperation(*client.version())),
+        };
+        into_summary(Some(response))?;
+        client.set_database(self.database.clone());
+        Ok(client)
+    }
+
+    fn parsed_uri(&self) -> Result<ConnectionUri> {
+        if self.uri.contains("://") {
+            self.uri.parse()
+        } else {
+            format!("bolt://{}", self.uri).parse()
+        }
+    }
+
+    async fn with_timeout<T>(&self, future: impl Future<Output = Result<T>>) -> Result<T> {
+        match self.connect_timeout {
+            Some(duration) => time::timeout(duration, future)
+                .await
+                .map_err(|_| Error::TimedOut(duration))?,
+            None => future.await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use tokio::io::BufStream;
+
+    use bolt_proto::message::*;
+    use bolt_proto::Message;
+
+    use crate::client::tests::server_bytes;
+    use crate::ReplayStream;
+
+    use super::*;
+
+    fn success() -> Message {
+        Message::Success(Success::new(HashMap::from_iter(vec![(
+            "server".to_string(),
+            Value::from("Neo4j/4.1.0"),
+        )])))
+    }
+
+    // Decode the messages a client sent after its handshake
+    async fn sent_messages(client: &Client<ReplayStream>) -> Vec<Message> {
+        let bytes = client.stream().client_bytes()[20..].to_vec();
+        let mut stream = BufStream::new(ReplayStream::new(bytes));
+        let mut messages = Vec::new();
+        while let Ok(message) = Message::from_stream(&mut stream).await {
+            messages.push(message);
+        }
+        messages
+    }
+
+    #[test]
+    fn debug_redacts_credentials() {
+        let builder = ClientBuilder::new().auth("neo4j", "hunter2");
+        let debug = format!("{:?}", builder);
+        assert!(!debug.contains("hunter2"));
+        assert!(debug.contains("neo4j"));
+    }
+
+    #[tokio::test]
+    async fn connect_stream() {
+        let mut server = vec![0, 0, 0, 4];
+        server.extend(server_bytes(vec![
+            success(),
+            Message::Success(Success::new(HashMap::new())),
+            Message::Success(Success::new(HashMap::new())),
+        ]));
+        let mut client = ClientBuilder::new()
+            .auth("neo4j", "password")
+            .database("movies")
+            .user_agent("my-app/1.0")
+            .connect_stream(ReplayStream::new(server))
+            .await
+            .unwrap();
+        assert_eq!(client.version(), &Some(4));
+        assert_eq!(client.database(), Some("movies"));
+        assert!(client.capabilities().unwrap().supports_multi_db());
+        client.run_query("RETURN 1;", None).await.unwrap();
+
+        let sent = sent_messages(&client).await;
+        let hello = Hello::try_from(sent[0].clone()).unwrap();
+        assert_eq!(hello.auth_scheme(), Some("basic"));
+        assert_eq!(
+            hello.metadata().get("user_agent"),
+            Some(&Value::from("my-app/1.0"))
+        );
+        assert_eq!(
+            hello.metadata().get("principal"),
+            Some(&Value::from("neo4j"))
+        );
+        let run = RunWithMetadata::try_from(sent[1].clone()).unwrap();
+        assert_eq!(run.metadata().get("db"), Some(&Value::from("movies")));
+    }
+
+    #[tokio::test]
+    async fn connect_stream_routing() {
+        let mut server = vec![0, 0, 4, 4];
+        server.extend(server_bytes(vec![success()]));
+        let client = ClientBuilder::new()
+            .uri("neo4j://example.com:7688?region=eu")
+            .connect_stream(ReplayStream::new(server))
+            .await
+            .unwrap();
+        assert_eq!(client.protocol_version(), Some(Version::new(4, 4)));
+
+        let sent = sent_messages(&client).await;
+        let hello = Hello::try_from(sent[0].clone()).unwrap();
+        assert_eq!(hello.auth_scheme(), Some("none"));
+        assert_eq!(
+            hello.metadata().get("routing"),
+            Some(&Value::from(HashMap::from_iter(vec![
+                ("region".to_string(), Value::from("eu")),
+                ("address".to_string(), Value::from("example.com:7688")),
+            ])))
+        );
+
+        // No routing context is sent for bolt URIs, or before Bolt v4.1
+        for (uri, handshake) in [
+            ("bolt://example.com", [0, 0, 4, 4]),
+            ("neo4j://example.com", [0, 0, 0, 4]),
+        ] {
+            let mut server = handshake.to_vec();
+            server.extend(server_bytes(vec![success()]));
+            let client = ClientBuilder::new()
+                .uri(uri)
+                .connect_stream(ReplayStream::new(server))
+                .await
+                .unwrap();
+            let sent = sent_messages(&client).await;
+            let hello = Hello::try_from(sent[0].clone()).unwrap();
+            assert_eq!(hello.metadata().get("routing"), None);
+        }
+    }
+
+    #[tokio::test]
+    async fn connect_stream_logon() {
+        let mut server = vec![0, 0, 1, 5];
+        server.extend(server_bytes(vec![
+            success(),
+            Message::Success(Success::new(HashMap::new())),
+        ]));
+        let client = ClientBuilder::new()
+            .auth("neo4j", "password")
+            .versions(vec![Version::new(5, 1).into()])
+            .connect_stream(ReplayStream::new(server))
+            .await
+            .unwrap();
+        assert_eq!(client.protocol_version(), Some(Version::new(5, 1)));
+
+        // From Bolt v5.1, the auth token is sent in LOGON instead of HELLO
+        let sent = sent_messages(&client).await;
+        let hello = Hello::try_from(sent[0].clone()).unwrap();
+        assert!(hello.auth_token().is_empty());
+        assert!(hello.metadata().contains_key("user_agent"));
+        let logon = Logon::try_from(sent[1].clone()).unwrap();
+        assert_eq!(logon.auth_scheme(), Some("basic"));
+        assert_eq!(
+            logon.auth_token().get("principal"),
+            Some(&Value::from("neo4j"))
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_uri() {
+        let result = ClientBuilder::new()
+            .uri("http://example.com")
+            .connect_stream(ReplayStream::new(vec![0, 0, 0, 4]))
+            .await;
+        assert!(matches!(result, Err(Error::InvalidUri { .. })));
+    }
+
+    #[tokio::test]
+    async fn connect_stream_v1() {
+        let mut server = vec![0, 0, 0, 1];
+        server.extend(server_bytes(vec![success()]));
+        let client = ClientBuilder::new()
+            .auth("neo4j", "password")
+            .versions([Version::new(1, 0).into()])
+            .connect_stream(ReplayStream::new(server))
+            .await
+            .unwrap();
+        assert_eq!(client.version(), &Some(1));
+
+        let sent = sent_messages(&client).await;
+        let init = Init::try_from(sent[0].clone()).unwrap();
+        assert_eq!(init.client_name(), DEFAULT_USER_AGENT);
+        assert_eq!(init.auth_scheme(), Some("basic"));
+    }
+
+    #[tokio::test]
+    async fn connect_stream_deserialize_config() {
+        let mut server = vec![0, 0, 0, 4];
+        server.extend(server_bytes(vec![Message::Success(Success::new(
+            HashMap::from_iter(vec![("hints".to_string(), Value::from(vec![1]))]),
+        ))]));
+        let result = ClientBuilder::new()
+            .deserialize_config(DeserializeConfig::new().with_max_depth(1))
+            .connect_stream(ReplayStream::new(server))
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::ProtocolError(
+                bolt_proto::error::Error::DeserializationError(
+                    bolt_proto::error::DeserializationError::LimitExceeded { limit: "depth", .. }
+                )
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn connect_stream_auth_failed() {
+        let mut server = vec![0, 0, 0, 4];
+        server.extend(server_bytes(vec![Message::Failure(Failure::new(
+            HashMap::from_iter(vec![
+                (
+                    "code".to_string(),
+                    Value::from("Neo.ClientError.Security.Unauthorized"),
+                ),
+                ("message".to_string(), Value::from("Invalid credentials")),
+            ]),
+        ))]));
+        let result = ClientBuilder::new()
+            .auth("neo4j", "wrong")
+            .connect_stream(ReplayStream::new(server))
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::QueryFailed { code, .. }) if code == "Neo.ClientError.Security.Unauthorized"
+        ));
+    }
+}
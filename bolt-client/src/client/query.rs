@@ -0,0 +1,427 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::iter::FromIterator;
+
+use tokio::prelude::*;
+
+use bolt_proto::error::ConversionError;
+use bolt_proto::graph::Graph;
+use bolt_proto::message::*;
+use bolt_proto::{FromRecord, Message, Value};
+
+use crate::error::*;
+use crate::{Client, Params};
+
+/// The outcome of a query executed with [`Client::run_query`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RunResult {
+    pub(crate) metadata: HashMap<String, Value>,
+    pub(crate) records: Vec<Record>,
+    pub(crate) summary: HashMap<String, Value>,
+}
+
+impl RunResult {
+    /// The metadata from the `SUCCESS` response to the run message, such as the `fields` of the result.
+    pub fn metadata(&self) -> &HashMap<String, Value> {
+        &self.metadata
+    }
+
+    /// The records returned by the query, in the order they were received.
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// The metadata from the `SUCCESS` response that ended the result stream.
+    pub fn summary(&self) -> &HashMap<String, Value> {
+        &self.summary
+    }
+
+    /// Consume the result, returning its records.
+    pub fn into_records(self) -> Vec<Record> {
+        self.records
+    }
+
+    /// The names of the columns of the result, listed under `fields` in the metadata. This is empty if the server
+    /// didn't list any, and an error is returned if they aren't strings.
+    pub fn columns(&self) -> Result<Vec<String>> {
+        match self.metadata.get("fields") {
+            Some(fields) => Ok(Vec::<String>::try_from(fields.clone())?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Consume the result, converting each record into a `T` by the names of its columns, see [`FromRecord`].
+    pub fn into_structs<T: FromRecord>(self) -> Result<Vec<T>> {
+        let columns = self.columns()?;
+        self.records
+            .into_iter()
+            .map(|record| Ok(T::from_record(record, &columns)?))
+            .collect()
+    }
+
+    /// Consume the result, collecting the nodes, relationships, and paths in its records into a [`Graph`].
+    pub fn into_graph(self) -> Result<Graph> {
+        Ok(Graph::from_records(self.records)?)
+    }
+}
+
+/// The outcome of a [`Client::self_test`], listing which values failed to round-trip between the client and server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfTestReport {
+    pub(crate) checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    /// Whether all values were decoded as expected.
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(SelfTestCheck::passed)
+    }
+
+    /// All checks that were performed.
+    pub fn checks(&self) -> &[SelfTestCheck] {
+        &self.checks
+    }
+
+    /// The checks whose values were not decoded as expected.
+    pub fn failures(&self) -> impl Iterator<Item = &SelfTestCheck> {
+        self.checks.iter().filter(|check| !check.passed())
+    }
+}
+
+/// A single value checked by [`Client::self_test`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfTestCheck {
+    pub(crate) name: &'static str,
+    pub(crate) expected: Value,
+    pub(crate) actual: Option<Value>,
+}
+
+impl SelfTestCheck {
+    /// The type of value being checked, e.g. `"list"`.
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    pub fn expected(&self) -> &Value {
+        &self.expected
+    }
+
+    /// The value received from the server, or `None` if it was missing from the result.
+    pub fn actual(&self) -> Option<&Value> {
+        self.actual.as_ref()
+    }
+
+    pub fn passed(&self) -> bool {
+        self.actual.as_ref() == Some(&self.expected)
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
+    /// Run a query on the server and retrieve all of its records, using the appropriate messages for the client's
+    /// protocol version.
+    ///
+    /// For Bolt v4+, the query runs against the client's database if one is set (see
+    /// [`set_database`](Client::set_database)), otherwise against the server's default database.
+    ///
+    /// The run message and the pull message are pipelined. A `FAILURE` response is returned as
+    /// [`Error::QueryFailed`] and an `IGNORED` response as [`Error::RequestIgnored`]; in both cases the responses to
+    /// both messages are consumed, but the failure must still be acknowledged with
+    /// [`reset`](Client::reset) or [`ack_failure`](Client::ack_failure) before sending anything else.
+    pub async fn run_query(
+        &mut self,
+        query: impl Into<String>,
+        parameters: Option<Params>,
+    ) -> Result<RunResult> {
+        let query = query.into();
+        let parameters = self.encode_parameters(parameters)?;
+        let (run_msg, pull_msg) = match self.version {
+            Some(1) | Some(2) => (Message::Run(Run::new(query, parameters)), Message::PullAll),
+            Some(3) => (
+                Message::RunWithMetadata(RunWithMetadata::new(
+                    query,
+                    parameters,
+                    Default::default(),
+                )),
+                Message::PullAll,
+            ),
+            Some(4) | Some(5) => (
+                Message::RunWithMetadata(RunWithMetadata::new(
+                    query,
+                    parameters,
+                    self.database
+                        .iter()
+                        .map(|db| ("db".to_string(), Value::from(db.as_str())))
+                        .collect(),
+                )),
+                Message::Pull(Pull::all()),
+            ),
+            other => return Err(Error::UnsupportedOperation(other)),
+        };
+
+        let mut responses = self.pipeline(vec![run_msg, pull_msg]).await?.into_iter();
+        let metadata = into_summary(responses.next())?;
+        let mut records = Vec::new();
+        let mut summary = None;
+        for response in responses {
+            match response {
+                Message::Record(record) => records.push(record),
+                other => summary = Some(other),
+            }
+        }
+        let summary = into_summary(summary)?;
+
+        Ok(RunResult {
+            metadata,
+            records,
+            summary,
+        })
+    }
+}
+
+const SELF_TEST_QUERY: &str = "RETURN 1 AS n, [1, 2, 3] AS l, {a: 1} AS m;";
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
+    /// Check that values are exchanged correctly with the server, by running a query that returns an integer, a list,
+    /// and a map, and comparing the decoded values to the expected ones. The session must already be initialized.
+    ///
+    /// Errors from running the query are returned as-is, while values that don't match are reported in the returned
+    /// [`SelfTestReport`].
+    pub async fn self_test(&mut self) -> Result<SelfTestReport> {
+        let result = self.run_query(SELF_TEST_QUERY, None).await?;
+        let fields = result
+            .records()
+            .first()
+            .map(|record| record.fields().to_vec())
+            .unwrap_or_default();
+        let expected = vec![
+            ("integer", Value::from(1)),
+            ("list", Value::from(vec![1, 2, 3])),
+            (
+                "map",
+                Value::from(HashMap::from_iter(vec![("a", Value::from(1))])),
+            ),
+        ];
+        let checks = expected
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, expected))| SelfTestCheck {
+                name,
+                expected,
+                actual: fields.get(i).cloned(),
+            })
+            .collect();
+        Ok(SelfTestReport { checks })
+    }
+}
+
+pub(crate) fn into_summary(message: Option<Message>) -> Result<HashMap<String, Value>> {
+    match message {
+        Some(Message::Success(success)) => Ok(success.metadata().clone()),
+        Some(Message::Failure(failure)) => {
+            let field = |name| {
+                failure
+                    .metadata()
+                    .get(name)
+                    .cloned()
+                    .and_then(|value| String::try_from(value).ok())
+                    .unwrap_or_default()
+            };
+            Err(Error::QueryFailed {
+                code: field("code"),
+                message: field("message"),
+            })
+        }
+        Some(Message::Ignored) => Err(Error::RequestIgnored),
+        Some(other) => Err(
+            bolt_proto::error::Error::from(ConversionError::FromMessage(Box::new(other))).into(),
+        ),
+        None => Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::tests::*;
+    use crate::ReplayStream;
+
+    use super::*;
+
+    fn success(metadata: Vec<(&str, Value)>) -> Message {
+        Message::Success(Success::new(HashMap::from_iter(
+            metadata.into_iter().map(|(k, v)| (k.to_string(), v)),
+        )))
+    }
+
+    fn failure(code: &str, message: &str) -> Message {
+        Message::Failure(Failure::new(HashMap::from_iter(vec![
+            ("code".to_string(), Value::from(code)),
+            ("message".to_string(), Value::from(message)),
+        ])))
+    }
+
+    fn client(version: u32, responses: Vec<Message>) -> Client<ReplayStream> {
+        let mut client = Client::from_stream(ReplayStream::new(server_bytes(responses)));
+        client.version = Some(version);
+        client
+    }
+
+    #[tokio::test]
+    async fn run_query() {
+        for &version in &[1, 3, 4] {
+            let mut client = client(
+                version,
+                vec![
+                    success(vec![("fields", Value::from(vec!["n"]))]),
+                    Message::Record(Record::new(vec![Value::from(1)])),
+                    Message::Record(Record::new(vec![Value::from(2)])),
+                    success(vec![("type", Value::from("r"))]),
+                ],
+            );
+            let result = client
+                .run_query("UNWIND [1, 2] AS n RETURN n;", None)
+                .await
+                .unwrap();
+            assert_eq!(
+                result.metadata().get("fields"),
+                Some(&Value::from(vec!["n"]))
+            );
+            assert_eq!(
+                result.records(),
+                &[
+                    Record::new(vec![Value::from(1)]),
+                    Record::new(vec![Value::from(2)])
+                ]
+            );
+            assert_eq!(result.summary().get("type"), Some(&Value::from("r")));
+        }
+    }
+
+    #[tokio::test]
+    async fn into_structs() {
+        #[derive(Debug, PartialEq, FromRecord)]
+        struct Person {
+            name: String,
+            age: i64,
+        }
+
+        let mut client = client(
+            4,
+            vec![
+                success(vec![("fields", Value::from(vec!["age", "name"]))]),
+                Message::Record(Record::new(vec![Value::from(30), Value::from("Alice")])),
+                success(vec![]),
+            ],
+        );
+        let result = client
+            .run_query(
+                "MATCH (p:Person) RETURN p.age AS age, p.name AS name;",
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.columns().unwrap(), vec!["age", "name"]);
+        assert_eq!(
+            result.into_structs::<Person>().unwrap(),
+            vec![Person {
+                name: "Alice".to_string(),
+                age: 30
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn into_graph() {
+        let node = |identity| {
+            bolt_proto::value::Node::builder(identity)
+                .label("Person")
+                .build()
+                .unwrap()
+        };
+        let mut client = client(
+            4,
+            vec![
+                success(vec![("fields", Value::from(vec!["n"]))]),
+                Message::Record(Record::new(vec![Value::from(node(1))])),
+                Message::Record(Record::new(vec![Value::from(node(2))])),
+                Message::Record(Record::new(vec![Value::from(node(1))])),
+                success(vec![]),
+            ],
+        );
+        let result = client.run_query("MATCH (n) RETURN n;", None).await.unwrap();
+        let graph = result.into_graph().unwrap();
+        assert_eq!(graph.nodes(), &[node(1), node(2)]);
+    }
+
+    #[tokio::test]
+    async fn run_query_failure() {
+        let mut client = client(
+            4,
+            vec![
+                failure("Neo.ClientError.Statement.SyntaxError", "Invalid input"),
+                Message::Ignored,
+            ],
+        );
+        match client.run_query("RETURN", None).await {
+            Err(Error::QueryFailed { code, message }) => {
+                assert_eq!(code, "Neo.ClientError.Statement.SyntaxError");
+                assert_eq!(message, "Invalid input");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn run_query_ignored() {
+        let mut client = client(4, vec![Message::Ignored, Message::Ignored]);
+        assert!(matches!(
+            client.run_query("RETURN 1;", None).await,
+            Err(Error::RequestIgnored)
+        ));
+    }
+
+    #[tokio::test]
+    async fn run_query_unsupported_version() {
+        let mut client = client(6, vec![]);
+        assert!(matches!(
+            client.run_query("RETURN 1;", None).await,
+            Err(Error::UnsupportedOperation(Some(6)))
+        ));
+    }
+
+    fn self_test_client(fields: Vec<Value>) -> Client<ReplayStream> {
+        client(
+            4,
+            vec![
+                success(vec![("fields", Value::from(vec!["n", "l", "m"]))]),
+                Message::Record(Record::new(fields)),
+                success(vec![]),
+            ],
+        )
+    }
+
+    #[tokio::test]
+    async fn self_test() {
+        let mut client = self_test_client(vec![
+            Value::from(1),
+            Value::from(vec![1, 2, 3]),
+            Value::from(HashMap::from_iter(vec![("a", 1)])),
+        ]);
+        let report = client.self_test().await.unwrap();
+        assert!(report.passed());
+        assert_eq!(report.checks().len(), 3);
+        assert_eq!(report.failures().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn self_test_failures() {
+        let mut client = self_test_client(vec![Value::from(1), Value::from(vec![1, 2])]);
+        let report = client.self_test().await.unwrap();
+        assert!(!report.passed());
+        let failures: Vec<&SelfTestCheck> = report.failures().collect();
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].name(), "list");
+        assert_eq!(failures[0].actual(), Some(&Value::from(vec![1, 2])));
+        assert_eq!(failures[1].name(), "map");
+        assert_eq!(failures[1].actual(), None);
+    }
+}
@@ -1,3 +1,5 @@
+use tokio::prelude::*;
+
 use bolt_client_macros::*;
 use bolt_proto::message::*;
 use bolt_proto::Message;
@@ -5,7 +7,7 @@ use bolt_proto::Message;
 use crate::error::*;
 use crate::{Client, Metadata, Params};
 
-impl Client {
+impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
     /// Send an `INIT` message to the server.
     ///
     /// # Description
@@ -55,6 +57,9 @@ impl Client {
     /// If an unacknowledged failure is pending from a previous exchange, the server will immediately respond with a
     /// single `IGNORED` message and take no further action.
     ///
+    /// Bolt v1 does not support temporal or spatial parameters, see
+    /// [`set_unsupported_value_policy`](Client::set_unsupported_value_policy).
+    ///
     /// # Response
     /// - `SUCCESS {…}` if the statement has been accepted for execution
     /// - `FAILURE {"code": …​, "message": …​}` if the request was malformed or if a statement may not be executed at this
@@ -65,7 +70,7 @@ impl Client {
         statement: impl Into<String>,
         parameters: Option<Params>,
     ) -> Result<Message> {
-        let run_msg = Run::new(statement.into(), parameters.unwrap_or_default().value);
+        let run_msg = Run::new(statement.into(), self.encode_parameters(parameters)?);
         self.send_message(Message::Run(run_msg)).await?;
         self.read_message().await
     }
@@ -163,11 +168,36 @@ impl Client {
     /// # Response
     /// - `SUCCESS {…}` if the session was successfully reset
     /// - `FAILURE {"code": …​, "message": …​}` if a reset is not currently possible
-    #[bolt_version(1, 2, 3, 4)]
+    #[bolt_version(1, 2, 3, 4, 5)]
     pub async fn reset(&mut self) -> Result<Message> {
         self.send_message(Message::Reset).await?;
         self.read_message().await
     }
+
+    /// Send a `RESET` message to the server immediately, discarding the responses to any requests that are still
+    /// outstanding (such as records from a partially consumed result stream). Returns the server's response to the
+    /// `RESET`.
+    ///
+    /// # Description
+    /// Unlike [`reset`](Client::reset), this can be used while responses to earlier messages have not been consumed
+    /// yet, e.g. when messages were sent with [`send_message`](Client::send_message). The server will abort the
+    /// current operation and respond to any pending requests with `IGNORED`, which are drained along with any records
+    /// still in flight. On success, the client's [`state`](Client::state) is
+    /// [`ClientState::Ready`](crate::ClientState::Ready).
+    ///
+    /// # Response
+    /// - `SUCCESS {…}` if the session was successfully reset
+    /// - `FAILURE {"code": …​, "message": …​}` if a reset is not currently possible
+    #[bolt_version(1, 2, 3, 4, 5)]
+    pub async fn interrupt(&mut self) -> Result<Message> {
+        self.send_message(Message::Reset).await?;
+        loop {
+            let message = self.read_message().await?;
+            if self.pending_responses == 0 {
+                return Ok(message);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -178,18 +208,19 @@ pub(crate) mod tests {
 
     use bolt_proto::message::*;
     use bolt_proto::value::*;
+    use bolt_proto::Version;
 
     use crate::{skip_if_handshake_failed, Metadata};
 
     use super::*;
 
-    pub(crate) async fn new_client(version: u32) -> Result<Client> {
+    pub(crate) async fn new_client(version: u8) -> Result<Client> {
         let mut client = Client::new(
             env::var("BOLT_TEST_ADDR").unwrap(),
             env::var("BOLT_TEST_DOMAIN").ok(),
         )
         .await?;
-        client.handshake(&[version, 0, 0, 0]).await?;
+        client.handshake(&[Version::new(version, 0).into()]).await?;
         Ok(client)
     }
 
@@ -225,7 +256,7 @@ pub(crate) mod tests {
         }
     }
 
-    pub(crate) async fn get_initialized_client(version: u32) -> Result<Client> {
+    pub(crate) async fn get_initialized_client(version: u8) -> Result<Client> {
         let mut client = new_client(version).await?;
         initialize_client(&mut client, true).await?;
         Ok(client)
@@ -280,10 +311,10 @@ pub(crate) mod tests {
         // The current behavior is to simply close the connection on a failed INIT.
         // Messages now fail to send since connection was closed
         let response = initialize_client(&mut client, true).await;
-        assert!(match response {
-            Err(Error::ProtocolError(bolt_proto::error::Error::IOError(_))) => true,
-            _ => false,
-        })
+        assert!(matches!(
+            response,
+            Err(Error::ProtocolError(bolt_proto::error::Error::IOError(_)))
+        ))
     }
 
     #[tokio::test]
@@ -307,10 +338,7 @@ pub(crate) mod tests {
         let response = run_invalid_query(&mut client).await.unwrap();
         assert!(Failure::try_from(response).is_ok());
         let response = run_valid_query(&mut client).await.unwrap();
-        assert!(match response {
-            Message::Ignored => true,
-            _ => false,
-        });
+        assert!(matches!(response, Message::Ignored));
         let response = client.ack_failure().await.unwrap();
         assert!(Success::try_from(response).is_ok());
         let response = run_valid_query(&mut client).await.unwrap();
@@ -349,10 +377,7 @@ pub(crate) mod tests {
             assert!(match response {
                 Message::Success(_) => true,
                 Message::Record(record) => {
-                    assert_eq!(
-                        Record::try_from(record).unwrap().fields()[0],
-                        Value::from(12_i8)
-                    );
+                    assert_eq!(record.fields()[0], Value::from(12_i8));
                     true
                 }
                 _ => false,
@@ -457,10 +482,7 @@ pub(crate) mod tests {
         let response = run_invalid_query(&mut client).await.unwrap();
         assert!(Failure::try_from(response).is_ok());
         let response = run_valid_query(&mut client).await.unwrap();
-        assert!(match response {
-            Message::Ignored => true,
-            _ => false,
-        });
+        assert!(matches!(response, Message::Ignored));
         let response = client.reset().await.unwrap();
         assert!(Success::try_from(response).is_ok());
         let response = run_valid_query(&mut client).await.unwrap();
@@ -475,10 +497,7 @@ pub(crate) mod tests {
         let response = run_invalid_query(&mut client).await.unwrap();
         assert!(Failure::try_from(response).is_ok());
         let response = run_valid_query(&mut client).await.unwrap();
-        assert!(match response {
-            Message::Ignored => true,
-            _ => false,
-        });
+        assert!(matches!(response, Message::Ignored));
     }
 
     #[tokio::test]
@@ -486,10 +505,10 @@ pub(crate) mod tests {
         let client = get_initialized_client(1).await;
         skip_if_handshake_failed!(client);
         let mut client = client.unwrap();
-        assert!(match client.commit().await {
-            Err(Error::UnsupportedOperation(Some(1))) => true,
-            _ => false,
-        });
+        assert!(matches!(
+            client.commit().await,
+            Err(Error::UnsupportedOperation(Some(1)))
+        ));
     }
 
     #[tokio::test]
@@ -498,10 +517,10 @@ pub(crate) mod tests {
         skip_if_handshake_failed!(client);
         let mut client = client.unwrap();
         client.send_message(Message::Commit).await.unwrap();
-        assert!(match client.read_message().await {
-            // Local server just closes connection, but GrapheneDB sends a FAILURE message
-            Err(Error::ProtocolError(_)) | Ok(Message::Failure(_)) => true,
-            _ => false,
-        });
+        // Local server just closes connection, but GrapheneDB sends a FAILURE message
+        assert!(matches!(
+            client.read_message().await,
+            Err(Error::ProtocolError(_)) | Ok(Message::Failure(_))
+        ));
     }
 }
@@ -32,10 +32,10 @@ mod tests {
         // The current behavior is to simply close the connection on a failed INIT.
         // Messages now fail to send since connection was closed
         let response = initialize_client(&mut client, true).await;
-        assert!(match response {
-            Err(Error::ProtocolError(bolt_proto::error::Error::IOError(_))) => true,
-            _ => false,
-        })
+        assert!(matches!(
+            response,
+            Err(Error::ProtocolError(bolt_proto::error::Error::IOError(_)))
+        ))
     }
 
     #[tokio::test]
@@ -59,10 +59,7 @@ mod tests {
         let response = run_invalid_query(&mut client).await.unwrap();
         assert!(Failure::try_from(response).is_ok());
         let response = run_valid_query(&mut client).await.unwrap();
-        assert!(match response {
-            Message::Ignored => true,
-            _ => false,
-        });
+        assert!(matches!(response, Message::Ignored));
         let response = client.ack_failure().await.unwrap();
         assert!(Success::try_from(response).is_ok());
         let response = run_valid_query(&mut client).await.unwrap();
@@ -101,10 +98,7 @@ mod tests {
             assert!(match response {
                 Message::Success(_) => true,
                 Message::Record(record) => {
-                    assert_eq!(
-                        Record::try_from(record).unwrap().fields()[0],
-                        Value::from(Duration::new(118, 7, 0, 0))
-                    );
+                    assert_eq!(record.fields()[0], Value::from(Duration::new(118, 7, 0, 0)));
                     true
                 }
                 _ => false,
@@ -132,8 +126,8 @@ mod tests {
         assert_eq!(
             records[0].fields(),
             &[Value::from(NaiveDateTime::new(
-                NaiveDate::from_ymd(2010, 3, 5),
-                NaiveTime::from_hms_nano(12, 30, 1, 500),
+                NaiveDate::from_ymd_opt(2010, 3, 5).unwrap(),
+                NaiveTime::from_hms_nano_opt(12, 30, 1, 500).unwrap(),
             ))]
         );
 
@@ -186,8 +180,11 @@ mod tests {
         assert_eq!(
             c.properties().get("starting"),
             Some(&Value::from(
-                FixedOffset::east(-8 * 3600).from_utc_datetime(
-                    &NaiveDate::from_ymd(2019, 12, 19).and_hms_milli(16, 8, 4, 322)
+                FixedOffset::east_opt(-8 * 3600).unwrap().from_utc_datetime(
+                    &NaiveDate::from_ymd_opt(2019, 12, 19)
+                        .unwrap()
+                        .and_hms_milli_opt(16, 8, 4, 322)
+                        .unwrap()
                 )
             ))
         );
@@ -243,10 +240,7 @@ mod tests {
         let response = run_invalid_query(&mut client).await.unwrap();
         assert!(Failure::try_from(response).is_ok());
         let response = run_valid_query(&mut client).await.unwrap();
-        assert!(match response {
-            Message::Ignored => true,
-            _ => false,
-        });
+        assert!(matches!(response, Message::Ignored));
         let response = client.reset().await.unwrap();
         assert!(Success::try_from(response).is_ok());
         let response = run_valid_query(&mut client).await.unwrap();
@@ -261,9 +255,6 @@ mod tests {
         let response = run_invalid_query(&mut client).await.unwrap();
         assert!(Failure::try_from(response).is_ok());
         let response = run_valid_query(&mut client).await.unwrap();
-        assert!(match response {
-            Message::Ignored => true,
-            _ => false,
-        });
+        assert!(matches!(response, Message::Ignored));
     }
 }
@@ -1,3 +1,5 @@
+use tokio::prelude::*;
+
 use bolt_client_macros::*;
 use bolt_proto::message::*;
 use bolt_proto::Message;
@@ -5,7 +7,7 @@ use bolt_proto::Message;
 use crate::error::*;
 use crate::{Client, Metadata, Params};
 
-impl Client {
+impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
     /// Send a `HELLO` message to the server.
     ///
     /// # Description
@@ -16,7 +18,7 @@ impl Client {
     /// - `SUCCESS {…}` if initialization has completed successfully
     /// - `FAILURE {"code": …​, "message": …​}` if the request was malformed, or if initialization
     ///     cannot be performed at this time, or if the authorization failed.
-    #[bolt_version(3, 4)]
+    #[bolt_version(3, 4, 5)]
     pub async fn hello(&mut self, metadata: Option<Metadata>) -> Result<Message> {
         let hello_msg = Hello::new(metadata.unwrap_or_default().value);
         self.send_message(Message::Hello(hello_msg)).await?;
@@ -28,7 +30,7 @@ impl Client {
     /// # Description
     /// The `GOODBYE` message is a Bolt v3+ client message used to end the session. The server will end the connection
     /// upon receipt of this message.
-    #[bolt_version(3, 4)]
+    #[bolt_version(3, 4, 5)]
     pub async fn goodbye(&mut self) -> Result<()> {
         self.send_message(Message::Goodbye).await?;
         Ok(())
@@ -44,7 +46,7 @@ impl Client {
     /// - `SUCCESS {…​}` if the statement has been accepted for execution
     /// - `FAILURE {"code": …​, "message": …​}` if the request was malformed or if a statement may not be executed at this
     ///     time
-    #[bolt_version(3, 4)]
+    #[bolt_version(3, 4, 5)]
     pub async fn run_with_metadata(
         &mut self,
         statement: impl Into<String>,
@@ -68,7 +70,7 @@ impl Client {
     /// # Response
     /// - `SUCCESS {…}` if transaction has started successfully
     /// - `FAILURE {"code": …​, "message": …​}` if the request was malformed, or if transaction could not be started
-    #[bolt_version(3, 4)]
+    #[bolt_version(3, 4, 5)]
     pub async fn begin(&mut self, metadata: Option<Metadata>) -> Result<Message> {
         let begin_msg = Begin::new(metadata.unwrap_or_default().value);
         self.send_message(Message::Begin(begin_msg)).await?;
@@ -84,7 +86,7 @@ impl Client {
     /// # Response
     /// - `SUCCESS {…}` if transaction has been committed successfully
     /// - `FAILURE {"code": …​, "message": …​}` if the request was malformed, or if transaction could not be committed
-    #[bolt_version(3, 4)]
+    #[bolt_version(3, 4, 5)]
     pub async fn commit(&mut self) -> Result<Message> {
         self.send_message(Message::Commit).await?;
         self.read_message().await
@@ -99,7 +101,7 @@ impl Client {
     /// # Response
     /// - `SUCCESS {…}` if transaction has been rolled back successfully
     /// - `FAILURE {"code": …​, "message": …​}` if the request was malformed, or if transaction could not be rolled back
-    #[bolt_version(3, 4)]
+    #[bolt_version(3, 4, 5)]
     pub async fn rollback(&mut self) -> Result<Message> {
         self.send_message(Message::Rollback).await?;
         self.read_message().await
@@ -179,10 +181,7 @@ mod tests {
             assert!(match response {
                 Message::Success(_) => true,
                 Message::Record(record) => {
-                    assert_eq!(
-                        Record::try_from(record).unwrap().fields()[0],
-                        Value::from(Duration::new(118, 7, 0, 0))
-                    );
+                    assert_eq!(record.fields()[0], Value::from(Duration::new(118, 7, 0, 0)));
                     true
                 }
                 _ => false,
@@ -241,8 +240,7 @@ mod tests {
         let mut node_exists = false;
         for response in client.pipeline(messages).await.unwrap() {
             if let Message::Record(record) = response {
-                let node =
-                    Node::try_from(Record::try_from(record).unwrap().fields()[0].clone()).unwrap();
+                let node = Node::try_from(record.fields()[0].clone()).unwrap();
                 assert_eq!(node.labels(), &["Database"]);
                 node_exists = true;
                 break;
@@ -299,11 +297,8 @@ mod tests {
             Message::PullAll,
         ];
         for response in client.pipeline(messages).await.unwrap() {
-            assert!(match response {
-                Message::Success(_) => true,
-                // There should be no RECORD messages
-                _ => false,
-            });
+            // There should be no RECORD messages
+            assert!(matches!(response, Message::Success(_)));
         }
     }
 
@@ -1,3 +1,5 @@
+use tokio::prelude::*;
+
 use bolt_client_macros::*;
 use bolt_proto::message::*;
 use bolt_proto::Message;
@@ -5,7 +7,7 @@ use bolt_proto::Message;
 use crate::error::*;
 use crate::{Client, Metadata};
 
-impl Client {
+impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
     /// Send a `DISCARD` message to the server.
     ///
     /// # Description
@@ -15,7 +17,7 @@ impl Client {
     /// # Response
     /// - `SUCCESS {…}` if the result stream has been successfully discarded
     /// - `FAILURE {"code": …​, "message": …​}` if no result stream is currently available
-    #[bolt_version(4)]
+    #[bolt_version(4, 5)]
     pub async fn discard(&mut self, metadata: Option<Metadata>) -> Result<Message> {
         let discard_msg = Discard::new(metadata.unwrap_or_default().value);
         self.send_message(Message::Discard(discard_msg)).await?;
@@ -31,7 +33,7 @@ impl Client {
     /// # Response
     /// - `SUCCESS {…​}` if the result stream has been successfully transferred
     /// - `FAILURE {"code": …​, "message": …​}` if no result stream is currently available or if retrieval fails
-    #[bolt_version(4)]
+    #[bolt_version(4, 5)]
     pub async fn pull(&mut self, metadata: Option<Metadata>) -> Result<(Message, Vec<Record>)> {
         let pull_msg = Pull::new(metadata.unwrap_or_default().value);
         self.send_message(Message::Pull(pull_msg)).await?;
@@ -120,10 +122,7 @@ mod tests {
             assert!(match response {
                 Message::Success(_) => true,
                 Message::Record(record) => {
-                    assert_eq!(
-                        Record::try_from(record).unwrap().fields()[0],
-                        Value::from(Duration::new(118, 7, 0, 0))
-                    );
+                    assert_eq!(record.fields()[0], Value::from(Duration::new(118, 7, 0, 0)));
                     true
                 }
                 _ => false,
@@ -229,8 +228,7 @@ mod tests {
         let mut node_exists = false;
         for response in client.pipeline(messages).await.unwrap() {
             if let Message::Record(record) = response {
-                let node =
-                    Node::try_from(Record::try_from(record).unwrap().fields()[0].clone()).unwrap();
+                let node = Node::try_from(record.fields()[0].clone()).unwrap();
                 assert_eq!(node.labels(), &["Database"]);
                 node_exists = true;
                 break;
@@ -290,11 +288,8 @@ mod tests {
             )]))),
         ];
         for response in client.pipeline(messages).await.unwrap() {
-            assert!(match response {
-                Message::Success(_) => true,
-                // There should be no RECORD messages
-                _ => false,
-            });
+            // There should be no RECORD messages
+            assert!(matches!(response, Message::Success(_)));
         }
     }
 
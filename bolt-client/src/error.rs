@@ -1,17 +1,45 @@
 use thiserror::Error;
 
+use bolt_proto::VersionRange;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Invalid DNS name: {0}")]
     InvalidDNSName(String),
+    #[error("Invalid URI {uri:?}: {reason}")]
+    InvalidUri { uri: String, reason: String },
     #[error(transparent)]
     IOError(#[from] std::io::Error),
-    #[error("Handshake with server failed")]
-    HandshakeFailed,
+    #[error(
+        "Handshake with server failed (offered versions: [{}], server response: {response:02x?})",
+        display_versions(offered)
+    )]
+    HandshakeFailed {
+        offered: Vec<VersionRange>,
+        response: [u8; 4],
+    },
+    #[error("Too many versions offered in handshake (max: 4, actual: {0})")]
+    TooManyVersions(usize),
+    #[error("Timed out after {0:?}")]
+    TimedOut(std::time::Duration),
     #[error("Unsupported operation for client with version = {0:?}")]
     UnsupportedOperation(Option<u32>),
+    #[error("Value not supported by protocol version {0}: {1:?}")]
+    UnsupportedValue(u32, Box<bolt_proto::Value>),
+    #[error("Query failed with code {code}: {message}")]
+    QueryFailed { code: String, message: String },
+    #[error("Request ignored by server due to a previous failure")]
+    RequestIgnored,
     #[error(transparent)]
     ProtocolError(#[from] bolt_proto::error::Error),
 }
+
+fn display_versions(versions: &[VersionRange]) -> String {
+    versions
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
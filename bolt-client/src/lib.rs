@@ -11,7 +11,7 @@
 //! use tokio::prelude::*;
 //!
 //! use bolt_client::*;
-//! use bolt_proto::{message::*, value::*, Message, Value};
+//! use bolt_proto::{message::*, value::*, Message, Value, Version};
 //!
 //! #[tokio::main]
 //! async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -22,8 +22,8 @@
 //!     let mut client = Client::new(env::var("BOLT_TEST_ADDR")?,
 //!                                  env::var("BOLT_TEST_DOMAIN").ok()).await?;
 //!     // This example demonstrates usage of the v4 protocol
-//!     let handshake_result = client.handshake(&[4, 0, 0, 0]).await;
-//! #   if let Err(bolt_client::error::Error::HandshakeFailed) = handshake_result {
+//!     let handshake_result = client.handshake(&[Version::new(4, 0).into()]).await;
+//! #   if let Err(bolt_client::error::Error::HandshakeFailed { .. }) = handshake_result {
 //! #       println!("Skipping test: client handshake failed");
 //! #       return Ok(());
 //! #   }
@@ -94,14 +94,14 @@
 //! # use tokio::prelude::*;
 //! #
 //! # use bolt_client::*;
-//! # use bolt_proto::{message::*, value::*, Message, Value};
+//! # use bolt_proto::{message::*, value::*, Message, Value, Version};
 //! #
 //! # #[tokio::main]
 //! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
 //! #     let mut client = Client::new(env::var("BOLT_TEST_ADDR")?,
 //! #                                  env::var("BOLT_TEST_DOMAIN").ok()).await?;
-//! #     let handshake_result = client.handshake(&[3, 0, 0, 0]).await;
-//! #     if let Err(bolt_client::error::Error::HandshakeFailed) = handshake_result {
+//! #     let handshake_result = client.handshake(&[Version::new(3, 0).into()]).await;
+//! #     if let Err(bolt_client::error::Error::HandshakeFailed { .. }) = handshake_result {
 //! #         println!("Skipping test: client handshake failed");
 //! #         return Ok(());
 //! #     }
@@ -153,15 +153,15 @@
 //! # use tokio::prelude::*;
 //! #
 //! # use bolt_client::*;
-//! # use bolt_proto::{message::*, value::*, Message, Value};
+//! # use bolt_proto::{message::*, value::*, Message, Value, Version};
 //! #
 //! # #[tokio::main]
 //! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
 //! #     let mut client = Client::new(env::var("BOLT_TEST_ADDR")?,
 //! #                                  env::var("BOLT_TEST_DOMAIN").ok()).await?;
 //! // For the handshake we want to support versions 1 and 2 only, preferring version 2.
-//! let handshake_result = client.handshake(&[2, 1, 0, 0]).await;
-//! #     if let Err(bolt_client::error::Error::HandshakeFailed) = handshake_result {
+//! let handshake_result = client.handshake(&[Version::new(2, 0).into(), Version::new(1, 0).into()]).await;
+//! #     if let Err(bolt_client::error::Error::HandshakeFailed { .. }) = handshake_result {
 //! #         println!("Skipping test: client handshake failed");
 //! #         return Ok(());
 //! #     }
@@ -209,12 +209,31 @@
 //! ```
 //! See the documentation of the [`Client`] struct for information on transaction management, error handling, and more.
 #[doc(inline)]
-pub use self::client::Client;
+pub use self::capabilities::Capabilities;
+#[doc(inline)]
+pub use self::client::{
+    Client, ClientBuilder, ClientState, RunResult, SelfTestCheck, SelfTestReport,
+    UnsupportedValuePolicy, DEFAULT_VERSIONS,
+};
+#[doc(inline)]
+pub use self::replay::{Recording, RecordingClient, RecordingStream, ReplayStream};
+#[doc(inline)]
+pub use self::stream::Stream;
+#[doc(inline)]
+pub use self::transaction_script::TransactionScript;
+#[doc(inline)]
+pub use self::uri::{ConnectionUri, Encryption};
+#[doc(no_inline)]
+pub use tokio_rustls::rustls;
 
+mod capabilities;
 mod client;
 mod define_value_map;
 pub mod error;
+mod replay;
 mod stream;
+mod transaction_script;
+mod uri;
 
 define_value_map!(Metadata);
 define_value_map!(Params);
@@ -223,13 +242,13 @@ define_value_map!(Params);
 #[macro_export]
 macro_rules! skip_if_handshake_failed {
     ($var:expr) => {
-        if let ::std::result::Result::Err(crate::error::Error::HandshakeFailed) = $var {
+        if let ::std::result::Result::Err($crate::error::Error::HandshakeFailed { .. }) = $var {
             println!("Skipping test: client handshake failed");
             return;
         }
     };
     ($var:expr, $ret:expr) => {
-        if let ::std::result::Result::Err(crate::error::Error::HandshakeFailed) = $var {
+        if let ::std::result::Result::Err($crate::error::Error::HandshakeFailed { .. }) = $var {
             println!("Skipping test: client handshake failed");
             return $ret;
         }
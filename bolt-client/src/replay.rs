@@ -0,0 +1,201 @@
+use std::io::{self, Read};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::prelude::*;
+
+use crate::{Client, Stream};
+
+/// A [`Client`] that records every byte exchanged with the server. The recording can be accessed with
+/// `client.stream().recording()`, see [`RecordingStream`].
+pub type RecordingClient<S = Stream> = Client<RecordingStream<S>>;
+
+/// The bytes exchanged between a client and a server during a session.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Recording {
+    pub(crate) sent: Vec<u8>,
+    pub(crate) received: Vec<u8>,
+}
+
+impl Recording {
+    /// The bytes sent by the client, in order.
+    pub fn sent(&self) -> &[u8] {
+        &self.sent
+    }
+
+    /// The bytes received from the server, in order.
+    pub fn received(&self) -> &[u8] {
+        &self.received
+    }
+
+    /// Create a [`ReplayStream`] that feeds the received bytes of this recording back to a client.
+    pub fn replay(&self) -> ReplayStream {
+        ReplayStream::new(self.received.clone())
+    }
+}
+
+/// A stream wrapper that records all bytes read from and written to the underlying stream.
+#[derive(Debug)]
+pub struct RecordingStream<S> {
+    stream: S,
+    recording: Recording,
+}
+
+impl<S> RecordingStream<S> {
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            recording: Recording::default(),
+        }
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    pub fn into_recording(self) -> Recording {
+        self.recording
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> AsyncRead for RecordingStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.stream).poll_read(cx, buf);
+        if let Poll::Ready(Ok(n)) = poll {
+            this.recording.received.extend_from_slice(&buf[..n]);
+        }
+        poll
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> AsyncWrite for RecordingStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.stream).poll_write(cx, buf);
+        if let Poll::Ready(Ok(n)) = poll {
+            this.recording.sent.extend_from_slice(&buf[..n]);
+        }
+        poll
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_shutdown(cx)
+    }
+}
+
+/// An in-memory stream that replays previously recorded server bytes, for testing clients without a server. Bytes
+/// written by the client are kept and can be compared against a [`Recording`] using
+/// [`client_bytes`](ReplayStream::client_bytes).
+#[derive(Debug, Default)]
+pub struct ReplayStream {
+    server_bytes: io::Cursor<Vec<u8>>,
+    client_bytes: Vec<u8>,
+}
+
+impl ReplayStream {
+    pub fn new(server_bytes: impl Into<Vec<u8>>) -> Self {
+        Self {
+            server_bytes: io::Cursor::new(server_bytes.into()),
+            client_bytes: Vec::new(),
+        }
+    }
+
+    /// The bytes written to this stream so far.
+    pub fn client_bytes(&self) -> &[u8] {
+        &self.client_bytes
+    }
+}
+
+impl AsyncRead for ReplayStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(Read::read(&mut self.get_mut().server_bytes, buf))
+    }
+}
+
+impl AsyncWrite for ReplayStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().client_bytes.extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::iter::FromIterator;
+
+    use bolt_proto::message::*;
+    use bolt_proto::{Message, Value, Version};
+
+    use crate::client::tests::server_bytes;
+    use crate::Metadata;
+
+    use super::*;
+
+    async fn handshake_and_init<S: AsyncRead + AsyncWrite + Unpin>(
+        client: &mut Client<S>,
+    ) -> Message {
+        client
+            .handshake(&[Version::new(2, 0).into(), Version::new(1, 0).into()])
+            .await
+            .unwrap();
+        client
+            .init(
+                "bolt-client/test",
+                Metadata::from_iter(vec![("scheme", "none")]),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn record_and_replay() {
+        let success = Message::Success(Success::new(HashMap::from_iter(vec![(
+            "server".to_string(),
+            Value::from("Neo4j/3.5.0"),
+        )])));
+        let mut server = vec![0, 0, 0, 2];
+        server.extend(server_bytes(vec![success.clone()]));
+
+        let mut client =
+            RecordingClient::from_stream(RecordingStream::new(ReplayStream::new(server.clone())));
+        assert_eq!(handshake_and_init(&mut client).await, success);
+        let recording = client.stream().recording().clone();
+        assert_eq!(recording.received(), server.as_slice());
+        assert_eq!(&recording.sent()[..4], &[0x60, 0x60, 0xB0, 0x17]);
+
+        let mut client = Client::from_stream(recording.replay());
+        assert_eq!(handshake_and_init(&mut client).await, success);
+        assert_eq!(client.version(), &Some(2));
+        assert_eq!(client.stream().client_bytes(), recording.sent());
+    }
+}
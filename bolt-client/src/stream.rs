@@ -7,8 +7,10 @@ use tokio::net::TcpStream;
 use tokio::prelude::*;
 use tokio_rustls::client::TlsStream;
 
+/// The stream used by clients created with [`Client::new`](crate::Client::new) and
+/// [`Client::new_tls`](crate::Client::new_tls).
 #[derive(Debug)]
-pub(crate) enum Stream {
+pub enum Stream {
     Tcp(TcpStream),
     SecureTcp(Box<TlsStream<TcpStream>>),
 }
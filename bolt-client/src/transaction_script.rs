@@ -0,0 +1,139 @@
+use std::convert::TryInto;
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+use bolt_proto::message::*;
+use bolt_proto::Message;
+
+use crate::error::*;
+use crate::{Metadata, Params};
+
+/// A sequence of messages making up a transaction, which can be turned into the exact bytes a client would send to
+/// the server for it, without a connection.
+///
+/// # Example
+/// ```
+/// use bolt_client::TransactionScript;
+///
+/// let bytes = TransactionScript::new()
+///     .begin(None)
+///     .run_with_metadata("CREATE (:Language {name: 'Rust'});", None, None)
+///     .pull_all()
+///     .commit()
+///     .to_bytes()
+///     .unwrap();
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct TransactionScript {
+    messages: Vec<Message>,
+}
+
+impl TransactionScript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a `BEGIN` message to the script.
+    pub fn begin(self, metadata: Option<Metadata>) -> Self {
+        self.message(Message::Begin(Begin::new(
+            metadata.unwrap_or_default().value,
+        )))
+    }
+
+    /// Add a `RUN` message to the script, as used by Bolt v1 - v2.
+    pub fn run(self, statement: impl Into<String>, parameters: Option<Params>) -> Self {
+        self.message(Message::Run(Run::new(
+            statement.into(),
+            parameters.unwrap_or_default().value,
+        )))
+    }
+
+    /// Add a `RUN` message with metadata to the script, as used by Bolt v3+.
+    pub fn run_with_metadata(
+        self,
+        statement: impl Into<String>,
+        parameters: Option<Params>,
+        metadata: Option<Metadata>,
+    ) -> Self {
+        self.message(Message::RunWithMetadata(RunWithMetadata::new(
+            statement.into(),
+            parameters.unwrap_or_default().value,
+            metadata.unwrap_or_default().value,
+        )))
+    }
+
+    /// Add a `PULL_ALL` message to the script, as used by Bolt v1 - v3.
+    pub fn pull_all(self) -> Self {
+        self.message(Message::PullAll)
+    }
+
+    /// Add a `PULL` message to the script, as used by Bolt v4+.
+    pub fn pull(self, metadata: Option<Metadata>) -> Self {
+        self.message(Message::Pull(Pull::new(metadata.unwrap_or_default().value)))
+    }
+
+    /// Add a `COMMIT` message to the script.
+    pub fn commit(self) -> Self {
+        self.message(Message::Commit)
+    }
+
+    /// Add a `ROLLBACK` message to the script.
+    pub fn rollback(self) -> Self {
+        self.message(Message::Rollback)
+    }
+
+    /// Add an arbitrary message to the script.
+    pub fn message(mut self, message: Message) -> Self {
+        self.messages.push(message);
+        self
+    }
+
+    /// Get the messages in this script, in the order they will be sent.
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    /// Serialize all messages in this script into a single stream of chunked messages, as they would be sent to the
+    /// server.
+    pub fn to_bytes(&self) -> Result<Bytes> {
+        let mut bytes = BytesMut::new();
+        for message in self.messages.iter().cloned() {
+            let chunks: Vec<Bytes> = message.try_into()?;
+            chunks.into_iter().for_each(|chunk| bytes.put(chunk));
+        }
+        Ok(bytes.freeze())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::FromIterator;
+
+    use tokio::io::BufStream;
+
+    use crate::ReplayStream;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn to_bytes() {
+        let script = TransactionScript::new()
+            .begin(Some(Metadata::from_iter(vec![("db", "neo4j")])))
+            .run_with_metadata(
+                "MATCH (n {name: $name}) RETURN n;",
+                Some(Params::from_iter(vec![("name", "Rust")])),
+                None,
+            )
+            .pull(Some(Metadata::from_iter(vec![("n", -1)])))
+            .commit();
+        assert_eq!(script.messages().len(), 4);
+
+        let bytes = script.to_bytes().unwrap();
+        let mut stream = BufStream::new(ReplayStream::new(bytes.to_vec()));
+        for expected in script.messages() {
+            assert_eq!(&Message::from_stream(&mut stream).await.unwrap(), expected);
+        }
+        // Nothing is left over
+        assert!(Message::from_stream(&mut stream).await.is_err());
+    }
+}
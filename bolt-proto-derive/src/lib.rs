@@ -2,9 +2,11 @@ extern crate proc_macro;
 
 use proc_macro::TokenStream;
 
-use syn::{Data, DataStruct, Fields, Generics, Ident, WhereClause};
+use syn::{
+    Data, DataStruct, Field, Fields, Generics, Ident, Lit, Meta, NestedMeta, Type, WhereClause,
+};
 
-use quote::{format_ident, quote};
+use quote::quote;
 
 fn get_struct_info(ast: &syn::DeriveInput) -> (&Ident, &Generics, &Option<WhereClause>, &Fields) {
     let name = &ast.ident;
@@ -37,7 +39,8 @@ pub fn signature_derive(input: TokenStream) -> TokenStream {
 #[proc_macro_derive(Marker)]
 pub fn marker_derive(input: TokenStream) -> TokenStream {
     let ast = &syn::parse(input).unwrap();
-    let (name, type_args, where_clause, _fields) = get_struct_info(ast);
+    let (name, type_args, where_clause, fields) = get_struct_info(ast);
+    let field_names = fields.iter().map(|f| f.ident.clone().unwrap());
     quote!(
         impl#type_args crate::serialization::Marker for #name#type_args
         #where_clause
@@ -45,6 +48,12 @@ pub fn marker_derive(input: TokenStream) -> TokenStream {
             fn get_marker(&self) -> crate::error::Result<u8> {
                 Ok(MARKER)
             }
+
+            fn serialized_len(&self) -> crate::error::Result<usize> {
+                use crate::serialization::Field;
+                // Marker byte, signature byte, then the fields
+                Ok(std::mem::size_of::<u8>() * 2 #(+ self.#field_names.field_len()?)*)
+            }
         }
     )
     .into()
@@ -55,20 +64,22 @@ pub fn serialize_derive(input: TokenStream) -> TokenStream {
     let ast = &syn::parse(input).unwrap();
     let (name, type_args, where_clause, fields) = get_struct_info(ast);
 
-    let byte_var_names: Vec<Ident> = fields
-        .iter()
-        .map(|f| format_ident!("{}_bytes", f.ident.clone().unwrap()))
-        .collect();
-
-    let byte_vars = byte_var_names.iter().zip(fields).map(|(var_name, field)| {
-        let field_name = field.ident.clone();
-        quote!(let #var_name = crate::Value::from(self.#field_name).try_into_bytes()?;)
-    });
+    let field_names = fields.iter().map(|field| field.ident.as_ref().unwrap());
 
     quote!(
         impl#type_args crate::serialization::Serialize for #name#type_args
         #where_clause
-        {}
+        {
+            fn serialize_into(&self, buf: &mut impl ::bytes::BufMut) -> crate::error::Result<()> {
+                use crate::serialization::Field;
+
+                // Marker byte, signature byte, then the fields
+                buf.put_u8(MARKER);
+                buf.put_u8(SIGNATURE);
+                #(self.#field_names.serialize_field(buf)?;)*
+                Ok(())
+            }
+        }
 
         impl#type_args ::std::convert::TryInto<::bytes::Bytes> for #name#type_args
         #where_clause
@@ -76,21 +87,7 @@ pub fn serialize_derive(input: TokenStream) -> TokenStream {
             type Error = crate::error::Error;
 
             fn try_into(self) -> crate::error::Result<::bytes::Bytes> {
-                use ::std::convert::{TryFrom, TryInto};
-                use ::bytes::BufMut;
-                use crate::serialization::Serialize;
-
-                let marker = MARKER;
-                let signature = SIGNATURE;
-                #(#byte_vars)*
-                // Marker byte, signature byte, then the rest of the data
-                let mut result_bytes_mut = ::bytes::BytesMut::with_capacity(
-                    std::mem::size_of::<u8>() * 2 #(+ #byte_var_names.len())*
-                );
-                result_bytes_mut.put_u8(MARKER);
-                result_bytes_mut.put_u8(SIGNATURE);
-                #(result_bytes_mut.put(#byte_var_names);)*
-                Ok(result_bytes_mut.freeze())
+                crate::serialization::serialize_to_bytes(&self)
             }
         }
     )
@@ -102,30 +99,214 @@ pub fn deserialize_derive(input: TokenStream) -> TokenStream {
     let ast = &syn::parse(input).unwrap();
     let (name, type_args, where_clause, fields) = get_struct_info(ast);
 
-    let deserialize_fields =
-        fields
-            .iter()
-            .map(|field| {
-                let field_name = field.ident.as_ref().unwrap();
-                quote!(#field_name: crate::Value::try_from(::std::sync::Arc::clone(&remaining_bytes_arc))?.try_into()?,)
-            });
+    let deserialize_fields = fields.iter().map(|field| {
+        let field_name = field.ident.as_ref().unwrap();
+        quote!(#field_name: crate::Value::deserialize(bytes)?.try_into()?,)
+    });
+    let field_names: Vec<&Ident> = fields
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap())
+        .collect();
+    let field_count = field_names.len();
 
     quote!(
         impl crate::serialization::Deserialize for #name#type_args
         #where_clause
-        {}
+        {
+            fn deserialize(bytes: &mut ::bytes::Bytes) -> crate::error::Result<Self> {
+                use ::std::convert::TryInto;
+                use crate::serialization::Deserialize;
+                Ok(#name {
+                    #(#deserialize_fields)*
+                })
+            }
+        }
+
+        impl crate::serialization::FromFields for #name#type_args
+        #where_clause
+        {
+            fn from_fields(fields: ::std::vec::Vec<crate::Value>) -> crate::error::Result<Self> {
+                use ::std::convert::TryInto;
+                let [#(#field_names),*] = crate::serialization::into_field_array::<#field_count>(fields)?;
+                Ok(#name {
+                    #(#field_names: #field_names.try_into()?,)*
+                })
+            }
+        }
 
         impl ::std::convert::TryFrom<::std::sync::Arc<::std::sync::Mutex<::bytes::Bytes>>> for #name#type_args
         #where_clause
         {
             type Error = crate::error::Error;
 
-            fn try_from(remaining_bytes_arc: ::std::sync::Arc<::std::sync::Mutex<::bytes::Bytes>>) -> crate::error::Result<Self> {
-                use ::std::convert::{TryFrom, TryInto};
+            fn try_from(input_arc: ::std::sync::Arc<::std::sync::Mutex<::bytes::Bytes>>) -> crate::error::Result<Self> {
+                crate::serialization::deserialize_shared(input_arc)
+            }
+        }
+    ).into()
+}
+
+// The key of a field in a value map, along with whether it should be skipped, from its #[bolt(...)] attributes
+fn get_field_key(field: &Field) -> (String, bool) {
+    let mut key = field.ident.as_ref().unwrap().to_string();
+    let mut skip = false;
+    for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("bolt")) {
+        let nested = match attr.parse_meta() {
+            Ok(Meta::List(list)) => list.nested,
+            _ => panic!("Expected #[bolt(...)] attribute."),
+        };
+        for meta in nested {
+            match meta {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => skip = true,
+                NestedMeta::Meta(Meta::NameValue(name_value))
+                    if name_value.path.is_ident("rename") =>
+                {
+                    match name_value.lit {
+                        Lit::Str(rename) => key = rename.value(),
+                        _ => panic!("Expected a string for #[bolt(rename = ...)]."),
+                    }
+                }
+                _ => panic!("Unknown #[bolt(...)] attribute, expected `rename` or `skip`."),
+            }
+        }
+    }
+    (key, skip)
+}
+
+// Whether the type of a field is an `Option`, so that it may be missing from a value map
+fn is_option(field: &Field) -> bool {
+    match &field.ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+fn get_named_fields(fields: &Fields) -> impl Iterator<Item = &Field> {
+    match fields {
+        Fields::Named(named) => named.named.iter(),
+        _ => panic!("Macro must be used on a struct with named fields."),
+    }
+}
+
+/// Convert a struct into a `Value::Map`, with an entry for each field. The key
+/// of an entry can be changed with `#[bolt(rename = "key")]`, and a field can be left out with `#[bolt(skip)]`.
+#[proc_macro_derive(IntoValue, attributes(bolt))]
+pub fn into_value_derive(input: TokenStream) -> TokenStream {
+    let ast = &syn::parse(input).unwrap();
+    let (name, _type_args, _where_clause, fields) = get_struct_info(ast);
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+
+    let entries = get_named_fields(fields).filter_map(|field| {
+        let (key, skip) = get_field_key(field);
+        let field_name = &field.ident;
+        if skip {
+            None
+        } else {
+            Some(quote!((
+                ::std::string::String::from(#key),
+                ::bolt_proto::Value::from(value.#field_name),
+            ),))
+        }
+    });
+
+    quote!(
+        impl#impl_generics ::std::convert::From<#name#type_generics> for ::bolt_proto::Value
+        #where_clause
+        {
+            fn from(value: #name#type_generics) -> Self {
+                let entries: ::std::collections::HashMap<::std::string::String, ::bolt_proto::Value> =
+                    ::std::iter::FromIterator::from_iter(::std::vec![#(#entries)*]);
+                ::bolt_proto::Value::from(entries)
+            }
+        }
+    )
+    .into()
+}
+
+/// Convert a `Value::Map` into a struct, by converting the entry for each
+/// field. Missing entries are converted from `Value::Null` for `Option` fields, and are an error
+/// (`ConversionError::MissingProperty`) for any other field. The key of an entry can be changed with
+/// `#[bolt(rename = "key")]`, and a field can be left out with `#[bolt(skip)]`, in which case it is set to its default
+/// value.
+#[proc_macro_derive(FromValue, attributes(bolt))]
+pub fn from_value_derive(input: TokenStream) -> TokenStream {
+    let ast = &syn::parse(input).unwrap();
+    let (name, _type_args, _where_clause, fields) = get_struct_info(ast);
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+
+    let field_values = get_named_fields(fields).map(|field| {
+        let (key, skip) = get_field_key(field);
+        let field_name = &field.ident;
+        if skip {
+            quote!(#field_name: ::std::default::Default::default(),)
+        } else if is_option(field) {
+            quote!(#field_name: ::std::convert::TryFrom::try_from(
+                entries.remove(#key).unwrap_or(::bolt_proto::Value::Null),
+            )?,)
+        } else {
+            quote!(#field_name: ::std::convert::TryFrom::try_from(
+                entries.remove(#key).ok_or_else(|| {
+                    ::bolt_proto::error::ConversionError::MissingProperty(::std::string::String::from(#key))
+                })?,
+            )?,)
+        }
+    });
+
+    quote!(
+        impl#impl_generics ::std::convert::TryFrom<::bolt_proto::Value> for #name#type_generics
+        #where_clause
+        {
+            type Error = ::bolt_proto::error::Error;
+
+            fn try_from(value: ::bolt_proto::Value) -> ::bolt_proto::error::Result<Self> {
+                let mut entries: ::std::collections::HashMap<::std::string::String, ::bolt_proto::Value> =
+                    ::std::convert::TryFrom::try_from(value)?;
                 Ok(#name {
-                    #(#deserialize_fields)*
+                    #(#field_values)*
                 })
             }
         }
-    ).into()
+    )
+    .into()
+}
+
+/// Implement `FromRecord` for a struct, converting each field from the column of the same name. The column of a field
+/// can be changed with `#[bolt(rename = "column")]`, and a field can be left out with `#[bolt(skip)]`, in which case it
+/// is set to its default value.
+#[proc_macro_derive(FromRecord, attributes(bolt))]
+pub fn from_record_derive(input: TokenStream) -> TokenStream {
+    let ast = &syn::parse(input).unwrap();
+    let (name, _type_args, _where_clause, fields) = get_struct_info(ast);
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+
+    let field_values = get_named_fields(fields).map(|field| {
+        let (column, skip) = get_field_key(field);
+        let field_name = &field.ident;
+        if skip {
+            quote!(#field_name: ::std::default::Default::default(),)
+        } else {
+            quote!(#field_name: ::bolt_proto::message::take_column(&mut entries, #column)?,)
+        }
+    });
+
+    quote!(
+        impl#impl_generics ::bolt_proto::FromRecord for #name#type_generics
+        #where_clause
+        {
+            fn from_record(
+                record: ::bolt_proto::message::Record,
+                columns: &[impl ::std::convert::AsRef<str>],
+            ) -> ::bolt_proto::error::Result<Self> {
+                let mut entries = record.into_map(columns)?;
+                Ok(#name {
+                    #(#field_values)*
+                })
+            }
+        }
+    )
+    .into()
 }
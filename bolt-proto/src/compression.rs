@@ -0,0 +1,123 @@
+use std::fmt::Debug;
+
+use bytes::Bytes;
+
+use crate::error::*;
+
+/// Compresses the body of a message before it is split into chunks.
+///
+/// Bolt itself has no notion of compression, so a compressor must be paired with a [`Decompressor`] on the other end
+/// of the connection, e.g. a proxy fronting the server.
+pub trait Compressor: Debug + Send + Sync {
+    fn compress(&self, bytes: Bytes) -> Result<Bytes>;
+}
+
+/// Decompresses the body of a message after its chunks have been joined together.
+pub trait Decompressor: Debug + Send + Sync {
+    fn decompress(&self, bytes: Bytes) -> Result<Bytes>;
+}
+
+/// A [`Compressor`] and [`Decompressor`] that passes message bodies through unchanged. This is the default.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct NoCompression;
+
+impl Compressor for NoCompression {
+    fn compress(&self, bytes: Bytes) -> Result<Bytes> {
+        Ok(bytes)
+    }
+}
+
+impl Decompressor for NoCompression {
+    fn decompress(&self, bytes: Bytes) -> Result<Bytes> {
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+    use std::io::Cursor;
+
+    use bytes::{BufMut, BytesMut};
+    use tokio::io::BufStream;
+
+    use crate::message::Record;
+    use crate::serialization::*;
+    use crate::{Message, Value};
+
+    use super::*;
+
+    // Encodes runs of identical bytes as (length, byte) pairs
+    #[derive(Debug)]
+    struct RunLength;
+
+    impl Compressor for RunLength {
+        fn compress(&self, bytes: Bytes) -> Result<Bytes> {
+            let mut compressed = BytesMut::new();
+            let mut iter = bytes.iter().peekable();
+            while let Some(&byte) = iter.next() {
+                let mut run: u8 = 1;
+                while run < u8::MAX && iter.peek() == Some(&&byte) {
+                    iter.next();
+                    run += 1;
+                }
+                compressed.put_u8(run);
+                compressed.put_u8(byte);
+            }
+            Ok(compressed.freeze())
+        }
+    }
+
+    impl Decompressor for RunLength {
+        fn decompress(&self, bytes: Bytes) -> Result<Bytes> {
+            if !bytes.len().is_multiple_of(2) {
+                return Err(std::io::Error::from(std::io::ErrorKind::InvalidData).into());
+            }
+            let mut decompressed = BytesMut::new();
+            for pair in bytes.chunks(2) {
+                decompressed.put_slice(&vec![pair[1]; pair[0] as usize]);
+            }
+            Ok(decompressed.freeze())
+        }
+    }
+
+    fn large_message() -> Message {
+        Message::Record(Record::new(vec![
+            Value::from("a".repeat(100_000)),
+            Value::from(vec![0; 10_000]),
+        ]))
+    }
+
+    async fn round_trip(
+        message: Message,
+        compressor: &dyn Compressor,
+        decompressor: &dyn Decompressor,
+    ) -> (Message, usize) {
+        let chunks = message.into_chunks_with(compressor).unwrap();
+        let bytes: Vec<u8> = chunks.iter().flat_map(|chunk| chunk.to_vec()).collect();
+        let len = bytes.len();
+        let mut stream = BufStream::new(Cursor::new(bytes));
+        let message = Message::from_stream_with(&mut stream, decompressor)
+            .await
+            .unwrap();
+        (message, len)
+    }
+
+    #[tokio::test]
+    async fn identity() {
+        let message = large_message();
+        let plain_chunks: Vec<Bytes> = message.clone().try_into().unwrap();
+        let (received, len) = round_trip(message.clone(), &NoCompression, &NoCompression).await;
+        assert_eq!(received, message);
+        assert_eq!(len, plain_chunks.iter().map(Bytes::len).sum::<usize>());
+    }
+
+    #[tokio::test]
+    async fn run_length() {
+        let message = large_message();
+        let plain_len = message.clone().try_into_bytes().unwrap().len();
+        let (received, len) = round_trip(message.clone(), &RunLength, &RunLength).await;
+        assert_eq!(received, message);
+        assert!(len < plain_len / 10);
+    }
+}
@@ -0,0 +1,441 @@
+use std::io;
+use std::time::Duration;
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::time;
+
+use crate::compression::*;
+use crate::error::*;
+use crate::serialization::*;
+use crate::value::{decode_with_extensions, ExtensionRegistry};
+use crate::Message;
+
+// How much to read from a stream at a time, at least
+const READ_SIZE: usize = 8 * 1024;
+
+/// The result of decoding from input that may not have fully arrived yet.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Decoded<T> {
+    Complete(T),
+    /// At least this many more bytes are needed before anything can be decoded.
+    Incomplete(usize),
+}
+
+/// Deserialize a value from the start of the given bytes, or return [`Decoded::Incomplete`] if they end before it
+/// does, in which case the bytes are left as they were so that decoding can be retried once more have arrived.
+///
+/// # Example
+/// ```
+/// # use bytes::Bytes;
+/// # use bolt_proto::decoder::{try_decode, Decoded};
+/// # use bolt_proto::Value;
+/// // A list of two integers, missing its last element
+/// let mut bytes = Bytes::from_static(&[0x92, 0x01]);
+/// assert_eq!(try_decode::<Value>(&mut bytes).unwrap(), Decoded::Incomplete(1));
+/// assert_eq!(bytes.len(), 2);
+/// ```
+pub fn try_decode<T: Deserialize>(bytes: &mut Bytes) -> Result<Decoded<T>> {
+    let mut input = bytes.clone();
+    match T::deserialize(&mut input) {
+        Ok(value) => {
+            *bytes = input;
+            Ok(Decoded::Complete(value))
+        }
+        Err(Error::DeserializationError(DeserializationError::UnexpectedEnd {
+            needed,
+            remaining,
+            ..
+        })) => Ok(Decoded::Incomplete(needed - remaining)),
+        Err(error) => Err(error),
+    }
+}
+
+/// Decodes messages from the raw bytes of a connection as they arrive, for transports that aren't driven by
+/// [`Message::from_stream`], e.g. a custom read loop. Feed it whatever has been read so far, then call
+/// [`next_message`](MessageDecoder::next_message) until it returns [`Decoded::Incomplete`].
+///
+/// Chunks are joined as soon as they are complete, so only the chunk currently being received is held back.
+///
+/// # Example
+/// ```
+/// # use std::convert::TryInto;
+/// # use bytes::Bytes;
+/// # use bolt_proto::decoder::{Decoded, MessageDecoder};
+/// # use bolt_proto::Message;
+/// let chunks: Vec<Bytes> = Message::Reset.try_into().unwrap();
+/// let bytes: Vec<u8> = chunks.concat();
+///
+/// let mut decoder = MessageDecoder::new();
+/// decoder.feed(&bytes[..3]);
+/// assert_eq!(decoder.next_message().unwrap(), Decoded::Incomplete(1));
+/// decoder.feed(&bytes[3..]);
+/// assert_eq!(decoder.next_message().unwrap(), Decoded::Complete(Message::Reset));
+/// ```
+#[derive(Debug, Default)]
+pub struct MessageDecoder {
+    // Bytes that have been fed but not yet joined into a message body
+    input: BytesMut,
+    // The chunks of the message being received
+    body: BytesMut,
+    max_message_size: Option<usize>,
+    deserialize_config: DeserializeConfig,
+    extensions: ExtensionRegistry,
+}
+
+impl MessageDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add bytes read from the connection.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.input.put_slice(bytes);
+    }
+
+    /// Set the maximum size of a message, counting the data of its chunks before they are decompressed. A message whose
+    /// chunks add up to more fails to decode with [`DeserializationError::LimitExceeded`] as soon as the size of the
+    /// chunk that exceeds the limit is known. By default, messages can be any size.
+    ///
+    /// A message that fails this way hasn't been consumed, so the decoder shouldn't be used afterwards.
+    pub fn set_max_message_size(&mut self, max_message_size: Option<usize>) {
+        self.max_message_size = max_message_size;
+    }
+
+    /// Set the limits enforced while deserializing the body of each message, see [`DeserializeConfig`]. Defaults to
+    /// [`DeserializeConfig::new`], regardless of any config set on the current thread with [`decode_with_config`].
+    pub fn set_deserialize_config(&mut self, config: DeserializeConfig) {
+        self.deserialize_config = config;
+    }
+
+    /// Set the decoders of custom structure types used while deserializing the body of each message, see
+    /// [`decode_with_extensions`]. By default, none are registered, regardless of any registry in use on the current
+    /// thread.
+    pub fn set_extensions(&mut self, registry: ExtensionRegistry) {
+        self.extensions = registry;
+    }
+
+    /// The number of bytes that have been fed but not yet decoded into a message.
+    pub fn buffered_len(&self) -> usize {
+        self.input.len() + self.body.len()
+    }
+
+    /// Decode the next message from the bytes fed so far, if all of its chunks have arrived.
+    pub fn next_message(&mut self) -> Result<Decoded<Message>> {
+        self.next_message_with(&NoCompression)
+    }
+
+    /// Like [`next_message`](MessageDecoder::next_message), but decompresses the body of the message with the given
+    /// [`Decompressor`] after its chunks have been joined together.
+    pub fn next_message_with(
+        &mut self,
+        decompressor: &dyn Decompressor,
+    ) -> Result<Decoded<Message>> {
+        loop {
+            // 16-bit size, then the chunk data
+            if self.input.len() < 2 {
+                return Ok(Decoded::Incomplete(2 - self.input.len()));
+            }
+            let chunk_len = u16::from_be_bytes([self.input[0], self.input[1]]) as usize;
+            // Empty chunks before a message are NOOPs, sent by servers as keep-alives since Bolt v4.1
+            if chunk_len == 0 && self.body.is_empty() {
+                self.input.advance(2);
+                continue;
+            }
+            // Messages end in a 0_u16
+            if chunk_len == 0 {
+                self.input.advance(2);
+                let body = self.body.split().freeze();
+                let mut body = decompressor.decompress(body)?;
+                let config = self.deserialize_config;
+                return decode_with_extensions(&self.extensions, || {
+                    decode_with_config(config, || Message::deserialize(&mut body))
+                })
+                .map(Decoded::Complete);
+            }
+            check_limit(
+                "message size",
+                self.max_message_size,
+                self.body.len() + chunk_len,
+            )?;
+            if self.input.len() < 2 + chunk_len {
+                return Ok(Decoded::Incomplete(2 + chunk_len - self.input.len()));
+            }
+            self.input.advance(2);
+            let chunk = self.input.split_to(chunk_len);
+            self.body.put(chunk);
+        }
+    }
+
+    /// Read the next message from the stream, keeping anything read after it for the next call.
+    ///
+    /// Unlike [`Message::from_stream`], this is cancel-safe: if the returned future is dropped before it completes,
+    /// e.g. by a timeout or in a `select!`, the bytes read so far stay in the decoder, and reading resumes where it
+    /// left off the next time this is called.
+    pub async fn read_from<T: Unpin + AsyncRead>(
+        &mut self,
+        stream: &mut T,
+        decompressor: &dyn Decompressor,
+    ) -> Result<Message> {
+        loop {
+            let needed = match self.next_message_with(decompressor)? {
+                Decoded::Complete(message) => return Ok(message),
+                Decoded::Incomplete(needed) => needed,
+            };
+            // Only completes once bytes have been moved into the buffer, so nothing is lost if it is cancelled
+            self.input.reserve(needed.max(READ_SIZE));
+            if stream.read_buf(&mut self.input).await? == 0 {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+            }
+        }
+    }
+
+    /// Like [`read_from`](MessageDecoder::read_from), but returns [`Error::TimedOut`] if a whole message hasn't been
+    /// read within the given duration, so that a stalled peer can't block the caller forever. Whatever was read before
+    /// the timeout is kept, so reading can be retried.
+    pub async fn read_from_timeout<T: Unpin + AsyncRead>(
+        &mut self,
+        stream: &mut T,
+        decompressor: &dyn Decompressor,
+        timeout: Duration,
+    ) -> Result<Message> {
+        time::timeout(timeout, self.read_from(stream, decompressor))
+            .await
+            .map_err(|_| Error::TimedOut(timeout))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::convert::TryInto;
+    use std::time::Duration;
+
+    use tokio::io::AsyncWriteExt;
+
+    use crate::message::*;
+    use crate::value::Structure;
+    use crate::Value;
+
+    use super::*;
+
+    fn get_bytes(messages: Vec<Message>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for message in messages {
+            let chunks: Vec<Bytes> = message.try_into().unwrap();
+            bytes.extend(chunks.concat());
+        }
+        bytes
+    }
+
+    #[test]
+    fn try_decode_value() {
+        let value = Value::from(vec!["a".repeat(100), "b".to_string()]);
+        let bytes = value.clone().try_into_bytes().unwrap();
+        for len in 0..bytes.len() {
+            let mut partial = bytes.slice(..len);
+            assert!(matches!(
+                try_decode::<Value>(&mut partial).unwrap(),
+                Decoded::Incomplete(needed) if needed > 0 && needed <= bytes.len() - len
+            ));
+            assert_eq!(partial.len(), len);
+        }
+        let mut extra = Bytes::from([&bytes[..], &[0x01]].concat());
+        assert_eq!(
+            try_decode::<Value>(&mut extra).unwrap(),
+            Decoded::Complete(value)
+        );
+        assert_eq!(&extra[..], &[0x01]);
+
+        let mut invalid = Bytes::from_static(&[0xC7]);
+        assert!(try_decode::<Value>(&mut invalid).is_err());
+    }
+
+    #[test]
+    fn byte_by_byte() {
+        let record = Message::Record(Record::new(vec![Value::from("a".repeat(20_000))]));
+        let bytes = get_bytes(vec![
+            record.clone(),
+            Message::Success(Success::new(HashMap::new())),
+        ]);
+
+        let mut decoder = MessageDecoder::new();
+        let mut messages = Vec::new();
+        for byte in bytes {
+            decoder.feed(&[byte]);
+            while let Decoded::Complete(message) = decoder.next_message().unwrap() {
+                messages.push(message);
+            }
+        }
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0], record);
+        assert_eq!(decoder.buffered_len(), 0);
+    }
+
+    #[cfg(feature = "v3")]
+    #[test]
+    fn incomplete_chunk() {
+        let bytes = get_bytes(vec![Message::Reset, Message::Goodbye]);
+        let mut decoder = MessageDecoder::new();
+        decoder.feed(&bytes[..8]);
+        assert_eq!(
+            decoder.next_message().unwrap(),
+            Decoded::Complete(Message::Reset)
+        );
+        // Only the chunk size of the next message has arrived
+        assert_eq!(decoder.next_message().unwrap(), Decoded::Incomplete(2));
+        decoder.feed(&bytes[8..]);
+        assert_eq!(
+            decoder.next_message().unwrap(),
+            Decoded::Complete(Message::Goodbye)
+        );
+        assert_eq!(decoder.next_message().unwrap(), Decoded::Incomplete(2));
+    }
+
+    #[test]
+    fn noop_chunks() {
+        let mut decoder = MessageDecoder::new();
+        decoder.feed(&[0, 0, 0, 0]);
+        decoder.feed(&get_bytes(vec![Message::Reset]));
+        decoder.feed(&[0, 0]);
+        assert_eq!(
+            decoder.next_message().unwrap(),
+            Decoded::Complete(Message::Reset)
+        );
+        assert_eq!(decoder.next_message().unwrap(), Decoded::Incomplete(2));
+        assert_eq!(decoder.buffered_len(), 0);
+    }
+
+    #[test]
+    fn max_message_size() {
+        let record = Message::Record(Record::new(vec![Value::from("a".repeat(20_000))]));
+        let len = record.serialized_len().unwrap();
+        let bytes = get_bytes(vec![record.clone()]);
+
+        let mut decoder = MessageDecoder::new();
+        decoder.set_max_message_size(Some(len));
+        decoder.feed(&bytes);
+        assert_eq!(decoder.next_message().unwrap(), Decoded::Complete(record));
+
+        // Fails once the size of the second chunk arrives
+        let mut decoder = MessageDecoder::new();
+        decoder.set_max_message_size(Some(len - 1));
+        let first_chunk_len = u16::from_be_bytes([bytes[0], bytes[1]]) as usize;
+        decoder.feed(&bytes[..2 + first_chunk_len + 2]);
+        assert!(matches!(
+            decoder.next_message(),
+            Err(Error::DeserializationError(
+                DeserializationError::LimitExceeded {
+                    limit: "message size",
+                    ..
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn deserialize_config() {
+        // [[1]]
+        let record = Message::Record(Record::new(vec![Value::from(vec![vec![1]])]));
+        let bytes = get_bytes(vec![record.clone()]);
+
+        let mut decoder = MessageDecoder::new();
+        decoder.feed(&bytes);
+        assert_eq!(decoder.next_message().unwrap(), Decoded::Complete(record));
+
+        let mut decoder = MessageDecoder::new();
+        decoder.set_deserialize_config(DeserializeConfig::new().with_max_depth(1));
+        decoder.feed(&bytes);
+        assert!(matches!(
+            decoder.next_message(),
+            Err(Error::DeserializationError(
+                DeserializationError::LimitExceeded { limit: "depth", .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn extensions() {
+        fn reject(_: Structure) -> Result<Value> {
+            Err(ConversionError::FromValue(Value::Null).into())
+        }
+
+        let record = Message::Record(Record::new(vec![Value::from(Structure::new(
+            0x01,
+            vec![1],
+        ))]));
+        let bytes = get_bytes(vec![record.clone()]);
+
+        let mut decoder = MessageDecoder::new();
+        decoder.feed(&bytes);
+        assert_eq!(decoder.next_message().unwrap(), Decoded::Complete(record));
+
+        let mut registry = ExtensionRegistry::new();
+        registry.register(0x01, reject);
+        let mut decoder = MessageDecoder::new();
+        decoder.set_extensions(registry);
+        decoder.feed(&bytes);
+        assert!(decoder.next_message().is_err());
+    }
+
+    #[tokio::test]
+    async fn read_from_after_cancellation() {
+        let record = Message::Record(Record::new(vec![Value::from("a".repeat(20_000))]));
+        let bytes = get_bytes(vec![record.clone(), Message::Reset]);
+        let (mut client, mut server) = tokio::io::duplex(64 * 1024);
+        let mut decoder = MessageDecoder::new();
+
+        // Only part of the first message arrives before the read is cancelled
+        server.write_all(&bytes[..10_000]).await.unwrap();
+        let read = decoder.read_from(&mut client, &NoCompression);
+        assert!(tokio::time::timeout(Duration::from_millis(10), read)
+            .await
+            .is_err());
+
+        server.write_all(&bytes[10_000..]).await.unwrap();
+        drop(server);
+        assert_eq!(
+            decoder
+                .read_from(&mut client, &NoCompression)
+                .await
+                .unwrap(),
+            record
+        );
+        assert_eq!(
+            decoder
+                .read_from(&mut client, &NoCompression)
+                .await
+                .unwrap(),
+            Message::Reset
+        );
+        assert!(decoder
+            .read_from(&mut client, &NoCompression)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn read_from_timeout() {
+        let bytes = get_bytes(vec![Message::Reset]);
+        let (mut client, mut server) = tokio::io::duplex(64);
+        let mut decoder = MessageDecoder::new();
+
+        server.write_all(&bytes[..2]).await.unwrap();
+        let timeout = Duration::from_millis(10);
+        assert!(matches!(
+            decoder
+                .read_from_timeout(&mut client, &NoCompression, timeout)
+                .await,
+            Err(Error::TimedOut(duration)) if duration == timeout
+        ));
+        // What was read before the timeout is kept
+        server.write_all(&bytes[2..]).await.unwrap();
+        assert_eq!(
+            decoder
+                .read_from_timeout(&mut client, &NoCompression, timeout)
+                .await
+                .unwrap(),
+            Message::Reset
+        );
+    }
+}
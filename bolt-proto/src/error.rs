@@ -2,7 +2,7 @@ use std::str::Utf8Error;
 
 use thiserror::Error;
 
-use crate::{Message, Value};
+use crate::{Message, Value, Version};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -10,20 +10,63 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub enum Error {
     #[error(transparent)]
     IOError(#[from] std::io::Error),
+    #[error("Timed out after {0:?}")]
+    TimedOut(std::time::Duration),
     #[error("Value too large (size: {0})")]
     ValueTooLarge(usize),
+    #[error("Invalid number of fields (expected: {expected}, actual: {actual})")]
+    InvalidFieldCount { expected: usize, actual: usize },
+    #[error("{name} is not supported by Bolt v{version}")]
+    UnsupportedByVersion {
+        name: &'static str,
+        version: Version,
+    },
     #[error(transparent)]
     ConversionError(#[from] ConversionError),
     #[error(transparent)]
     DeserializationError(#[from] DeserializationError),
 }
 
+// Lets message fields holding a plain Value be deserialized like any other field
+impl From<std::convert::Infallible> for Error {
+    fn from(infallible: std::convert::Infallible) -> Self {
+        match infallible {}
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ConversionError {
     #[error("Invalid conversion from value {0:?}")]
     FromValue(Value),
     #[error("Invalid conversion from message {0:?}")]
-    FromMessage(Message),
+    FromMessage(Box<Message>),
+    #[error("Invalid length (expected: {expected}, actual: {actual})")]
+    InvalidLength { expected: usize, actual: usize },
+    #[error("Invalid timestamp: {0}")]
+    InvalidTimestamp(i64),
+    #[error("Invalid time zone: {0}")]
+    InvalidTimeZone(String),
+    #[error("Invalid UTC offset: {0} seconds")]
+    InvalidOffset(i32),
+    #[error("Invalid time: {0} nanoseconds since midnight")]
+    InvalidTime(i64),
+    #[error("Invalid date: {0} days since the Unix epoch")]
+    InvalidDate(i64),
+    #[error("Missing column: {0}")]
+    MissingColumn(String),
+    #[error("Missing property: {0}")]
+    MissingProperty(String),
+    #[error("Invalid value for property {key}: {value:?}")]
+    InvalidProperty { key: String, value: Box<Value> },
+    #[error("Invalid path sequence index at position {0}")]
+    InvalidPathSequence(usize),
+    #[error("Invalid graph structure: {0}")]
+    InvalidGraphStructure(String),
+    #[error("Invalid value for column {column}: {value:?}")]
+    InvalidColumn { column: String, value: Box<Value> },
+    #[cfg(feature = "json")]
+    #[error("Invalid conversion from JSON value {0}")]
+    FromJson(serde_json::Value),
 }
 
 #[derive(Debug, Error)]
@@ -34,6 +77,24 @@ pub enum DeserializationError {
     InvalidMarkerByte(u8),
     #[error("Invalid signature byte: {0:x}")]
     InvalidSignatureByte(u8),
+    #[error(
+        "Unexpected end of input reading {reading} (needed: {needed}, remaining: {remaining})"
+    )]
+    UnexpectedEnd {
+        reading: &'static str,
+        needed: usize,
+        remaining: usize,
+    },
+    #[error("Non-canonical encoding of integer {value} with marker byte {marker:x}")]
+    NonCanonicalInteger { value: i64, marker: u8 },
+    #[error("Exceeded {limit} limit (max: {max}, actual: {actual})")]
+    LimitExceeded {
+        limit: &'static str,
+        max: usize,
+        actual: usize,
+    },
+    #[error("Unexpected bytes after the end of the value (remaining: {0})")]
+    TrailingBytes(usize),
     #[error("String deserialization failed: {0}")]
     InvalidUTF8(#[from] Utf8Error),
 }
@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+
+use crate::error::*;
+use crate::message::Record;
+use crate::value::{Node, Path, Relationship, Value};
+
+/// An in-memory graph of the nodes and relationships found in query results, for traversing them without matching on
+/// values by hand.
+///
+/// Nodes and relationships are deduplicated by their element id, or by their legacy numeric identity if they don't have
+/// one. When the same node or relationship is added again, the latest copy is kept.
+///
+/// # Example
+/// ```
+/// # use std::collections::HashMap;
+/// # use bolt_proto::graph::Graph;
+/// # use bolt_proto::message::Record;
+/// # use bolt_proto::value::*;
+/// let alice = Node::builder(1).label("Person").build().unwrap();
+/// let bob = Node::builder(2).label("Person").build().unwrap();
+/// let knows = Relationship::builder(3, 1, 2, "KNOWS").build().unwrap();
+/// let records = vec![
+///     Record::new(vec![Value::from(alice.clone()), Value::from(knows), Value::from(bob)]),
+///     Record::new(vec![Value::from(alice.clone())]),
+/// ];
+///
+/// let graph = Graph::from_records(records).unwrap();
+/// assert_eq!(graph.node_count(), 2);
+/// let friends: Vec<i64> = graph.neighbors(&alice).map(Node::node_identity).collect();
+/// assert_eq!(friends, vec![2]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Graph {
+    nodes: Vec<Node>,
+    relationships: Vec<Relationship>,
+    node_indices: HashMap<Id, usize>,
+    relationship_indices: HashMap<Id, usize>,
+    outgoing: HashMap<Id, Vec<usize>>,
+    incoming: HashMap<Id, Vec<usize>>,
+}
+
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+enum Id {
+    Element(String),
+    Legacy(i64),
+}
+
+impl Id {
+    fn new(element_id: Option<&str>, identity: i64) -> Self {
+        match element_id {
+            Some(element_id) => Id::Element(element_id.to_string()),
+            None => Id::Legacy(identity),
+        }
+    }
+
+    fn of_node(node: &Node) -> Self {
+        Self::new(node.element_id(), node.node_identity())
+    }
+
+    fn of_relationship(rel: &Relationship) -> Self {
+        Self::new(rel.element_id(), rel.rel_identity())
+    }
+}
+
+fn endpoints(rel: &Relationship) -> (Id, Id) {
+    (
+        Id::new(rel.start_node_element_id(), rel.start_node_identity()),
+        Id::new(rel.end_node_element_id(), rel.end_node_identity()),
+    )
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a graph from the graph structures in the given records, see [`add_record`](Graph::add_record).
+    pub fn from_records(records: impl IntoIterator<Item = Record>) -> Result<Self> {
+        let mut graph = Self::new();
+        for record in records {
+            graph.add_record(record)?;
+        }
+        Ok(graph)
+    }
+
+    /// Add the graph structures in the fields of a record, see [`add_value`](Graph::add_value).
+    pub fn add_record(&mut self, record: Record) -> Result<()> {
+        for field in record.fields {
+            self.add_value(field)?;
+        }
+        Ok(())
+    }
+
+    /// Add the nodes, relationships, and paths in a value, including those nested in lists and maps. Other values are
+    /// ignored. Returns an error if a path's sequence of indices is invalid.
+    pub fn add_value(&mut self, value: Value) -> Result<()> {
+        match value {
+            Value::Node(node) => self.add_node(node),
+            Value::Relationship(rel) => self.add_relationship(rel),
+            Value::Path(path) => self.add_path(&path)?,
+            Value::List(list) => {
+                for value in list.value {
+                    self.add_value(value)?;
+                }
+            }
+            Value::Map(map) => {
+                for value in map.value.into_values() {
+                    self.add_value(value)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub fn add_node(&mut self, node: Node) {
+        let id = Id::of_node(&node);
+        match self.node_indices.get(&id) {
+            Some(&index) => self.nodes[index] = node,
+            None => {
+                self.node_indices.insert(id, self.nodes.len());
+                self.nodes.push(node);
+            }
+        }
+    }
+
+    /// Add a relationship. Its start and end nodes don't need to be in the graph.
+    pub fn add_relationship(&mut self, rel: Relationship) {
+        let id = Id::of_relationship(&rel);
+        let (start, end) = endpoints(&rel);
+        let index = match self.relationship_indices.get(&id) {
+            Some(&index) => {
+                let (old_start, old_end) = endpoints(&self.relationships[index]);
+                if (&old_start, &old_end) == (&start, &end) {
+                    self.relationships[index] = rel;
+                    return;
+                }
+                // The nodes it connects have changed, so remove it from the adjacency lists of the old ones
+                let remove = |adjacency: &mut HashMap<Id, Vec<usize>>, node| {
+                    if let Some(indices) = adjacency.get_mut(&node) {
+                        indices.retain(|&i| i != index);
+                    }
+                };
+                remove(&mut self.outgoing, old_start);
+                remove(&mut self.incoming, old_end);
+                self.relationships[index] = rel;
+                index
+            }
+            None => {
+                self.relationship_indices
+                    .insert(id, self.relationships.len());
+                self.relationships.push(rel);
+                self.relationships.len() - 1
+            }
+        };
+        self.outgoing.entry(start).or_default().push(index);
+        self.incoming.entry(end).or_default().push(index);
+    }
+
+    /// Add the nodes of a path, and its relationships bound to the nodes they connect.
+    pub fn add_path(&mut self, path: &Path) -> Result<()> {
+        for node in path.nodes() {
+            self.add_node(node.clone());
+        }
+        for segment in path.segments() {
+            let segment = segment?;
+            let (start, end, unbound_rel) = (
+                segment.start_node(),
+                segment.end_node(),
+                segment.relationship(),
+            );
+            let rel = Relationship::new(
+                unbound_rel.rel_identity(),
+                start.node_identity(),
+                end.node_identity(),
+                unbound_rel.rel_type().to_string(),
+                unbound_rel.properties().clone(),
+            );
+            let rel = match (
+                unbound_rel.element_id(),
+                start.element_id(),
+                end.element_id(),
+            ) {
+                (Some(element_id), Some(start_id), Some(end_id)) => {
+                    rel.with_element_ids(element_id, start_id, end_id)
+                }
+                _ => rel,
+            };
+            self.add_relationship(rel);
+        }
+        Ok(())
+    }
+
+    /// The distinct nodes of the graph, in the order they were first added.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// The distinct relationships of the graph, in the order they were first added.
+    pub fn relationships(&self) -> &[Relationship] {
+        &self.relationships
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn relationship_count(&self) -> usize {
+        self.relationships.len()
+    }
+
+    /// Find a node by its legacy numeric identity, for nodes without an element id.
+    pub fn node(&self, node_identity: i64) -> Option<&Node> {
+        self.find_node(&Id::Legacy(node_identity))
+    }
+
+    pub fn node_by_element_id(&self, element_id: &str) -> Option<&Node> {
+        self.find_node(&Id::Element(element_id.to_string()))
+    }
+
+    fn find_node(&self, id: &Id) -> Option<&Node> {
+        self.node_indices.get(id).map(|&index| &self.nodes[index])
+    }
+
+    /// The relationships starting at the given node.
+    pub fn outgoing(&self, node: &Node) -> impl Iterator<Item = &Relationship> {
+        self.adjacent(&self.outgoing, node)
+    }
+
+    /// The relationships ending at the given node.
+    pub fn incoming(&self, node: &Node) -> impl Iterator<Item = &Relationship> {
+        self.adjacent(&self.incoming, node)
+    }
+
+    /// The nodes at the end of the relationships starting at the given node, if they are in the graph.
+    pub fn neighbors(&self, node: &Node) -> impl Iterator<Item = &Node> {
+        self.outgoing(node)
+            .filter_map(move |rel| self.find_node(&endpoints(rel).1))
+    }
+
+    fn adjacent<'a>(
+        &'a self,
+        adjacency: &'a HashMap<Id, Vec<usize>>,
+        node: &Node,
+    ) -> impl Iterator<Item = &'a Relationship> {
+        adjacency
+            .get(&Id::of_node(node))
+            .into_iter()
+            .flatten()
+            .map(move |&index| &self.relationships[index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::value::UnboundRelationship;
+
+    use super::*;
+
+    fn get_node(identity: i64) -> Node {
+        Node::builder(identity).label("Person").build().unwrap()
+    }
+
+    fn identities<'a>(nodes: impl Iterator<Item = &'a Node>) -> Vec<i64> {
+        nodes.map(|node| node.node_identity()).collect()
+    }
+
+    #[test]
+    fn from_records() {
+        let knows = Relationship::builder(10, 1, 2, "KNOWS").build().unwrap();
+        let likes = Relationship::builder(11, 1, 3, "LIKES").build().unwrap();
+        let records = vec![
+            Record::new(vec![
+                Value::from(get_node(1)),
+                Value::from(knows.clone()),
+                Value::from(get_node(2)),
+            ]),
+            Record::new(vec![Value::from(vec![
+                Value::from(get_node(1)),
+                Value::from(likes.clone()),
+                Value::from(get_node(3)),
+            ])]),
+            Record::new(vec![Value::from(knows.clone()), Value::from(1)]),
+        ];
+        let graph = Graph::from_records(records).unwrap();
+        assert_eq!(identities(graph.nodes().iter()), vec![1, 2, 3]);
+        assert_eq!(graph.relationships(), &[knows.clone(), likes.clone()]);
+        assert_eq!(
+            graph.outgoing(&get_node(1)).collect::<Vec<_>>(),
+            vec![&knows, &likes]
+        );
+        assert_eq!(
+            graph.incoming(&get_node(2)).collect::<Vec<_>>(),
+            vec![&knows]
+        );
+        assert_eq!(graph.outgoing(&get_node(2)).count(), 0);
+        assert_eq!(identities(graph.neighbors(&get_node(1))), vec![2, 3]);
+        assert_eq!(graph.node(3), Some(&get_node(3)));
+        assert_eq!(graph.node(4), None);
+
+        // Adding a relationship again replaces it, along with the nodes it connects
+        let mut graph = graph;
+        graph.add_relationship(Relationship::builder(10, 3, 2, "KNOWS").build().unwrap());
+        assert_eq!(graph.relationship_count(), 2);
+        assert_eq!(identities(graph.neighbors(&get_node(1))), vec![3]);
+        assert_eq!(identities(graph.neighbors(&get_node(3))), vec![2]);
+    }
+
+    #[test]
+    fn element_ids() {
+        let mut graph = Graph::new();
+        let alice = Node::builder(-1).element_id("4:abc:1").build().unwrap();
+        let bob = Node::builder(-1).element_id("4:abc:2").build().unwrap();
+        graph.add_node(alice.clone());
+        graph.add_node(bob.clone());
+        graph.add_relationship(
+            Relationship::builder(-1, -1, -1, "KNOWS")
+                .element_ids("5:abc:3", "4:abc:1", "4:abc:2")
+                .build()
+                .unwrap(),
+        );
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.node_by_element_id("4:abc:2"), Some(&bob));
+        assert_eq!(graph.neighbors(&alice).collect::<Vec<_>>(), vec![&bob]);
+    }
+
+    #[test]
+    fn paths() {
+        // (1)-[10]->(2)<-[11]-(3)
+        let path = Path::builder(get_node(1))
+            .forward(
+                UnboundRelationship::builder(10, "KNOWS").build().unwrap(),
+                get_node(2),
+            )
+            .backward(
+                UnboundRelationship::builder(11, "KNOWS").build().unwrap(),
+                get_node(3),
+            )
+            .build()
+            .unwrap();
+        let mut graph = Graph::new();
+        graph.add_value(Value::from(path)).unwrap();
+        assert_eq!(graph.node_count(), 3);
+        let endpoints: Vec<(i64, i64)> = graph
+            .relationships()
+            .iter()
+            .map(|rel| (rel.start_node_identity(), rel.end_node_identity()))
+            .collect();
+        assert_eq!(endpoints, vec![(1, 2), (3, 2)]);
+        assert_eq!(identities(graph.neighbors(&get_node(3))), vec![2]);
+
+        let invalid_path = Path::new(vec![get_node(1)], vec![], vec![1, 0]);
+        assert!(graph.add_value(Value::from(invalid_path)).is_err());
+    }
+}
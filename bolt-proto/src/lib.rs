@@ -1,11 +1,24 @@
-pub use message::Message;
-pub use serialization::{Deserialize, Marker, Serialize, Signature};
-pub use value::Value;
+// Lets the derive macros for users refer to this crate by name from within it as well
+extern crate self as bolt_proto;
 
+pub use bolt_proto_derive::{FromRecord, FromValue, IntoValue};
+pub use message::{FromRecord, Message};
+pub use serialization::{
+    decode_strict, decode_with_config, encode_deterministic, encode_utc, encode_with_version,
+    Deserialize, DeserializeConfig, Marker, Serialize, Signature,
+};
+pub use value::{decode_with_extensions, Value};
+pub use version::{Version, VersionRange};
+
+pub mod compression;
+pub mod decoder;
 pub mod error;
+pub mod graph;
 pub mod message;
+pub mod packstream;
 mod serialization;
 pub mod value;
+pub mod version;
 
 #[doc(hidden)]
 #[macro_export]
@@ -27,6 +40,38 @@ macro_rules! impl_message_with_metadata {
     };
 }
 
+// Implements the legacy conversion from bytes shared behind a mutex, in terms of Deserialize
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_try_from_shared_bytes {
+    ($T:ty) => {
+        impl ::std::convert::TryFrom<::std::sync::Arc<::std::sync::Mutex<::bytes::Bytes>>> for $T {
+            type Error = $crate::error::Error;
+
+            fn try_from(
+                input_arc: ::std::sync::Arc<::std::sync::Mutex<::bytes::Bytes>>,
+            ) -> $crate::error::Result<Self> {
+                $crate::serialization::deserialize_shared(input_arc)
+            }
+        }
+    };
+}
+
+// Implements the conversion to bytes, in terms of Serialize
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_try_into_bytes {
+    ($T:ty) => {
+        impl ::std::convert::TryInto<::bytes::Bytes> for $T {
+            type Error = $crate::error::Error;
+
+            fn try_into(self) -> $crate::error::Result<::bytes::Bytes> {
+                $crate::serialization::serialize_to_bytes(&self)
+            }
+        }
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! impl_try_from_message {
@@ -37,7 +82,7 @@ macro_rules! impl_try_from_message {
             fn try_from(message: $crate::Message) -> $crate::error::Result<Self> {
                 match message {
                     $crate::Message::$V(inner) => Ok(inner),
-                    _ => Err($crate::error::ConversionError::FromMessage(message).into()),
+                    _ => Err($crate::error::ConversionError::FromMessage(Box::new(message)).into()),
                 }
             }
         }
@@ -53,7 +98,7 @@ macro_rules! impl_empty_message_tests {
             use ::std::convert::TryFrom;
             use ::std::sync::{Arc, Mutex};
 
-            use crate::serialization::*;
+            use $crate::serialization::*;
 
             use super::*;
 
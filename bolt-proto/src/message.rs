@@ -1,53 +1,187 @@
-use std::convert::{TryFrom, TryInto};
+#[cfg(any(feature = "v1", feature = "v3"))]
+use std::collections::HashMap;
+use std::collections::VecDeque;
+#[cfg(feature = "v3")]
+use std::convert::TryFrom;
+use std::convert::TryInto;
+use std::io::{self, IoSlice};
 use std::mem;
-use std::ops::DerefMut;
-use std::panic::catch_unwind;
-use std::sync::{Arc, Mutex};
+#[cfg(feature = "v3")]
+use std::time::Duration;
 
-use bytes::{BufMut, Bytes, BytesMut};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use tokio::io::BufStream;
 use tokio::prelude::*;
 
+#[cfg(feature = "v1")]
 pub use ack_failure::AckFailure;
-pub use begin::Begin;
+#[cfg(feature = "v3")]
+pub use begin::{AccessMode, Begin, BeginBuilder};
+#[cfg(feature = "v3")]
 pub use commit::Commit;
+#[cfg(feature = "v4")]
 pub use discard::Discard;
+#[cfg(feature = "v1")]
 pub use discard_all::DiscardAll;
-pub use failure::Failure;
+pub use failure::{Classification, ErrorCode, Failure, SecurityError};
+#[cfg(feature = "v3")]
 pub use goodbye::Goodbye;
-pub use hello::Hello;
+#[cfg(feature = "v3")]
+pub use hello::{Hello, HelloBuilder};
 pub use ignored::Ignored;
-pub use init::Init;
+#[cfg(feature = "v1")]
+pub use init::{Init, InitBuilder};
+#[cfg(feature = "v5")]
+pub use logon::Logon;
+#[cfg(feature = "v4")]
 pub use pull::Pull;
+#[cfg(feature = "v1")]
 pub use pull_all::PullAll;
-pub use record::Record;
+#[doc(hidden)]
+pub use record::take_column;
+pub use record::{FromRecord, Record};
 pub use reset::Reset;
+#[cfg(feature = "v3")]
 pub use rollback::Rollback;
+#[cfg(feature = "v4")]
+pub use route::{Route, RoutingTable};
+#[cfg(feature = "v1")]
 pub use run::Run;
-pub use run_with_metadata::RunWithMetadata;
+#[cfg(feature = "v3")]
+pub use run_with_metadata::{RunWithMetadata, RunWithMetadataBuilder};
 pub use success::Success;
+pub use summary::{
+    Notification, NotificationCategory, Plan, Position, QueryType, Severity, SummaryCounters,
+};
+#[cfg(feature = "v5")]
+pub use telemetry::Telemetry;
 
+use crate::compression::*;
 use crate::error::*;
+use crate::impl_try_from_shared_bytes;
+use crate::impl_try_into_bytes;
 use crate::serialization::*;
+#[cfg(any(feature = "v1", feature = "v3"))]
+use crate::Value;
+#[cfg(feature = "v4")]
+use crate::Version;
 
+#[cfg(feature = "v1")]
 pub(crate) mod ack_failure;
+#[cfg(feature = "v3")]
 pub(crate) mod begin;
+#[cfg(feature = "v3")]
 pub(crate) mod commit;
+#[cfg(feature = "v4")]
 pub(crate) mod discard;
+#[cfg(feature = "v1")]
 pub(crate) mod discard_all;
 pub(crate) mod failure;
+#[cfg(feature = "v3")]
 pub(crate) mod goodbye;
+#[cfg(feature = "v3")]
 pub(crate) mod hello;
 pub(crate) mod ignored;
+#[cfg(feature = "v1")]
 pub(crate) mod init;
+#[cfg(feature = "v5")]
+pub(crate) mod logon;
+#[cfg(feature = "v4")]
 pub(crate) mod pull;
+#[cfg(feature = "v1")]
 pub(crate) mod pull_all;
 pub(crate) mod record;
 pub(crate) mod reset;
+#[cfg(feature = "v3")]
 pub(crate) mod rollback;
+#[cfg(feature = "v4")]
+pub(crate) mod route;
+#[cfg(feature = "v1")]
 pub(crate) mod run;
+#[cfg(feature = "v3")]
 pub(crate) mod run_with_metadata;
 pub(crate) mod success;
+pub(crate) mod summary;
+#[cfg(feature = "v5")]
+pub(crate) mod telemetry;
+
+// The metadata key for transaction timeouts, which are given in milliseconds
+#[cfg(feature = "v3")]
+const TX_TIMEOUT_KEY: &str = "tx_timeout";
+
+#[cfg(feature = "v3")]
+pub(crate) fn set_tx_timeout(metadata: &mut HashMap<String, Value>, timeout: Duration) {
+    let millis = i64::try_from(timeout.as_millis()).unwrap_or(i64::MAX);
+    metadata.insert(TX_TIMEOUT_KEY.to_string(), Value::from(millis));
+}
+
+#[cfg(feature = "v3")]
+pub(crate) fn get_tx_timeout(metadata: &HashMap<String, Value>) -> Option<Duration> {
+    let millis = i64::try_from(metadata.get(TX_TIMEOUT_KEY)?.clone()).ok()?;
+    Some(Duration::from_millis(u64::try_from(millis).ok()?))
+}
+
+// The keys of an auth token, which are part of the metadata of a HELLO message
+#[cfg(feature = "v3")]
+pub(crate) const AUTH_TOKEN_KEYS: [&str; 5] =
+    ["scheme", "principal", "credentials", "realm", "parameters"];
+
+#[cfg(any(feature = "v1", feature = "v3"))]
+pub(crate) fn get_auth_scheme(auth_token: &HashMap<String, Value>) -> Option<&str> {
+    match auth_token.get("scheme")? {
+        Value::String(scheme) => scheme.as_str().ok(),
+        _ => None,
+    }
+}
+
+// The entries of an auth token that may hold secrets, which are masked when printing messages
+#[cfg(any(feature = "v1", feature = "v3"))]
+const SECRET_AUTH_TOKEN_KEYS: [&str; 2] = ["credentials", "parameters"];
+
+/// Get a copy of the given auth token with the entries that may hold secrets (`credentials` and `parameters`) masked,
+/// which is safe to log. The `Debug` output of INIT, HELLO and LOGON messages is masked the same way.
+#[cfg(any(feature = "v1", feature = "v3"))]
+pub fn redact_auth_token(auth_token: &HashMap<String, Value>) -> HashMap<String, Value> {
+    let mut auth_token = auth_token.clone();
+    for key in &SECRET_AUTH_TOKEN_KEYS {
+        if let Some(value) = auth_token.get_mut(*key) {
+            *value = Value::from("********");
+        }
+    }
+    auth_token
+}
+
+// The longest statement shown in a message summary, in characters
+#[cfg(any(feature = "v1", feature = "v3"))]
+const SUMMARY_STATEMENT_LEN: usize = 50;
+
+#[cfg(any(feature = "v1", feature = "v3"))]
+fn summarize_statement(statement: &str) -> String {
+    let mut summary: String = statement.chars().take(SUMMARY_STATEMENT_LEN).collect();
+    if summary.len() < statement.len() {
+        summary.push('…');
+    }
+    format!("{:?}", summary)
+}
+
+#[cfg(feature = "v4")]
+fn summarize_stream_request(name: &str, metadata: &HashMap<String, Value>) -> String {
+    let mut summary = name.to_string();
+    for key in &["n", "qid"] {
+        if let Some(value) = metadata.get(*key) {
+            summary.push_str(&format!(" {}={}", key, value));
+        }
+    }
+    summary
+}
+
+fn count(n: usize, noun: &str) -> String {
+    match (n, noun.strip_suffix('y')) {
+        (1, _) => format!("1 {}", noun),
+        (n, Some(stem)) => format!("{} {}ies", n, stem),
+        (n, None) => format!("{} {}s", n, noun),
+    }
+}
 
 // This is the default maximum chunk size in the official driver, minus header length
 const CHUNK_SIZE: usize = 16383 - mem::size_of::<u16>();
@@ -55,10 +189,15 @@ const CHUNK_SIZE: usize = 16383 - mem::size_of::<u16>();
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Message {
     // V1-compatible message types
+    #[cfg(feature = "v1")]
     Init(Init),
+    #[cfg(feature = "v1")]
     Run(Run),
+    #[cfg(feature = "v1")]
     DiscardAll,
+    #[cfg(feature = "v1")]
     PullAll,
+    #[cfg(feature = "v1")]
     AckFailure,
     Reset,
     Record(Record),
@@ -67,198 +206,1239 @@ pub enum Message {
     Ignored,
 
     // V3+-compatible message types
+    #[cfg(feature = "v3")]
     Hello(Hello),
+    #[cfg(feature = "v3")]
     Goodbye,
+    #[cfg(feature = "v3")]
     RunWithMetadata(RunWithMetadata),
+    #[cfg(feature = "v3")]
     Begin(Begin),
+    #[cfg(feature = "v3")]
     Commit,
+    #[cfg(feature = "v3")]
     Rollback,
 
     // V4+-compatible message types
+    #[cfg(feature = "v4")]
     Discard(Discard),
+    #[cfg(feature = "v4")]
     Pull(Pull),
+
+    // V4.3+-compatible message types
+    #[cfg(feature = "v4")]
+    Route(Route),
+
+    // V5.1+-compatible message types
+    #[cfg(feature = "v5")]
+    Logon(Logon),
+
+    // V5.4+-compatible message types
+    #[cfg(feature = "v5")]
+    Telemetry(Telemetry),
 }
 
 impl Message {
+    /// Read a message from the stream.
+    ///
+    /// This isn't cancel-safe: if the returned future is dropped partway through a message, the part that was read is
+    /// lost, and the stream is left in the middle of the message. Use
+    /// [`MessageDecoder::read_from`](crate::decoder::MessageDecoder::read_from) where reads may be cancelled.
     pub async fn from_stream<T: Unpin + AsyncRead + AsyncWrite>(
         buf_stream: &mut BufStream<T>,
+    ) -> Result<Message> {
+        Message::from_stream_with(buf_stream, &NoCompression).await
+    }
+
+    /// Read a message from the stream, decompressing its body with the given [`Decompressor`] after its chunks have
+    /// been joined together.
+    pub async fn from_stream_with<T: Unpin + AsyncRead + AsyncWrite>(
+        buf_stream: &mut BufStream<T>,
+        decompressor: &dyn Decompressor,
+    ) -> Result<Message> {
+        Message::read_from_stream(buf_stream, decompressor, None).await
+    }
+
+    /// Like [`from_stream_with`](Message::from_stream_with), but returns [`DeserializationError::LimitExceeded`] as
+    /// soon as the chunks of the message add up to more than the given number of bytes, so that a peer sending
+    /// endless chunks can't make the message grow without bound. The rest of the message is left unread.
+    pub async fn from_stream_with_max_size<T: Unpin + AsyncRead + AsyncWrite>(
+        buf_stream: &mut BufStream<T>,
+        decompressor: &dyn Decompressor,
+        max_size: usize,
+    ) -> Result<Message> {
+        Message::read_from_stream(buf_stream, decompressor, Some(max_size)).await
+    }
+
+    async fn read_from_stream<T: Unpin + AsyncRead + AsyncWrite>(
+        buf_stream: &mut BufStream<T>,
+        decompressor: &dyn Decompressor,
+        max_size: Option<usize>,
     ) -> Result<Message> {
         let mut bytes = BytesMut::new();
         let mut chunk_len = buf_stream.read_u16().await? as usize;
+        // Empty chunks before a message are NOOPs, sent by servers as keep-alives since Bolt v4.1
+        while chunk_len == 0 {
+            chunk_len = buf_stream.read_u16().await? as usize;
+        }
         // Messages end in a 0_u16
         while chunk_len > 0 {
-            let mut buf = vec![0; chunk_len];
-            buf_stream.read_exact(&mut buf).await?;
-            bytes.put_slice(&buf);
+            check_limit("message size", max_size, bytes.len() + chunk_len)?;
+            read_chunk(buf_stream, chunk_len, &mut bytes).await?;
             chunk_len = buf_stream.read_u16().await? as usize;
         }
-        Message::try_from(Arc::new(Mutex::new(bytes.freeze())))
+        let mut bytes = decompressor.decompress(bytes.freeze())?;
+        Message::deserialize(&mut bytes)
+    }
+
+    /// Write this message to the given stream as chunks, without flushing it.
+    ///
+    /// The chunk sizes and data are handed to the stream together, so streams supporting vectored writes (e.g.
+    /// a `TcpStream`) can send the whole message in as few system calls as possible.
+    pub async fn write_to<T: Unpin + AsyncWrite>(&self, stream: &mut T) -> Result<()> {
+        let chunks = self.to_chunks_in(&mut BytesMut::new(), &NoCompression)?;
+        Message::write_chunks_to(chunks, stream).await
+    }
+
+    /// Write chunks returned by [`into_chunks_with`](Message::into_chunks_with) or
+    /// [`to_chunks_in`](Message::to_chunks_in) to the given stream, without flushing it. Like
+    /// [`write_to`](Message::write_to), this uses vectored writes if the stream supports them.
+    pub async fn write_chunks_to<T: Unpin + AsyncWrite>(
+        chunks: Vec<Bytes>,
+        stream: &mut T,
+    ) -> Result<()> {
+        let mut chunks = Chunks::from(chunks);
+        while chunks.has_remaining() {
+            if stream.write_buf(&mut chunks).await? == 0 {
+                return Err(io::Error::from(io::ErrorKind::WriteZero).into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Serialize this message into chunks, compressing its body with the given [`Compressor`] before it is split up.
+    ///
+    /// Each chunk is returned as its 16-bit size followed by a slice of the serialized message, so that the message
+    /// isn't copied again while chunking. The last element is the 0-sized chunk that ends the message.
+    pub fn into_chunks_with(self, compressor: &dyn Compressor) -> Result<Vec<Bytes>> {
+        self.to_chunks_in(&mut BytesMut::new(), compressor)
+    }
+
+    /// Like [`into_chunks_with`](Message::into_chunks_with), but uses the given buffer for the serialized message and
+    /// its chunk sizes rather than allocating new ones. Once the returned chunks have been dropped, the buffer's
+    /// allocation is reclaimed by the next message serialized into it, so reusing one buffer for every message sent
+    /// on a connection avoids allocating per message.
+    ///
+    /// Anything already in the buffer is cleared first.
+    pub fn to_chunks_in(
+        &self,
+        buf: &mut BytesMut,
+        compressor: &dyn Compressor,
+    ) -> Result<Vec<Bytes>> {
+        // Converted once here, rather than in each of the serialization methods below
+        if let Some(message) = self.for_encode_version()? {
+            return message.to_chunks_in(buf, compressor);
+        }
+        // Room for the message, followed by the sizes of its chunks, so that both can share one allocation
+        let len = self.serialized_len()?;
+        buf.clear();
+        buf.reserve(len + mem::size_of::<u16>() * chunk_count(len));
+        self.serialize_into(buf)?;
+        let bytes = compressor.compress(buf.split().freeze())?;
+
+        // Sizes and slices of all the chunks, plus the message footer
+        let chunk_count = chunk_count(bytes.len());
+        let mut result: Vec<Bytes> = Vec::with_capacity(chunk_count * 2 + 1);
+        buf.reserve(mem::size_of::<u16>() * chunk_count);
+        for start in (0..bytes.len()).step_by(CHUNK_SIZE) {
+            let end = bytes.len().min(start + CHUNK_SIZE);
+            // Length of slice is at most CHUNK_SIZE, which can fit in a u16
+            buf.put_u16((end - start) as u16);
+            result.push(buf.split().freeze());
+            result.push(bytes.slice(start..end));
+        }
+        // End message
+        result.push(Bytes::from_static(&[0, 0]));
+
+        Ok(result)
+    }
+
+    /// A compact, single-line description of this message for logging, e.g. `RUN "MATCH (n) RETURN n" {2 params}` or
+    /// `RECORD [5 fields]`. Unlike the `Debug` output, it leaves out parameters, fields, and metadata, and shortens
+    /// long statements.
+    pub fn summary(&self) -> String {
+        match self {
+            #[cfg(feature = "v1")]
+            Message::Init(init) => format!("INIT {:?}", init.client_name()),
+            #[cfg(feature = "v1")]
+            Message::Run(run) => format!(
+                "RUN {} {{{}}}",
+                summarize_statement(run.statement()),
+                count(run.parameters().len(), "param")
+            ),
+            #[cfg(feature = "v1")]
+            Message::DiscardAll => "DISCARD_ALL".to_string(),
+            #[cfg(feature = "v1")]
+            Message::PullAll => "PULL_ALL".to_string(),
+            #[cfg(feature = "v1")]
+            Message::AckFailure => "ACK_FAILURE".to_string(),
+            Message::Reset => "RESET".to_string(),
+            Message::Record(record) => {
+                format!("RECORD [{}]", count(record.fields().len(), "field"))
+            }
+            Message::Success(success) => {
+                format!("SUCCESS {{{}}}", count(success.metadata().len(), "entry"))
+            }
+            Message::Failure(failure) => match failure.code() {
+                Some(code) => format!("FAILURE {}", code),
+                None => format!("FAILURE {{{}}}", count(failure.metadata().len(), "entry")),
+            },
+            Message::Ignored => "IGNORED".to_string(),
+            #[cfg(feature = "v3")]
+            Message::Hello(hello) => {
+                format!("HELLO {{{}}}", count(hello.metadata().len(), "entry"))
+            }
+            #[cfg(feature = "v3")]
+            Message::Goodbye => "GOODBYE".to_string(),
+            #[cfg(feature = "v3")]
+            Message::RunWithMetadata(run) => format!(
+                "RUN {} {{{}}} {{{}}}",
+                summarize_statement(run.statement()),
+                count(run.parameters().len(), "param"),
+                count(run.metadata().len(), "entry")
+            ),
+            #[cfg(feature = "v3")]
+            Message::Begin(begin) => {
+                format!("BEGIN {{{}}}", count(begin.metadata().len(), "entry"))
+            }
+            #[cfg(feature = "v3")]
+            Message::Commit => "COMMIT".to_string(),
+            #[cfg(feature = "v3")]
+            Message::Rollback => "ROLLBACK".to_string(),
+            #[cfg(feature = "v4")]
+            Message::Discard(discard) => summarize_stream_request("DISCARD", discard.metadata()),
+            #[cfg(feature = "v4")]
+            Message::Pull(pull) => summarize_stream_request("PULL", pull.metadata()),
+            #[cfg(feature = "v4")]
+            Message::Route(route) => match route.db() {
+                Some(db) => format!("ROUTE db={:?}", db),
+                None => "ROUTE".to_string(),
+            },
+            #[cfg(feature = "v5")]
+            Message::Logon(logon) => match logon.auth_scheme() {
+                Some(scheme) => format!("LOGON scheme={:?}", scheme),
+                None => "LOGON".to_string(),
+            },
+            #[cfg(feature = "v5")]
+            Message::Telemetry(telemetry) => format!("TELEMETRY api={}", telemetry.api()),
+        }
+    }
+
+    // The form of this message for the version in use by encode_with_version, if it has a different one. Returns an
+    // error if the version has no form for the message.
+    fn for_encode_version(&self) -> Result<Option<Message>> {
+        let version = match encode_version() {
+            Some(version) => version,
+            None => return Ok(None),
+        };
+        // Unused if none of the protocol version features are enabled
+        #[allow(unused_variables)]
+        let unsupported = |name: &'static str| -> Result<Option<Message>> {
+            Err(Error::UnsupportedByVersion { name, version })
+        };
+        match self {
+            #[cfg(all(feature = "v1", feature = "v3"))]
+            Message::Init(init) if version.major() >= 3 => {
+                let mut metadata = init.auth_token.clone();
+                metadata.insert(
+                    "user_agent".to_string(),
+                    Value::from(init.client_name.clone()),
+                );
+                Ok(Some(Message::Hello(Hello::new(metadata))))
+            }
+            #[cfg(all(feature = "v1", feature = "v3"))]
+            Message::Hello(hello) if version.major() < 3 => {
+                let mut auth_token = hello.metadata.clone();
+                let client_name = match auth_token.remove("user_agent") {
+                    Some(user_agent) => String::try_from(user_agent)?,
+                    None => {
+                        return Err(
+                            ConversionError::MissingProperty("user_agent".to_string()).into()
+                        )
+                    }
+                };
+                Ok(Some(Message::Init(Init::new(client_name, auth_token))))
+            }
+            #[cfg(all(feature = "v1", feature = "v3"))]
+            Message::Run(run) if version.major() >= 3 => {
+                Ok(Some(Message::RunWithMetadata(RunWithMetadata::new(
+                    run.statement.clone(),
+                    run.parameters.clone(),
+                    HashMap::new(),
+                ))))
+            }
+            #[cfg(all(feature = "v1", feature = "v3"))]
+            Message::RunWithMetadata(run) if version.major() < 3 => {
+                if run.metadata.is_empty() {
+                    Ok(Some(Message::Run(Run::new(
+                        run.statement.clone(),
+                        run.parameters.clone(),
+                    ))))
+                } else {
+                    unsupported("RUN metadata")
+                }
+            }
+            #[cfg(all(feature = "v1", feature = "v4"))]
+            Message::DiscardAll if version.major() >= 4 => {
+                Ok(Some(Message::Discard(Discard::all())))
+            }
+            #[cfg(all(feature = "v1", feature = "v4"))]
+            Message::PullAll if version.major() >= 4 => Ok(Some(Message::Pull(Pull::all()))),
+            // Only discarding or pulling all records can be expressed before Bolt v4
+            #[cfg(all(feature = "v1", feature = "v4"))]
+            Message::Discard(discard) if version.major() < 4 => {
+                if discard == &Discard::all() {
+                    Ok(Some(Message::DiscardAll))
+                } else {
+                    unsupported("DISCARD")
+                }
+            }
+            #[cfg(all(feature = "v1", feature = "v4"))]
+            Message::Pull(pull) if version.major() < 4 => {
+                if pull == &Pull::all() {
+                    Ok(Some(Message::PullAll))
+                } else {
+                    unsupported("PULL")
+                }
+            }
+            #[cfg(feature = "v1")]
+            Message::AckFailure if version.major() >= 3 => unsupported("ACK_FAILURE"),
+            #[cfg(feature = "v3")]
+            Message::Goodbye if version.major() < 3 => unsupported("GOODBYE"),
+            #[cfg(feature = "v3")]
+            Message::Begin(_) if version.major() < 3 => unsupported("BEGIN"),
+            #[cfg(feature = "v3")]
+            Message::Commit if version.major() < 3 => unsupported("COMMIT"),
+            #[cfg(feature = "v3")]
+            Message::Rollback if version.major() < 3 => unsupported("ROLLBACK"),
+            #[cfg(feature = "v4")]
+            Message::Route(_) if version < Version::new(4, 3) => unsupported("ROUTE"),
+            // The database is sent on its own in Bolt v4.3, and in a map along with the impersonated user since v4.4
+            #[cfg(feature = "v4")]
+            Message::Route(route) => match (&route.extra, version >= Version::new(4, 4)) {
+                (Value::Map(_), false) if route.imp_user().is_some() => {
+                    unsupported("ROUTE impersonation")
+                }
+                (Value::Map(_), false) => Ok(Some(Message::Route(Route::new(
+                    route.context.clone(),
+                    route.bookmarks.clone(),
+                    route.db().map(str::to_string),
+                )))),
+                (Value::Map(_), true) | (_, false) => Ok(None),
+                (_, true) => Ok(Some(Message::Route(Route::with_extra(
+                    route.context.clone(),
+                    route.bookmarks.clone(),
+                    route.db().map(str::to_string),
+                    None,
+                )))),
+            },
+            #[cfg(feature = "v5")]
+            Message::Logon(_) if version < Version::new(5, 1) => unsupported("LOGON"),
+            #[cfg(feature = "v5")]
+            Message::Telemetry(_) if version < Version::new(5, 4) => unsupported("TELEMETRY"),
+            // Without the features for both forms of a message, it can only be sent in the form that is available
+            #[cfg(all(feature = "v1", not(feature = "v3")))]
+            Message::Init(_) | Message::Run(_) if version.major() >= 3 => unsupported("INIT/RUN"),
+            #[cfg(all(feature = "v3", not(feature = "v1")))]
+            Message::Hello(_) | Message::RunWithMetadata(_) if version.major() < 3 => {
+                unsupported("HELLO/RUN_WITH_METADATA")
+            }
+            #[cfg(all(feature = "v1", not(feature = "v4")))]
+            Message::DiscardAll | Message::PullAll if version.major() >= 4 => {
+                unsupported("DISCARD_ALL/PULL_ALL")
+            }
+            #[cfg(all(feature = "v4", not(feature = "v1")))]
+            Message::Discard(_) | Message::Pull(_) if version.major() < 4 => {
+                unsupported("DISCARD/PULL")
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+// The number of chunks a message body of the given length is split into, excluding the footer
+fn chunk_count(len: usize) -> usize {
+    len.div_ceil(CHUNK_SIZE)
+}
+
+// Reads a chunk of the given length straight into the end of the buffer, without an intermediate buffer per chunk
+async fn read_chunk<T: Unpin + AsyncRead>(
+    stream: &mut T,
+    len: usize,
+    bytes: &mut BytesMut,
+) -> Result<()> {
+    bytes.reserve(len);
+    let mut chunk = (&mut *stream).take(len as u64);
+    while chunk.limit() > 0 {
+        if chunk.read_buf(bytes).await? == 0 {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+    }
+    Ok(())
+}
+
+// The chunks of a message as a single buffer, which exposes each of them as a separate slice for vectored writes
+struct Chunks {
+    chunks: VecDeque<Bytes>,
+    remaining: usize,
+}
+
+impl From<Vec<Bytes>> for Chunks {
+    fn from(chunks: Vec<Bytes>) -> Self {
+        Self {
+            remaining: chunks.iter().map(Bytes::len).sum(),
+            chunks: VecDeque::from(chunks),
+        }
+    }
+}
+
+impl Buf for Chunks {
+    fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    fn bytes(&self) -> &[u8] {
+        self.chunks.front().map_or(&[], |chunk| &chunk[..])
+    }
+
+    fn bytes_vectored<'a>(&'a self, dst: &mut [IoSlice<'a>]) -> usize {
+        let mut count = 0;
+        for (slice, chunk) in dst.iter_mut().zip(&self.chunks) {
+            *slice = IoSlice::new(chunk);
+            count += 1;
+        }
+        count
+    }
+
+    fn advance(&mut self, mut cnt: usize) {
+        self.remaining -= cnt;
+        while let Some(chunk) = self.chunks.front_mut() {
+            if cnt < chunk.len() {
+                chunk.advance(cnt);
+                return;
+            }
+            cnt -= chunk.len();
+            self.chunks.pop_front();
+        }
     }
 }
 
 impl Marker for Message {
     fn get_marker(&self) -> Result<u8> {
+        if let Some(message) = self.for_encode_version()? {
+            return message.get_marker();
+        }
         match self {
+            #[cfg(feature = "v1")]
             Message::Init(init) => init.get_marker(),
+            #[cfg(feature = "v1")]
             Message::Run(run) => run.get_marker(),
+            #[cfg(feature = "v1")]
             Message::DiscardAll => DiscardAll.get_marker(),
+            #[cfg(feature = "v1")]
             Message::PullAll => PullAll.get_marker(),
+            #[cfg(feature = "v1")]
             Message::AckFailure => AckFailure.get_marker(),
             Message::Reset => Reset.get_marker(),
             Message::Record(record) => record.get_marker(),
             Message::Success(success) => success.get_marker(),
             Message::Failure(failure) => failure.get_marker(),
             Message::Ignored => Ignored.get_marker(),
+            #[cfg(feature = "v3")]
             Message::Hello(hello) => hello.get_marker(),
+            #[cfg(feature = "v3")]
             Message::Goodbye => Goodbye.get_marker(),
+            #[cfg(feature = "v3")]
             Message::RunWithMetadata(run_with_metadata) => run_with_metadata.get_marker(),
+            #[cfg(feature = "v3")]
             Message::Begin(begin) => begin.get_marker(),
+            #[cfg(feature = "v3")]
             Message::Commit => Commit.get_marker(),
+            #[cfg(feature = "v3")]
             Message::Rollback => Rollback.get_marker(),
+            #[cfg(feature = "v4")]
             Message::Discard(discard) => discard.get_marker(),
+            #[cfg(feature = "v4")]
             Message::Pull(pull) => pull.get_marker(),
+            #[cfg(feature = "v4")]
+            Message::Route(route) => route.get_marker(),
+            #[cfg(feature = "v5")]
+            Message::Logon(logon) => logon.get_marker(),
+            #[cfg(feature = "v5")]
+            Message::Telemetry(telemetry) => telemetry.get_marker(),
+        }
+    }
+
+    fn serialized_len(&self) -> Result<usize> {
+        if let Some(message) = self.for_encode_version()? {
+            return message.serialized_len();
+        }
+        match self {
+            #[cfg(feature = "v1")]
+            Message::Init(init) => init.serialized_len(),
+            #[cfg(feature = "v1")]
+            Message::Run(run) => run.serialized_len(),
+            #[cfg(feature = "v1")]
+            Message::DiscardAll => DiscardAll.serialized_len(),
+            #[cfg(feature = "v1")]
+            Message::PullAll => PullAll.serialized_len(),
+            #[cfg(feature = "v1")]
+            Message::AckFailure => AckFailure.serialized_len(),
+            Message::Reset => Reset.serialized_len(),
+            Message::Record(record) => record.serialized_len(),
+            Message::Success(success) => success.serialized_len(),
+            Message::Failure(failure) => failure.serialized_len(),
+            Message::Ignored => Ignored.serialized_len(),
+            #[cfg(feature = "v3")]
+            Message::Hello(hello) => hello.serialized_len(),
+            #[cfg(feature = "v3")]
+            Message::Goodbye => Goodbye.serialized_len(),
+            #[cfg(feature = "v3")]
+            Message::RunWithMetadata(run_with_metadata) => run_with_metadata.serialized_len(),
+            #[cfg(feature = "v3")]
+            Message::Begin(begin) => begin.serialized_len(),
+            #[cfg(feature = "v3")]
+            Message::Commit => Commit.serialized_len(),
+            #[cfg(feature = "v3")]
+            Message::Rollback => Rollback.serialized_len(),
+            #[cfg(feature = "v4")]
+            Message::Discard(discard) => discard.serialized_len(),
+            #[cfg(feature = "v4")]
+            Message::Pull(pull) => pull.serialized_len(),
+            #[cfg(feature = "v4")]
+            Message::Route(route) => route.serialized_len(),
+            #[cfg(feature = "v5")]
+            Message::Logon(logon) => logon.serialized_len(),
+            #[cfg(feature = "v5")]
+            Message::Telemetry(telemetry) => telemetry.serialized_len(),
         }
     }
 }
 
 impl Signature for Message {
     fn get_signature(&self) -> u8 {
+        // If the message is unsupported, serializing it fails anyway
+        if let Ok(Some(message)) = self.for_encode_version() {
+            return message.get_signature();
+        }
         match self {
+            #[cfg(feature = "v1")]
             Message::Init(init) => init.get_signature(),
+            #[cfg(feature = "v1")]
             Message::Run(run) => run.get_signature(),
+            #[cfg(feature = "v1")]
             Message::DiscardAll => DiscardAll.get_signature(),
+            #[cfg(feature = "v1")]
             Message::PullAll => PullAll.get_signature(),
+            #[cfg(feature = "v1")]
             Message::AckFailure => AckFailure.get_signature(),
             Message::Reset => Reset.get_signature(),
             Message::Record(record) => record.get_signature(),
             Message::Success(success) => success.get_signature(),
             Message::Failure(failure) => failure.get_signature(),
             Message::Ignored => Ignored.get_signature(),
+            #[cfg(feature = "v3")]
             Message::Hello(hello) => hello.get_signature(),
+            #[cfg(feature = "v3")]
             Message::Goodbye => Goodbye.get_signature(),
+            #[cfg(feature = "v3")]
             Message::RunWithMetadata(run_with_metadata) => run_with_metadata.get_signature(),
+            #[cfg(feature = "v3")]
             Message::Begin(begin) => begin.get_signature(),
+            #[cfg(feature = "v3")]
             Message::Commit => Commit.get_signature(),
+            #[cfg(feature = "v3")]
             Message::Rollback => Rollback.get_signature(),
+            #[cfg(feature = "v4")]
             Message::Discard(discard) => discard.get_signature(),
+            #[cfg(feature = "v4")]
             Message::Pull(pull) => pull.get_signature(),
+            #[cfg(feature = "v4")]
+            Message::Route(route) => route.get_signature(),
+            #[cfg(feature = "v5")]
+            Message::Logon(logon) => logon.get_signature(),
+            #[cfg(feature = "v5")]
+            Message::Telemetry(telemetry) => telemetry.get_signature(),
         }
     }
 }
 
-impl Serialize for Message {}
-
-impl TryInto<Bytes> for Message {
-    type Error = Error;
-
-    fn try_into(self) -> Result<Bytes> {
+impl Serialize for Message {
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()> {
+        if let Some(message) = self.for_encode_version()? {
+            return message.serialize_into(buf);
+        }
         match self {
-            Message::Init(init) => init.try_into(),
-            Message::Run(run) => run.try_into(),
-            Message::DiscardAll => DiscardAll.try_into(),
-            Message::PullAll => PullAll.try_into(),
-            Message::AckFailure => AckFailure.try_into(),
-            Message::Reset => Reset.try_into(),
-            Message::Record(record) => record.try_into(),
-            Message::Success(success) => success.try_into(),
-            Message::Failure(failure) => failure.try_into(),
-            Message::Ignored => Ignored.try_into(),
-            Message::Hello(hello) => hello.try_into(),
-            Message::Goodbye => Goodbye.try_into(),
-            Message::RunWithMetadata(run_with_metadata) => run_with_metadata.try_into(),
-            Message::Begin(begin) => begin.try_into(),
-            Message::Commit => Commit.try_into(),
-            Message::Rollback => Rollback.try_into(),
-            Message::Discard(discard) => discard.try_into(),
-            Message::Pull(pull) => pull.try_into(),
+            #[cfg(feature = "v1")]
+            Message::Init(init) => init.serialize_into(buf),
+            #[cfg(feature = "v1")]
+            Message::Run(run) => run.serialize_into(buf),
+            #[cfg(feature = "v1")]
+            Message::DiscardAll => DiscardAll.serialize_into(buf),
+            #[cfg(feature = "v1")]
+            Message::PullAll => PullAll.serialize_into(buf),
+            #[cfg(feature = "v1")]
+            Message::AckFailure => AckFailure.serialize_into(buf),
+            Message::Reset => Reset.serialize_into(buf),
+            Message::Record(record) => record.serialize_into(buf),
+            Message::Success(success) => success.serialize_into(buf),
+            Message::Failure(failure) => failure.serialize_into(buf),
+            Message::Ignored => Ignored.serialize_into(buf),
+            #[cfg(feature = "v3")]
+            Message::Hello(hello) => hello.serialize_into(buf),
+            #[cfg(feature = "v3")]
+            Message::Goodbye => Goodbye.serialize_into(buf),
+            #[cfg(feature = "v3")]
+            Message::RunWithMetadata(run_with_metadata) => run_with_metadata.serialize_into(buf),
+            #[cfg(feature = "v3")]
+            Message::Begin(begin) => begin.serialize_into(buf),
+            #[cfg(feature = "v3")]
+            Message::Commit => Commit.serialize_into(buf),
+            #[cfg(feature = "v3")]
+            Message::Rollback => Rollback.serialize_into(buf),
+            #[cfg(feature = "v4")]
+            Message::Discard(discard) => discard.serialize_into(buf),
+            #[cfg(feature = "v4")]
+            Message::Pull(pull) => pull.serialize_into(buf),
+            #[cfg(feature = "v4")]
+            Message::Route(route) => route.serialize_into(buf),
+            #[cfg(feature = "v5")]
+            Message::Logon(logon) => logon.serialize_into(buf),
+            #[cfg(feature = "v5")]
+            Message::Telemetry(telemetry) => telemetry.serialize_into(buf),
         }
     }
 }
 
-impl Deserialize for Message {}
+impl_try_into_bytes!(Message);
 
-impl TryFrom<Arc<Mutex<Bytes>>> for Message {
-    type Error = Error;
+impl Deserialize for Message {
+    fn deserialize(bytes: &mut Bytes) -> Result<Self> {
+        let (_marker, field_count, signature) = get_info_from_bytes(bytes)?;
 
-    fn try_from(input_arc: Arc<Mutex<Bytes>>) -> Result<Self> {
-        catch_unwind(move || {
-            let (marker, signature) = get_info_from_bytes(input_arc.lock().unwrap().deref_mut())?;
-
-            match signature {
-                init::SIGNATURE => {
-                    // Equal to hello::SIGNATURE, so we have to check for metadata.
-                    // INIT has 2 fields, while HELLO has 1.
-                    if marker == init::MARKER {
-                        Ok(Message::Init(Init::try_from(input_arc)?))
-                    } else {
-                        Ok(Message::Hello(Hello::try_from(input_arc)?))
-                    }
-                }
-                run::SIGNATURE => {
-                    // Equal to run_with_metadata::SIGNATURE, so we have to check for metadata.
-                    // RUN has 2 fields, while RUN_WITH_METADATA has 3.
-                    if marker == run::MARKER {
-                        Ok(Message::Run(Run::try_from(input_arc)?))
-                    } else {
-                        Ok(Message::RunWithMetadata(RunWithMetadata::try_from(
-                            input_arc,
-                        )?))
-                    }
-                }
-                discard_all::SIGNATURE => {
-                    // Equal to discard::SIGNATURE, so we have to check for metadata.
-                    // DISCARD_ALL has 0 fields, while DISCARD has 1.
-                    if marker == discard_all::MARKER {
-                        Ok(Message::DiscardAll)
-                    } else {
-                        Ok(Message::Discard(Discard::try_from(input_arc)?))
-                    }
-                }
-                pull_all::SIGNATURE => {
-                    // Equal to pull::SIGNATURE, so we have to check for metadata.
-                    // PULL_ALL has 0 fields, while PULL has 1.
-                    if marker == pull_all::MARKER {
-                        Ok(Message::PullAll)
-                    } else {
-                        Ok(Message::Pull(Pull::try_from(input_arc)?))
-                    }
-                }
-                ack_failure::SIGNATURE => Ok(Message::AckFailure),
-                reset::SIGNATURE => Ok(Message::Reset),
-                record::SIGNATURE => Ok(Message::Record(Record::try_from(input_arc)?)),
-                success::SIGNATURE => Ok(Message::Success(Success::try_from(input_arc)?)),
-                failure::SIGNATURE => Ok(Message::Failure(Failure::try_from(input_arc)?)),
-                ignored::SIGNATURE => Ok(Message::Ignored),
-                goodbye::SIGNATURE => Ok(Message::Goodbye),
-                begin::SIGNATURE => Ok(Message::Begin(Begin::try_from(input_arc)?)),
-                commit::SIGNATURE => Ok(Message::Commit),
-                rollback::SIGNATURE => Ok(Message::Rollback),
-                _ => Err(DeserializationError::InvalidSignatureByte(signature).into()),
+        // Some messages share a signature with another message that has a different number of fields, so the field
+        // count decides between them
+        match signature {
+            #[cfg(feature = "v1")]
+            init::SIGNATURE if field_count == 2 => Ok(Message::Init(Init::deserialize(bytes)?)),
+            #[cfg(feature = "v3")]
+            hello::SIGNATURE => {
+                check_field_count(hello::MARKER, field_count)?;
+                Ok(Message::Hello(Hello::deserialize(bytes)?))
             }
-        })
-        .map_err(|_| DeserializationError::Panicked)?
+            #[cfg(feature = "v1")]
+            run::SIGNATURE if field_count == 2 => Ok(Message::Run(Run::deserialize(bytes)?)),
+            #[cfg(feature = "v3")]
+            run_with_metadata::SIGNATURE => {
+                check_field_count(run_with_metadata::MARKER, field_count)?;
+                Ok(Message::RunWithMetadata(RunWithMetadata::deserialize(
+                    bytes,
+                )?))
+            }
+            #[cfg(feature = "v1")]
+            discard_all::SIGNATURE if field_count == 0 => Ok(Message::DiscardAll),
+            #[cfg(feature = "v4")]
+            discard::SIGNATURE => {
+                check_field_count(discard::MARKER, field_count)?;
+                Ok(Message::Discard(Discard::deserialize(bytes)?))
+            }
+            #[cfg(feature = "v1")]
+            pull_all::SIGNATURE if field_count == 0 => Ok(Message::PullAll),
+            #[cfg(feature = "v4")]
+            pull::SIGNATURE => {
+                check_field_count(pull::MARKER, field_count)?;
+                Ok(Message::Pull(Pull::deserialize(bytes)?))
+            }
+            #[cfg(feature = "v1")]
+            ack_failure::SIGNATURE => {
+                check_field_count(ack_failure::MARKER, field_count)?;
+                Ok(Message::AckFailure)
+            }
+            reset::SIGNATURE => {
+                check_field_count(reset::MARKER, field_count)?;
+                Ok(Message::Reset)
+            }
+            record::SIGNATURE => {
+                check_field_count(record::MARKER, field_count)?;
+                Ok(Message::Record(Record::deserialize(bytes)?))
+            }
+            success::SIGNATURE => {
+                check_field_count(success::MARKER, field_count)?;
+                Ok(Message::Success(Success::deserialize(bytes)?))
+            }
+            failure::SIGNATURE => {
+                check_field_count(failure::MARKER, field_count)?;
+                Ok(Message::Failure(Failure::deserialize(bytes)?))
+            }
+            ignored::SIGNATURE => {
+                check_field_count(ignored::MARKER, field_count)?;
+                Ok(Message::Ignored)
+            }
+            #[cfg(feature = "v3")]
+            goodbye::SIGNATURE => {
+                check_field_count(goodbye::MARKER, field_count)?;
+                Ok(Message::Goodbye)
+            }
+            #[cfg(feature = "v3")]
+            begin::SIGNATURE => {
+                check_field_count(begin::MARKER, field_count)?;
+                Ok(Message::Begin(Begin::deserialize(bytes)?))
+            }
+            #[cfg(feature = "v3")]
+            commit::SIGNATURE => {
+                check_field_count(commit::MARKER, field_count)?;
+                Ok(Message::Commit)
+            }
+            #[cfg(feature = "v3")]
+            rollback::SIGNATURE => {
+                check_field_count(rollback::MARKER, field_count)?;
+                Ok(Message::Rollback)
+            }
+            #[cfg(feature = "v4")]
+            route::SIGNATURE => {
+                check_field_count(route::MARKER, field_count)?;
+                Ok(Message::Route(Route::deserialize(bytes)?))
+            }
+            #[cfg(feature = "v5")]
+            logon::SIGNATURE => {
+                check_field_count(logon::MARKER, field_count)?;
+                Ok(Message::Logon(Logon::deserialize(bytes)?))
+            }
+            #[cfg(feature = "v5")]
+            telemetry::SIGNATURE => {
+                check_field_count(telemetry::MARKER, field_count)?;
+                Ok(Message::Telemetry(Telemetry::deserialize(bytes)?))
+            }
+            _ => Err(DeserializationError::InvalidSignatureByte(signature).into()),
+        }
     }
 }
 
+impl_try_from_shared_bytes!(Message);
+
 impl TryInto<Vec<Bytes>> for Message {
     type Error = Error;
 
     fn try_into(self) -> Result<Vec<Bytes>> {
-        let bytes: Bytes = self.try_into_bytes()?;
+        self.into_chunks_with(&NoCompression)
+    }
+}
 
-        // Big enough to hold all the chunks, plus a partial chunk, plus the message footer
-        let mut result: Vec<Bytes> = Vec::with_capacity(bytes.len() / CHUNK_SIZE + 2);
-        for slice in bytes.chunks(CHUNK_SIZE) {
-            // 16-bit size, then the chunk data
-            let mut chunk = BytesMut::with_capacity(mem::size_of::<u16>() + slice.len());
-            // Length of slice is at most CHUNK_SIZE, which can fit in a u16
-            chunk.put_u16(slice.len() as u16);
-            chunk.put(slice);
-            result.push(chunk.freeze());
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::iter::FromIterator;
+
+    use super::*;
+    use crate::Value;
+
+    #[cfg(feature = "v3")]
+    #[test]
+    fn invalid_field_count() {
+        // A RESET with one field, followed by a GOODBYE
+        let mut bytes =
+            Bytes::from_static(&[0xB1, reset::SIGNATURE, 0x01, 0xB0, goodbye::SIGNATURE]);
+        assert!(matches!(
+            Message::deserialize(&mut bytes),
+            Err(Error::InvalidFieldCount {
+                expected: 0,
+                actual: 1
+            })
+        ));
+        // A HELLO with three fields
+        let mut bytes = Bytes::from_static(&[0xB3, hello::SIGNATURE, 0xA0, 0xA0, 0xA0]);
+        assert!(matches!(
+            Message::deserialize(&mut bytes),
+            Err(Error::InvalidFieldCount {
+                expected: 1,
+                actual: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn summary() {
+        assert_eq!(
+            Message::Record(Record::new(vec![Value::from(1); 5])).summary(),
+            "RECORD [5 fields]"
+        );
+        assert_eq!(
+            Message::Record(Record::new(vec![Value::from(1)])).summary(),
+            "RECORD [1 field]"
+        );
+        assert_eq!(
+            Message::Success(Success::new(HashMap::from_iter(vec![(
+                "fields".to_string(),
+                Value::from(vec!["n"]),
+            )])))
+            .summary(),
+            "SUCCESS {1 entry}"
+        );
+        assert_eq!(
+            Message::Failure(Failure::new(HashMap::from_iter(vec![(
+                "code".to_string(),
+                Value::from("Neo.ClientError.Statement.SyntaxError"),
+            )])))
+            .summary(),
+            "FAILURE Neo.ClientError.Statement.SyntaxError"
+        );
+    }
+
+    #[cfg(feature = "v1")]
+    #[test]
+    fn summary_v1() {
+        assert_eq!(
+            Message::Run(Run::new("RETURN 1;".to_string(), HashMap::new())).summary(),
+            "RUN \"RETURN 1;\" {0 params}"
+        );
+        assert_eq!(
+            Message::Init(Init::builder("MyClient/1.0").credentials("secret").build()).summary(),
+            "INIT \"MyClient/1.0\""
+        );
+        assert_eq!(Message::PullAll.summary(), "PULL_ALL");
+    }
+
+    #[cfg(feature = "v3")]
+    #[test]
+    fn summary_v3() {
+        let statement = "MATCH (n:Person) WHERE n.name = $name AND n.age > $age RETURN n;";
+        let run = Message::RunWithMetadata(RunWithMetadata::new(
+            statement.to_string(),
+            HashMap::from_iter(vec![
+                ("name".to_string(), Value::from("a".repeat(1_000_000))),
+                ("age".to_string(), Value::from(30)),
+            ]),
+            HashMap::new(),
+        ));
+        assert_eq!(
+            run.summary(),
+            "RUN \"MATCH (n:Person) WHERE n.name = $name AND n.age > …\" {2 params} {0 entries}"
+        );
+    }
+
+    #[cfg(feature = "v4")]
+    #[test]
+    fn summary_v4() {
+        assert_eq!(
+            Message::Pull(Pull::n_for(10, 2)).summary(),
+            "PULL n=10 qid=2"
+        );
+        assert_eq!(Message::Discard(Discard::all()).summary(), "DISCARD n=-1");
+        assert_eq!(
+            Message::Route(Route::new(
+                HashMap::new(),
+                vec![],
+                Some("neo4j".to_string())
+            ))
+            .summary(),
+            "ROUTE db=\"neo4j\""
+        );
+    }
+
+    #[cfg(feature = "v5")]
+    #[test]
+    fn summary_v5() {
+        assert_eq!(
+            Message::Telemetry(Telemetry::new(2)).summary(),
+            "TELEMETRY api=2"
+        );
+        let logon = Logon::new(HashMap::from_iter(vec![
+            ("scheme".to_string(), Value::from("basic")),
+            ("credentials".to_string(), Value::from("hunter2")),
+        ]));
+        assert_eq!(Message::Logon(logon).summary(), "LOGON scheme=\"basic\"");
+        assert_eq!(
+            Message::Logon(Logon::new(HashMap::new())).summary(),
+            "LOGON"
+        );
+    }
+
+    #[test]
+    fn to_chunks_in() {
+        let record = Message::Record(Record::new(vec![Value::from("a".repeat(20_000))]));
+        let mut buf = BytesMut::from(&b"stale"[..]);
+        let chunks = record.to_chunks_in(&mut buf, &NoCompression).unwrap();
+        assert_eq!(
+            chunks,
+            record.clone().into_chunks_with(&NoCompression).unwrap()
+        );
+        assert!(buf.is_empty());
+
+        // Once the chunks are dropped, their allocation is reused
+        let ptr = chunks[1].as_ptr();
+        drop(chunks);
+        let chunks = record.to_chunks_in(&mut buf, &NoCompression).unwrap();
+        assert_eq!(chunks[1].as_ptr(), ptr);
+    }
+
+    #[test]
+    fn into_chunks_without_copying() {
+        let record = Message::Record(Record::new(vec![Value::from("a".repeat(20_000))]));
+        let len = record.serialized_len().unwrap();
+        let chunks: Vec<Bytes> = record.try_into().unwrap();
+        let sizes: Vec<&[u8]> = chunks.iter().step_by(2).map(|size| &size[..]).collect();
+        assert_eq!(
+            sizes,
+            vec![
+                &(CHUNK_SIZE as u16).to_be_bytes()[..],
+                &((len - CHUNK_SIZE) as u16).to_be_bytes()[..],
+                &[0, 0][..]
+            ]
+        );
+        // The chunk data are consecutive slices of the serialized message
+        assert_eq!(
+            chunks[3].as_ptr(),
+            chunks[1].as_ptr().wrapping_add(CHUNK_SIZE)
+        );
+    }
+
+    // Records the slices of each write, accepting at most the given number of bytes at a time
+    struct VectoredWriter {
+        writes: Vec<Vec<Vec<u8>>>,
+        max_write: usize,
+    }
+
+    impl AsyncWrite for VectoredWriter {
+        fn poll_write(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<io::Result<usize>> {
+            let len = buf.len().min(self.max_write);
+            self.get_mut().writes.push(vec![buf[..len].to_vec()]);
+            std::task::Poll::Ready(Ok(len))
         }
-        // End message
-        result.push(Bytes::from_static(&[0, 0]));
 
-        Ok(result)
+        fn poll_write_buf<B: Buf>(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &mut B,
+        ) -> std::task::Poll<io::Result<usize>> {
+            let this = self.get_mut();
+            let mut slices = [IoSlice::new(&[]); 64];
+            let count = buf.bytes_vectored(&mut slices);
+            let mut write = Vec::new();
+            let mut len = 0;
+            for slice in &slices[..count] {
+                let slice = &slice[..slice.len().min(this.max_write - len)];
+                len += slice.len();
+                write.push(slice.to_vec());
+            }
+            this.writes.push(write);
+            buf.advance(len);
+            std::task::Poll::Ready(Ok(len))
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn from_stream() {
+        let record = Message::Record(Record::new(vec![Value::from("a".repeat(40_000))]));
+        let bytes: Vec<u8> = record
+            .clone()
+            .into_chunks_with(&NoCompression)
+            .unwrap()
+            .concat();
+
+        let mut stream = BufStream::new(std::io::Cursor::new(bytes.clone()));
+        assert_eq!(Message::from_stream(&mut stream).await.unwrap(), record);
+
+        let mut truncated = BufStream::new(std::io::Cursor::new(bytes[..20_000].to_vec()));
+        assert!(matches!(
+            Message::from_stream(&mut truncated).await,
+            Err(Error::IOError(error)) if error.kind() == io::ErrorKind::UnexpectedEof
+        ));
+    }
+
+    #[cfg(feature = "v3")]
+    #[tokio::test]
+    async fn from_stream_noop_chunks() {
+        let mut bytes = vec![0, 0, 0, 0];
+        bytes.extend(
+            Message::Reset
+                .into_chunks_with(&NoCompression)
+                .unwrap()
+                .concat(),
+        );
+        bytes.extend_from_slice(&[0, 0]);
+        bytes.extend(
+            Message::Goodbye
+                .into_chunks_with(&NoCompression)
+                .unwrap()
+                .concat(),
+        );
+
+        let mut stream = BufStream::new(std::io::Cursor::new(bytes));
+        assert_eq!(
+            Message::from_stream(&mut stream).await.unwrap(),
+            Message::Reset
+        );
+        assert_eq!(
+            Message::from_stream(&mut stream).await.unwrap(),
+            Message::Goodbye
+        );
+        assert!(Message::from_stream(&mut stream).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn from_stream_with_max_size() {
+        let record = Message::Record(Record::new(vec![Value::from("a".repeat(20_000))]));
+        let len = record.serialized_len().unwrap();
+        let bytes: Vec<u8> = record
+            .clone()
+            .into_chunks_with(&NoCompression)
+            .unwrap()
+            .concat();
+
+        let mut stream = BufStream::new(std::io::Cursor::new(bytes.clone()));
+        assert_eq!(
+            Message::from_stream_with_max_size(&mut stream, &NoCompression, len)
+                .await
+                .unwrap(),
+            record
+        );
+        let mut stream = BufStream::new(std::io::Cursor::new(bytes));
+        assert!(matches!(
+            Message::from_stream_with_max_size(&mut stream, &NoCompression, len - 1).await,
+            Err(Error::DeserializationError(DeserializationError::LimitExceeded {
+                limit: "message size",
+                max,
+                actual
+            })) if max == len - 1 && actual == len
+        ));
+    }
+
+    #[tokio::test]
+    async fn write_to() {
+        let record = Message::Record(Record::new(vec![Value::from("a".repeat(20_000))]));
+        let expected: Vec<u8> = record
+            .clone()
+            .into_chunks_with(&NoCompression)
+            .unwrap()
+            .concat();
+
+        let mut writer = VectoredWriter {
+            writes: Vec::new(),
+            max_write: usize::MAX,
+        };
+        record.write_to(&mut writer).await.unwrap();
+        // Two chunks and the footer, in one write
+        assert_eq!(writer.writes.len(), 1);
+        assert_eq!(writer.writes[0].len(), 5);
+        assert_eq!(writer.writes[0].concat(), expected);
+
+        // Partial writes resume where they left off
+        let mut writer = VectoredWriter {
+            writes: Vec::new(),
+            max_write: 1000,
+        };
+        record.write_to(&mut writer).await.unwrap();
+        assert_eq!(writer.writes.concat().concat(), expected);
+    }
+
+    #[cfg(feature = "v4")]
+    fn encode_for(version: Version, message: &Message) -> Result<Message> {
+        let mut bytes = encode_with_version(version, || -> Result<Bytes> {
+            let len = message.serialized_len()?;
+            let bytes = message.clone().try_into_bytes()?;
+            assert_eq!(len, bytes.len());
+            Ok(bytes)
+        })?;
+        Message::deserialize(&mut bytes)
+    }
+
+    #[cfg(all(feature = "v1", feature = "v4"))]
+    #[test]
+    fn version_conversions() {
+        let v1 = Version::new(1, 0);
+        let v3 = Version::new(3, 0);
+        let v4 = Version::new(4, 0);
+
+        let auth_token = HashMap::from_iter(vec![("scheme".to_string(), Value::from("none"))]);
+        let init = Message::Init(Init::new("bolt-rs".to_string(), auth_token.clone()));
+        let mut metadata = auth_token;
+        metadata.insert("user_agent".to_string(), Value::from("bolt-rs"));
+        let hello = Message::Hello(Hello::new(metadata));
+        assert_eq!(encode_for(v3, &init).unwrap(), hello);
+        assert_eq!(encode_for(v1, &hello).unwrap(), init);
+        assert_eq!(encode_for(v1, &init).unwrap(), init);
+        assert!(encode_for(v1, &Message::Hello(Hello::new(HashMap::new()))).is_err());
+
+        let run = Message::Run(Run::new("RETURN 1".to_string(), HashMap::new()));
+        let run_with_metadata = Message::RunWithMetadata(RunWithMetadata::new(
+            "RETURN 1".to_string(),
+            HashMap::new(),
+            HashMap::new(),
+        ));
+        assert_eq!(encode_for(v4, &run).unwrap(), run_with_metadata);
+        assert_eq!(encode_for(v1, &run_with_metadata).unwrap(), run);
+        let run_in_db = Message::RunWithMetadata(RunWithMetadata::new(
+            "RETURN 1".to_string(),
+            HashMap::new(),
+            HashMap::from_iter(vec![("db".to_string(), Value::from("neo4j"))]),
+        ));
+        assert!(encode_for(v1, &run_in_db).is_err());
+
+        assert_eq!(
+            encode_for(v4, &Message::PullAll).unwrap(),
+            Message::Pull(Pull::all())
+        );
+        assert_eq!(
+            encode_for(v3, &Message::Pull(Pull::all())).unwrap(),
+            Message::PullAll
+        );
+        assert!(encode_for(v3, &Message::Pull(Pull::some(10))).is_err());
+        assert_eq!(
+            encode_for(v4, &Message::DiscardAll).unwrap(),
+            Message::Discard(Discard::all())
+        );
+        assert_eq!(
+            encode_for(v3, &Message::Discard(Discard::all())).unwrap(),
+            Message::DiscardAll
+        );
+
+        assert!(matches!(
+            encode_for(v1, &Message::Commit),
+            Err(Error::UnsupportedByVersion { name: "COMMIT", version }) if version == v1
+        ));
+        assert!(encode_for(v3, &Message::AckFailure).is_err());
+        #[cfg(feature = "v5")]
+        {
+            assert!(encode_for(v4, &Message::Telemetry(Telemetry::new(2))).is_err());
+            assert!(encode_for(Version::new(5, 4), &Message::Telemetry(Telemetry::new(2))).is_ok());
+            let logon = Message::Logon(Logon::new(HashMap::new()));
+            assert!(encode_for(Version::new(5, 0), &logon).is_err());
+            assert!(encode_for(Version::new(5, 1), &logon).is_ok());
+        }
+        // Server messages are the same in every version
+        let success = Message::Success(Success::new(HashMap::new()));
+        assert_eq!(encode_for(v1, &success).unwrap(), success);
+    }
+
+    #[cfg(feature = "v4")]
+    #[test]
+    fn route_version_conversions() {
+        let route = Message::Route(Route::new(
+            HashMap::new(),
+            vec![],
+            Some("neo4j".to_string()),
+        ));
+        let route_with_extra = Message::Route(Route::with_extra(
+            HashMap::new(),
+            vec![],
+            Some("neo4j".to_string()),
+            None,
+        ));
+        assert!(encode_for(Version::new(4, 2), &route).is_err());
+        assert_eq!(encode_for(Version::new(4, 3), &route).unwrap(), route);
+        assert_eq!(
+            encode_for(Version::new(4, 3), &route_with_extra).unwrap(),
+            route
+        );
+        assert_eq!(
+            encode_for(Version::new(4, 4), &route).unwrap(),
+            route_with_extra
+        );
+
+        let impersonating = Message::Route(Route::with_extra(
+            HashMap::new(),
+            vec![],
+            None,
+            Some("alice".to_string()),
+        ));
+        assert!(encode_for(Version::new(4, 3), &impersonating).is_err());
+        assert_eq!(
+            encode_for(Version::new(5, 0), &impersonating).unwrap(),
+            impersonating
+        );
+    }
+
+    #[cfg(all(feature = "v1", feature = "v4"))]
+    #[test]
+    fn to_chunks_with_version() {
+        let chunks = encode_with_version(Version::new(4, 0), || {
+            Message::PullAll.to_chunks_in(&mut BytesMut::new(), &NoCompression)
+        })
+        .unwrap();
+        assert_eq!(
+            chunks.concat(),
+            vec![0x00, 0x06, 0xB1, 0x3F, 0xA1, 0x81, b'n', 0xFF, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn serialized_len() {
+        let parameters = HashMap::from_iter(vec![("name".to_string(), Value::from("Alice"))]);
+        #[allow(unused_mut)]
+        let mut messages = vec![
+            Message::Reset,
+            Message::Record(Record::new(vec![
+                Value::from(1),
+                Value::from(parameters.clone()),
+            ])),
+        ];
+        #[cfg(feature = "v3")]
+        messages.push(Message::RunWithMetadata(RunWithMetadata::new(
+            "RETURN $name".to_string(),
+            parameters,
+            HashMap::new(),
+        )));
+        #[cfg(feature = "v4")]
+        messages.push(Message::Route(Route::new(HashMap::new(), vec![], None)));
+        #[cfg(feature = "v5")]
+        messages.push(Message::Logon(Logon::new(HashMap::new())));
+        #[cfg(feature = "v5")]
+        messages.push(Message::Telemetry(Telemetry::new(2)));
+        for message in messages {
+            let len = message.serialized_len().unwrap();
+            assert_eq!(len, message.try_into_bytes().unwrap().len());
+        }
+    }
+
+    #[cfg(all(feature = "v1", feature = "v3"))]
+    #[test]
+    fn serialize_into() {
+        let messages = vec![
+            Message::Hello(Hello::new(HashMap::from_iter(vec![(
+                "user_agent".to_string(),
+                Value::from("bolt-client"),
+            )]))),
+            Message::Record(Record::new(vec![Value::from(vec![1.5, 2.5])])),
+            Message::PullAll,
+        ];
+        let mut expected = BytesMut::new();
+        for message in messages.clone() {
+            expected.put(message.try_into_bytes().unwrap());
+        }
+        // Messages are appended to anything already in the buffer
+        let mut buf: Vec<u8> = vec![0xFF];
+        for message in &messages {
+            message.serialize_into(&mut buf).unwrap();
+        }
+        assert_eq!(buf[0], 0xFF);
+        assert_eq!(&buf[1..], &expected[..]);
     }
 }
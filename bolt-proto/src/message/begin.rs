@@ -1,7 +1,9 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use bolt_proto_derive::*;
 
+use crate::message::{get_tx_timeout, set_tx_timeout};
 use crate::{impl_message_with_metadata, impl_try_from_message, Value};
 
 pub(crate) const MARKER: u8 = 0xB1;
@@ -13,13 +15,100 @@ pub struct Begin {
 }
 
 impl_message_with_metadata!(Begin);
+
+impl Begin {
+    /// Assemble the metadata of a BEGIN message from typed transaction options, instead of building the map by hand.
+    pub fn builder() -> BeginBuilder {
+        BeginBuilder::default()
+    }
+
+    /// Set the transaction timeout, which is sent to the server in milliseconds. Timeouts too large to represent are
+    /// clamped.
+    pub fn with_tx_timeout(mut self, timeout: Duration) -> Self {
+        set_tx_timeout(&mut self.metadata, timeout);
+        self
+    }
+
+    /// Get the transaction timeout, if one is set to a valid number of milliseconds.
+    pub fn tx_timeout(&self) -> Option<Duration> {
+        get_tx_timeout(&self.metadata)
+    }
+}
+
 impl_try_from_message!(Begin, Begin);
 
+/// Whether a transaction reads or writes data, which lets a cluster route it to a suitable server.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum AccessMode {
+    Read,
+    Write,
+}
+
+/// Assembles the metadata of a [`Begin`] message, see [`Begin::builder`].
+#[derive(Debug, Clone, Default)]
+pub struct BeginBuilder {
+    metadata: HashMap<String, Value>,
+}
+
+impl BeginBuilder {
+    /// Set the bookmarks the transaction must wait for, i.e. the transactions that must be visible to it.
+    pub fn bookmarks(mut self, bookmarks: Vec<String>) -> Self {
+        self.metadata
+            .insert("bookmarks".to_string(), Value::from(bookmarks));
+        self
+    }
+
+    /// Set the transaction timeout, see [`Begin::with_tx_timeout`].
+    pub fn tx_timeout(mut self, timeout: Duration) -> Self {
+        set_tx_timeout(&mut self.metadata, timeout);
+        self
+    }
+
+    /// Set metadata to attach to the transaction, which shows up in the server's query log and transaction listing.
+    pub fn tx_metadata(mut self, tx_metadata: HashMap<String, Value>) -> Self {
+        self.metadata
+            .insert("tx_metadata".to_string(), Value::from(tx_metadata));
+        self
+    }
+
+    /// Set the access mode. Transactions are write transactions unless set otherwise.
+    pub fn mode(mut self, mode: AccessMode) -> Self {
+        match mode {
+            AccessMode::Read => {
+                self.metadata.insert("mode".to_string(), Value::from("r"));
+            }
+            AccessMode::Write => {
+                self.metadata.remove("mode");
+            }
+        }
+        self
+    }
+
+    /// Set the database to run the transaction against. Sent by Bolt v4+ clients.
+    pub fn db(mut self, db: impl Into<String>) -> Self {
+        self.metadata
+            .insert("db".to_string(), Value::from(db.into()));
+        self
+    }
+
+    /// Set the user to impersonate for the transaction. Sent by Bolt v4.4+ clients.
+    pub fn imp_user(mut self, imp_user: impl Into<String>) -> Self {
+        self.metadata
+            .insert("imp_user".to_string(), Value::from(imp_user.into()));
+        self
+    }
+
+    pub fn build(self) -> Begin {
+        Begin::new(self.metadata)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::convert::TryFrom;
     use std::iter::FromIterator;
     use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
     use bytes::Bytes;
 
@@ -35,6 +124,37 @@ mod tests {
         )]))
     }
 
+    #[test]
+    fn builder() {
+        let tx_metadata = HashMap::from_iter(vec![("app".to_string(), Value::from("test"))]);
+        let msg = Begin::builder()
+            .bookmarks(vec!["b1".to_string(), "b2".to_string()])
+            .tx_timeout(Duration::from_secs(2))
+            .tx_metadata(tx_metadata.clone())
+            .mode(AccessMode::Read)
+            .db("neo4j")
+            .imp_user("alice")
+            .build();
+        assert_eq!(
+            msg,
+            Begin::new(HashMap::from_iter(vec![
+                ("bookmarks".to_string(), Value::from(vec!["b1", "b2"])),
+                ("tx_timeout".to_string(), Value::from(2000)),
+                ("tx_metadata".to_string(), Value::from(tx_metadata)),
+                ("mode".to_string(), Value::from("r")),
+                ("db".to_string(), Value::from("neo4j")),
+                ("imp_user".to_string(), Value::from("alice")),
+            ]))
+        );
+        assert_eq!(msg.tx_timeout(), Some(Duration::from_secs(2)));
+
+        let msg = Begin::builder()
+            .mode(AccessMode::Read)
+            .mode(AccessMode::Write)
+            .build();
+        assert!(msg.metadata().is_empty());
+    }
+
     #[test]
     fn get_marker() {
         assert_eq!(new_msg().get_marker().unwrap(), MARKER);
@@ -97,4 +217,19 @@ mod tests {
             msg
         );
     }
+
+    #[test]
+    fn tx_timeout() {
+        let msg = new_msg().with_tx_timeout(Duration::from_secs(5));
+        assert_eq!(msg.metadata().get("tx_timeout"), Some(&Value::from(5000)));
+        assert_eq!(msg.tx_timeout(), Some(Duration::from_secs(5)));
+
+        let msg_bytes = msg.clone().try_into_bytes().unwrap();
+        let msg_bytes = msg_bytes.slice(2..);
+        let decoded = Begin::try_from(Arc::new(Mutex::new(msg_bytes))).unwrap();
+        assert_eq!(decoded, msg);
+        assert_eq!(decoded.tx_timeout(), Some(Duration::from_secs(5)));
+
+        assert_eq!(new_msg().tx_timeout(), None);
+    }
 }
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::iter::FromIterator;
 
 use bolt_proto_derive::*;
 
@@ -15,10 +16,42 @@ pub struct Discard {
 impl_message_with_metadata!(Discard);
 impl_try_from_message!(Discard, Discard);
 
+impl Discard {
+    /// Create a `DISCARD` message that will discard all remaining records in the result stream (`n = -1`).
+    pub fn all() -> Self {
+        Self::new(HashMap::from_iter(vec![("n".to_string(), Value::from(-1))]))
+    }
+
+    /// Create a `DISCARD` message that will discard at most `n` records from the result stream. Counts larger than
+    /// [`i64::MAX`] are clamped.
+    pub fn some(n: u64) -> Self {
+        let n = n.min(i64::MAX as u64) as i64;
+        Self::new(HashMap::from_iter(vec![("n".to_string(), Value::from(n))]))
+    }
+
+    /// Create a `DISCARD` message that will discard at most `n` records from the result stream, or all remaining records
+    /// if `n` is -1. Other negative counts are treated as -1.
+    pub fn n(n: i64) -> Self {
+        Self::new(HashMap::from_iter(vec![(
+            "n".to_string(),
+            Value::from(n.max(-1)),
+        )]))
+    }
+
+    /// Like [`n`](Discard::n), but for the result of the query with the given `qid` in an explicit transaction, as
+    /// returned in response to its RUN message. A `qid` of -1 means the most recent query, which is also what the
+    /// server assumes without a `qid`.
+    pub fn n_for(n: i64, qid: i64) -> Self {
+        let mut msg = Self::n(n);
+        msg.metadata
+            .insert("qid".to_string(), Value::from(qid.max(-1)));
+        msg
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::convert::TryFrom;
-    use std::iter::FromIterator;
     use std::sync::{Arc, Mutex};
 
     use bytes::Bytes;
@@ -35,6 +68,24 @@ mod tests {
         )]))
     }
 
+    #[test]
+    fn helpers() {
+        assert_eq!(Discard::n(-1), Discard::all());
+        assert_eq!(Discard::n(-5), Discard::all());
+        assert_eq!(Discard::n(10), Discard::some(10));
+        assert_eq!(
+            Discard::n_for(10, 2),
+            Discard::new(HashMap::from_iter(vec![
+                ("n".to_string(), Value::from(10)),
+                ("qid".to_string(), Value::from(2)),
+            ]))
+        );
+        assert_eq!(
+            Discard::n_for(-1, -3).metadata().get("qid"),
+            Some(&Value::from(-1))
+        );
+    }
+
     #[test]
     fn get_marker() {
         assert_eq!(new_msg().get_marker().unwrap(), MARKER);
@@ -97,4 +148,38 @@ mod tests {
             msg
         );
     }
+
+    #[test]
+    fn all() {
+        assert_eq!(
+            Discard::all().try_into_bytes().unwrap(),
+            Bytes::from_static(&[
+                MARKER,
+                SIGNATURE,
+                map::MARKER_TINY | 1,
+                string::MARKER_TINY | 1,
+                b'n',
+                0xFF,
+            ])
+        );
+    }
+
+    #[test]
+    fn some() {
+        assert_eq!(
+            Discard::some(50).try_into_bytes().unwrap(),
+            Bytes::from_static(&[
+                MARKER,
+                SIGNATURE,
+                map::MARKER_TINY | 1,
+                string::MARKER_TINY | 1,
+                b'n',
+                50,
+            ])
+        );
+        assert_eq!(
+            Discard::some(u64::MAX).metadata().get("n"),
+            Some(&Value::from(i64::MAX))
+        );
+    }
 }
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fmt;
 
 use bolt_proto_derive::*;
 
@@ -13,9 +14,140 @@ pub struct Failure {
 }
 
 impl_message_with_metadata!(Failure);
+
+impl Failure {
+    /// The status code of the failure, e.g. `Neo.ClientError.Statement.SyntaxError`, if it is present and a string.
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self.metadata.get("code") {
+            Some(Value::String(code)) => code.as_str().ok().map(ErrorCode::new),
+            _ => None,
+        }
+    }
+
+    /// The description of the failure, if it is present and a string.
+    pub fn message(&self) -> Option<&str> {
+        match self.metadata.get("message") {
+            Some(Value::String(message)) => message.as_str().ok(),
+            _ => None,
+        }
+    }
+}
+
 impl_try_from_message!(Failure, Failure);
 
-#[cfg(test)]
+/// A Neo4j status code, which has the form `Neo.<classification>.<category>.<title>`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ErrorCode {
+    code: String,
+}
+
+impl ErrorCode {
+    pub fn new(code: impl Into<String>) -> Self {
+        Self { code: code.into() }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.code
+    }
+
+    pub fn classification(&self) -> Classification {
+        match self.part(1) {
+            Some("ClientError") => Classification::ClientError,
+            Some("TransientError") => Classification::TransientError,
+            Some("DatabaseError") => Classification::DatabaseError,
+            _ => Classification::Unknown,
+        }
+    }
+
+    /// The category of the code, e.g. `Statement` or `Security`.
+    pub fn category(&self) -> Option<&str> {
+        self.part(2)
+    }
+
+    /// The title of the code, e.g. `SyntaxError`.
+    pub fn title(&self) -> Option<&str> {
+        self.part(3)
+    }
+
+    /// The kind of security error, if this is the code of one.
+    pub fn security_error(&self) -> Option<SecurityError> {
+        if self.classification() != Classification::ClientError
+            || self.category() != Some("Security")
+        {
+            return None;
+        }
+        Some(match self.title()? {
+            "Unauthorized" => SecurityError::Unauthorized,
+            "AuthenticationRateLimit" => SecurityError::AuthenticationRateLimit,
+            "CredentialsExpired" => SecurityError::CredentialsExpired,
+            "TokenExpired" => SecurityError::TokenExpired,
+            "Forbidden" => SecurityError::Forbidden,
+            "AuthorizationExpired" => SecurityError::AuthorizationExpired,
+            _ => SecurityError::Other,
+        })
+    }
+
+    /// Whether the work that failed may succeed if it is retried, possibly against another server of a cluster.
+    pub fn is_retryable(&self) -> bool {
+        match self.classification() {
+            // Transactions terminated or stopped by a user would be terminated again
+            Classification::TransientError => !matches!(
+                self.code.as_str(),
+                "Neo.TransientError.Transaction.Terminated"
+                    | "Neo.TransientError.Transaction.LockClientStopped"
+            ),
+            // The cluster's leader or the database's availability changed, or the authorization must be refreshed
+            Classification::ClientError => matches!(
+                self.code.as_str(),
+                "Neo.ClientError.Cluster.NotALeader"
+                    | "Neo.ClientError.General.ForbiddenOnReadOnlyDatabase"
+                    | "Neo.ClientError.Security.AuthorizationExpired"
+            ),
+            Classification::DatabaseError | Classification::Unknown => false,
+        }
+    }
+
+    fn part(&self, index: usize) -> Option<&str> {
+        let mut parts = self.code.split('.');
+        if parts.next() != Some("Neo") {
+            return None;
+        }
+        parts.nth(index - 1)
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.code)
+    }
+}
+
+/// Who is responsible for a failure, according to the classification of its [`ErrorCode`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Classification {
+    /// The request was invalid, and must be changed before it can succeed.
+    ClientError,
+    /// The request failed due to a temporary condition, and may succeed if it is retried.
+    TransientError,
+    /// The server failed to process a valid request.
+    DatabaseError,
+    /// The code doesn't have the form of a Neo4j status code.
+    Unknown,
+}
+
+/// The kinds of `Neo.ClientError.Security` codes.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SecurityError {
+    Unauthorized,
+    AuthenticationRateLimit,
+    CredentialsExpired,
+    TokenExpired,
+    Forbidden,
+    AuthorizationExpired,
+    Other,
+}
+
+#[cfg(all(test, feature = "chrono"))]
 mod tests {
     use std::convert::TryFrom;
     use std::iter::FromIterator;
@@ -32,10 +164,61 @@ mod tests {
     fn new_msg() -> Failure {
         Failure::new(HashMap::from_iter(vec![(
             "failing_since".to_string(),
-            Value::from(NaiveDate::from_ymd(1985, 6, 26)),
+            Value::from(NaiveDate::from_ymd_opt(1985, 6, 26).unwrap()),
         )]))
     }
 
+    #[test]
+    fn code() {
+        let msg = Failure::new(HashMap::from_iter(vec![
+            (
+                "code".to_string(),
+                Value::from("Neo.ClientError.Statement.SyntaxError"),
+            ),
+            ("message".to_string(), Value::from("Invalid input")),
+        ]));
+        let code = msg.code().unwrap();
+        assert_eq!(code.as_str(), "Neo.ClientError.Statement.SyntaxError");
+        assert_eq!(code.classification(), Classification::ClientError);
+        assert_eq!(code.category(), Some("Statement"));
+        assert_eq!(code.title(), Some("SyntaxError"));
+        assert_eq!(code.security_error(), None);
+        assert!(!code.is_retryable());
+        assert_eq!(msg.message(), Some("Invalid input"));
+        assert_eq!(new_msg().code(), None);
+        assert_eq!(new_msg().message(), None);
+
+        let code = ErrorCode::new("Neo.ClientError.Security.Unauthorized");
+        assert_eq!(code.security_error(), Some(SecurityError::Unauthorized));
+        assert!(!code.is_retryable());
+        let code = ErrorCode::new("Neo.ClientError.Security.AuthorizationExpired");
+        assert_eq!(
+            code.security_error(),
+            Some(SecurityError::AuthorizationExpired)
+        );
+        assert!(code.is_retryable());
+        assert_eq!(
+            ErrorCode::new("Neo.ClientError.Security.SomethingNew").security_error(),
+            Some(SecurityError::Other)
+        );
+
+        let code = ErrorCode::new("Neo.TransientError.Transaction.DeadlockDetected");
+        assert_eq!(code.classification(), Classification::TransientError);
+        assert!(code.is_retryable());
+        assert!(!ErrorCode::new("Neo.TransientError.Transaction.Terminated").is_retryable());
+        assert!(ErrorCode::new("Neo.ClientError.Cluster.NotALeader").is_retryable());
+
+        let code = ErrorCode::new("Neo.DatabaseError.General.UnknownError");
+        assert_eq!(code.classification(), Classification::DatabaseError);
+        assert!(!code.is_retryable());
+
+        let code = ErrorCode::new("Something.TransientError.Else");
+        assert_eq!(code.classification(), Classification::Unknown);
+        assert_eq!(code.category(), None);
+        assert!(!code.is_retryable());
+        assert_eq!(code.to_string(), "Something.TransientError.Else");
+    }
+
     #[test]
     fn get_marker() {
         assert_eq!(new_msg().get_marker().unwrap(), MARKER);
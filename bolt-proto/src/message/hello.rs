@@ -1,20 +1,127 @@
 use std::collections::HashMap;
+use std::fmt;
 
 use bolt_proto_derive::*;
 
-use crate::{impl_message_with_metadata, impl_try_from_message, Value};
+use crate::message::{get_auth_scheme, redact_auth_token, AUTH_TOKEN_KEYS};
+use crate::{impl_message_with_metadata, impl_try_from_message, Value, Version};
 
 pub(crate) const MARKER: u8 = 0xB1;
 pub(crate) const SIGNATURE: u8 = 0x01;
 
-#[derive(Debug, Clone, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+// Debug is implemented by hand to mask the credentials
+#[derive(Clone, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
 pub struct Hello {
     pub(crate) metadata: HashMap<String, Value>,
 }
 
 impl_message_with_metadata!(Hello);
+
+impl Hello {
+    /// Assemble the metadata of a HELLO message from typed entries, instead of building the map by hand.
+    pub fn builder() -> HelloBuilder {
+        HelloBuilder::default()
+    }
+
+    /// Get the entries of the metadata that make up the auth token, e.g. `scheme` and `principal`.
+    pub fn auth_token(&self) -> HashMap<&str, &Value> {
+        AUTH_TOKEN_KEYS
+            .iter()
+            .filter_map(|&key| Some((key, self.metadata.get(key)?)))
+            .collect()
+    }
+
+    /// Get the auth scheme from the metadata (e.g. `"basic"`), if it is present and a string.
+    pub fn auth_scheme(&self) -> Option<&str> {
+        get_auth_scheme(&self.metadata)
+    }
+
+    /// Get a copy of this message with the secret entries of the auth token masked, which is safe to log. The `Debug`
+    /// output of a message is masked the same way.
+    pub fn redacted(&self) -> Self {
+        Self::new(redact_auth_token(&self.metadata))
+    }
+}
+
+impl fmt::Debug for Hello {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hello")
+            .field("metadata", &redact_auth_token(&self.metadata))
+            .finish()
+    }
+}
+
 impl_try_from_message!(Hello, Hello);
 
+/// Assembles the metadata of a [`Hello`] message, see [`Hello::builder`].
+#[derive(Clone, Default)]
+pub struct HelloBuilder {
+    metadata: HashMap<String, Value>,
+}
+
+impl fmt::Debug for HelloBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HelloBuilder")
+            .field("metadata", &redact_auth_token(&self.metadata))
+            .finish()
+    }
+}
+
+impl HelloBuilder {
+    /// Set the name and version the client identifies itself with, e.g. `MyClient/1.0`. Servers require this.
+    pub fn user_agent(self, user_agent: impl Into<String>) -> Self {
+        self.with("user_agent", user_agent.into())
+    }
+
+    /// Set the auth scheme, e.g. `basic`. Without a scheme, servers use `none`.
+    pub fn scheme(self, scheme: impl Into<String>) -> Self {
+        self.with("scheme", scheme.into())
+    }
+
+    pub fn principal(self, principal: impl Into<String>) -> Self {
+        self.with("principal", principal.into())
+    }
+
+    pub fn credentials(self, credentials: impl Into<String>) -> Self {
+        self.with("credentials", credentials.into())
+    }
+
+    /// Set the routing context, e.g. the address the client connected to. This also tells the server that routing is
+    /// wanted. Sent by Bolt v4.1+ clients.
+    pub fn routing(self, context: HashMap<String, Value>) -> Self {
+        self.with("routing", context)
+    }
+
+    /// Set the name and version of the driver, e.g. `bolt-client/0.11.0`, as opposed to the application's user agent.
+    /// Sent by Bolt v5.3+ clients.
+    pub fn bolt_agent(self, product: impl Into<String>) -> Self {
+        let mut bolt_agent = HashMap::new();
+        bolt_agent.insert("product".to_string(), Value::from(product.into()));
+        self.with("bolt_agent", bolt_agent)
+    }
+
+    fn with(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.metadata.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Create a HELLO message with all of the entries that were set.
+    pub fn build(self) -> Hello {
+        Hello::new(self.metadata)
+    }
+
+    /// Create a HELLO message for the given protocol version, leaving out the entries it doesn't know of.
+    pub fn build_for(mut self, version: Version) -> Hello {
+        if version < Version::new(4, 1) {
+            self.metadata.remove("routing");
+        }
+        if version < Version::new(5, 3) {
+            self.metadata.remove("bolt_agent");
+        }
+        self.build()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::convert::TryFrom;
@@ -25,6 +132,7 @@ mod tests {
 
     use crate::serialization::*;
     use crate::value::*;
+    use crate::Message;
 
     use super::*;
 
@@ -35,6 +143,88 @@ mod tests {
         )]))
     }
 
+    #[test]
+    fn auth_scheme() {
+        let msg = new_msg();
+        assert_eq!(msg.auth_scheme(), None);
+        assert!(msg.auth_token().is_empty());
+
+        let msg = Hello::new(HashMap::from_iter(vec![
+            ("user_agent".to_string(), Value::from("MyClient/1.0")),
+            ("scheme".to_string(), Value::from("basic")),
+            ("principal".to_string(), Value::from("neo4j")),
+            ("credentials".to_string(), Value::from("test")),
+        ]));
+        let bytes = msg.try_into_bytes().unwrap();
+        let msg = match Message::try_from(Arc::new(Mutex::new(bytes))).unwrap() {
+            Message::Hello(hello) => hello,
+            other => panic!("expected a HELLO message, got {:?}", other),
+        };
+        assert_eq!(msg.auth_scheme(), Some("basic"));
+        let auth_token = msg.auth_token();
+        assert_eq!(auth_token.len(), 3);
+        assert_eq!(auth_token.get("principal"), Some(&&Value::from("neo4j")));
+        assert!(!auth_token.contains_key("user_agent"));
+    }
+
+    #[test]
+    fn builder() {
+        let routing = HashMap::from_iter(vec![("address".to_string(), Value::from("a:7687"))]);
+        let builder = Hello::builder()
+            .user_agent("MyClient/1.0")
+            .scheme("basic")
+            .principal("neo4j")
+            .credentials("test")
+            .routing(routing.clone())
+            .bolt_agent("bolt-client/1.0");
+
+        let msg = builder.clone().build();
+        assert_eq!(msg.auth_scheme(), Some("basic"));
+        assert_eq!(msg.auth_token().len(), 3);
+        assert_eq!(
+            msg.metadata().get("user_agent"),
+            Some(&Value::from("MyClient/1.0"))
+        );
+        assert_eq!(msg.metadata().get("routing"), Some(&Value::from(routing)));
+        assert_eq!(
+            msg.metadata().get("bolt_agent"),
+            Some(&Value::from(HashMap::from_iter(vec![(
+                "product".to_string(),
+                Value::from("bolt-client/1.0"),
+            )])))
+        );
+        assert_eq!(builder.clone().build_for(Version::new(5, 3)), msg);
+
+        let msg = builder.clone().build_for(Version::new(4, 4));
+        assert!(msg.metadata().contains_key("routing"));
+        assert!(!msg.metadata().contains_key("bolt_agent"));
+        let msg = builder.build_for(Version::new(4, 0));
+        assert_eq!(msg.metadata().len(), 4);
+        assert_eq!(Hello::builder().build(), Hello::new(HashMap::new()));
+    }
+
+    #[test]
+    fn redacted() {
+        let builder = Hello::builder()
+            .user_agent("MyClient/1.0")
+            .principal("neo4j")
+            .credentials("hunter2");
+        assert!(!format!("{:?}", builder).contains("hunter2"));
+        let msg = builder.build();
+        let debug = format!("{:?}", Message::Hello(msg.clone()));
+        assert!(!debug.contains("hunter2"));
+        assert!(debug.contains("neo4j"));
+        assert_eq!(format!("{:?}", msg.redacted()), format!("{:?}", msg));
+        assert_eq!(
+            msg.redacted().metadata().get("credentials"),
+            Some(&Value::from("********"))
+        );
+        assert_eq!(
+            msg.metadata().get("credentials"),
+            Some(&Value::from("hunter2"))
+        );
+    }
+
     #[test]
     fn get_marker() {
         assert_eq!(new_msg().get_marker().unwrap(), MARKER);
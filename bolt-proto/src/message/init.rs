@@ -1,13 +1,16 @@
 use std::collections::HashMap;
+use std::fmt;
 
 use bolt_proto_derive::*;
 
+use crate::message::{get_auth_scheme, redact_auth_token};
 use crate::{impl_try_from_message, Value};
 
 pub(crate) const MARKER: u8 = 0xB2;
 pub(crate) const SIGNATURE: u8 = 0x01;
 
-#[derive(Debug, Clone, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+// Debug is implemented by hand to mask the credentials
+#[derive(Clone, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
 pub struct Init {
     pub(crate) client_name: String,
     pub(crate) auth_token: HashMap<String, Value>,
@@ -21,6 +24,14 @@ impl Init {
         }
     }
 
+    /// Assemble an INIT message from typed auth token entries, instead of building the map by hand.
+    pub fn builder(client_name: impl Into<String>) -> InitBuilder {
+        InitBuilder {
+            client_name: client_name.into(),
+            auth_token: HashMap::new(),
+        }
+    }
+
     pub fn client_name(&self) -> &str {
         &self.client_name
     }
@@ -28,10 +39,73 @@ impl Init {
     pub fn auth_token(&self) -> &HashMap<String, Value> {
         &self.auth_token
     }
+
+    /// Get the auth scheme from the auth token (e.g. `"basic"`), if it is present and a string.
+    pub fn auth_scheme(&self) -> Option<&str> {
+        get_auth_scheme(&self.auth_token)
+    }
+
+    /// Get a copy of this message with the secret entries of the auth token masked, which is safe to log. The `Debug`
+    /// output of a message is masked the same way.
+    pub fn redacted(&self) -> Self {
+        Self::new(
+            self.client_name.clone(),
+            redact_auth_token(&self.auth_token),
+        )
+    }
+}
+
+impl fmt::Debug for Init {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Init")
+            .field("client_name", &self.client_name)
+            .field("auth_token", &redact_auth_token(&self.auth_token))
+            .finish()
+    }
 }
 
 impl_try_from_message!(Init, Init);
 
+/// Assembles an [`Init`] message, see [`Init::builder`].
+#[derive(Clone)]
+pub struct InitBuilder {
+    client_name: String,
+    auth_token: HashMap<String, Value>,
+}
+
+impl fmt::Debug for InitBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InitBuilder")
+            .field("client_name", &self.client_name)
+            .field("auth_token", &redact_auth_token(&self.auth_token))
+            .finish()
+    }
+}
+
+impl InitBuilder {
+    /// Set the auth scheme, e.g. `basic`. Without a scheme, servers use `none`.
+    pub fn scheme(self, scheme: impl Into<String>) -> Self {
+        self.with("scheme", scheme.into())
+    }
+
+    pub fn principal(self, principal: impl Into<String>) -> Self {
+        self.with("principal", principal.into())
+    }
+
+    pub fn credentials(self, credentials: impl Into<String>) -> Self {
+        self.with("credentials", credentials.into())
+    }
+
+    fn with(mut self, key: &str, value: String) -> Self {
+        self.auth_token.insert(key.to_string(), Value::from(value));
+        self
+    }
+
+    pub fn build(self) -> Init {
+        Init::new(self.client_name, self.auth_token)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::convert::TryFrom;
@@ -52,6 +126,48 @@ mod tests {
         )
     }
 
+    #[test]
+    fn auth_scheme() {
+        assert_eq!(new_msg().auth_scheme(), Some("basic"));
+        let msg = Init::new("MyClient/1.0".to_string(), HashMap::new());
+        assert_eq!(msg.auth_scheme(), None);
+    }
+
+    #[test]
+    fn builder() {
+        assert_eq!(
+            Init::builder("MyClient/1.0").scheme("basic").build(),
+            new_msg()
+        );
+        let msg = Init::builder("MyClient/1.0")
+            .scheme("basic")
+            .principal("neo4j")
+            .credentials("test")
+            .build();
+        assert_eq!(msg.client_name(), "MyClient/1.0");
+        assert_eq!(msg.auth_token().len(), 3);
+        assert_eq!(
+            msg.auth_token().get("credentials"),
+            Some(&Value::from("test"))
+        );
+    }
+
+    #[test]
+    fn redacted() {
+        let builder = Init::builder("MyClient/1.0")
+            .scheme("basic")
+            .credentials("hunter2");
+        assert!(!format!("{:?}", builder).contains("hunter2"));
+        let msg = builder.build();
+        let debug = format!("{:?}", msg);
+        assert!(!debug.contains("hunter2"));
+        assert!(debug.contains("MyClient/1.0"));
+        assert_eq!(
+            msg.redacted().auth_token().get("credentials"),
+            Some(&Value::from("********"))
+        );
+    }
+
     #[test]
     fn get_marker() {
         assert_eq!(new_msg().get_marker().unwrap(), MARKER);
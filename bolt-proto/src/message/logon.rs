@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use bolt_proto_derive::*;
+
+use crate::message::{get_auth_scheme, redact_auth_token};
+use crate::{impl_try_from_message, Value};
+
+pub(crate) const MARKER: u8 = 0xB1;
+pub(crate) const SIGNATURE: u8 = 0x6A;
+
+/// A message sent by Bolt v5.1+ clients to authenticate after HELLO, which no longer carries the auth token from that
+/// version on.
+// Debug is implemented by hand to mask the credentials
+#[derive(Clone, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+pub struct Logon {
+    pub(crate) auth_token: HashMap<String, Value>,
+}
+
+impl Logon {
+    pub fn new(auth_token: HashMap<String, Value>) -> Self {
+        Self { auth_token }
+    }
+
+    pub fn auth_token(&self) -> &HashMap<String, Value> {
+        &self.auth_token
+    }
+
+    /// Get the auth scheme from the auth token (e.g. `"basic"`), if it is present and a string.
+    pub fn auth_scheme(&self) -> Option<&str> {
+        get_auth_scheme(&self.auth_token)
+    }
+
+    /// Get a copy of this message with the secret entries of the auth token masked, which is safe to log. The `Debug`
+    /// output of a message is masked the same way.
+    pub fn redacted(&self) -> Self {
+        Self::new(redact_auth_token(&self.auth_token))
+    }
+}
+
+impl fmt::Debug for Logon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Logon")
+            .field("auth_token", &redact_auth_token(&self.auth_token))
+            .finish()
+    }
+}
+
+impl_try_from_message!(Logon, Logon);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::iter::FromIterator;
+    use std::sync::{Arc, Mutex};
+
+    use bytes::Bytes;
+
+    use crate::serialization::*;
+    use crate::value::*;
+
+    use super::*;
+
+    fn new_msg() -> Logon {
+        Logon::new(HashMap::from_iter(vec![(
+            "scheme".to_string(),
+            Value::from("basic"),
+        )]))
+    }
+
+    #[test]
+    fn get_marker() {
+        assert_eq!(new_msg().get_marker().unwrap(), MARKER);
+    }
+
+    #[test]
+    fn get_signature() {
+        assert_eq!(new_msg().get_signature(), SIGNATURE);
+    }
+
+    #[test]
+    fn try_into_bytes() {
+        let msg = new_msg();
+        assert_eq!(
+            msg.try_into_bytes().unwrap(),
+            Bytes::from_static(&[
+                MARKER,
+                SIGNATURE,
+                map::MARKER_TINY | 1,
+                string::MARKER_TINY | 6,
+                b's',
+                b'c',
+                b'h',
+                b'e',
+                b'm',
+                b'e',
+                string::MARKER_TINY | 5,
+                b'b',
+                b'a',
+                b's',
+                b'i',
+                b'c',
+            ])
+        );
+    }
+
+    #[test]
+    fn try_from_bytes() {
+        let msg = new_msg();
+        let msg_bytes = &[
+            map::MARKER_TINY | 1,
+            string::MARKER_TINY | 6,
+            b's',
+            b'c',
+            b'h',
+            b'e',
+            b'm',
+            b'e',
+            string::MARKER_TINY | 5,
+            b'b',
+            b'a',
+            b's',
+            b'i',
+            b'c',
+        ];
+        assert_eq!(
+            Logon::try_from(Arc::new(Mutex::new(Bytes::from_static(msg_bytes)))).unwrap(),
+            msg
+        );
+    }
+
+    #[test]
+    fn redacted() {
+        let mut auth_token = new_msg().auth_token;
+        auth_token.insert("credentials".to_string(), Value::from("hunter2"));
+        let msg = Logon::new(auth_token);
+        assert_eq!(msg.auth_scheme(), Some("basic"));
+        assert!(!format!("{:?}", msg).contains("hunter2"));
+        assert_eq!(
+            msg.redacted().auth_token().get("credentials"),
+            Some(&Value::from("********"))
+        );
+    }
+}
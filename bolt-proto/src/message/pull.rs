@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::iter::FromIterator;
 
 use bolt_proto_derive::*;
 
@@ -15,11 +16,43 @@ pub struct Pull {
 impl_message_with_metadata!(Pull);
 impl_try_from_message!(Pull, Pull);
 
+impl Pull {
+    /// Create a `PULL` message that will retrieve all remaining records in the result stream (`n = -1`).
+    pub fn all() -> Self {
+        Self::new(HashMap::from_iter(vec![("n".to_string(), Value::from(-1))]))
+    }
+
+    /// Create a `PULL` message that will retrieve at most `n` records from the result stream. Counts larger than
+    /// [`i64::MAX`] are clamped.
+    pub fn some(n: u64) -> Self {
+        let n = n.min(i64::MAX as u64) as i64;
+        Self::new(HashMap::from_iter(vec![("n".to_string(), Value::from(n))]))
+    }
+
+    /// Create a `PULL` message that will retrieve at most `n` records from the result stream, or all remaining records
+    /// if `n` is -1. Other negative counts are treated as -1.
+    pub fn n(n: i64) -> Self {
+        Self::new(HashMap::from_iter(vec![(
+            "n".to_string(),
+            Value::from(n.max(-1)),
+        )]))
+    }
+
+    /// Like [`n`](Pull::n), but for the result of the query with the given `qid` in an explicit transaction, as
+    /// returned in response to its RUN message. A `qid` of -1 means the most recent query, which is also what the
+    /// server assumes without a `qid`.
+    pub fn n_for(n: i64, qid: i64) -> Self {
+        let mut msg = Self::n(n);
+        msg.metadata
+            .insert("qid".to_string(), Value::from(qid.max(-1)));
+        msg
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use bytes::Bytes;
     use std::convert::TryFrom;
-    use std::iter::FromIterator;
     use std::sync::{Arc, Mutex};
 
     use crate::serialization::*;
@@ -34,6 +67,24 @@ mod tests {
         )]))
     }
 
+    #[test]
+    fn helpers() {
+        assert_eq!(Pull::n(-1), Pull::all());
+        assert_eq!(Pull::n(-5), Pull::all());
+        assert_eq!(Pull::n(10), Pull::some(10));
+        assert_eq!(
+            Pull::n_for(10, 2),
+            Pull::new(HashMap::from_iter(vec![
+                ("n".to_string(), Value::from(10)),
+                ("qid".to_string(), Value::from(2)),
+            ]))
+        );
+        assert_eq!(
+            Pull::n_for(-1, -3).metadata().get("qid"),
+            Some(&Value::from(-1))
+        );
+    }
+
     #[test]
     fn get_marker() {
         assert_eq!(new_msg().get_marker().unwrap(), MARKER);
@@ -96,4 +147,38 @@ mod tests {
             msg
         );
     }
+
+    #[test]
+    fn all() {
+        assert_eq!(
+            Pull::all().try_into_bytes().unwrap(),
+            Bytes::from_static(&[
+                MARKER,
+                SIGNATURE,
+                map::MARKER_TINY | 1,
+                string::MARKER_TINY | 1,
+                b'n',
+                0xFF,
+            ])
+        );
+    }
+
+    #[test]
+    fn some() {
+        assert_eq!(
+            Pull::some(50).try_into_bytes().unwrap(),
+            Bytes::from_static(&[
+                MARKER,
+                SIGNATURE,
+                map::MARKER_TINY | 1,
+                string::MARKER_TINY | 1,
+                b'n',
+                50,
+            ])
+        );
+        assert_eq!(
+            Pull::some(u64::MAX).metadata().get("n"),
+            Some(&Value::from(i64::MAX))
+        );
+    }
 }
@@ -1,5 +1,9 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
 use bolt_proto_derive::*;
 
+use crate::error::*;
 use crate::{impl_try_from_message, Value};
 
 pub(crate) const MARKER: u8 = 0xB1;
@@ -18,6 +22,78 @@ impl Record {
     pub fn fields(&self) -> &[Value] {
         &self.fields
     }
+
+    /// Check that this record contains exactly `n` fields, returning an error otherwise. Useful for detecting a
+    /// desynchronized result stream when the number of columns returned by a query is known in advance.
+    pub fn expect_fields(&self, n: usize) -> Result<()> {
+        if self.fields.len() == n {
+            Ok(())
+        } else {
+            Err(Error::InvalidFieldCount {
+                expected: n,
+                actual: self.fields.len(),
+            })
+        }
+    }
+
+    /// Pair the fields of this record with the given column names, as listed under `fields` in the metadata of the
+    /// `SUCCESS` response to the run message. Returns an error if the number of columns doesn't match.
+    pub fn into_map(self, columns: &[impl AsRef<str>]) -> Result<HashMap<String, Value>> {
+        self.expect_fields(columns.len())?;
+        Ok(columns
+            .iter()
+            .map(|column| column.as_ref().to_string())
+            .zip(self.fields)
+            .collect())
+    }
+}
+
+/// Conversion from a record into a type, using the names of the record's columns. This can be derived for structs with
+/// named fields, where each field is converted from the column of the same name. The column of a field can be changed
+/// with `#[bolt(rename = "column")]`, and a field can be left out with `#[bolt(skip)]`, in which case it is set to its
+/// default value.
+///
+/// # Example
+/// ```
+/// use bolt_proto::message::Record;
+/// use bolt_proto::{FromRecord, Value};
+///
+/// #[derive(FromRecord)]
+/// struct Movie {
+///     title: String,
+///     #[bolt(rename = "year")]
+///     released: i64,
+/// }
+///
+/// let record = Record::new(vec![Value::from("The Matrix"), Value::from(1999)]);
+/// let movie = Movie::from_record(record, &["title", "year"]).unwrap();
+/// assert_eq!(movie.released, 1999);
+/// ```
+pub trait FromRecord: Sized {
+    /// Convert a record with the given column names. Returns [`ConversionError::MissingColumn`] if a column is
+    /// missing, and [`ConversionError::InvalidColumn`] if the value of a column can't be converted.
+    fn from_record(record: Record, columns: &[impl AsRef<str>]) -> Result<Self>;
+}
+
+// Used by the FromRecord derive to convert each column
+#[doc(hidden)]
+pub fn take_column<T>(entries: &mut HashMap<String, Value>, column: &str) -> Result<T>
+where
+    T: TryFrom<Value, Error = Error>,
+{
+    let value = entries
+        .remove(column)
+        .ok_or_else(|| ConversionError::MissingColumn(column.to_string()))?;
+    T::try_from(value).map_err(|error| match error {
+        Error::ConversionError(ConversionError::FromValue(value)) => {
+            ConversionError::InvalidColumn {
+                column: column.to_string(),
+                value: Box::new(value),
+            }
+            .into()
+        }
+        error => error,
+    })
 }
 
 impl_try_from_message!(Record, Record);
@@ -127,4 +203,73 @@ mod tests {
             msg
         );
     }
+
+    #[test]
+    fn expect_fields() {
+        let msg = Record::new(vec![Value::from(1_i8), Value::from("two")]);
+        assert!(msg.expect_fields(2).is_ok());
+        assert!(matches!(
+            msg.expect_fields(3),
+            Err(Error::InvalidFieldCount {
+                expected: 3,
+                actual: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn into_map() {
+        let msg = Record::new(vec![Value::from(1_i8), Value::from("two")]);
+        assert_eq!(
+            msg.clone().into_map(&["one", "two"]).unwrap(),
+            HashMap::from_iter(vec![
+                ("one".to_string(), Value::from(1_i8)),
+                ("two".to_string(), Value::from("two")),
+            ])
+        );
+        assert!(matches!(
+            msg.into_map(&["one"]),
+            Err(Error::InvalidFieldCount {
+                expected: 1,
+                actual: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn from_record() {
+        use crate::FromRecord;
+
+        #[derive(Debug, PartialEq, FromRecord)]
+        struct Person {
+            name: std::string::String,
+            #[bolt(rename = "born")]
+            year_of_birth: i64,
+            #[bolt(skip)]
+            cached: Option<usize>,
+        }
+
+        let columns = vec!["born".to_string(), "name".to_string()];
+        let record = Record::new(vec![Value::from(1990), Value::from("Alice")]);
+        assert_eq!(
+            Person::from_record(record, &columns).unwrap(),
+            Person {
+                name: "Alice".to_string(),
+                year_of_birth: 1990,
+                cached: None,
+            }
+        );
+
+        let record = Record::new(vec![Value::from("Alice")]);
+        assert!(matches!(
+            Person::from_record(record, &["name"]),
+            Err(Error::ConversionError(ConversionError::MissingColumn(column))) if column == "born"
+        ));
+        let record = Record::new(vec![Value::from("1990"), Value::from("Alice")]);
+        assert!(matches!(
+            Person::from_record(record, &columns),
+            Err(Error::ConversionError(ConversionError::InvalidColumn { column, value }))
+                if column == "born" && *value == Value::from("1990")
+        ));
+    }
 }
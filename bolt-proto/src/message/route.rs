@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use bolt_proto_derive::*;
+
+use crate::error::*;
+use crate::value::{get_opt_property, get_property};
+use crate::{impl_try_from_message, Value};
+
+pub(crate) const MARKER: u8 = 0xB3;
+pub(crate) const SIGNATURE: u8 = 0x66;
+
+/// A message sent by Bolt v4.3+ clients to request a routing table for a database, which the server returns in the
+/// `rt` metadata of its SUCCESS response (see [`Success::routing_table`](crate::message::Success::routing_table)).
+#[derive(Debug, Clone, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+pub struct Route {
+    pub(crate) context: HashMap<String, Value>,
+    pub(crate) bookmarks: Vec<String>,
+    // The database name (or null) in Bolt v4.3, or a map of "db" and "imp_user" since Bolt v4.4
+    pub(crate) extra: Value,
+}
+
+impl Route {
+    /// Create a ROUTE message in the Bolt v4.3 format, with a null database meaning the default database.
+    pub fn new(
+        context: HashMap<String, Value>,
+        bookmarks: Vec<String>,
+        db: Option<String>,
+    ) -> Self {
+        Self {
+            context,
+            bookmarks,
+            extra: db.map(Value::from).unwrap_or(Value::Null),
+        }
+    }
+
+    /// Create a ROUTE message in the Bolt v4.4+ format, which can also impersonate a user.
+    pub fn with_extra(
+        context: HashMap<String, Value>,
+        bookmarks: Vec<String>,
+        db: Option<String>,
+        imp_user: Option<String>,
+    ) -> Self {
+        let mut extra = HashMap::new();
+        if let Some(db) = db {
+            extra.insert("db".to_string(), Value::from(db));
+        }
+        if let Some(imp_user) = imp_user {
+            extra.insert("imp_user".to_string(), Value::from(imp_user));
+        }
+        Self {
+            context,
+            bookmarks,
+            extra: Value::from(extra),
+        }
+    }
+
+    pub fn context(&self) -> &HashMap<String, Value> {
+        &self.context
+    }
+
+    pub fn bookmarks(&self) -> &[String] {
+        &self.bookmarks
+    }
+
+    pub fn db(&self) -> Option<&str> {
+        match &self.extra {
+            Value::String(db) => db.as_str().ok(),
+            _ => self.extra_field("db"),
+        }
+    }
+
+    pub fn imp_user(&self) -> Option<&str> {
+        self.extra_field("imp_user")
+    }
+
+    fn extra_field(&self, key: &str) -> Option<&str> {
+        match &self.extra {
+            Value::Map(extra) => match extra.value.get(key) {
+                Some(Value::String(value)) => value.as_str().ok(),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl_try_from_message!(Route, Route);
+
+/// The routing table returned by the server in response to a ROUTE message.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RoutingTable {
+    pub(crate) ttl: Duration,
+    pub(crate) db: Option<String>,
+    pub(crate) routers: Vec<String>,
+    pub(crate) readers: Vec<String>,
+    pub(crate) writers: Vec<String>,
+}
+
+impl RoutingTable {
+    // Parses the "rt" entry of a SUCCESS message's metadata. Servers with an unknown role are ignored.
+    pub(crate) fn from_metadata(metadata: &HashMap<String, Value>) -> Result<Self> {
+        let table: HashMap<String, Value> = get_property(metadata, "rt")?;
+        let ttl: i64 = get_property(&table, "ttl")?;
+        let ttl = u64::try_from(ttl).map_err(|_| ConversionError::InvalidProperty {
+            key: "ttl".to_string(),
+            value: Box::new(Value::from(ttl)),
+        })?;
+
+        let mut routing_table = Self {
+            ttl: Duration::from_secs(ttl),
+            db: get_opt_property(&table, "db")?,
+            routers: Vec::new(),
+            readers: Vec::new(),
+            writers: Vec::new(),
+        };
+        let servers: Vec<HashMap<String, Value>> = get_property(&table, "servers")?;
+        for server in servers {
+            let role: String = get_property(&server, "role")?;
+            let addresses: Vec<String> = get_property(&server, "addresses")?;
+            match role.as_str() {
+                "ROUTE" => routing_table.routers.extend(addresses),
+                "READ" => routing_table.readers.extend(addresses),
+                "WRITE" => routing_table.writers.extend(addresses),
+                _ => {}
+            }
+        }
+        Ok(routing_table)
+    }
+
+    /// How long the routing table may be used before it must be requested again.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// The database the routing table is for, which is only sent by Bolt v4.4+ servers.
+    pub fn db(&self) -> Option<&str> {
+        self.db.as_deref()
+    }
+
+    pub fn routers(&self) -> &[String] {
+        &self.routers
+    }
+
+    pub fn readers(&self) -> &[String] {
+        &self.readers
+    }
+
+    pub fn writers(&self) -> &[String] {
+        &self.writers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::iter::FromIterator;
+    use std::sync::{Arc, Mutex};
+
+    use bytes::Bytes;
+
+    use crate::message::Success;
+    use crate::serialization::*;
+
+    use super::*;
+
+    fn new_msg() -> Route {
+        Route::new(
+            HashMap::from_iter(vec![("address".to_string(), Value::from("a:7687"))]),
+            vec!["b1".to_string()],
+            Some("neo4j".to_string()),
+        )
+    }
+
+    fn routing_metadata(ttl: i64, servers: Vec<Value>) -> HashMap<String, Value> {
+        HashMap::from_iter(vec![(
+            "rt".to_string(),
+            Value::from(HashMap::from_iter(vec![
+                ("ttl".to_string(), Value::from(ttl)),
+                ("db".to_string(), Value::from("neo4j")),
+                ("servers".to_string(), Value::from(servers)),
+            ])),
+        )])
+    }
+
+    fn server(role: &str, addresses: Vec<&str>) -> Value {
+        Value::from(HashMap::from_iter(vec![
+            ("role".to_string(), Value::from(role)),
+            ("addresses".to_string(), Value::from(addresses)),
+        ]))
+    }
+
+    #[test]
+    fn get_marker() {
+        assert_eq!(new_msg().get_marker().unwrap(), MARKER);
+    }
+
+    #[test]
+    fn get_signature() {
+        assert_eq!(new_msg().get_signature(), SIGNATURE);
+    }
+
+    #[test]
+    fn try_into_bytes() {
+        let msg = new_msg();
+        assert_eq!(
+            msg.try_into_bytes().unwrap(),
+            Bytes::from_static(&[
+                MARKER, SIGNATURE, 0xA1, 0x87, 0x61, 0x64, 0x64, 0x72, 0x65, 0x73, 0x73, 0x86,
+                0x61, 0x3A, 0x37, 0x36, 0x38, 0x37, 0x91, 0x82, 0x62, 0x31, 0x85, 0x6E, 0x65, 0x6F,
+                0x34, 0x6A,
+            ])
+        );
+    }
+
+    #[test]
+    fn try_from_bytes() {
+        let msg = new_msg();
+        let msg_bytes = &[
+            0xA1, 0x87, 0x61, 0x64, 0x64, 0x72, 0x65, 0x73, 0x73, 0x86, 0x61, 0x3A, 0x37, 0x36,
+            0x38, 0x37, 0x91, 0x82, 0x62, 0x31, 0x85, 0x6E, 0x65, 0x6F, 0x34, 0x6A,
+        ];
+        assert_eq!(
+            Route::try_from(Arc::new(Mutex::new(Bytes::from_static(msg_bytes)))).unwrap(),
+            msg
+        );
+    }
+
+    #[test]
+    fn extra() {
+        let msg = new_msg();
+        assert_eq!(msg.db(), Some("neo4j"));
+        assert_eq!(msg.imp_user(), None);
+
+        let msg = Route::new(HashMap::new(), vec![], None);
+        assert_eq!(msg.db(), None);
+        assert_eq!(
+            msg.try_into_bytes().unwrap(),
+            Bytes::from_static(&[MARKER, SIGNATURE, 0xA0, 0x90, 0xC0])
+        );
+
+        let msg = Route::with_extra(
+            HashMap::new(),
+            vec![],
+            Some("neo4j".to_string()),
+            Some("alice".to_string()),
+        );
+        assert_eq!(msg.db(), Some("neo4j"));
+        assert_eq!(msg.imp_user(), Some("alice"));
+        let bytes = msg.clone().try_into_bytes().unwrap().slice(2..);
+        assert_eq!(Route::try_from(Arc::new(Mutex::new(bytes))).unwrap(), msg);
+    }
+
+    #[test]
+    fn routing_table() {
+        let success = Success::new(routing_metadata(
+            300,
+            vec![
+                server("ROUTE", vec!["a:7687", "b:7687"]),
+                server("READ", vec!["b:7687"]),
+                server("WRITE", vec!["a:7687"]),
+                server("SOMETHING NEW", vec!["c:7687"]),
+            ],
+        ));
+        let table = success.routing_table().unwrap();
+        assert_eq!(table.ttl(), Duration::from_secs(300));
+        assert_eq!(table.db(), Some("neo4j"));
+        assert_eq!(table.routers(), &["a:7687", "b:7687"]);
+        assert_eq!(table.readers(), &["b:7687"]);
+        assert_eq!(table.writers(), &["a:7687"]);
+    }
+
+    #[test]
+    fn invalid_routing_table() {
+        assert!(matches!(
+            Success::new(HashMap::new()).routing_table(),
+            Err(Error::ConversionError(ConversionError::MissingProperty(key))) if key == "rt"
+        ));
+        assert!(matches!(
+            Success::new(routing_metadata(-1, vec![])).routing_table(),
+            Err(Error::ConversionError(ConversionError::InvalidProperty { key, value }))
+                if key == "ttl" && *value == Value::from(-1)
+        ));
+        assert!(
+            Success::new(routing_metadata(300, vec![Value::from("a:7687")]))
+                .routing_table()
+                .is_err()
+        );
+        assert!(
+            Success::new(routing_metadata(300, vec![server("READ", vec![])]))
+                .routing_table()
+                .is_ok()
+        );
+    }
+}
@@ -1,8 +1,11 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use bolt_proto_derive::*;
 
-use crate::{impl_try_from_message, Value};
+use crate::error::*;
+use crate::message::{get_tx_timeout, set_tx_timeout, AccessMode, Begin, BeginBuilder};
+use crate::{impl_try_from_message, Value, Version};
 
 pub(crate) const MARKER: u8 = 0xB3;
 pub(crate) const SIGNATURE: u8 = 0x10;
@@ -27,6 +30,16 @@ impl RunWithMetadata {
         }
     }
 
+    /// Assemble a RUN message from parameters of any type convertible to [`Value`] and typed transaction options,
+    /// instead of building the maps by hand.
+    pub fn builder(statement: impl Into<String>) -> RunWithMetadataBuilder {
+        RunWithMetadataBuilder {
+            statement: statement.into(),
+            parameters: HashMap::new(),
+            extra: Begin::builder(),
+        }
+    }
+
     pub fn statement(&self) -> &str {
         &self.statement
     }
@@ -38,15 +51,102 @@ impl RunWithMetadata {
     pub fn metadata(&self) -> &HashMap<String, Value> {
         &self.metadata
     }
+
+    /// Set the transaction timeout, which is sent to the server in milliseconds. Timeouts too large to represent are
+    /// clamped.
+    pub fn with_tx_timeout(mut self, timeout: Duration) -> Self {
+        set_tx_timeout(&mut self.metadata, timeout);
+        self
+    }
+
+    /// Get the transaction timeout, if one is set to a valid number of milliseconds.
+    pub fn tx_timeout(&self) -> Option<Duration> {
+        get_tx_timeout(&self.metadata)
+    }
 }
 
 impl_try_from_message!(RunWithMetadata, RunWithMetadata);
 
+/// Assembles a [`RunWithMetadata`] message, see [`RunWithMetadata::builder`].
+#[derive(Debug, Clone)]
+pub struct RunWithMetadataBuilder {
+    statement: String,
+    parameters: HashMap<String, Value>,
+    // A RUN message outside of an explicit transaction has the same options as a BEGIN message
+    extra: BeginBuilder,
+}
+
+impl RunWithMetadataBuilder {
+    pub fn param(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.parameters.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn params<K: Into<String>, V: Into<Value>>(
+        mut self,
+        params: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        self.parameters
+            .extend(params.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
+    /// Set the database to run the query against. Sent by Bolt v4+ clients.
+    pub fn db(mut self, db: impl Into<String>) -> Self {
+        self.extra = self.extra.db(db);
+        self
+    }
+
+    pub fn mode(mut self, mode: AccessMode) -> Self {
+        self.extra = self.extra.mode(mode);
+        self
+    }
+
+    pub fn bookmarks(mut self, bookmarks: Vec<String>) -> Self {
+        self.extra = self.extra.bookmarks(bookmarks);
+        self
+    }
+
+    pub fn tx_timeout(mut self, timeout: Duration) -> Self {
+        self.extra = self.extra.tx_timeout(timeout);
+        self
+    }
+
+    pub fn tx_metadata(mut self, tx_metadata: HashMap<String, Value>) -> Self {
+        self.extra = self.extra.tx_metadata(tx_metadata);
+        self
+    }
+
+    /// Set the user to impersonate for the query. Sent by Bolt v4.4+ clients.
+    pub fn imp_user(mut self, imp_user: impl Into<String>) -> Self {
+        self.extra = self.extra.imp_user(imp_user);
+        self
+    }
+
+    /// Create a RUN message with all of the options that were set.
+    pub fn build(self) -> RunWithMetadata {
+        RunWithMetadata::new(self.statement, self.parameters, self.extra.build().metadata)
+    }
+
+    /// Create a RUN message for the given protocol version. Returns an error if an option was set that the version
+    /// doesn't support, since servers would silently ignore it.
+    pub fn build_for(self, version: Version) -> Result<RunWithMetadata> {
+        let run = self.build();
+        for &(name, since) in &[("db", Version::new(4, 0)), ("imp_user", Version::new(4, 4))] {
+            if version < since && run.metadata.contains_key(name) {
+                return Err(Error::UnsupportedByVersion { name, version });
+            }
+        }
+        Ok(run)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::convert::TryFrom;
     use std::iter::FromIterator;
     use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
     use bytes::Bytes;
 
@@ -63,6 +163,45 @@ mod tests {
         )
     }
 
+    #[test]
+    fn builder() {
+        let builder = RunWithMetadata::builder("RETURN $n, $s;")
+            .param("n", 1)
+            .params(vec![("s", "a")])
+            .db("neo4j")
+            .mode(AccessMode::Read)
+            .bookmarks(vec!["b1".to_string()])
+            .tx_timeout(Duration::from_millis(500))
+            .tx_metadata(HashMap::new());
+        let msg = builder.clone().build();
+        assert_eq!(msg.statement(), "RETURN $n, $s;");
+        assert_eq!(
+            msg.parameters(),
+            &HashMap::from_iter(vec![
+                ("n".to_string(), Value::from(1)),
+                ("s".to_string(), Value::from("a")),
+            ])
+        );
+        assert_eq!(msg.metadata().len(), 5);
+        assert_eq!(msg.metadata().get("db"), Some(&Value::from("neo4j")));
+        assert_eq!(msg.tx_timeout(), Some(Duration::from_millis(500)));
+        assert_eq!(builder.clone().build_for(Version::new(4, 0)).unwrap(), msg);
+
+        assert!(matches!(
+            builder.clone().build_for(Version::new(3, 0)),
+            Err(Error::UnsupportedByVersion { name: "db", .. })
+        ));
+        let builder = builder.imp_user("alice");
+        assert!(builder.clone().build_for(Version::new(4, 4)).is_ok());
+        assert!(matches!(
+            builder.build_for(Version::new(4, 3)),
+            Err(Error::UnsupportedByVersion {
+                name: "imp_user",
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn get_marker() {
         assert_eq!(new_msg().get_marker().unwrap(), MARKER);
@@ -149,4 +288,19 @@ mod tests {
             msg
         );
     }
+
+    #[test]
+    fn tx_timeout() {
+        let msg = new_msg().with_tx_timeout(Duration::from_secs(5));
+        assert_eq!(msg.metadata().get("tx_timeout"), Some(&Value::from(5000)));
+        assert_eq!(msg.tx_timeout(), Some(Duration::from_secs(5)));
+
+        let msg_bytes = msg.clone().try_into_bytes().unwrap();
+        let msg_bytes = msg_bytes.slice(2..);
+        let decoded = RunWithMetadata::try_from(Arc::new(Mutex::new(msg_bytes))).unwrap();
+        assert_eq!(decoded, msg);
+        assert_eq!(decoded.tx_timeout(), Some(Duration::from_secs(5)));
+
+        assert_eq!(new_msg().tx_timeout(), None);
+    }
 }
@@ -1,7 +1,14 @@
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::time::Duration;
 
 use bolt_proto_derive::*;
 
+use crate::error::*;
+#[cfg(feature = "v4")]
+use crate::message::RoutingTable;
+use crate::message::{Notification, Plan, QueryType, SummaryCounters};
+use crate::value::get_opt_property;
 use crate::{impl_message_with_metadata, impl_try_from_message, Value};
 
 pub(crate) const MARKER: u8 = 0xB1;
@@ -12,6 +19,97 @@ pub struct Success {
     pub(crate) metadata: HashMap<String, Value>,
 }
 
+// The metadata accessors return None for entries that are missing or null, and an error for entries of the wrong type.
+impl Success {
+    /// The names of the columns of a result, in response to RUN.
+    pub fn fields(&self) -> Result<Option<Vec<String>>> {
+        get_opt_property(&self.metadata, "fields")
+    }
+
+    /// How long it took for the first record of a result to be available, in response to RUN.
+    pub fn t_first(&self) -> Result<Option<Duration>> {
+        self.millis("t_first")
+    }
+
+    /// How long it took to consume the rest of a result, in response to PULL or DISCARD.
+    pub fn t_last(&self) -> Result<Option<Duration>> {
+        self.millis("t_last")
+    }
+
+    /// The bookmark of a transaction, in response to COMMIT or to the end of an auto-commit transaction's result.
+    pub fn bookmark(&self) -> Result<Option<String>> {
+        get_opt_property(&self.metadata, "bookmark")
+    }
+
+    /// The id of a query in an explicit transaction, in response to RUN. Sent by Bolt v4+ servers.
+    pub fn qid(&self) -> Result<Option<i64>> {
+        get_opt_property(&self.metadata, "qid")
+    }
+
+    /// Whether a result has more records that can be pulled, in response to PULL or DISCARD. Sent by Bolt v4+
+    /// servers.
+    pub fn has_more(&self) -> Result<bool> {
+        Ok(get_opt_property(&self.metadata, "has_more")?.unwrap_or(false))
+    }
+
+    /// The database a query ran against, at the end of its result. Sent by Bolt v4+ servers.
+    pub fn db(&self) -> Result<Option<String>> {
+        get_opt_property(&self.metadata, "db")
+    }
+
+    /// The name and version of the server, e.g. `Neo4j/4.4.0`, in response to INIT or HELLO.
+    pub fn server(&self) -> Result<Option<String>> {
+        get_opt_property(&self.metadata, "server")
+    }
+
+    /// The changes a query made, at the end of its result. All counters are zero if the server sent no stats.
+    pub fn counters(&self) -> Result<SummaryCounters> {
+        SummaryCounters::from_metadata(&self.metadata)
+    }
+
+    /// The kind of changes a query may make, at the end of its result.
+    pub fn query_type(&self) -> Result<Option<QueryType>> {
+        QueryType::from_metadata(&self.metadata)
+    }
+
+    /// The execution plan of a query prefixed with EXPLAIN, at the end of its result.
+    pub fn plan(&self) -> Result<Option<Plan>> {
+        Plan::from_metadata(&self.metadata, "plan")
+    }
+
+    /// The execution plan of a query prefixed with PROFILE, along with the statistics of running it, at the end of its
+    /// result.
+    pub fn profile(&self) -> Result<Option<Plan>> {
+        Plan::from_metadata(&self.metadata, "profile")
+    }
+
+    /// The notifications about a query, e.g. about deprecated features it uses, at the end of its result.
+    pub fn notifications(&self) -> Result<Vec<Notification>> {
+        Notification::from_metadata(&self.metadata)
+    }
+
+    fn millis(&self, key: &str) -> Result<Option<Duration>> {
+        match get_opt_property::<i64>(&self.metadata, key)? {
+            Some(millis) => u64::try_from(millis)
+                .map(|millis| Some(Duration::from_millis(millis)))
+                .map_err(|_| {
+                    ConversionError::InvalidProperty {
+                        key: key.to_string(),
+                        value: Box::new(Value::from(millis)),
+                    }
+                    .into()
+                }),
+            None => Ok(None),
+        }
+    }
+
+    /// Parse the routing table returned in response to a ROUTE message.
+    #[cfg(feature = "v4")]
+    pub fn routing_table(&self) -> Result<RoutingTable> {
+        RoutingTable::from_metadata(&self.metadata)
+    }
+}
+
 impl_message_with_metadata!(Success);
 impl_try_from_message!(Success, Success);
 
@@ -20,6 +118,7 @@ mod tests {
     use std::convert::TryFrom;
     use std::iter::FromIterator;
     use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
     use bytes::Bytes;
 
@@ -28,6 +127,51 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn metadata_accessors() {
+        let msg = Success::new(HashMap::from_iter(vec![
+            ("fields".to_string(), Value::from(vec!["n", "m"])),
+            ("t_first".to_string(), Value::from(12)),
+            ("t_last".to_string(), Value::from(34)),
+            ("bookmark".to_string(), Value::from("FB:kcwQ")),
+            ("qid".to_string(), Value::from(2)),
+            ("has_more".to_string(), Value::from(true)),
+            ("db".to_string(), Value::from("neo4j")),
+            ("server".to_string(), Value::from("Neo4j/4.4.0")),
+        ]));
+        assert_eq!(
+            msg.fields().unwrap(),
+            Some(vec!["n".to_string(), "m".to_string()])
+        );
+        assert_eq!(msg.t_first().unwrap(), Some(Duration::from_millis(12)));
+        assert_eq!(msg.t_last().unwrap(), Some(Duration::from_millis(34)));
+        assert_eq!(msg.bookmark().unwrap().as_deref(), Some("FB:kcwQ"));
+        assert_eq!(msg.qid().unwrap(), Some(2));
+        assert!(msg.has_more().unwrap());
+        assert_eq!(msg.db().unwrap().as_deref(), Some("neo4j"));
+        assert_eq!(msg.server().unwrap().as_deref(), Some("Neo4j/4.4.0"));
+
+        let msg = Success::new(HashMap::from_iter(vec![(
+            "bookmark".to_string(),
+            Value::Null,
+        )]));
+        assert_eq!(msg.fields().unwrap(), None);
+        assert_eq!(msg.t_first().unwrap(), None);
+        assert_eq!(msg.bookmark().unwrap(), None);
+        assert!(!msg.has_more().unwrap());
+
+        let msg = Success::new(HashMap::from_iter(vec![
+            ("t_first".to_string(), Value::from(-1)),
+            ("qid".to_string(), Value::from("2")),
+        ]));
+        assert!(matches!(
+            msg.t_first(),
+            Err(Error::ConversionError(ConversionError::InvalidProperty { key, value }))
+                if key == "t_first" && *value == Value::from(-1)
+        ));
+        assert!(msg.qid().is_err());
+    }
+
     fn new_msg() -> Success {
         Success::new(HashMap::from_iter(vec![(
             "some key".to_string(),
@@ -0,0 +1,533 @@
+use std::collections::HashMap;
+
+use crate::error::*;
+use crate::value::{get_opt_property, get_property};
+use crate::Value;
+
+/// The kind of changes a query may make, listed under `type` in the metadata at the end of its result.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum QueryType {
+    ReadOnly,
+    ReadWrite,
+    WriteOnly,
+    SchemaWrite,
+}
+
+impl QueryType {
+    pub(crate) fn from_metadata(metadata: &HashMap<String, Value>) -> Result<Option<Self>> {
+        let query_type: Option<String> = get_opt_property(metadata, "type")?;
+        match query_type.as_deref() {
+            None => Ok(None),
+            Some("r") => Ok(Some(QueryType::ReadOnly)),
+            Some("rw") => Ok(Some(QueryType::ReadWrite)),
+            Some("w") => Ok(Some(QueryType::WriteOnly)),
+            Some("s") => Ok(Some(QueryType::SchemaWrite)),
+            Some(other) => Err(ConversionError::InvalidProperty {
+                key: "type".to_string(),
+                value: Box::new(Value::from(other)),
+            }
+            .into()),
+        }
+    }
+}
+
+// Declares the counters along with the keys they are listed under in the stats, which are omitted if zero
+macro_rules! summary_counters {
+    ($($counter:ident: $key:literal),* $(,)?) => {
+        /// The changes a query made, listed under `stats` in the metadata at the end of its result.
+        #[derive(Debug, Clone, Default, Eq, PartialEq)]
+        pub struct SummaryCounters {
+            $(pub(crate) $counter: i64,)*
+            pub(crate) contains_updates: bool,
+            pub(crate) contains_system_updates: bool,
+        }
+
+        impl SummaryCounters {
+            $(
+                pub fn $counter(&self) -> i64 {
+                    self.$counter
+                }
+            )*
+
+            fn parse_counters(stats: &HashMap<String, Value>) -> Result<Self> {
+                Ok(Self {
+                    $($counter: get_opt_property(stats, $key)?.unwrap_or(0),)*
+                    contains_updates: false,
+                    contains_system_updates: false,
+                })
+            }
+        }
+    };
+}
+
+summary_counters! {
+    nodes_created: "nodes-created",
+    nodes_deleted: "nodes-deleted",
+    relationships_created: "relationships-created",
+    relationships_deleted: "relationships-deleted",
+    properties_set: "properties-set",
+    labels_added: "labels-added",
+    labels_removed: "labels-removed",
+    indexes_added: "indexes-added",
+    indexes_removed: "indexes-removed",
+    constraints_added: "constraints-added",
+    constraints_removed: "constraints-removed",
+    system_updates: "system-updates",
+}
+
+impl SummaryCounters {
+    // Parses the "stats" entry of a SUCCESS message's metadata. Without stats, the query made no changes.
+    pub(crate) fn from_metadata(metadata: &HashMap<String, Value>) -> Result<Self> {
+        let stats: HashMap<String, Value> = match get_opt_property(metadata, "stats")? {
+            Some(stats) => stats,
+            None => return Ok(Self::default()),
+        };
+        let mut counters = Self::parse_counters(&stats)?;
+        // Servers before Neo4j 4.1 don't send these flags, so they are derived from the counters
+        counters.contains_updates = match get_opt_property(&stats, "contains-updates")? {
+            Some(contains_updates) => contains_updates,
+            None => {
+                counters.nodes_created
+                    + counters.nodes_deleted
+                    + counters.relationships_created
+                    + counters.relationships_deleted
+                    + counters.properties_set
+                    + counters.labels_added
+                    + counters.labels_removed
+                    + counters.indexes_added
+                    + counters.indexes_removed
+                    + counters.constraints_added
+                    + counters.constraints_removed
+                    > 0
+            }
+        };
+        counters.contains_system_updates = get_opt_property(&stats, "contains-system-updates")?
+            .unwrap_or(counters.system_updates > 0);
+        Ok(counters)
+    }
+
+    /// Whether the query changed the data or the schema of a database.
+    pub fn contains_updates(&self) -> bool {
+        self.contains_updates
+    }
+
+    /// Whether the query changed the system database, e.g. by creating a user.
+    pub fn contains_system_updates(&self) -> bool {
+        self.contains_system_updates
+    }
+}
+
+/// An operator of the execution plan of a query, listed under `plan` in the metadata at the end of its result if the
+/// query was prefixed with EXPLAIN, or under `profile` if the query was prefixed with PROFILE. A profiled plan also
+/// has the statistics of running the query.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Plan {
+    pub(crate) operator_type: String,
+    pub(crate) identifiers: Vec<String>,
+    pub(crate) arguments: HashMap<String, Value>,
+    pub(crate) children: Vec<Plan>,
+    pub(crate) db_hits: Option<i64>,
+    pub(crate) rows: Option<i64>,
+    pub(crate) page_cache_hits: Option<i64>,
+    pub(crate) page_cache_misses: Option<i64>,
+}
+
+impl Plan {
+    // Parses a plan entry of a SUCCESS message's metadata, if present
+    pub(crate) fn from_metadata(
+        metadata: &HashMap<String, Value>,
+        key: &str,
+    ) -> Result<Option<Self>> {
+        match get_opt_property(metadata, key)? {
+            Some(plan) => Self::parse(&plan).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn parse(plan: &HashMap<String, Value>) -> Result<Self> {
+        let children: Vec<HashMap<String, Value>> =
+            get_opt_property(plan, "children")?.unwrap_or_default();
+        Ok(Self {
+            operator_type: get_property(plan, "operatorType")?,
+            identifiers: get_opt_property(plan, "identifiers")?.unwrap_or_default(),
+            arguments: get_opt_property(plan, "args")?.unwrap_or_default(),
+            children: children.iter().map(Self::parse).collect::<Result<_>>()?,
+            db_hits: get_opt_property(plan, "dbHits")?,
+            rows: get_opt_property(plan, "rows")?,
+            page_cache_hits: get_opt_property(plan, "pageCacheHits")?,
+            page_cache_misses: get_opt_property(plan, "pageCacheMisses")?,
+        })
+    }
+
+    /// The name of the operator, e.g. `NodeByLabelScan`.
+    pub fn operator_type(&self) -> &str {
+        &self.operator_type
+    }
+
+    /// The variables the operator produces.
+    pub fn identifiers(&self) -> &[String] {
+        &self.identifiers
+    }
+
+    /// Details of the operator, e.g. its `EstimatedRows`.
+    pub fn arguments(&self) -> &HashMap<String, Value> {
+        &self.arguments
+    }
+
+    /// The operators that produce the input of this one.
+    pub fn children(&self) -> &[Plan] {
+        &self.children
+    }
+
+    /// The number of times the operator accessed the database, in a profiled plan.
+    pub fn db_hits(&self) -> Option<i64> {
+        self.db_hits
+    }
+
+    /// The number of rows the operator produced, in a profiled plan.
+    pub fn rows(&self) -> Option<i64> {
+        self.rows
+    }
+
+    pub fn page_cache_hits(&self) -> Option<i64> {
+        self.page_cache_hits
+    }
+
+    pub fn page_cache_misses(&self) -> Option<i64> {
+        self.page_cache_misses
+    }
+}
+
+/// A warning or piece of information about a query, e.g. that it uses a deprecated feature. Listed under
+/// `notifications` in the metadata at the end of its result.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Notification {
+    pub(crate) code: String,
+    pub(crate) title: String,
+    pub(crate) description: String,
+    pub(crate) position: Option<Position>,
+    pub(crate) severity: Severity,
+    pub(crate) category: NotificationCategory,
+}
+
+impl Notification {
+    // Parses the "notifications" entry of a SUCCESS message's metadata, which is omitted if there are none
+    pub(crate) fn from_metadata(metadata: &HashMap<String, Value>) -> Result<Vec<Self>> {
+        let notifications: Vec<HashMap<String, Value>> =
+            get_opt_property(metadata, "notifications")?.unwrap_or_default();
+        notifications.iter().map(Self::parse).collect()
+    }
+
+    fn parse(notification: &HashMap<String, Value>) -> Result<Self> {
+        let position = match get_opt_property::<HashMap<String, Value>>(notification, "position")? {
+            Some(position) => Some(Position {
+                offset: get_property(&position, "offset")?,
+                line: get_property(&position, "line")?,
+                column: get_property(&position, "column")?,
+            }),
+            None => None,
+        };
+        let severity: Option<String> = get_opt_property(notification, "severity")?;
+        let category: Option<String> = get_opt_property(notification, "category")?;
+        Ok(Self {
+            code: get_property(notification, "code")?,
+            title: get_opt_property(notification, "title")?.unwrap_or_default(),
+            description: get_opt_property(notification, "description")?.unwrap_or_default(),
+            position,
+            severity: match severity.as_deref() {
+                Some("WARNING") => Severity::Warning,
+                Some("INFORMATION") => Severity::Information,
+                _ => Severity::Unknown,
+            },
+            category: match category.as_deref() {
+                Some("HINT") => NotificationCategory::Hint,
+                Some("UNRECOGNIZED") => NotificationCategory::Unrecognized,
+                Some("UNSUPPORTED") => NotificationCategory::Unsupported,
+                Some("PERFORMANCE") => NotificationCategory::Performance,
+                Some("DEPRECATION") => NotificationCategory::Deprecation,
+                Some("SECURITY") => NotificationCategory::Security,
+                Some("TOPOLOGY") => NotificationCategory::Topology,
+                Some("SCHEMA") => NotificationCategory::Schema,
+                Some("GENERIC") => NotificationCategory::Generic,
+                _ => NotificationCategory::Unknown,
+            },
+        })
+    }
+
+    /// The status code of the notification, e.g. `Neo.ClientNotification.Statement.FeatureDeprecationWarning`.
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Where in the query the notification applies, if anywhere in particular.
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// The category of the notification. Only sent by Neo4j 5+ servers.
+    pub fn category(&self) -> NotificationCategory {
+        self.category
+    }
+}
+
+/// A position in the text of a query, where the offset starts at 0, and the line and column start at 1.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Position {
+    pub(crate) offset: i64,
+    pub(crate) line: i64,
+    pub(crate) column: i64,
+}
+
+impl Position {
+    pub fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    pub fn line(&self) -> i64 {
+        self.line
+    }
+
+    pub fn column(&self) -> i64 {
+        self.column
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Severity {
+    Warning,
+    Information,
+    /// The severity is missing or not one of the above.
+    Unknown,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum NotificationCategory {
+    Hint,
+    Unrecognized,
+    Unsupported,
+    Performance,
+    Deprecation,
+    Security,
+    Topology,
+    Schema,
+    Generic,
+    /// The category is missing or not one of the above.
+    Unknown,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::FromIterator;
+
+    use crate::message::Success;
+
+    use super::*;
+
+    fn stats(entries: Vec<(&str, Value)>) -> Success {
+        let stats = entries
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect::<HashMap<_, _>>();
+        Success::new(HashMap::from_iter(vec![
+            ("type".to_string(), Value::from("w")),
+            ("stats".to_string(), Value::from(stats)),
+        ]))
+    }
+
+    #[test]
+    fn counters() {
+        let success = stats(vec![
+            ("nodes-created", Value::from(2)),
+            ("properties-set", Value::from(3)),
+            ("labels-added", Value::from(2)),
+        ]);
+        let counters = success.counters().unwrap();
+        assert_eq!(counters.nodes_created(), 2);
+        assert_eq!(counters.properties_set(), 3);
+        assert_eq!(counters.labels_added(), 2);
+        assert_eq!(counters.relationships_created(), 0);
+        assert!(counters.contains_updates());
+        assert!(!counters.contains_system_updates());
+        assert_eq!(success.query_type().unwrap(), Some(QueryType::WriteOnly));
+
+        let counters = stats(vec![("system-updates", Value::from(1))])
+            .counters()
+            .unwrap();
+        assert!(!counters.contains_updates());
+        assert!(counters.contains_system_updates());
+
+        // The server's flags take precedence
+        let counters = stats(vec![
+            ("contains-updates", Value::from(true)),
+            ("contains-system-updates", Value::from(false)),
+            ("system-updates", Value::from(1)),
+        ])
+        .counters()
+        .unwrap();
+        assert!(counters.contains_updates());
+        assert!(!counters.contains_system_updates());
+
+        let success = Success::new(HashMap::new());
+        assert_eq!(success.counters().unwrap(), SummaryCounters::default());
+        assert_eq!(success.query_type().unwrap(), None);
+    }
+
+    #[test]
+    fn invalid_counters() {
+        assert!(stats(vec![("nodes-created", Value::from("2"))])
+            .counters()
+            .is_err());
+        let success = Success::new(HashMap::from_iter(vec![(
+            "type".to_string(),
+            Value::from("x"),
+        )]));
+        assert!(matches!(
+            success.query_type(),
+            Err(Error::ConversionError(ConversionError::InvalidProperty { key, value }))
+                if key == "type" && *value == Value::from("x")
+        ));
+    }
+
+    fn operator(operator_type: &str, children: Vec<Value>, db_hits: Option<i64>) -> Value {
+        let mut plan = HashMap::from_iter(vec![
+            ("operatorType".to_string(), Value::from(operator_type)),
+            ("identifiers".to_string(), Value::from(vec!["n"])),
+            (
+                "args".to_string(),
+                Value::from(HashMap::from_iter(vec![(
+                    "EstimatedRows".to_string(),
+                    Value::from(10),
+                )])),
+            ),
+            ("children".to_string(), Value::from(children)),
+        ]);
+        if let Some(db_hits) = db_hits {
+            plan.insert("dbHits".to_string(), Value::from(db_hits));
+            plan.insert("rows".to_string(), Value::from(10));
+        }
+        Value::from(plan)
+    }
+
+    #[test]
+    fn plan() {
+        let success = Success::new(HashMap::from_iter(vec![(
+            "plan".to_string(),
+            operator(
+                "ProduceResults",
+                vec![operator("NodeByLabelScan", vec![], None)],
+                None,
+            ),
+        )]));
+        let plan = success.plan().unwrap().unwrap();
+        assert_eq!(plan.operator_type(), "ProduceResults");
+        assert_eq!(plan.identifiers(), &["n"]);
+        assert_eq!(
+            plan.arguments().get("EstimatedRows"),
+            Some(&Value::from(10))
+        );
+        assert_eq!(plan.children().len(), 1);
+        assert_eq!(plan.children()[0].operator_type(), "NodeByLabelScan");
+        assert!(plan.children()[0].children().is_empty());
+        assert_eq!(plan.db_hits(), None);
+        assert_eq!(success.profile().unwrap(), None);
+
+        let success = Success::new(HashMap::from_iter(vec![(
+            "profile".to_string(),
+            operator(
+                "ProduceResults",
+                vec![operator("NodeByLabelScan", vec![], Some(11))],
+                Some(0),
+            ),
+        )]));
+        let profile = success.profile().unwrap().unwrap();
+        assert_eq!(profile.db_hits(), Some(0));
+        assert_eq!(profile.children()[0].db_hits(), Some(11));
+        assert_eq!(profile.children()[0].rows(), Some(10));
+        assert_eq!(profile.page_cache_hits(), None);
+        assert_eq!(success.plan().unwrap(), None);
+
+        let success = Success::new(HashMap::from_iter(vec![(
+            "plan".to_string(),
+            operator("ProduceResults", vec![Value::from("not a plan")], None),
+        )]));
+        assert!(success.plan().is_err());
+    }
+
+    #[test]
+    fn notifications() {
+        let success = Success::new(HashMap::from_iter(vec![(
+            "notifications".to_string(),
+            Value::from(vec![
+                Value::from(HashMap::from_iter(vec![
+                    (
+                        "code".to_string(),
+                        Value::from("Neo.ClientNotification.Statement.CartesianProduct"),
+                    ),
+                    ("title".to_string(), Value::from("Cartesian product")),
+                    ("description".to_string(), Value::from("Avoid it")),
+                    ("severity".to_string(), Value::from("INFORMATION")),
+                    ("category".to_string(), Value::from("PERFORMANCE")),
+                    (
+                        "position".to_string(),
+                        Value::from(HashMap::from_iter(vec![
+                            ("offset".to_string(), Value::from(0)),
+                            ("line".to_string(), Value::from(1)),
+                            ("column".to_string(), Value::from(1)),
+                        ])),
+                    ),
+                ])),
+                Value::from(HashMap::from_iter(vec![
+                    (
+                        "code".to_string(),
+                        Value::from("Neo.ClientNotification.Statement.FeatureDeprecationWarning"),
+                    ),
+                    ("severity".to_string(), Value::from("WARNING")),
+                ])),
+            ]),
+        )]));
+        let notifications = success.notifications().unwrap();
+        assert_eq!(notifications.len(), 2);
+        let notification = &notifications[0];
+        assert_eq!(
+            notification.code(),
+            "Neo.ClientNotification.Statement.CartesianProduct"
+        );
+        assert_eq!(notification.title(), "Cartesian product");
+        assert_eq!(notification.description(), "Avoid it");
+        assert_eq!(notification.severity(), Severity::Information);
+        assert_eq!(notification.category(), NotificationCategory::Performance);
+        let position = notification.position().unwrap();
+        assert_eq!(
+            (position.offset(), position.line(), position.column()),
+            (0, 1, 1)
+        );
+
+        let notification = &notifications[1];
+        assert_eq!(notification.severity(), Severity::Warning);
+        assert_eq!(notification.category(), NotificationCategory::Unknown);
+        assert_eq!(notification.title(), "");
+        assert_eq!(notification.position(), None);
+
+        assert!(Success::new(HashMap::new())
+            .notifications()
+            .unwrap()
+            .is_empty());
+        let success = Success::new(HashMap::from_iter(vec![(
+            "notifications".to_string(),
+            Value::from(vec![Value::from(HashMap::<String, Value>::new())]),
+        )]));
+        assert!(success.notifications().is_err());
+    }
+}
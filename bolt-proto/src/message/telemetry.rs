@@ -0,0 +1,69 @@
+use bolt_proto_derive::*;
+
+use crate::impl_try_from_message;
+
+pub(crate) const MARKER: u8 = 0xB1;
+pub(crate) const SIGNATURE: u8 = 0x54;
+
+/// A message sent by Bolt v5.4+ clients to report which driver API was used, if the server has telemetry enabled.
+#[derive(Debug, Clone, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+pub struct Telemetry {
+    pub(crate) api: i64,
+}
+
+impl Telemetry {
+    pub fn new(api: i64) -> Self {
+        Self { api }
+    }
+
+    pub fn api(&self) -> i64 {
+        self.api
+    }
+}
+
+impl_try_from_message!(Telemetry, Telemetry);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::sync::{Arc, Mutex};
+
+    use bytes::Bytes;
+
+    use crate::serialization::*;
+
+    use super::*;
+
+    fn new_msg() -> Telemetry {
+        Telemetry::new(2)
+    }
+
+    #[test]
+    fn get_marker() {
+        assert_eq!(new_msg().get_marker().unwrap(), MARKER);
+    }
+
+    #[test]
+    fn get_signature() {
+        assert_eq!(new_msg().get_signature(), SIGNATURE);
+    }
+
+    #[test]
+    fn try_into_bytes() {
+        let msg = new_msg();
+        assert_eq!(
+            msg.try_into_bytes().unwrap(),
+            Bytes::from_static(&[MARKER, SIGNATURE, 0x02])
+        );
+    }
+
+    #[test]
+    fn try_from_bytes() {
+        let msg = new_msg();
+        let msg_bytes = &[0x02];
+        assert_eq!(
+            Telemetry::try_from(Arc::new(Mutex::new(Bytes::from_static(msg_bytes)))).unwrap(),
+            msg
+        );
+    }
+}
@@ -0,0 +1,313 @@
+//! Reading and writing PackStream, the binary format Bolt messages are serialized in, one value or container header at
+//! a time. This is for projects that build on the format itself (servers, proxies, traffic analyzers) without going
+//! through [`Value`] and [`Message`](crate::Message).
+//!
+//! Writers append to any [`BufMut`]. Readers consume from the start of a [`Bytes`], and leave it untouched if they
+//! return an error. Lists, maps, and structures are read and written as a header giving their size, followed by that
+//! many elements (twice as many for the keys and values of a map, and preceded by the signature for a structure).
+//!
+//! # Example
+//! ```
+//! use bolt_proto::packstream::*;
+//! use bytes::BytesMut;
+//!
+//! let mut buf = BytesMut::new();
+//! write_map_header(&mut buf, 1).unwrap();
+//! write_string(&mut buf, "ids").unwrap();
+//! write_list_header(&mut buf, 2).unwrap();
+//! write_int(&mut buf, 1);
+//! write_int(&mut buf, 1_000);
+//!
+//! let mut bytes = buf.freeze();
+//! assert_eq!(peek_kind(&bytes).unwrap(), Kind::Map);
+//! assert_eq!(read_map_header(&mut bytes).unwrap(), 1);
+//! assert_eq!(read_string(&mut bytes).unwrap(), "ids");
+//! assert_eq!(read_list_header(&mut bytes).unwrap(), 2);
+//! assert_eq!(read_int(&mut bytes).unwrap(), 1);
+//! assert_eq!(read_int(&mut bytes).unwrap(), 1_000);
+//! assert!(bytes.is_empty());
+//! ```
+
+use bytes::{BufMut, Bytes};
+
+pub use crate::serialization::{Deserialize, Marker, Serialize, Signature};
+
+use crate::error::*;
+use crate::serialization::*;
+use crate::value::*;
+
+/// The kind of value that follows, as determined by its marker byte, see [`peek_kind`].
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum Kind {
+    Null,
+    Boolean,
+    Integer,
+    Float,
+    Bytes,
+    String,
+    List,
+    Map,
+    Structure,
+}
+
+/// The kind of the next value, without consuming anything. Returns [`DeserializationError::InvalidMarkerByte`] if
+/// its marker isn't valid.
+pub fn peek_kind(bytes: &Bytes) -> Result<Kind> {
+    let marker = bytes.peek_u8("marker")?;
+    let kind = match marker {
+        null::MARKER => Kind::Null,
+        boolean::MARKER_FALSE | boolean::MARKER_TRUE => Kind::Boolean,
+        marker if (-16..=127).contains(&(marker as i8)) => Kind::Integer,
+        integer::MARKER_INT_8
+        | integer::MARKER_INT_16
+        | integer::MARKER_INT_32
+        | integer::MARKER_INT_64 => Kind::Integer,
+        float::MARKER => Kind::Float,
+        byte_array::MARKER_SMALL | byte_array::MARKER_MEDIUM | byte_array::MARKER_LARGE => {
+            Kind::Bytes
+        }
+        marker if is_sized_marker(marker, string::MARKERS) => Kind::String,
+        marker if is_sized_marker(marker, list::MARKERS) => Kind::List,
+        marker if is_sized_marker(marker, map::MARKERS) => Kind::Map,
+        marker if (STRUCT_MARKER_TINY..=(STRUCT_MARKER_TINY | 0x0F)).contains(&marker) => {
+            Kind::Structure
+        }
+        STRUCT_MARKER_SMALL | STRUCT_MARKER_MEDIUM => Kind::Structure,
+        _ => return Err(DeserializationError::InvalidMarkerByte(marker).into()),
+    };
+    Ok(kind)
+}
+
+fn is_sized_marker(marker: u8, [tiny, small, medium, large]: [u8; 4]) -> bool {
+    (tiny..=(tiny | 0x0F)).contains(&marker)
+        || marker == small
+        || marker == medium
+        || marker == large
+}
+
+pub fn write_null(buf: &mut impl BufMut) {
+    buf.put_u8(null::MARKER);
+}
+
+pub fn write_bool(buf: &mut impl BufMut, value: bool) {
+    buf.put_u8(if value {
+        boolean::MARKER_TRUE
+    } else {
+        boolean::MARKER_FALSE
+    });
+}
+
+/// Write an integer in the smallest encoding that fits it.
+pub fn write_int(buf: &mut impl BufMut, value: i64) {
+    // Integers of every size can be serialized
+    let _ = value.serialize_field(buf);
+}
+
+pub fn write_float(buf: &mut impl BufMut, value: f64) {
+    let _ = value.serialize_field(buf);
+}
+
+/// Write a byte array. Returns [`Error::ValueTooLarge`] if it is longer than `u32::MAX` bytes.
+pub fn write_bytes(buf: &mut impl BufMut, value: &[u8]) -> Result<()> {
+    byte_array::put_byte_array(buf, value)
+}
+
+/// Write a string. Returns [`Error::ValueTooLarge`] if it is longer than `u32::MAX` bytes.
+pub fn write_string(buf: &mut impl BufMut, value: &str) -> Result<()> {
+    value.serialize_field(buf)
+}
+
+/// Write the header of a list with the given number of elements, which must be written after it. Returns
+/// [`Error::ValueTooLarge`] if there are more than `u32::MAX` elements.
+pub fn write_list_header(buf: &mut impl BufMut, size: usize) -> Result<()> {
+    put_sized_header(buf, size, list::MARKERS)
+}
+
+/// Write the header of a map with the given number of entries, whose keys and values must be written after it,
+/// alternating between them. Keys must be strings. Returns [`Error::ValueTooLarge`] if there are more than `u32::MAX`
+/// entries.
+pub fn write_map_header(buf: &mut impl BufMut, size: usize) -> Result<()> {
+    put_sized_header(buf, size, map::MARKERS)
+}
+
+/// Write the header of a structure with the given number of fields and signature, whose fields must be written after
+/// it. Returns [`Error::ValueTooLarge`] if there are more than `u16::MAX` fields.
+pub fn write_structure_header(
+    buf: &mut impl BufMut,
+    field_count: usize,
+    signature: u8,
+) -> Result<()> {
+    match field_count {
+        0..=15 => buf.put_u8(STRUCT_MARKER_TINY | field_count as u8),
+        16..=255 => {
+            buf.put_u8(STRUCT_MARKER_SMALL);
+            buf.put_u8(field_count as u8);
+        }
+        256..=65_535 => {
+            buf.put_u8(STRUCT_MARKER_MEDIUM);
+            buf.put_u16(field_count as u16);
+        }
+        _ => return Err(Error::ValueTooLarge(field_count)),
+    }
+    buf.put_u8(signature);
+    Ok(())
+}
+
+// Reads with the given deserializer, restoring the input if it fails
+fn read<T>(bytes: &mut Bytes, f: impl FnOnce(&mut Bytes) -> Result<T>) -> Result<T> {
+    let mut input = bytes.clone();
+    let value = f(&mut input)?;
+    *bytes = input;
+    Ok(value)
+}
+
+pub fn read_null(bytes: &mut Bytes) -> Result<()> {
+    read(bytes, |bytes| Null::deserialize(bytes).map(|_| ()))
+}
+
+pub fn read_bool(bytes: &mut Bytes) -> Result<bool> {
+    read(bytes, |bytes| Ok(Boolean::deserialize(bytes)?.value))
+}
+
+pub fn read_int(bytes: &mut Bytes) -> Result<i64> {
+    read(bytes, |bytes| Ok(Integer::deserialize(bytes)?.value))
+}
+
+pub fn read_float(bytes: &mut Bytes) -> Result<f64> {
+    read(bytes, |bytes| Ok(Float::deserialize(bytes)?.value))
+}
+
+/// Read a byte array, as a slice of the input rather than a copy.
+pub fn read_bytes(bytes: &mut Bytes) -> Result<Bytes> {
+    read(bytes, |bytes| Ok(ByteArray::deserialize(bytes)?.value))
+}
+
+/// Read a string, returning [`DeserializationError::InvalidUTF8`] if it isn't valid UTF-8.
+pub fn read_string(bytes: &mut Bytes) -> Result<std::string::String> {
+    read(bytes, |bytes| String::deserialize(bytes)?.into_string())
+}
+
+/// Read the header of a list, returning its number of elements.
+pub fn read_list_header(bytes: &mut Bytes) -> Result<usize> {
+    read(bytes, |bytes| read_sized_header(bytes, list::MARKERS))
+}
+
+/// Read the header of a map, returning its number of entries.
+pub fn read_map_header(bytes: &mut Bytes) -> Result<usize> {
+    read(bytes, |bytes| read_sized_header(bytes, map::MARKERS))
+}
+
+/// Read the header of a structure, returning its number of fields and its signature.
+pub fn read_structure_header(bytes: &mut Bytes) -> Result<(usize, u8)> {
+    read(bytes, |bytes| {
+        let (_marker, field_count, signature) = get_info_from_bytes(bytes)?;
+        Ok((field_count, signature))
+    })
+}
+
+fn read_sized_header(bytes: &mut Bytes, [tiny, small, medium, large]: [u8; 4]) -> Result<usize> {
+    let marker = bytes.try_get_u8("marker")?;
+    match marker {
+        marker if (tiny..=(tiny | 0x0F)).contains(&marker) => Ok(0x0F & marker as usize),
+        marker if marker == small => Ok(bytes.try_get_u8("size")? as usize),
+        marker if marker == medium => Ok(bytes.try_get_u16("size")? as usize),
+        marker if marker == large => Ok(bytes.try_get_u32("size")? as usize),
+        _ => Err(DeserializationError::InvalidMarkerByte(marker).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::iter::FromIterator;
+
+    use bytes::BytesMut;
+
+    use super::*;
+
+    #[test]
+    fn matches_value_serialization() {
+        let mut buf = BytesMut::new();
+        write_list_header(&mut buf, 8).unwrap();
+        write_null(&mut buf);
+        write_bool(&mut buf, true);
+        write_int(&mut buf, -1_000_000);
+        write_float(&mut buf, 1.5);
+        write_bytes(&mut buf, &[1, 2, 3]).unwrap();
+        write_string(&mut buf, &"a".repeat(300)).unwrap();
+        write_map_header(&mut buf, 1).unwrap();
+        write_string(&mut buf, "key").unwrap();
+        write_int(&mut buf, 1);
+        write_structure_header(&mut buf, 3, point_2d::SIGNATURE).unwrap();
+        write_int(&mut buf, 7203);
+        write_float(&mut buf, 1.0);
+        write_float(&mut buf, 2.0);
+
+        let value = Value::from(vec![
+            Value::Null,
+            Value::from(true),
+            Value::from(-1_000_000),
+            Value::from(1.5),
+            Value::from(vec![1_u8, 2, 3]),
+            Value::from("a".repeat(300)),
+            Value::from(HashMap::<_, Value>::from_iter(vec![(
+                "key".to_string(),
+                Value::from(1),
+            )])),
+            Value::from(Point2D::new(7203, 1.0, 2.0)),
+        ]);
+        assert_eq!(buf.freeze(), value.try_into_bytes().unwrap());
+    }
+
+    #[test]
+    fn read_values() {
+        let mut bytes = Value::from(vec![
+            Value::Null,
+            Value::from(false),
+            Value::from(i64::MIN),
+            Value::from(-0.5),
+            Value::from(vec![0_u8; 300]),
+            Value::from("string"),
+            Value::from(HashMap::<std::string::String, Value>::new()),
+            Value::from(Point2D::new(7203, 1.0, 2.0)),
+        ])
+        .try_into_bytes()
+        .unwrap();
+
+        assert_eq!(peek_kind(&bytes).unwrap(), Kind::List);
+        assert_eq!(read_list_header(&mut bytes).unwrap(), 8);
+        assert_eq!(peek_kind(&bytes).unwrap(), Kind::Null);
+        read_null(&mut bytes).unwrap();
+        assert!(!read_bool(&mut bytes).unwrap());
+        assert_eq!(read_int(&mut bytes).unwrap(), i64::MIN);
+        assert_eq!(read_float(&mut bytes).unwrap(), -0.5);
+        assert_eq!(read_bytes(&mut bytes).unwrap(), vec![0_u8; 300]);
+        assert_eq!(peek_kind(&bytes).unwrap(), Kind::String);
+        assert_eq!(read_string(&mut bytes).unwrap(), "string");
+        assert_eq!(read_map_header(&mut bytes).unwrap(), 0);
+        assert_eq!(peek_kind(&bytes).unwrap(), Kind::Structure);
+        assert_eq!(
+            read_structure_header(&mut bytes).unwrap(),
+            (3, point_2d::SIGNATURE)
+        );
+        assert_eq!(read_int(&mut bytes).unwrap(), 7203);
+        assert_eq!(read_float(&mut bytes).unwrap(), 1.0);
+        assert_eq!(read_float(&mut bytes).unwrap(), 2.0);
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn failed_reads() {
+        let mut bytes = Bytes::from_static(&[list::MARKER_SMALL]);
+        assert!(read_list_header(&mut bytes).is_err());
+        assert!(read_map_header(&mut bytes).is_err());
+        // Nothing is consumed by a failed read
+        assert_eq!(bytes.len(), 1);
+        assert!(matches!(
+            peek_kind(&Bytes::from_static(&[0xC4])),
+            Err(Error::DeserializationError(
+                DeserializationError::InvalidMarkerByte(0xC4)
+            ))
+        ));
+    }
+}
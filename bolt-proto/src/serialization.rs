@@ -1,43 +1,702 @@
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
+use std::mem;
 use std::sync::{Arc, Mutex};
+use std::thread::LocalKey;
 
-use bytes::{Buf, Bytes};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 
 use crate::error::*;
+use crate::value::integer::Integer;
+use crate::value::{float, list, string};
+use crate::{Value, Version};
 
 pub trait Serialize: TryInto<Bytes, Error = Error> {
     fn try_into_bytes(self) -> Result<Bytes> {
         self.try_into()
     }
+
+    /// Serialize into the given buffer, after anything already in it, so that several values or a whole message can be
+    /// encoded into one reusable buffer. If an error is returned, part of the value may have been written already.
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()>;
+}
+
+// Serializes into a buffer of exactly the right size, for the TryInto<Bytes> conversions
+pub(crate) fn serialize_to_bytes<T: Marker>(value: &T) -> Result<Bytes> {
+    let mut bytes = BytesMut::with_capacity(value.serialized_len()?);
+    value.serialize_into(&mut bytes)?;
+    Ok(bytes.freeze())
+}
+
+pub trait Deserialize: Sized {
+    /// Deserialize a value from the start of the given bytes, advancing them past it, so that anything after it is
+    /// left for the caller. The known graph and temporal structures expect their marker and signature to have been
+    /// read already, since the signature is what picks the type; deserialize a [`Value`] to read one in full.
+    fn deserialize(bytes: &mut Bytes) -> Result<Self>;
+
+    /// Deserialize a value that must take up all of the given bytes, returning
+    /// [`DeserializationError::TrailingBytes`] if any are left over. This is useful for validating fixtures and
+    /// detecting framing bugs.
+    ///
+    /// # Example
+    /// ```
+    /// use bolt_proto::{Deserialize, Value};
+    /// use bytes::Bytes;
+    ///
+    /// assert_eq!(Value::try_from_bytes_exact(Bytes::from_static(&[0x01])).unwrap(), Value::from(1));
+    /// assert!(Value::try_from_bytes_exact(Bytes::from_static(&[0x01, 0x02])).is_err());
+    /// ```
+    fn try_from_bytes_exact(mut bytes: Bytes) -> Result<Self> {
+        let value = Self::deserialize(&mut bytes)?;
+        if bytes.is_empty() {
+            Ok(value)
+        } else {
+            Err(DeserializationError::TrailingBytes(bytes.len()).into())
+        }
+    }
+}
+
+// Builds a structure from its fields once they have been deserialized, so that nested values can be read without
+// recursion
+pub(crate) trait FromFields: Sized {
+    fn from_fields(fields: Vec<Value>) -> Result<Self>;
+}
+
+pub(crate) fn into_field_array<const N: usize>(fields: Vec<Value>) -> Result<[Value; N]> {
+    <[Value; N]>::try_from(fields).map_err(|fields| Error::InvalidFieldCount {
+        expected: N,
+        actual: fields.len(),
+    })
+}
+
+// Deserializes from bytes shared behind a mutex, for the legacy `TryFrom<Arc<Mutex<Bytes>>>` conversions
+pub(crate) fn deserialize_shared<T: Deserialize>(input_arc: Arc<Mutex<Bytes>>) -> Result<T> {
+    let mut bytes = input_arc
+        .lock()
+        .map_err(|_| DeserializationError::Panicked)?;
+    T::deserialize(&mut bytes)
 }
 
-pub trait Deserialize: TryFrom<Arc<Mutex<Bytes>>, Error = Error> {}
+// Reads that check the remaining length first, returning DeserializationError::UnexpectedEnd with a description of
+// what was being read instead of panicking at the end of the input
+pub(crate) trait CheckedBuf: Buf {
+    fn ensure_remaining(&self, needed: usize, reading: &'static str) -> Result<()> {
+        let remaining = self.remaining();
+        if remaining < needed {
+            return Err(DeserializationError::UnexpectedEnd {
+                reading,
+                needed,
+                remaining,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    fn peek_u8(&self, reading: &'static str) -> Result<u8> {
+        self.ensure_remaining(1, reading)?;
+        Ok(self.bytes()[0])
+    }
+
+    fn try_get_u8(&mut self, reading: &'static str) -> Result<u8> {
+        self.ensure_remaining(1, reading)?;
+        Ok(self.get_u8())
+    }
+
+    fn try_get_u16(&mut self, reading: &'static str) -> Result<u16> {
+        self.ensure_remaining(2, reading)?;
+        Ok(self.get_u16())
+    }
+
+    fn try_get_u32(&mut self, reading: &'static str) -> Result<u32> {
+        self.ensure_remaining(4, reading)?;
+        Ok(self.get_u32())
+    }
+
+    fn try_get_i8(&mut self, reading: &'static str) -> Result<i8> {
+        self.ensure_remaining(1, reading)?;
+        Ok(self.get_i8())
+    }
+
+    fn try_get_i16(&mut self, reading: &'static str) -> Result<i16> {
+        self.ensure_remaining(2, reading)?;
+        Ok(self.get_i16())
+    }
+
+    fn try_get_i32(&mut self, reading: &'static str) -> Result<i32> {
+        self.ensure_remaining(4, reading)?;
+        Ok(self.get_i32())
+    }
+
+    fn try_get_i64(&mut self, reading: &'static str) -> Result<i64> {
+        self.ensure_remaining(8, reading)?;
+        Ok(self.get_i64())
+    }
+
+    fn try_get_f64(&mut self, reading: &'static str) -> Result<f64> {
+        self.ensure_remaining(8, reading)?;
+        Ok(self.get_f64())
+    }
+}
+
+impl<B: Buf> CheckedBuf for B {}
 
 pub trait Marker: Serialize + Deserialize {
     fn get_marker(&self) -> Result<u8>;
+
+    /// The number of bytes this serializes to, computed without serializing it, e.g. to allocate a buffer up front or
+    /// to check a size limit before encoding. Returns the same errors serializing would for values that are too large.
+    fn serialized_len(&self) -> Result<usize>;
+}
+
+// How a field of a structure is serialized, without converting it to a Value first
+pub(crate) trait Field {
+    fn field_len(&self) -> Result<usize>;
+
+    fn serialize_field(&self, buf: &mut impl BufMut) -> Result<()>;
+}
+
+impl<T: Marker> Field for T {
+    fn field_len(&self) -> Result<usize> {
+        self.serialized_len()
+    }
+
+    fn serialize_field(&self, buf: &mut impl BufMut) -> Result<()> {
+        self.serialize_into(buf)
+    }
+}
+
+impl Field for i64 {
+    fn field_len(&self) -> Result<usize> {
+        Integer { value: *self }.serialized_len()
+    }
+
+    fn serialize_field(&self, buf: &mut impl BufMut) -> Result<()> {
+        Integer { value: *self }.serialize_into(buf)
+    }
+}
+
+impl Field for i32 {
+    fn field_len(&self) -> Result<usize> {
+        i64::from(*self).field_len()
+    }
+
+    fn serialize_field(&self, buf: &mut impl BufMut) -> Result<()> {
+        i64::from(*self).serialize_field(buf)
+    }
+}
+
+impl Field for f64 {
+    fn field_len(&self) -> Result<usize> {
+        // Marker byte, then the 64-bit value
+        Ok(mem::size_of::<u8>() + mem::size_of::<f64>())
+    }
+
+    fn serialize_field(&self, buf: &mut impl BufMut) -> Result<()> {
+        buf.put_u8(float::MARKER);
+        buf.put_f64(*self);
+        Ok(())
+    }
+}
+
+// Strings are serialized straight from their bytes, whether they are fields or values
+impl Field for str {
+    fn field_len(&self) -> Result<usize> {
+        Ok(sized_header_len(self.len())? + self.len())
+    }
+
+    fn serialize_field(&self, buf: &mut impl BufMut) -> Result<()> {
+        put_sized_header(buf, self.len(), string::MARKERS)?;
+        buf.put_slice(self.as_bytes());
+        Ok(())
+    }
+}
+
+impl Field for std::string::String {
+    fn field_len(&self) -> Result<usize> {
+        self.as_str().field_len()
+    }
+
+    fn serialize_field(&self, buf: &mut impl BufMut) -> Result<()> {
+        self.as_str().serialize_field(buf)
+    }
+}
+
+impl<T: Field> Field for Vec<T> {
+    fn field_len(&self) -> Result<usize> {
+        self.iter()
+            .try_fold(sized_header_len(self.len())?, |len, value| {
+                Ok(len + value.field_len()?)
+            })
+    }
+
+    fn serialize_field(&self, buf: &mut impl BufMut) -> Result<()> {
+        put_sized_header(buf, self.len(), list::MARKERS)?;
+        for value in self {
+            value.serialize_field(buf)?;
+        }
+        Ok(())
+    }
+}
+
+impl Field for HashMap<std::string::String, Value> {
+    fn field_len(&self) -> Result<usize> {
+        self.iter()
+            .try_fold(sized_header_len(self.len())?, |len, (key, value)| {
+                Ok(len + key.field_len()? + value.serialized_len()?)
+            })
+    }
+
+    fn serialize_field(&self, buf: &mut impl BufMut) -> Result<()> {
+        crate::value::map::serialize_entries(self, buf)
+    }
+}
+
+// The marker of a string, list, or map with the given size, from its tiny, small, medium, and large markers
+pub(crate) fn sized_marker(size: usize, [tiny, small, medium, large]: [u8; 4]) -> Result<u8> {
+    match size {
+        0..=15 => Ok(tiny | size as u8),
+        16..=255 => Ok(small),
+        256..=65_535 => Ok(medium),
+        65_536..=4_294_967_295 => Ok(large),
+        _ => Err(Error::ValueTooLarge(size)),
+    }
+}
+
+// Writes the marker of a string, list, or map with the given size, followed by the size unless it fits in the marker
+pub(crate) fn put_sized_header(buf: &mut impl BufMut, size: usize, markers: [u8; 4]) -> Result<()> {
+    buf.put_u8(sized_marker(size, markers)?);
+    match size {
+        0..=15 => {}
+        16..=255 => buf.put_u8(size as u8),
+        256..=65_535 => buf.put_u16(size as u16),
+        _ => buf.put_u32(size as u32),
+    }
+    Ok(())
+}
+
+// The length of the marker and size of a string, list, or map with the given size
+pub(crate) fn sized_header_len(size: usize) -> Result<usize> {
+    match size {
+        0..=15 => Ok(mem::size_of::<u8>()),
+        16..=255 => Ok(mem::size_of::<u8>() * 2),
+        256..=65_535 => Ok(mem::size_of::<u8>() + mem::size_of::<u16>()),
+        65_536..=4_294_967_295 => Ok(mem::size_of::<u8>() + mem::size_of::<u32>()),
+        _ => Err(Error::ValueTooLarge(size)),
+    }
 }
 
 pub trait Signature: Marker {
     fn get_signature(&self) -> u8;
 }
 
+thread_local! {
+    static STRICT_DECODING: Cell<bool> = const { Cell::new(false) };
+    static UTC_ENCODING: Cell<bool> = const { Cell::new(false) };
+    static DETERMINISTIC_ENCODING: Cell<bool> = const { Cell::new(false) };
+    static DESERIALIZE_CONFIG: Cell<DeserializeConfig> = const { Cell::new(DeserializeConfig::new()) };
+    static ENCODE_VERSION: Cell<Option<Version>> = const { Cell::new(None) };
+}
+
+// Runs the closure with the given value in a thread-local, restoring its previous value afterwards. This backs all of
+// the modes that apply to the current thread.
+pub(crate) fn with_scoped<V: 'static, T>(
+    key: &'static LocalKey<Cell<V>>,
+    value: V,
+    f: impl FnOnce() -> T,
+) -> T {
+    // Restores the previous value even if the closure panics
+    struct Reset<V: 'static>(&'static LocalKey<Cell<V>>, Option<V>);
+    impl<V> Drop for Reset<V> {
+        fn drop(&mut self) {
+            if let Some(previous) = self.1.take() {
+                self.0.with(|current| current.set(previous));
+            }
+        }
+    }
+
+    let _reset = Reset(key, Some(key.with(|current| current.replace(value))));
+    f()
+}
+
+/// Run the given closure with strict decoding enabled on the current thread, which rejects values that use a wider
+/// encoding than necessary (e.g. an `INT_16` marker for an integer that fits in a tiny int). Decoding is lenient by
+/// default, since such encodings are valid, just wasteful.
+///
+/// This is intended for conformance testing of servers and proxies.
+///
+/// # Example
+/// ```
+/// use bolt_proto::{decode_strict, Deserialize, Value};
+/// use bytes::Bytes;
+///
+/// // 5, encoded as an INT_16
+/// let bytes = Bytes::from_static(&[0xC9, 0x00, 0x05]);
+/// assert!(Value::deserialize(&mut bytes.clone()).is_ok());
+/// assert!(decode_strict(|| Value::deserialize(&mut bytes.clone())).is_err());
+/// ```
+pub fn decode_strict<T>(f: impl FnOnce() -> T) -> T {
+    with_scoped(&STRICT_DECODING, true, f)
+}
+
+pub(crate) fn is_strict_decoding() -> bool {
+    STRICT_DECODING.with(Cell::get)
+}
+
+/// Run the given closure with UTC encoding enabled on the current thread, which serializes date-times with a UTC offset
+/// or a time zone id using the structures introduced in Bolt v5 (also available in Bolt v4.4 with the `utc` patch).
+/// By default, the legacy structures are used, unless encoding for Bolt v5+ with [`encode_with_version`].
+///
+/// Both forms are always accepted when deserializing.
+pub fn encode_utc<T>(f: impl FnOnce() -> T) -> T {
+    with_scoped(&UTC_ENCODING, true, f)
+}
+
+pub(crate) fn is_utc_encoding() -> bool {
+    UTC_ENCODING.with(Cell::get) || encode_version().is_some_and(|version| version.major() >= 5)
+}
+
+/// Run the given closure with a negotiated protocol version in use on the current thread, so that messages and values
+/// are serialized the way that version expects. Messages are sent in the form the version has for them, e.g. `INIT`
+/// is sent as `HELLO` in Bolt v3+ and `PULL_ALL` as `PULL` in Bolt v4+, and date-times are sent in UTC in Bolt v5+.
+/// Messages the version has no form for fail to serialize with [`Error::UnsupportedByVersion`]. The previously used
+/// version (if any) is replaced until the closure returns.
+///
+/// Without a version, everything is serialized as it is.
+///
+/// # Example
+/// ```
+/// use bolt_proto::message::Begin;
+/// use bolt_proto::{encode_with_version, Message, Serialize, Version};
+///
+/// let bytes = encode_with_version(Version::new(4, 4), || Message::PullAll.try_into_bytes()).unwrap();
+/// assert_eq!(&bytes[..], &[0xB1, 0x3F, 0xA1, 0x81, b'n', 0xFF]);
+///
+/// let begin = Message::Begin(Begin::new(Default::default()));
+/// assert!(encode_with_version(Version::new(1, 0), || begin.try_into_bytes()).is_err());
+/// ```
+pub fn encode_with_version<T>(version: Version, f: impl FnOnce() -> T) -> T {
+    with_scoped(&ENCODE_VERSION, Some(version), f)
+}
+
+pub(crate) fn encode_version() -> Option<Version> {
+    ENCODE_VERSION.with(Cell::get)
+}
+
+/// Run the given closure with deterministic encoding enabled on the current thread, which serializes the entries of
+/// maps sorted by key, so that equal values (and messages) always serialize to the same bytes. By default, entries are
+/// serialized in the arbitrary order of the underlying `HashMap`.
+///
+/// This is useful for contract tests, caching serialized messages, and comparing captured traffic.
+///
+/// # Example
+/// ```
+/// use std::collections::HashMap;
+///
+/// use bolt_proto::{encode_deterministic, Serialize, Value};
+///
+/// let mut map = HashMap::new();
+/// map.insert("b", 2);
+/// map.insert("a", 1);
+/// let bytes = encode_deterministic(|| Value::from(map).try_into_bytes()).unwrap();
+/// assert_eq!(&bytes[..], &[0xA2, 0x81, b'a', 0x01, 0x81, b'b', 0x02]);
+/// ```
+pub fn encode_deterministic<T>(f: impl FnOnce() -> T) -> T {
+    with_scoped(&DETERMINISTIC_ENCODING, true, f)
+}
+
+pub(crate) fn is_deterministic_encoding() -> bool {
+    DETERMINISTIC_ENCODING.with(Cell::get)
+}
+
+/// Limits on incoming data, enforced while deserializing, so that a malicious or buggy peer can't make the parser
+/// allocate excessively. A value exceeding a limit fails to deserialize with [`DeserializationError::LimitExceeded`].
+/// Custom limits can be enforced with [`decode_with_config`].
+///
+/// By default, values can be nested at most [`DEFAULT_MAX_DEPTH`](DeserializeConfig::DEFAULT_MAX_DEPTH) levels deep,
+/// since cloning, comparing, hashing, formatting, serializing, and dropping values all recurse into nested values, so a
+/// deeply nested value would overflow the call stack. Collection lengths and total sizes aren't limited by default,
+/// since they are already bounded by the size of the input.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct DeserializeConfig {
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) max_collection_len: Option<usize>,
+    pub(crate) max_total_bytes: Option<usize>,
+}
+
+impl DeserializeConfig {
+    pub const DEFAULT_MAX_DEPTH: usize = 512;
+
+    /// The default limits, see [`DeserializeConfig`].
+    pub const fn new() -> Self {
+        Self {
+            max_depth: Some(Self::DEFAULT_MAX_DEPTH),
+            max_collection_len: None,
+            max_total_bytes: None,
+        }
+    }
+
+    /// No limits at all. Only use this for trusted input, since a deeply nested value can then overflow the call stack.
+    pub const fn unlimited() -> Self {
+        Self {
+            max_depth: None,
+            max_collection_len: None,
+            max_total_bytes: None,
+        }
+    }
+
+    /// The maximum number of lists, maps, and structures a value can be nested in, counting itself, e.g. a list of
+    /// lists has a depth of 2.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// The maximum number of elements of a list, or entries of a map. Strings and byte arrays are not limited, since
+    /// they refer to the input rather than being copied.
+    pub fn with_max_collection_len(mut self, max_collection_len: usize) -> Self {
+        self.max_collection_len = Some(max_collection_len);
+        self
+    }
+
+    /// The maximum number of bytes a single value can be serialized in.
+    pub fn with_max_total_bytes(mut self, max_total_bytes: usize) -> Self {
+        self.max_total_bytes = Some(max_total_bytes);
+        self
+    }
+
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    pub fn max_collection_len(&self) -> Option<usize> {
+        self.max_collection_len
+    }
+
+    pub fn max_total_bytes(&self) -> Option<usize> {
+        self.max_total_bytes
+    }
+}
+
+impl Default for DeserializeConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Returns an error if the actual amount exceeds the limit, if there is one
+pub(crate) fn check_limit(limit: &'static str, max: Option<usize>, actual: usize) -> Result<()> {
+    match max {
+        Some(max) if actual > max => {
+            Err(DeserializationError::LimitExceeded { limit, max, actual }.into())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Run the given closure with the limits of a [`DeserializeConfig`] enforced on the current thread, wherever values
+/// are deserialized, including the fields of messages. The previously used config is replaced until the closure
+/// returns.
+///
+/// # Example
+/// ```
+/// use bolt_proto::{decode_with_config, DeserializeConfig, Deserialize, Value};
+/// use bytes::Bytes;
+///
+/// // [[1]]
+/// let bytes = Bytes::from_static(&[0x91, 0x91, 0x01]);
+/// let config = DeserializeConfig::new().with_max_depth(1);
+/// assert!(Value::deserialize(&mut bytes.clone()).is_ok());
+/// assert!(decode_with_config(config, || Value::deserialize(&mut bytes.clone())).is_err());
+/// ```
+pub fn decode_with_config<T>(config: DeserializeConfig, f: impl FnOnce() -> T) -> T {
+    with_scoped(&DESERIALIZE_CONFIG, config, f)
+}
+
+pub(crate) fn deserialize_config() -> DeserializeConfig {
+    DESERIALIZE_CONFIG.with(Cell::get)
+}
+
 pub(crate) const STRUCT_MARKER_TINY: u8 = 0xB0;
 pub(crate) const STRUCT_MARKER_SMALL: u8 = 0xDC;
 pub(crate) const STRUCT_MARKER_MEDIUM: u8 = 0xDD;
 
-// Might panic. Use this inside a catch_unwind block
-pub(crate) fn get_info_from_bytes(bytes: &mut impl Buf) -> Result<(u8, u8)> {
-    let marker = bytes.get_u8();
-    let _size = match marker {
+// Reads a structure header, returning its marker, declared field count, and signature.
+pub(crate) fn get_info_from_bytes(bytes: &mut impl Buf) -> Result<(u8, usize, u8)> {
+    let marker = bytes.try_get_u8("structure marker")?;
+    let field_count = match marker {
         marker if (STRUCT_MARKER_TINY..=(STRUCT_MARKER_TINY | 0x0F)).contains(&marker) => {
             0x0F & marker as usize
         }
-        STRUCT_MARKER_SMALL => bytes.get_u8() as usize,
-        STRUCT_MARKER_MEDIUM => bytes.get_u16() as usize,
+        STRUCT_MARKER_SMALL => bytes.try_get_u8("structure size")? as usize,
+        STRUCT_MARKER_MEDIUM => bytes.try_get_u16("structure size")? as usize,
         _ => {
             return Err(DeserializationError::InvalidMarkerByte(marker).into());
         }
     };
-    let signature = bytes.get_u8();
-    Ok((marker, signature))
+    let signature = bytes.try_get_u8("structure signature")?;
+    Ok((marker, field_count, signature))
+}
+
+// Checks the number of fields declared for a structure against the number its type has, according to its marker, so
+// that reading a different number of fields can't leave the rest of the input out of step
+pub(crate) fn check_field_count(marker: u8, field_count: usize) -> Result<()> {
+    let expected = (marker & 0x0F) as usize;
+    if field_count == expected {
+        Ok(())
+    } else {
+        Err(Error::InvalidFieldCount {
+            expected,
+            actual: field_count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_strict_scope() {
+        assert!(!is_strict_decoding());
+        decode_strict(|| {
+            assert!(is_strict_decoding());
+            decode_strict(|| assert!(is_strict_decoding()));
+            assert!(is_strict_decoding());
+        });
+        assert!(!is_strict_decoding());
+        let _ = std::panic::catch_unwind(|| decode_strict(|| panic!()));
+        assert!(!is_strict_decoding());
+    }
+
+    #[test]
+    fn encode_utc_scope() {
+        assert!(!is_utc_encoding());
+        encode_utc(|| {
+            assert!(is_utc_encoding());
+            // Flags are independent
+            assert!(!is_strict_decoding());
+        });
+        assert!(!is_utc_encoding());
+    }
+
+    #[test]
+    fn encode_with_version_scope() {
+        assert_eq!(encode_version(), None);
+        encode_with_version(Version::new(4, 4), || {
+            assert_eq!(encode_version(), Some(Version::new(4, 4)));
+            assert!(!is_utc_encoding());
+            encode_with_version(Version::new(5, 0), || assert!(is_utc_encoding()));
+            assert_eq!(encode_version(), Some(Version::new(4, 4)));
+            encode_utc(|| assert!(is_utc_encoding()));
+        });
+        assert_eq!(encode_version(), None);
+    }
+
+    #[test]
+    fn encode_deterministic_scope() {
+        assert!(!is_deterministic_encoding());
+        encode_deterministic(|| {
+            assert!(is_deterministic_encoding());
+            assert!(!is_utc_encoding());
+        });
+        assert!(!is_deterministic_encoding());
+    }
+
+    #[test]
+    fn decode_with_config_scope() {
+        assert_eq!(deserialize_config(), DeserializeConfig::default());
+        let config = DeserializeConfig::new().with_max_depth(4);
+        decode_with_config(config, || {
+            assert_eq!(deserialize_config().max_depth(), Some(4));
+            decode_with_config(DeserializeConfig::unlimited(), || {
+                assert_eq!(deserialize_config().max_depth(), None)
+            });
+            assert_eq!(deserialize_config(), config);
+        });
+        assert_eq!(deserialize_config(), DeserializeConfig::default());
+    }
+
+    #[test]
+    fn get_info_from_bytes_field_count() {
+        let mut two_fields = Bytes::from_static(&[STRUCT_MARKER_TINY | 2, 0x01]);
+        assert_eq!(
+            get_info_from_bytes(&mut two_fields).unwrap(),
+            (STRUCT_MARKER_TINY | 2, 2, 0x01)
+        );
+        let mut one_field = Bytes::from_static(&[STRUCT_MARKER_TINY | 1, 0x01]);
+        assert_eq!(
+            get_info_from_bytes(&mut one_field).unwrap(),
+            (STRUCT_MARKER_TINY | 1, 1, 0x01)
+        );
+        let mut small = Bytes::from_static(&[STRUCT_MARKER_SMALL, 20, 0x71]);
+        assert_eq!(
+            get_info_from_bytes(&mut small).unwrap(),
+            (STRUCT_MARKER_SMALL, 20, 0x71)
+        );
+        let mut truncated = Bytes::from_static(&[STRUCT_MARKER_SMALL]);
+        assert!(matches!(
+            get_info_from_bytes(&mut truncated),
+            Err(Error::DeserializationError(
+                DeserializationError::UnexpectedEnd {
+                    reading: "structure size",
+                    ..
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn try_from_bytes_exact() {
+        let bytes = Value::from(vec![1, 2]).try_into_bytes().unwrap();
+        assert_eq!(
+            Value::try_from_bytes_exact(bytes.clone()).unwrap(),
+            Value::from(vec![1, 2])
+        );
+        let trailing = Bytes::from([&bytes[..], &[0x00, 0x00]].concat());
+        assert!(matches!(
+            Value::try_from_bytes_exact(trailing),
+            Err(Error::DeserializationError(
+                DeserializationError::TrailingBytes(2)
+            ))
+        ));
+        assert!(matches!(
+            Value::try_from_bytes_exact(bytes.slice(..2)),
+            Err(Error::DeserializationError(
+                DeserializationError::UnexpectedEnd { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn checked_reads() {
+        let mut bytes = Bytes::from_static(&[0x01, 0x02, 0x03]);
+        assert_eq!(bytes.peek_u8("marker").unwrap(), 0x01);
+        assert_eq!(bytes.try_get_u16("size").unwrap(), 0x0102);
+        assert!(matches!(
+            bytes.try_get_i32("integer"),
+            Err(Error::DeserializationError(
+                DeserializationError::UnexpectedEnd {
+                    reading: "integer",
+                    needed: 4,
+                    remaining: 1
+                }
+            ))
+        ));
+        // Nothing is consumed by a failed read
+        assert_eq!(bytes.try_get_u8("marker").unwrap(), 0x03);
+        assert!(bytes.peek_u8("marker").is_err());
+    }
+
+    #[test]
+    fn get_info_from_bytes_invalid_marker() {
+        let mut bytes = Bytes::from_static(&[0x90, 0x01]);
+        assert!(matches!(
+            get_info_from_bytes(&mut bytes),
+            Err(Error::DeserializationError(
+                DeserializationError::InvalidMarkerByte(0x90)
+            ))
+        ));
+    }
 }
@@ -1,34 +1,44 @@
-use std::convert::{TryFrom, TryInto};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::hash::{Hash, Hasher};
-use std::ops::DerefMut;
-use std::panic::catch_unwind;
-use std::sync::{Arc, Mutex};
 
-use bytes::{Buf, Bytes};
+use bytes::{BufMut, Bytes};
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+#[cfg(feature = "chrono")]
+use chrono_tz::Tz;
 
 pub(crate) use boolean::Boolean;
 pub(crate) use byte_array::ByteArray;
-pub(crate) use date::Date;
-pub(crate) use date_time_offset::DateTimeOffset;
-pub(crate) use date_time_zoned::DateTimeZoned;
+pub use date::Date;
+pub use date_time_offset::DateTimeOffset;
+pub use date_time_zoned::DateTimeZoned;
 pub use duration::Duration;
+pub use extension::{
+    decode_with_extensions, ExtensionRegistry, StructureDecoder, StructureExtension,
+};
 pub(crate) use float::Float;
 pub(crate) use integer::Integer;
-pub(crate) use list::List;
-pub(crate) use local_date_time::LocalDateTime;
-pub(crate) use local_time::LocalTime;
-pub(crate) use map::Map;
-pub use node::Node;
+pub use list::List;
+pub use local_date_time::LocalDateTime;
+pub use local_time::LocalTime;
+pub use map::Map;
+pub use node::{Node, NodeBuilder};
 pub(crate) use null::Null;
-pub use path::Path;
+pub use path::{Path, PathBuilder, PathSegment, PathSegments};
 pub use point_2d::Point2D;
 pub use point_3d::Point3D;
-pub use relationship::Relationship;
+pub use relationship::{Relationship, RelationshipBuilder};
 pub(crate) use string::String;
+pub use structure::Structure;
 pub use time::Time;
-pub use unbound_relationship::UnboundRelationship;
+pub use unbound_relationship::{UnboundRelationship, UnboundRelationshipBuilder};
+pub use value_ref::ValueRef;
 
 use crate::error::*;
+use crate::impl_try_from_shared_bytes;
+use crate::impl_try_into_bytes;
 use crate::serialization::*;
 
 pub(crate) mod boolean;
@@ -37,8 +47,13 @@ pub(crate) mod conversions;
 pub(crate) mod date;
 pub(crate) mod date_time_offset;
 pub(crate) mod date_time_zoned;
+pub(crate) mod decode;
+pub(crate) mod display;
 pub(crate) mod duration;
+pub(crate) mod extension;
 pub(crate) mod float;
+#[cfg(feature = "json")]
+pub(crate) mod geojson;
 pub(crate) mod integer;
 pub(crate) mod list;
 pub(crate) mod local_date_time;
@@ -46,22 +61,31 @@ pub(crate) mod local_time;
 pub(crate) mod map;
 pub(crate) mod node;
 pub(crate) mod null;
+pub(crate) mod ordering;
 pub(crate) mod path;
 pub(crate) mod point_2d;
 pub(crate) mod point_3d;
 pub(crate) mod relationship;
+#[cfg(feature = "serde")]
+pub(crate) mod serde_impl;
 pub(crate) mod string;
+pub(crate) mod structure;
 pub(crate) mod time;
 pub(crate) mod unbound_relationship;
+pub(crate) mod value_ref;
 
 /// An enum that can hold values of all Bolt-compatible types.
 ///
 /// Conversions are provided for most types, and are usually pretty intuitive ([`bool`] to [`Value::Boolean`], [`i32`]
-/// to [`Value::Integer`], [`HashMap`](std::collections::HashMap) to [`Value::Map`], etc.), but some types have no
+/// to [`Value::Integer`], [`HashMap`] to [`Value::Map`], etc.), but some types have no
 /// analog in Rust, like a timezone-aware time. For such types, conversions are still provided, but may feel a bit
-/// clunky (for example, you can convert a `(`[`NaiveTime`](chrono::NaiveTime)`, impl `[`Offset`](chrono::Offset)`)`
+/// clunky (for example, you can convert a `(`[`NaiveTime`]`, impl `[`Offset`](chrono::Offset)`)`
 /// tuple into a [`Value::Time`]).
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Conversions between temporal values and [`chrono`] types require the `chrono` feature, which is enabled by default.
+/// With the `serde` feature, values also implement serde's `Serialize` and `Deserialize`, although only primitives,
+/// lists, and maps can be deserialized into a `Value`.
+#[derive(Debug, Clone, PartialEq, Hash)]
 pub enum Value {
     // V1-compatible value types
     Boolean(Boolean),
@@ -87,46 +111,327 @@ pub enum Value {
     Duration(Duration),
     Point2D(Point2D),
     Point3D(Point3D),
+
+    // A structure with an unrecognized signature, kept as received
+    Structure(Structure),
 }
 
-#[allow(clippy::derive_hash_xor_eq)]
-// We implement Hash here despite deriving PartialEq because f64 and HashMap cannot be hashed and must panic
-impl Hash for Value {
-    fn hash<H: Hasher>(&self, state: &mut H) {
+impl Value {
+    /// Create an empty map value with space for at least `capacity` entries, see [`Map::with_capacity`].
+    pub fn map_with_capacity(capacity: usize) -> Self {
+        Value::Map(Map::with_capacity(capacity))
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
         match self {
-            Value::Float(_)
-            | Value::Bytes(_)
-            | Value::Map(_)
-            | Value::Node(_)
-            | Value::Relationship(_)
-            | Value::UnboundRelationship(_)
-            | Value::Path(_)
-            | Value::Point2D(_)
-            | Value::Point3D(_) => panic!("Cannot hash a {:?}", self),
-            Value::Boolean(boolean) => boolean.hash(state),
-            Value::Integer(integer) => integer.hash(state),
-            Value::List(list) => list.hash(state),
-            Value::Null => Null.hash(state),
-            Value::String(string) => string.hash(state),
-            Value::Date(date) => date.hash(state),
-            Value::Time(time) => time.hash(state),
-            Value::DateTimeOffset(date_time_offset) => date_time_offset.hash(state),
-            Value::DateTimeZoned(date_time_zoned) => date_time_zoned.hash(state),
-            Value::LocalTime(local_time) => local_time.hash(state),
-            Value::LocalDateTime(local_date_time) => local_date_time.hash(state),
-            Value::Duration(duration) => duration.hash(state),
+            Value::Boolean(boolean) => Some(boolean.value),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Value::Integer(integer) => Some(integer.value),
+            _ => None,
+        }
+    }
+
+    /// Get the value of a float. Integers are not converted, see [`loosely_eq`](Value::loosely_eq) for comparing
+    /// numbers of either type.
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Float(float) => Some(float.value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(byte_array) => Some(&byte_array.value),
+            _ => None,
         }
     }
-}
 
-impl Eq for Value {
-    fn assert_receiver_is_total_eq(&self) {
-        if let Value::Float(_) | Value::Point2D(_) | Value::Point3D(_) = self {
-            panic!("{:?} does not impl Eq", self)
+    /// Get the value as a str, if it is a string of valid UTF-8. Deserialized strings are only validated when they are
+    /// first read, and converting one into a `String` returns [`DeserializationError::InvalidUTF8`] if it is invalid.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(string) => string.as_str().ok(),
+            _ => None,
         }
     }
+
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Value::List(list) => Some(&list.value),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&HashMap<std::string::String, Value>> {
+        match self {
+            Value::Map(map) => Some(&map.value),
+            _ => None,
+        }
+    }
+
+    pub fn as_node(&self) -> Option<&Node> {
+        match self {
+            Value::Node(node) => Some(node),
+            _ => None,
+        }
+    }
+
+    pub fn as_relationship(&self) -> Option<&Relationship> {
+        match self {
+            Value::Relationship(relationship) => Some(relationship),
+            _ => None,
+        }
+    }
+
+    pub fn as_path(&self) -> Option<&Path> {
+        match self {
+            Value::Path(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    pub fn as_unbound_relationship(&self) -> Option<&UnboundRelationship> {
+        match self {
+            Value::UnboundRelationship(unbound_relationship) => Some(unbound_relationship),
+            _ => None,
+        }
+    }
+
+    pub fn as_date(&self) -> Option<&Date> {
+        match self {
+            Value::Date(date) => Some(date),
+            _ => None,
+        }
+    }
+
+    pub fn as_time(&self) -> Option<&Time> {
+        match self {
+            Value::Time(time) => Some(time),
+            _ => None,
+        }
+    }
+
+    pub fn as_date_time_offset(&self) -> Option<&DateTimeOffset> {
+        match self {
+            Value::DateTimeOffset(date_time_offset) => Some(date_time_offset),
+            _ => None,
+        }
+    }
+
+    pub fn as_date_time_zoned(&self) -> Option<&DateTimeZoned> {
+        match self {
+            Value::DateTimeZoned(date_time_zoned) => Some(date_time_zoned),
+            _ => None,
+        }
+    }
+
+    pub fn as_local_time(&self) -> Option<&LocalTime> {
+        match self {
+            Value::LocalTime(local_time) => Some(local_time),
+            _ => None,
+        }
+    }
+
+    pub fn as_local_date_time(&self) -> Option<&LocalDateTime> {
+        match self {
+            Value::LocalDateTime(local_date_time) => Some(local_date_time),
+            _ => None,
+        }
+    }
+
+    pub fn as_duration(&self) -> Option<&Duration> {
+        match self {
+            Value::Duration(duration) => Some(duration),
+            _ => None,
+        }
+    }
+
+    pub fn as_point_2d(&self) -> Option<&Point2D> {
+        match self {
+            Value::Point2D(point_2d) => Some(point_2d),
+            _ => None,
+        }
+    }
+
+    pub fn as_point_3d(&self) -> Option<&Point3D> {
+        match self {
+            Value::Point3D(point_3d) => Some(point_3d),
+            _ => None,
+        }
+    }
+
+    pub fn as_structure(&self) -> Option<&Structure> {
+        match self {
+            Value::Structure(structure) => Some(structure),
+            _ => None,
+        }
+    }
+
+    /// Whether this value is of a temporal type (dates, times, date-times, and durations), which are supported starting
+    /// with Bolt v2.
+    pub fn is_temporal(&self) -> bool {
+        matches!(
+            self,
+            Value::Date(_)
+                | Value::Time(_)
+                | Value::DateTimeOffset(_)
+                | Value::DateTimeZoned(_)
+                | Value::LocalTime(_)
+                | Value::LocalDateTime(_)
+                | Value::Duration(_)
+        )
+    }
+
+    /// Whether this value is of a spatial type, which are supported starting with Bolt v2.
+    pub fn is_spatial(&self) -> bool {
+        matches!(self, Value::Point2D(_) | Value::Point3D(_))
+    }
+
+    /// Compare two values, treating integers and floats with the same numeric value as equal (e.g. `1` and `1.0`).
+    /// This is applied element-wise to lists and maps, so `[1, 2, 3]` is loosely equal to `[1.0, 2.0, 3.0]`. All other
+    /// values are compared with the usual equality.
+    pub fn loosely_eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Integer(integer), Value::Float(float))
+            | (Value::Float(float), Value::Integer(integer)) => integer.value as f64 == float.value,
+            (Value::List(list), Value::List(other_list)) => {
+                list.value.len() == other_list.value.len()
+                    && list
+                        .value
+                        .iter()
+                        .zip(&other_list.value)
+                        .all(|(value, other_value)| value.loosely_eq(other_value))
+            }
+            (Value::Map(map), Value::Map(other_map)) => {
+                map.value.len() == other_map.value.len()
+                    && map.value.iter().all(|(key, value)| {
+                        other_map
+                            .value
+                            .get(key)
+                            .is_some_and(|other_value| value.loosely_eq(other_value))
+                    })
+            }
+            _ => self == other,
+        }
+    }
+
+    /// Format a temporal value as an ISO 8601 string, as Cypher's temporal functions would accept it. Returns `None` if
+    /// this value is not temporal or can't be represented as a chrono value, such as a date-time with an unknown time
+    /// zone. Date-times with a named time zone are followed by the zone id in brackets, e.g.
+    /// `2020-12-25T10:00:00+01:00[Europe/Paris]`.
+    #[cfg(feature = "chrono")]
+    pub fn to_iso_8601(&self) -> Option<std::string::String> {
+        const TIME_FORMAT: &str = "%H:%M:%S%.f";
+        const DATE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+        let value = self.clone();
+        let string = match self {
+            Value::Date(_) => NaiveDate::try_from(value).ok()?.to_string(),
+            Value::Time(time) => {
+                let (naive_time, offset) = (time.naive_time().ok()?, time.offset().ok()?);
+                format!("{}{}", naive_time.format(TIME_FORMAT), offset)
+            }
+            Value::DateTimeOffset(_) => DateTime::<FixedOffset>::try_from(value)
+                .ok()?
+                .format(&format!("{}%:z", DATE_TIME_FORMAT))
+                .to_string(),
+            Value::DateTimeZoned(_) => {
+                let date_time = DateTime::<Tz>::try_from(value).ok()?;
+                format!(
+                    "{}[{}]",
+                    date_time.format(&format!("{}%:z", DATE_TIME_FORMAT)),
+                    date_time.timezone().name()
+                )
+            }
+            Value::LocalTime(_) => NaiveTime::try_from(value)
+                .ok()?
+                .format(TIME_FORMAT)
+                .to_string(),
+            Value::LocalDateTime(_) => NaiveDateTime::try_from(value)
+                .ok()?
+                .format(DATE_TIME_FORMAT)
+                .to_string(),
+            Value::Duration(duration) => duration.to_iso_8601(),
+            _ => return None,
+        };
+        Some(string)
+    }
+}
+
+// Floats are hashed by their bits, treating -0.0 as 0.0 and all NaNs alike, to stay consistent with PartialEq
+pub(crate) fn hash_f64<H: Hasher>(value: f64, state: &mut H) {
+    let value = if value == 0.0 {
+        0.0
+    } else if value.is_nan() {
+        f64::NAN
+    } else {
+        value
+    };
+    value.to_bits().hash(state);
+}
+
+// Clones and converts a single property, for the property getters of graph structures
+pub(crate) fn get_property<T>(
+    properties: &HashMap<std::string::String, Value>,
+    key: &str,
+) -> Result<T>
+where
+    T: TryFrom<Value, Error = Error>,
+{
+    match properties.get(key) {
+        Some(value) => T::try_from(value.clone()),
+        None => Err(ConversionError::MissingProperty(key.to_string()).into()),
+    }
+}
+
+pub(crate) fn get_opt_property<T>(
+    properties: &HashMap<std::string::String, Value>,
+    key: &str,
+) -> Result<Option<T>>
+where
+    T: TryFrom<Value, Error = Error>,
+{
+    match properties.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => T::try_from(value.clone()).map(Some),
+    }
+}
+
+pub(crate) fn invalid_graph_structure(reason: impl Into<std::string::String>) -> Error {
+    ConversionError::InvalidGraphStructure(reason.into()).into()
 }
 
+pub(crate) fn validate_properties(properties: &HashMap<std::string::String, Value>) -> Result<()> {
+    if properties.contains_key("") {
+        return Err(invalid_graph_structure("property key is empty"));
+    }
+    Ok(())
+}
+
+// Maps are hashed as the sum of the hashes of their entries, so that the hash does not depend on iteration order
+pub(crate) fn hash_map<K: Hash, V: Hash, H: Hasher>(map: &HashMap<K, V>, state: &mut H) {
+    let sum = map.iter().fold(0_u64, |sum, entry| {
+        let mut hasher = DefaultHasher::new();
+        entry.hash(&mut hasher);
+        sum.wrapping_add(hasher.finish())
+    });
+    state.write_usize(map.len());
+    state.write_u64(sum);
+}
+
+// Float, Point2D, and Point3D contain f64 values, which are not totally equal, so be careful comparing those variants
+impl Eq for Value {}
+
 impl Marker for Value {
     fn get_marker(&self) -> Result<u8> {
         match self {
@@ -151,152 +456,247 @@ impl Marker for Value {
             Value::Duration(duration) => duration.get_marker(),
             Value::Point2D(point_2d) => point_2d.get_marker(),
             Value::Point3D(point_3d) => point_3d.get_marker(),
+            Value::Structure(structure) => structure.get_marker(),
         }
     }
-}
-
-impl Serialize for Value {}
 
-impl TryInto<Bytes> for Value {
-    type Error = Error;
-
-    fn try_into(self) -> Result<Bytes> {
+    fn serialized_len(&self) -> Result<usize> {
         match self {
-            Value::Boolean(boolean) => boolean.try_into(),
-            Value::Integer(integer) => integer.try_into(),
-            Value::Float(float) => float.try_into(),
-            Value::Bytes(byte_array) => byte_array.try_into(),
-            Value::List(list) => list.try_into(),
-            Value::Map(map) => map.try_into(),
-            Value::Null => Null.try_into(),
-            Value::String(string) => string.try_into(),
-            Value::Node(node) => node.try_into(),
-            Value::Relationship(rel) => rel.try_into(),
-            Value::Path(path) => path.try_into(),
-            Value::UnboundRelationship(unbound_rel) => unbound_rel.try_into(),
-            Value::Date(date) => date.try_into(),
-            Value::Time(time) => time.try_into(),
-            Value::DateTimeOffset(date_time_offset) => date_time_offset.try_into(),
-            Value::DateTimeZoned(date_time_zoned) => date_time_zoned.try_into(),
-            Value::LocalTime(local_time) => local_time.try_into(),
-            Value::LocalDateTime(local_date_time) => local_date_time.try_into(),
-            Value::Duration(duration) => duration.try_into(),
-            Value::Point2D(point_2d) => point_2d.try_into(),
-            Value::Point3D(point_3d) => point_3d.try_into(),
+            Value::Boolean(boolean) => boolean.serialized_len(),
+            Value::Integer(integer) => integer.serialized_len(),
+            Value::Float(float) => float.serialized_len(),
+            Value::Bytes(byte_array) => byte_array.serialized_len(),
+            Value::List(list) => list.serialized_len(),
+            Value::Map(map) => map.serialized_len(),
+            Value::Null => Null.serialized_len(),
+            Value::String(string) => string.serialized_len(),
+            Value::Node(node) => node.serialized_len(),
+            Value::Relationship(rel) => rel.serialized_len(),
+            Value::Path(path) => path.serialized_len(),
+            Value::UnboundRelationship(unbound_rel) => unbound_rel.serialized_len(),
+            Value::Date(date) => date.serialized_len(),
+            Value::Time(time) => time.serialized_len(),
+            Value::DateTimeOffset(date_time_offset) => date_time_offset.serialized_len(),
+            Value::DateTimeZoned(date_time_zoned) => date_time_zoned.serialized_len(),
+            Value::LocalTime(local_time) => local_time.serialized_len(),
+            Value::LocalDateTime(local_date_time) => local_date_time.serialized_len(),
+            Value::Duration(duration) => duration.serialized_len(),
+            Value::Point2D(point_2d) => point_2d.serialized_len(),
+            Value::Point3D(point_3d) => point_3d.serialized_len(),
+            Value::Structure(structure) => structure.serialized_len(),
         }
     }
 }
 
-impl Deserialize for Value {}
-
-impl TryFrom<Arc<Mutex<Bytes>>> for Value {
-    type Error = Error;
-
-    fn try_from(input_arc: Arc<Mutex<Bytes>>) -> Result<Self> {
-        catch_unwind(move || {
-            let marker = input_arc.lock().unwrap()[0];
-            match marker {
-                null::MARKER => {
-                    input_arc.lock().unwrap().advance(1);
-                    Ok(Value::Null)
-                }
-                boolean::MARKER_FALSE => {
-                    input_arc.lock().unwrap().advance(1);
-                    Ok(Value::Boolean(Boolean::from(false)))
-                }
-                boolean::MARKER_TRUE => {
-                    input_arc.lock().unwrap().advance(1);
-                    Ok(Value::Boolean(Boolean::from(true)))
-                }
-                // Tiny int
-                marker if (-16..=127).contains(&(marker as i8)) => {
-                    input_arc.lock().unwrap().advance(1);
-                    Ok(Value::Integer(Integer::from(marker as i8)))
-                }
-                // Other int types
-                integer::MARKER_INT_8
-                | integer::MARKER_INT_16
-                | integer::MARKER_INT_32
-                | integer::MARKER_INT_64 => Ok(Value::Integer(Integer::try_from(input_arc)?)),
-                float::MARKER => Ok(Value::Float(Float::try_from(input_arc)?)),
-                byte_array::MARKER_SMALL | byte_array::MARKER_MEDIUM | byte_array::MARKER_LARGE => {
-                    Ok(Value::Bytes(ByteArray::try_from(input_arc)?))
-                }
-                // Tiny string
-                marker
-                    if (string::MARKER_TINY..=(string::MARKER_TINY | 0x0F)).contains(&marker) =>
-                {
-                    Ok(Value::String(String::try_from(input_arc)?))
-                }
-                string::MARKER_SMALL | string::MARKER_MEDIUM | string::MARKER_LARGE => {
-                    Ok(Value::String(String::try_from(input_arc)?))
-                }
-                // Tiny list
-                marker if (list::MARKER_TINY..=(list::MARKER_TINY | 0x0F)).contains(&marker) => {
-                    Ok(Value::List(List::try_from(input_arc)?))
-                }
-                list::MARKER_SMALL | list::MARKER_MEDIUM | list::MARKER_LARGE => {
-                    Ok(Value::List(List::try_from(input_arc)?))
-                }
-                // Tiny map
-                marker if (map::MARKER_TINY..=(map::MARKER_TINY | 0x0F)).contains(&marker) => {
-                    Ok(Value::Map(Map::try_from(input_arc)?))
-                }
-                map::MARKER_SMALL | map::MARKER_MEDIUM | map::MARKER_LARGE => {
-                    Ok(Value::Map(Map::try_from(input_arc)?))
-                }
-                // Tiny structure
-                marker if (STRUCT_MARKER_TINY..=(STRUCT_MARKER_TINY | 0x0F)).contains(&marker) => {
-                    deserialize_structure(input_arc)
-                }
-                STRUCT_MARKER_SMALL | STRUCT_MARKER_MEDIUM => deserialize_structure(input_arc),
-                _ => Err(DeserializationError::InvalidMarkerByte(marker).into()),
-            }
-        })
-        .map_err(|_| DeserializationError::Panicked)?
+impl Serialize for Value {
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()> {
+        match self {
+            Value::Boolean(boolean) => boolean.serialize_into(buf),
+            Value::Integer(integer) => integer.serialize_into(buf),
+            Value::Float(float) => float.serialize_into(buf),
+            Value::Bytes(byte_array) => byte_array.serialize_into(buf),
+            Value::List(list) => list.serialize_into(buf),
+            Value::Map(map) => map.serialize_into(buf),
+            Value::Null => Null.serialize_into(buf),
+            Value::String(string) => string.serialize_into(buf),
+            Value::Node(node) => node.serialize_into(buf),
+            Value::Relationship(rel) => rel.serialize_into(buf),
+            Value::Path(path) => path.serialize_into(buf),
+            Value::UnboundRelationship(unbound_rel) => unbound_rel.serialize_into(buf),
+            Value::Date(date) => date.serialize_into(buf),
+            Value::Time(time) => time.serialize_into(buf),
+            Value::DateTimeOffset(date_time_offset) => date_time_offset.serialize_into(buf),
+            Value::DateTimeZoned(date_time_zoned) => date_time_zoned.serialize_into(buf),
+            Value::LocalTime(local_time) => local_time.serialize_into(buf),
+            Value::LocalDateTime(local_date_time) => local_date_time.serialize_into(buf),
+            Value::Duration(duration) => duration.serialize_into(buf),
+            Value::Point2D(point_2d) => point_2d.serialize_into(buf),
+            Value::Point3D(point_3d) => point_3d.serialize_into(buf),
+            Value::Structure(structure) => structure.serialize_into(buf),
+        }
     }
 }
 
-fn deserialize_structure(input_arc: Arc<Mutex<Bytes>>) -> Result<Value> {
-    catch_unwind(move || {
-        let (_marker, signature) = get_info_from_bytes(input_arc.lock().unwrap().deref_mut())?;
-        match signature {
-            node::SIGNATURE => Ok(Value::Node(Node::try_from(input_arc)?)),
-            relationship::SIGNATURE => Ok(Value::Relationship(Relationship::try_from(input_arc)?)),
-            path::SIGNATURE => Ok(Value::Path(Path::try_from(input_arc)?)),
-            unbound_relationship::SIGNATURE => Ok(Value::UnboundRelationship(
-                UnboundRelationship::try_from(input_arc)?,
-            )),
-            date::SIGNATURE => Ok(Value::Date(Date::try_from(input_arc)?)),
-            time::SIGNATURE => Ok(Value::Time(Time::try_from(input_arc)?)),
-            date_time_offset::SIGNATURE => {
-                Ok(Value::DateTimeOffset(DateTimeOffset::try_from(input_arc)?))
-            }
-            date_time_zoned::SIGNATURE => {
-                Ok(Value::DateTimeZoned(DateTimeZoned::try_from(input_arc)?))
-            }
-            local_time::SIGNATURE => Ok(Value::LocalTime(LocalTime::try_from(input_arc)?)),
-            local_date_time::SIGNATURE => {
-                Ok(Value::LocalDateTime(LocalDateTime::try_from(input_arc)?))
-            }
-            duration::SIGNATURE => Ok(Value::Duration(Duration::try_from(input_arc)?)),
-            point_2d::SIGNATURE => Ok(Value::Point2D(Point2D::try_from(input_arc)?)),
-            point_3d::SIGNATURE => Ok(Value::Point3D(Point3D::try_from(input_arc)?)),
-            _ => Err(DeserializationError::InvalidSignatureByte(signature).into()),
-        }
-    })
-    .map_err(|_| DeserializationError::Panicked)?
+impl_try_into_bytes!(Value);
+
+impl Deserialize for Value {
+    fn deserialize(bytes: &mut Bytes) -> Result<Self> {
+        decode::deserialize_value(bytes)
+    }
 }
 
+impl_try_from_shared_bytes!(Value);
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
     use std::iter::FromIterator;
+    use std::sync::{Arc, Mutex};
 
+    #[cfg(feature = "chrono")]
     use chrono::{FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc};
 
     use super::*;
 
+    #[test]
+    fn hash_any_value() {
+        use std::collections::HashSet;
+
+        fn hash(value: &Value) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let mut set = HashSet::new();
+        set.insert(Value::from(1.5));
+        set.insert(Value::from(vec![Value::from(2.5)]));
+        set.insert(Value::from(1.5));
+        set.insert(Value::from(Point2D::new(7203, 1.0, 2.0)));
+        set.insert(Value::from(Node::new(
+            1,
+            vec!["Person".to_string()],
+            HashMap::from_iter(vec![("name".to_string(), "Alice")]),
+        )));
+        assert_eq!(set.len(), 4);
+        assert!(set.contains(&Value::from(1.5)));
+        assert!(!set.contains(&Value::from(2.5)));
+        assert!(set.contains(&Value::from(Point2D::new(7203, 1.0, 2.0))));
+
+        assert_eq!(hash(&Value::from(0.0)), hash(&Value::from(-0.0)));
+        let entries: Vec<(std::string::String, Value)> =
+            (0..32).map(|i| (i.to_string(), Value::from(i))).collect();
+        assert_eq!(
+            hash(&Value::from(HashMap::from_iter(entries.clone()))),
+            hash(&Value::from(HashMap::from_iter(entries.into_iter().rev())))
+        );
+    }
+
+    #[test]
+    fn deserialize_in_sequence() {
+        let mut bytes = Bytes::from_static(&[
+            0x01,
+            list::MARKER_TINY | 2,
+            string::MARKER_TINY | 1,
+            b'a',
+            boolean::MARKER_TRUE,
+            null::MARKER,
+        ]);
+        assert_eq!(Value::deserialize(&mut bytes).unwrap(), Value::from(1));
+        assert_eq!(
+            Value::deserialize(&mut bytes).unwrap(),
+            Value::from(vec![Value::from("a"), Value::from(true)])
+        );
+        assert_eq!(bytes, Bytes::from_static(&[null::MARKER]));
+        assert_eq!(Value::deserialize(&mut bytes).unwrap(), Value::Null);
+        assert!(bytes.is_empty());
+        assert!(Value::deserialize(&mut bytes).is_err());
+    }
+
+    #[test]
+    fn serialized_len() {
+        fn assert_len(value: Value) {
+            let len = value.serialized_len().unwrap();
+            assert_eq!(len, value.try_into_bytes().unwrap().len());
+        }
+
+        let properties = HashMap::from_iter(vec![("name".to_string(), Value::from("Alice"))]);
+        let node = Node::new(1, vec!["Person".to_string()], properties.clone());
+        let rel = UnboundRelationship::new(2, "KNOWS".to_string(), properties.clone());
+        let values = vec![
+            Value::Null,
+            Value::from(true),
+            Value::from(1.5),
+            Value::from(vec![0_u8; 300]),
+            Value::from("a".repeat(70_000)),
+            Value::from(vec![Value::from(1); 16]),
+            Value::from(properties.clone()),
+            Value::from(node.clone().with_element_id("4:abc:1")),
+            Value::from(Relationship::new(2, 1, 3, "KNOWS".to_string(), properties)),
+            Value::from(rel.clone()),
+            Value::from(Path::new(vec![node.clone(), node], vec![rel], vec![1, 1])),
+            Value::from(Duration::new(1, 2, 3, 4)),
+            Value::from(Point3D::new(4979, 1.0, 2.0, 3.0)),
+            Value::from(Structure::new(0x01, vec![1; 20])),
+        ];
+        for value in values {
+            assert_len(value);
+        }
+        for &integer in &[0, -16, -17, 128, -129, 40_000, 3_000_000_000_i64, i64::MIN] {
+            assert_len(Value::from(integer));
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn serialized_len_date_times() {
+        let date_time = NaiveDate::from_ymd_opt(2020, 12, 25)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap();
+        for value in IntoIterator::into_iter([
+            Value::from(date_time),
+            Value::from(date_time.date()),
+            Value::from((date_time, chrono_tz::Europe::Paris)),
+            Value::from(Utc.from_utc_datetime(&date_time)),
+        ]) {
+            assert_eq!(
+                value.serialized_len().unwrap(),
+                value.clone().try_into_bytes().unwrap().len()
+            );
+            assert_eq!(
+                encode_utc(|| value.serialized_len()).unwrap(),
+                encode_utc(|| value.try_into_bytes()).unwrap().len()
+            );
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn to_iso_8601() {
+        let date = NaiveDate::from_ymd_opt(2020, 12, 25).unwrap();
+        let time = NaiveTime::from_hms_milli_opt(10, 30, 15, 500).unwrap();
+        let offset = FixedOffset::east_opt(3600).unwrap();
+        assert_eq!(Value::from(date).to_iso_8601().unwrap(), "2020-12-25");
+        assert_eq!(
+            Value::from((time, offset)).to_iso_8601().unwrap(),
+            "10:30:15.500+01:00"
+        );
+        assert_eq!(Value::from(time).to_iso_8601().unwrap(), "10:30:15.500");
+        assert_eq!(
+            Value::from(date.and_time(time)).to_iso_8601().unwrap(),
+            "2020-12-25T10:30:15.500"
+        );
+        assert_eq!(
+            Value::from(offset.from_local_datetime(&date.and_time(time)).unwrap())
+                .to_iso_8601()
+                .unwrap(),
+            "2020-12-25T10:30:15.500+01:00"
+        );
+        assert_eq!(
+            Value::from((date.and_time(time), chrono_tz::Europe::Paris))
+                .to_iso_8601()
+                .unwrap(),
+            "2020-12-25T11:30:15.500+01:00[Europe/Paris]"
+        );
+        assert_eq!(
+            Value::from(Duration::new(1, 2, 3, 4))
+                .to_iso_8601()
+                .unwrap(),
+            "P1M2DT3.000000004S"
+        );
+        assert_eq!(
+            Value::from(Duration::new(0, 0, -1, 500_000_000))
+                .to_iso_8601()
+                .unwrap(),
+            "P0M0DT-0.500000000S"
+        );
+        assert!(Value::from(date).is_temporal());
+        assert!(!Value::from(1).is_temporal());
+        assert!(Value::from(Point2D::new(1, 1.0, 2.0)).is_spatial());
+        assert_eq!(Value::from(1).to_iso_8601(), None);
+    }
+
     #[test]
     fn null_from_bytes() {
         let null_bytes = Null.try_into_bytes().unwrap();
@@ -323,17 +723,17 @@ mod tests {
     #[test]
     fn integer_from_bytes() {
         let tiny = Integer::from(110_i8);
-        let tiny_bytes = tiny.clone().try_into_bytes().unwrap();
+        let tiny_bytes = tiny.try_into_bytes().unwrap();
         let small = Integer::from(-50_i8);
-        let small_bytes = small.clone().try_into_bytes().unwrap();
+        let small_bytes = small.try_into_bytes().unwrap();
         let medium = Integer::from(8000_i16);
-        let medium_bytes = medium.clone().try_into_bytes().unwrap();
+        let medium_bytes = medium.try_into_bytes().unwrap();
         let medium_negative = Integer::from(-18621_i16);
-        let medium_negative_bytes = medium_negative.clone().try_into_bytes().unwrap();
+        let medium_negative_bytes = medium_negative.try_into_bytes().unwrap();
         let large = Integer::from(-1_000_000_000_i32);
-        let large_bytes = large.clone().try_into_bytes().unwrap();
+        let large_bytes = large.try_into_bytes().unwrap();
         let very_large = Integer::from(9_000_000_000_000_000_000_i64);
-        let very_large_bytes = very_large.clone().try_into_bytes().unwrap();
+        let very_large_bytes = very_large.try_into_bytes().unwrap();
         assert_eq!(
             Value::try_from(Arc::new(Mutex::new(tiny_bytes))).unwrap(),
             Value::Integer(tiny)
@@ -362,9 +762,9 @@ mod tests {
 
     #[test]
     fn float_from_bytes() {
-        let min = Float::from(std::f64::MIN_POSITIVE);
+        let min = Float::from(f64::MIN_POSITIVE);
         let min_bytes = min.clone().try_into_bytes().unwrap();
-        let max = Float::from(std::f64::MAX);
+        let max = Float::from(f64::MAX);
         let max_bytes = max.clone().try_into_bytes().unwrap();
         let e = Float::from(std::f64::consts::E);
         let e_bytes = e.clone().try_into_bytes().unwrap();
@@ -418,7 +818,7 @@ mod tests {
 
     #[test]
     fn string_from_bytes() {
-        let tiny = String::from("string".repeat(1));
+        let tiny = String::from("string".to_string());
         let tiny_bytes = tiny.clone().try_into_bytes().unwrap();
         let small = String::from("string".repeat(10));
         let small_bytes = small.clone().try_into_bytes().unwrap();
@@ -473,7 +873,6 @@ mod tests {
     }
 
     #[test]
-    #[ignore]
     fn large_list_from_bytes() {
         let large_list: List = vec![1_i8; 70_000].into();
         let large_list_bytes = large_list.clone().try_into_bytes().unwrap();
@@ -578,9 +977,252 @@ mod tests {
         );
     }
 
+    #[test]
+    fn accessors() {
+        assert!(Value::Null.is_null());
+        assert!(!Value::from(0).is_null());
+        assert_eq!(Value::from(true).as_bool(), Some(true));
+        assert_eq!(Value::from(1).as_integer(), Some(1));
+        assert_eq!(Value::from(1).as_float(), None);
+        assert_eq!(Value::from(1.5).as_float(), Some(1.5));
+        assert_eq!(Value::from(vec![1_u8]).as_bytes(), Some(&[1_u8][..]));
+        assert_eq!(Value::from("a").as_str(), Some("a"));
+        assert_eq!(Value::from(1).as_str(), None);
+        assert_eq!(
+            Value::from(vec![1, 2]).as_list(),
+            Some(&[Value::from(1), Value::from(2)][..])
+        );
+        assert_eq!(
+            Value::from([("a", 1)]).as_map().unwrap().get("a"),
+            Some(&Value::from(1))
+        );
+        let node = get_node();
+        assert_eq!(Value::from(node.clone()).as_node(), Some(&node));
+        assert_eq!(Value::from(node).as_relationship(), None);
+        let duration = Duration::new(1, 2, 3, 4);
+        assert_eq!(Value::from(duration.clone()).as_duration(), Some(&duration));
+    }
+
+    #[test]
+    fn from_collections() {
+        let list = Value::from(vec![Value::from(1), Value::from("two"), Value::from(3.0)]);
+        assert_eq!(Value::from((1, "two", 3.0)), list);
+        assert_eq!(Value::from((1,)), Value::from(vec![1]));
+        assert_eq!(Value::from([1, 2, 3]), Value::from(vec![1, 2, 3]));
+        assert_eq!(Value::from(&["a", "b"][..]), Value::from(vec!["a", "b"]));
+        assert_eq!(Value::from([1_u8, 2]), Value::from(vec![1_u8, 2]));
+
+        let map = Value::from(HashMap::from_iter(vec![("a", 1), ("b", 2)]));
+        assert_eq!(Value::from([("a", 1), ("b", 2)]), map);
+        assert_eq!(Value::from(&[("a", 1), ("b", 2)][..]), map);
+
+        assert_eq!(Value::from(Some(1)), Value::from(1));
+        assert_eq!(Value::from(None::<i64>), Value::Null);
+    }
+
+    #[test]
+    fn from_system_time() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let after = UNIX_EPOCH + Duration::new(1_600_000_000, 5);
+        assert!(matches!(
+            Value::from(after),
+            Value::DateTimeOffset(DateTimeOffset {
+                epoch_seconds: 1_600_000_000,
+                nanos: 5,
+                offset_seconds: 0
+            })
+        ));
+        let before = UNIX_EPOCH - Duration::new(10, 250_000_000);
+        assert!(matches!(
+            Value::from(before),
+            Value::DateTimeOffset(DateTimeOffset {
+                epoch_seconds: -11,
+                nanos: 750_000_000,
+                offset_seconds: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn option_from_value() {
+        assert_eq!(Option::<i64>::try_from(Value::Null).unwrap(), None);
+        assert_eq!(Option::<i64>::try_from(Value::from(1)).unwrap(), Some(1));
+        assert!(Option::<i64>::try_from(Value::from("one")).is_err());
+        assert_eq!(
+            Vec::<Option<std::string::String>>::try_from(Value::from(vec![
+                Value::from("a"),
+                Value::Null
+            ]))
+            .unwrap(),
+            vec![Some("a".to_string()), None]
+        );
+        assert_eq!(
+            HashMap::<std::string::String, Option<f64>>::try_from(Value::from(HashMap::from_iter(
+                vec![("x", Value::Null)]
+            )))
+            .unwrap(),
+            HashMap::from_iter(vec![("x".to_string(), None)])
+        );
+    }
+
+    #[test]
+    fn derive_value_conversions() {
+        use crate::{FromValue, IntoValue};
+
+        #[derive(Debug, PartialEq, FromValue, IntoValue)]
+        struct Person {
+            name: std::string::String,
+            #[bolt(rename = "yearOfBirth")]
+            year_of_birth: i64,
+            nickname: Option<std::string::String>,
+            #[bolt(skip)]
+            cached: Option<usize>,
+        }
+
+        let person = Person {
+            name: "Alice".to_string(),
+            year_of_birth: 1990,
+            nickname: None,
+            cached: Some(1),
+        };
+        let value = Value::from(person);
+        assert_eq!(
+            value,
+            Value::from(HashMap::from_iter(vec![
+                ("name", Value::from("Alice")),
+                ("yearOfBirth", Value::from(1990)),
+                ("nickname", Value::Null),
+            ]))
+        );
+        assert_eq!(
+            Person::try_from(value).unwrap(),
+            Person {
+                name: "Alice".to_string(),
+                year_of_birth: 1990,
+                nickname: None,
+                cached: None,
+            }
+        );
+
+        // Missing entries are only allowed for optional fields
+        let missing_nickname = Value::from(HashMap::from_iter(vec![
+            ("name", Value::from("Bob")),
+            ("yearOfBirth", Value::from(1985)),
+        ]));
+        assert_eq!(Person::try_from(missing_nickname).unwrap().nickname, None);
+        let missing_year = Value::from(HashMap::from_iter(vec![("name", "Bob")]));
+        assert!(matches!(
+            Person::try_from(missing_year),
+            Err(Error::ConversionError(ConversionError::MissingProperty(key))) if key == "yearOfBirth"
+        ));
+        let null_year = Value::from(HashMap::from_iter(vec![
+            ("name", Value::from("Bob")),
+            ("yearOfBirth", Value::Null),
+        ]));
+        assert!(matches!(
+            Person::try_from(null_year),
+            Err(Error::ConversionError(ConversionError::FromValue(
+                Value::Null
+            )))
+        ));
+        assert!(Person::try_from(Value::from(1)).is_err());
+    }
+
+    #[test]
+    fn loosely_eq() {
+        assert!(Value::from(1).loosely_eq(&Value::from(1.0)));
+        assert!(Value::from(1.0).loosely_eq(&Value::from(1)));
+        assert!(!Value::from(1).loosely_eq(&Value::from(1.5)));
+        assert!(!Value::from(1).loosely_eq(&Value::from("1")));
+        assert!(Value::from("a").loosely_eq(&Value::from("a")));
+
+        assert!(Value::from(vec![1, 2, 3]).loosely_eq(&Value::from(vec![1.0, 2.0, 3.0])));
+        assert!(!Value::from(vec![1, 2, 3]).loosely_eq(&Value::from(vec![1.0, 2.0])));
+        assert!(!Value::from(vec![1, 2, 3]).loosely_eq(&Value::from(vec![3.0, 2.0, 1.0])));
+
+        let map = Value::from(HashMap::from_iter(vec![
+            ("a", Value::from(1)),
+            ("b", Value::from(vec![2, 3])),
+        ]));
+        let float_map = Value::from(HashMap::from_iter(vec![
+            ("a", Value::from(1.0)),
+            ("b", Value::from(vec![2.0, 3.0])),
+        ]));
+        assert!(map.loosely_eq(&float_map));
+        assert!(!map.loosely_eq(&Value::from(HashMap::from_iter(vec![("a", 1.0)]))));
+    }
+
+    #[test]
+    fn map_with_capacity() {
+        match Value::map_with_capacity(100) {
+            Value::Map(map) => {
+                assert!(map.is_empty());
+                assert!(map.capacity() >= 100);
+            }
+            other => panic!("expected a map, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn node_v5_from_bytes() {
+        let node = get_node().with_element_id("4:a1b2:24");
+        let node_bytes: Bytes = node.clone().try_into_bytes().unwrap();
+        assert_eq!(node_bytes[0], node::MARKER_V5);
+
+        let value = Value::try_from(Arc::new(Mutex::new(node_bytes))).unwrap();
+        assert_eq!(value, Value::Node(node));
+        assert_eq!(
+            Node::try_from(value).unwrap().element_id(),
+            Some("4:a1b2:24")
+        );
+        assert_eq!(get_node().element_id(), None);
+    }
+
+    #[test]
+    fn relationship_builder() {
+        let rel = Relationship::builder(24, 32, 128, "TestRel")
+            .property("key1", -2_i8)
+            .property("key2", 2_i8)
+            .build()
+            .unwrap();
+        assert_eq!(rel, get_rel());
+        let rel = Relationship::builder(24, 32, 128, "TestRel")
+            .element_ids("5:a1b2:24", "4:a1b2:32", "4:a1b2:128")
+            .build()
+            .unwrap();
+        assert_eq!(rel.start_node_element_id(), Some("4:a1b2:32"));
+
+        assert!(matches!(
+            Relationship::builder(24, 32, 128, "").build(),
+            Err(Error::ConversionError(
+                ConversionError::InvalidGraphStructure(_)
+            ))
+        ));
+        assert!(Relationship::builder(24, 32, 128, "TestRel")
+            .property("", 1)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn relationship_v5_from_bytes() {
+        let rel = get_rel().with_element_ids("5:a1b2:24", "4:a1b2:32", "4:a1b2:128");
+        let rel_bytes: Bytes = rel.clone().try_into_bytes().unwrap();
+        assert_eq!(rel_bytes[0], relationship::MARKER_V5);
+
+        let value = Value::try_from(Arc::new(Mutex::new(rel_bytes))).unwrap();
+        assert_eq!(value, Value::Relationship(rel));
+        let rel = Relationship::try_from(value).unwrap();
+        assert_eq!(rel.element_id(), Some("5:a1b2:24"));
+        assert_eq!(rel.start_node_element_id(), Some("4:a1b2:32"));
+        assert_eq!(rel.end_node_element_id(), Some("4:a1b2:128"));
+        assert_eq!(get_rel().element_id(), None);
+    }
+
     #[test]
     fn path_from_bytes() {
-        let path = Path::new(vec![get_node()], vec![get_unbound_rel()], 100_i64);
+        let path = Path::new(vec![get_node()], vec![get_unbound_rel()], vec![1, 0]);
         let path_bytes: Bytes = path.clone().try_into_bytes().unwrap();
 
         assert_eq!(
@@ -599,9 +1241,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unbound_relationship_v5_from_bytes() {
+        let rel = get_unbound_rel().with_element_id("5:a1b2:24");
+        let rel_bytes: Bytes = rel.clone().try_into_bytes().unwrap();
+        assert_eq!(rel_bytes[0], unbound_relationship::MARKER_V5);
+
+        let value = Value::try_from(Arc::new(Mutex::new(rel_bytes))).unwrap();
+        assert_eq!(value, Value::UnboundRelationship(rel));
+        assert_eq!(
+            UnboundRelationship::try_from(value).unwrap().element_id(),
+            Some("5:a1b2:24")
+        );
+        assert_eq!(get_unbound_rel().element_id(), None);
+
+        // Paths hold v5 graph structures too
+        let path = Path::new(
+            vec![get_node().with_element_id("4:a1b2:32")],
+            vec![get_unbound_rel().with_element_id("5:a1b2:24")],
+            vec![],
+        );
+        let path_bytes: Bytes = path.clone().try_into_bytes().unwrap();
+        assert_eq!(
+            Value::try_from(Arc::new(Mutex::new(path_bytes))).unwrap(),
+            Value::Path(path)
+        );
+    }
+
+    #[cfg(feature = "chrono")]
     #[test]
     fn date_from_bytes() {
-        let christmas = Date::from(NaiveDate::from_ymd(2020, 12, 25));
+        let christmas = Date::from(NaiveDate::from_ymd_opt(2020, 12, 25).unwrap());
         let christmas_bytes: Bytes = christmas.clone().try_into_bytes().unwrap();
         assert_eq!(
             Value::try_from(Arc::new(Mutex::new(christmas_bytes))).unwrap(),
@@ -609,13 +1279,14 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "chrono")]
     #[test]
     fn time_from_bytes() {
-        let midnight_utc = Time::from((NaiveTime::from_hms_nano(0, 0, 0, 0), Utc));
+        let midnight_utc = Time::from((NaiveTime::from_hms_nano_opt(0, 0, 0, 0).unwrap(), Utc));
         let midnight_utc_bytes = midnight_utc.clone().try_into_bytes().unwrap();
         let about_four_pm_pacific = Time::from((
-            NaiveTime::from_hms_nano(16, 4, 35, 235),
-            FixedOffset::east(-8 * 3600),
+            NaiveTime::from_hms_nano_opt(16, 4, 35, 235).unwrap(),
+            FixedOffset::east_opt(-8 * 3600).unwrap(),
         ));
         let about_four_pm_pacific_bytes = about_four_pm_pacific.clone().try_into_bytes().unwrap();
         assert_eq!(
@@ -628,11 +1299,16 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "chrono")]
     #[test]
     fn date_time_offset_from_bytes() {
         let date_time = DateTimeOffset::from(
-            FixedOffset::east(-5 * 3600)
-                .from_utc_datetime(&NaiveDate::from_ymd(2050, 12, 31).and_hms_nano(23, 59, 59, 10)),
+            FixedOffset::east_opt(-5 * 3600).unwrap().from_utc_datetime(
+                &NaiveDate::from_ymd_opt(2050, 12, 31)
+                    .unwrap()
+                    .and_hms_nano_opt(23, 59, 59, 10)
+                    .unwrap(),
+            ),
         );
         let date_time_bytes = date_time.clone().try_into_bytes().unwrap();
         assert_eq!(
@@ -641,10 +1317,14 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "chrono")]
     #[test]
     fn date_time_zoned_from_bytes() {
         let date_time = DateTimeZoned::from((
-            NaiveDate::from_ymd(2030, 8, 3).and_hms_milli(14, 30, 1, 2),
+            NaiveDate::from_ymd_opt(2030, 8, 3)
+                .unwrap()
+                .and_hms_milli_opt(14, 30, 1, 2)
+                .unwrap(),
             chrono_tz::Asia::Ulaanbaatar,
         ));
         let date_time_bytes = date_time.clone().try_into_bytes().unwrap();
@@ -654,9 +1334,10 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "chrono")]
     #[test]
     fn local_time_from_bytes() {
-        let local_time = LocalTime::from(NaiveTime::from_hms_nano(23, 59, 59, 999));
+        let local_time = LocalTime::from(NaiveTime::from_hms_nano_opt(23, 59, 59, 999).unwrap());
         let local_time_bytes = local_time.clone().try_into_bytes().unwrap();
         assert_eq!(
             Value::try_from(Arc::new(Mutex::new(local_time_bytes))).unwrap(),
@@ -664,10 +1345,15 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "chrono")]
     #[test]
     fn local_date_time_from_bytes() {
-        let local_date_time =
-            LocalDateTime::from(NaiveDate::from_ymd(1999, 2, 27).and_hms_nano(1, 0, 0, 9999));
+        let local_date_time = LocalDateTime::from(
+            NaiveDate::from_ymd_opt(1999, 2, 27)
+                .unwrap()
+                .and_hms_nano_opt(1, 0, 0, 9999)
+                .unwrap(),
+        );
         let local_date_time_bytes = local_date_time.clone().try_into_bytes().unwrap();
         assert_eq!(
             Value::try_from(Arc::new(Mutex::new(local_date_time_bytes))).unwrap(),
@@ -685,6 +1371,42 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn date_time_utc_from_bytes() {
+        let date_time = NaiveDate::from_ymd_opt(2021, 1, 15)
+            .unwrap()
+            .and_hms_opt(8, 30, 0)
+            .unwrap();
+        let offset = FixedOffset::east_opt(-5 * 3600).unwrap();
+        let date_time_offset = Value::from(offset.from_local_datetime(&date_time).unwrap());
+        let date_time_zoned = Value::from((date_time, chrono_tz::America::New_York));
+        for value in [date_time_offset, date_time_zoned] {
+            let legacy_bytes = value.clone().try_into_bytes().unwrap();
+            let utc_bytes = encode_utc(|| value.clone().try_into_bytes().unwrap());
+            assert_ne!(legacy_bytes[1], utc_bytes[1]);
+            assert_eq!(
+                Value::try_from(Arc::new(Mutex::new(legacy_bytes))).unwrap(),
+                value
+            );
+            assert_eq!(
+                Value::try_from(Arc::new(Mutex::new(utc_bytes))).unwrap(),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn duration_to_std() {
+        let std_duration = std::time::Duration::new(90_061, 5);
+        assert_eq!(
+            std::time::Duration::try_from(Value::from(std_duration)).unwrap(),
+            std_duration
+        );
+        assert!(std::time::Duration::try_from(Value::from(Duration::new(1, 0, 0, 0))).is_err());
+        assert!(std::time::Duration::try_from(Value::from(1)).is_err());
+    }
+
     #[test]
     fn point_from_bytes() {
         let point2d = Point2D::new(9876, 12.312_345, 134_564.123_567_543);
@@ -702,6 +1424,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unknown_structure_from_bytes() {
+        let bytes = Bytes::from_static(&[
+            list::MARKER_TINY | 2,
+            STRUCT_MARKER_TINY | 2,
+            0x01,
+            0x01,
+            string::MARKER_TINY | 1,
+            b'a',
+            0x02,
+        ]);
+        let value = Value::try_from(Arc::new(Mutex::new(bytes.clone()))).unwrap();
+        let structure = Structure::new(0x01, vec![Value::from(1), Value::from("a")]);
+        assert_eq!(
+            value,
+            Value::from(vec![Value::Structure(structure), Value::from(2)])
+        );
+        // Re-serialized verbatim
+        assert_eq!(value.try_into_bytes().unwrap(), bytes);
+    }
+
     #[test]
     #[ignore]
     fn value_size() {
@@ -1,10 +1,8 @@
-use std::convert::{TryFrom, TryInto};
-use std::panic::catch_unwind;
-use std::sync::{Arc, Mutex};
-
-use bytes::{Buf, Bytes};
+use bytes::{BufMut, Bytes};
 
 use crate::error::*;
+use crate::impl_try_from_shared_bytes;
+use crate::impl_try_into_bytes;
 use crate::serialization::*;
 
 pub(crate) const MARKER_FALSE: u8 = 0xC2;
@@ -23,38 +21,34 @@ impl Marker for Boolean {
             Ok(MARKER_FALSE)
         }
     }
-}
 
-impl Serialize for Boolean {}
-
-impl TryInto<Bytes> for Boolean {
-    type Error = Error;
+    fn serialized_len(&self) -> Result<usize> {
+        Ok(std::mem::size_of::<u8>())
+    }
+}
 
-    fn try_into(self) -> Result<Bytes> {
-        Ok(Bytes::copy_from_slice(&[self.get_marker()?]))
+impl Serialize for Boolean {
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()> {
+        buf.put_u8(self.get_marker()?);
+        Ok(())
     }
 }
 
-impl Deserialize for Boolean {}
-
-impl TryFrom<Arc<Mutex<Bytes>>> for Boolean {
-    type Error = Error;
-
-    fn try_from(input_arc: Arc<Mutex<Bytes>>) -> Result<Self> {
-        catch_unwind(move || {
-            let mut input_bytes = input_arc.lock().unwrap();
-            let marker = input_bytes.get_u8();
-            debug_assert!(!input_bytes.has_remaining());
-            match marker {
-                MARKER_TRUE => Ok(Boolean::from(true)),
-                MARKER_FALSE => Ok(Boolean::from(false)),
-                _ => Err(DeserializationError::InvalidMarkerByte(marker).into()),
-            }
-        })
-        .map_err(|_| DeserializationError::Panicked)?
+impl_try_into_bytes!(Boolean);
+
+impl Deserialize for Boolean {
+    fn deserialize(bytes: &mut Bytes) -> Result<Self> {
+        let marker = bytes.try_get_u8("boolean marker")?;
+        match marker {
+            MARKER_TRUE => Ok(Boolean::from(true)),
+            MARKER_FALSE => Ok(Boolean::from(false)),
+            _ => Err(DeserializationError::InvalidMarkerByte(marker).into()),
+        }
     }
 }
 
+impl_try_from_shared_bytes!(Boolean);
+
 impl From<bool> for Boolean {
     fn from(value: bool) -> Self {
         Self { value }
@@ -1,20 +1,20 @@
-use std::convert::{TryFrom, TryInto};
 use std::mem;
-use std::panic::catch_unwind;
-use std::sync::{Arc, Mutex};
 
-use bytes::{Buf, BufMut, Bytes, BytesMut};
+use bytes::{BufMut, Bytes};
 
 use crate::error::*;
+use crate::impl_try_from_shared_bytes;
+use crate::impl_try_into_bytes;
 use crate::serialization::*;
 
 pub(crate) const MARKER_SMALL: u8 = 0xCC;
 pub(crate) const MARKER_MEDIUM: u8 = 0xCD;
 pub(crate) const MARKER_LARGE: u8 = 0xCE;
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+// Backed by a slice of the buffer it was deserialized from, so large byte arrays aren't copied
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub struct ByteArray {
-    pub(crate) value: Vec<u8>,
+    pub(crate) value: Bytes,
 }
 
 impl Marker for ByteArray {
@@ -26,63 +26,83 @@ impl Marker for ByteArray {
             _ => Err(Error::ValueTooLarge(self.value.len())),
         }
     }
-}
 
-impl Serialize for ByteArray {}
+    fn serialized_len(&self) -> Result<usize> {
+        // Marker byte, then an 8, 16, or 32-bit size, then the bytes
+        let size_len = match self.get_marker()? {
+            MARKER_SMALL => mem::size_of::<u8>(),
+            MARKER_MEDIUM => mem::size_of::<u16>(),
+            _ => mem::size_of::<u32>(),
+        };
+        Ok(mem::size_of::<u8>() + size_len + self.value.len())
+    }
+}
 
-impl TryInto<Bytes> for ByteArray {
-    type Error = Error;
+impl Serialize for ByteArray {
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()> {
+        put_byte_array(buf, &self.value)
+    }
+}
 
-    fn try_into(self) -> Result<Bytes> {
-        // Worst case is a large ByteArray, with marker byte, 32-bit size value, and length
-        let mut bytes = BytesMut::with_capacity(
-            mem::size_of::<u8>() + mem::size_of::<u32>() + self.value.len(),
-        );
-        bytes.put_u8(self.get_marker()?);
-        match self.value.len() {
-            0..=255 => bytes.put_u8(self.value.len() as u8),
-            256..=65_535 => bytes.put_u16(self.value.len() as u16),
-            65_536..=4_294_967_295 => bytes.put_u32(self.value.len() as u32),
-            _ => return Err(Error::ValueTooLarge(self.value.len())),
+pub(crate) fn put_byte_array(buf: &mut impl BufMut, value: &[u8]) -> Result<()> {
+    match value.len() {
+        0..=255 => {
+            buf.put_u8(MARKER_SMALL);
+            buf.put_u8(value.len() as u8);
+        }
+        256..=65_535 => {
+            buf.put_u8(MARKER_MEDIUM);
+            buf.put_u16(value.len() as u16);
         }
-        bytes.put_slice(&self.value);
-        Ok(bytes.freeze())
+        65_536..=4_294_967_295 => {
+            buf.put_u8(MARKER_LARGE);
+            buf.put_u32(value.len() as u32);
+        }
+        _ => return Err(Error::ValueTooLarge(value.len())),
+    }
+    buf.put_slice(value);
+    Ok(())
+}
+
+impl_try_into_bytes!(ByteArray);
+
+impl Deserialize for ByteArray {
+    fn deserialize(bytes: &mut Bytes) -> Result<Self> {
+        let marker = bytes.try_get_u8("byte array marker")?;
+        let size = match marker {
+            MARKER_SMALL => bytes.try_get_u8("byte array size")? as usize,
+            MARKER_MEDIUM => bytes.try_get_u16("byte array size")? as usize,
+            MARKER_LARGE => bytes.try_get_u32("byte array size")? as usize,
+            _ => {
+                return Err(DeserializationError::InvalidMarkerByte(marker).into());
+            }
+        };
+        bytes.ensure_remaining(size, "byte array")?;
+        Ok(ByteArray::from(bytes.split_to(size)))
     }
 }
 
-impl Deserialize for ByteArray {}
-
-impl TryFrom<Arc<Mutex<Bytes>>> for ByteArray {
-    type Error = Error;
-
-    fn try_from(input_arc: Arc<Mutex<Bytes>>) -> Result<Self> {
-        catch_unwind(move || {
-            let mut input_bytes = input_arc.lock().unwrap();
-            let marker = input_bytes.get_u8();
-            let size = match marker {
-                MARKER_SMALL => input_bytes.get_u8() as usize,
-                MARKER_MEDIUM => input_bytes.get_u16() as usize,
-                MARKER_LARGE => input_bytes.get_u32() as usize,
-                _ => {
-                    return Err(DeserializationError::InvalidMarkerByte(marker).into());
-                }
-            };
-            let mut byte_arr = vec![0; size];
-            input_bytes.copy_to_slice(&mut byte_arr);
-            Ok(ByteArray::from(byte_arr))
-        })
-        .map_err(|_| DeserializationError::Panicked)?
+impl_try_from_shared_bytes!(ByteArray);
+
+impl From<Bytes> for ByteArray {
+    fn from(value: Bytes) -> Self {
+        Self { value }
     }
 }
 
 impl From<Vec<u8>> for ByteArray {
     fn from(value: Vec<u8>) -> Self {
-        Self { value }
+        Self {
+            value: Bytes::from(value),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::convert::TryFrom;
+    use std::sync::{Arc, Mutex};
+
     use crate::value::*;
 
     use super::*;
@@ -183,4 +203,13 @@ mod tests {
             large_arr
         );
     }
+
+    #[test]
+    fn deserialize_without_copying() {
+        let input = ByteArray::from(vec![1_u8; 1000]).try_into_bytes().unwrap();
+        let value = Value::deserialize(&mut input.clone()).unwrap();
+        let bytes = value.as_bytes().unwrap();
+        assert_eq!(bytes, &[1_u8; 1000][..]);
+        assert_eq!(bytes.as_ptr(), input[3..].as_ptr());
+    }
 }
@@ -2,8 +2,12 @@ use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::hash::{BuildHasher, Hash};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone};
+use bytes::Bytes;
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone, Utc};
+#[cfg(feature = "chrono")]
 use chrono_tz::Tz;
 
 use crate::error::*;
@@ -42,6 +46,12 @@ impl From<&[u8]> for Value {
     }
 }
 
+impl From<Bytes> for Value {
+    fn from(value: Bytes) -> Self {
+        Value::Bytes(ByteArray::from(value))
+    }
+}
+
 impl From<Vec<u8>> for Value {
     fn from(value: Vec<u8>) -> Self {
         Value::Bytes(ByteArray::from(value))
@@ -57,9 +67,83 @@ where
     }
 }
 
+impl<const N: usize> From<[u8; N]> for Value {
+    fn from(value: [u8; N]) -> Self {
+        Value::Bytes(ByteArray::from(value.to_vec()))
+    }
+}
+
+impl<T> From<&[T]> for Value
+where
+    T: Into<Value> + Clone,
+{
+    fn from(value: &[T]) -> Self {
+        Value::List(List::from(value.to_vec()))
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for Value
+where
+    T: Into<Value>,
+{
+    fn from(value: [T; N]) -> Self {
+        Value::List(List::from(Vec::from(value)))
+    }
+}
+
+// Pairs with string keys are map entries, e.g. for building parameters
+impl<V> From<&[(&str, V)]> for Value
+where
+    V: Into<Value> + Clone,
+{
+    fn from(entries: &[(&str, V)]) -> Self {
+        Value::from(entries.iter().cloned().collect::<HashMap<_, _>>())
+    }
+}
+
+impl<V, const N: usize> From<[(&str, V); N]> for Value
+where
+    V: Into<Value>,
+{
+    fn from(entries: [(&str, V); N]) -> Self {
+        Value::from(IntoIterator::into_iter(entries).collect::<HashMap<_, _>>())
+    }
+}
+
+// Tuples become lists, except for pairs, which already convert into temporal values
+macro_rules! impl_from_tuple {
+    ($(($($T:ident $i:tt),+)),+) => {
+        $(
+            impl<$($T: Into<Value>),+> From<($($T,)+)> for Value {
+                fn from(tuple: ($($T,)+)) -> Self {
+                    Value::List(List::from(vec![$(tuple.$i.into()),+]))
+                }
+            }
+        )+
+    };
+}
+impl_from_tuple!(
+    (A 0),
+    (A 0, B 1, C 2),
+    (A 0, B 1, C 2, D 3),
+    (A 0, B 1, C 2, D 3, E 4),
+    (A 0, B 1, C 2, D 3, E 4, F 5),
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6),
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7)
+);
+
+impl<T> From<Option<T>> for Value
+where
+    T: Into<Value>,
+{
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
 impl<K, V> From<HashMap<K, V>> for Value
 where
-    K: Into<Value>,
+    K: Into<std::string::String>,
     V: Into<Value>,
 {
     fn from(value: HashMap<K, V, RandomState>) -> Self {
@@ -103,12 +187,14 @@ impl From<UnboundRelationship> for Value {
     }
 }
 
+#[cfg(feature = "chrono")]
 impl From<NaiveDate> for Value {
     fn from(value: NaiveDate) -> Self {
         Value::Date(Date::from(value))
     }
 }
 
+#[cfg(feature = "chrono")]
 // No timezone-aware time in chrono, so provide a separate conversion
 impl<O: Offset> From<(NaiveTime, O)> for Value {
     fn from(pair: (NaiveTime, O)) -> Self {
@@ -116,12 +202,14 @@ impl<O: Offset> From<(NaiveTime, O)> for Value {
     }
 }
 
+#[cfg(feature = "chrono")]
 impl<T: TimeZone> From<DateTime<T>> for Value {
     fn from(value: DateTime<T>) -> Self {
         Value::DateTimeOffset(DateTimeOffset::from(value))
     }
 }
 
+#[cfg(feature = "chrono")]
 // Can't decide between Offset or Zoned variant at runtime if using a T: TimeZone, so provide a separate conversion
 impl From<(NaiveDateTime, chrono_tz::Tz)> for Value {
     fn from(pair: (NaiveDateTime, chrono_tz::Tz)) -> Self {
@@ -129,12 +217,14 @@ impl From<(NaiveDateTime, chrono_tz::Tz)> for Value {
     }
 }
 
+#[cfg(feature = "chrono")]
 impl From<NaiveTime> for Value {
     fn from(value: NaiveTime) -> Self {
         Value::LocalTime(LocalTime::from(value))
     }
 }
 
+#[cfg(feature = "chrono")]
 impl From<NaiveDateTime> for Value {
     fn from(value: NaiveDateTime) -> Self {
         Value::LocalDateTime(LocalDateTime::from(value))
@@ -153,6 +243,33 @@ impl From<std::time::Duration> for Value {
     }
 }
 
+impl From<Structure> for Value {
+    fn from(value: Structure) -> Self {
+        Value::Structure(value)
+    }
+}
+
+// A point in time becomes a UTC date-time
+impl From<SystemTime> for Value {
+    fn from(value: SystemTime) -> Self {
+        let (epoch_seconds, nanos) = match value.duration_since(UNIX_EPOCH) {
+            Ok(since) => (since.as_secs() as i64, since.subsec_nanos() as i64),
+            Err(before) => {
+                let before = before.duration();
+                match before.subsec_nanos() {
+                    0 => (-(before.as_secs() as i64), 0),
+                    nanos => (-(before.as_secs() as i64) - 1, 1_000_000_000 - nanos as i64),
+                }
+            }
+        };
+        Value::DateTimeOffset(DateTimeOffset {
+            epoch_seconds,
+            nanos,
+            offset_seconds: 0,
+        })
+    }
+}
+
 impl From<Point2D> for Value {
     fn from(value: Point2D) -> Self {
         Value::Point2D(value)
@@ -228,7 +345,7 @@ impl TryFrom<Value> for Vec<u8> {
 
     fn try_from(value: Value) -> Result<Self> {
         match value {
-            Value::Bytes(byte_array) => Ok(byte_array.value),
+            Value::Bytes(byte_array) => Ok(byte_array.value.to_vec()),
             _ => Err(ConversionError::FromValue(value).into()),
         }
     }
@@ -248,6 +365,21 @@ where
     }
 }
 
+// Null is treated as None, e.g. for optional properties or columns
+impl<T> TryFrom<Value> for Option<T>
+where
+    T: TryFrom<Value, Error = Error>,
+{
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self> {
+        match value {
+            Value::Null => Ok(None),
+            _ => T::try_from(value).map(Some),
+        }
+    }
+}
+
 impl TryFrom<Value> for Vec<Value> {
     type Error = Error;
 
@@ -261,7 +393,7 @@ impl TryFrom<Value> for Vec<Value> {
 
 impl<K, V, S> TryFrom<Value> for HashMap<K, V, S>
 where
-    K: Hash + Eq + TryFrom<Value, Error = Error>,
+    K: Hash + Eq + From<std::string::String>,
     V: TryFrom<Value, Error = Error>,
     S: BuildHasher + Default,
 {
@@ -273,7 +405,7 @@ where
                 let mut new_map =
                     HashMap::with_capacity_and_hasher(map.value.len(), Default::default());
                 for (k, v) in map.value {
-                    new_map.insert(K::try_from(k)?, V::try_from(v)?);
+                    new_map.insert(K::from(k), V::try_from(v)?);
                 }
                 Ok(new_map)
             }
@@ -284,7 +416,7 @@ where
 
 impl<K, S> TryFrom<Value> for HashMap<K, Value, S>
 where
-    K: Hash + Eq + TryFrom<Value, Error = Error>,
+    K: Hash + Eq + From<std::string::String>,
     S: BuildHasher + Default,
 {
     type Error = Error;
@@ -295,7 +427,7 @@ where
                 let mut new_map =
                     HashMap::with_capacity_and_hasher(map.value.len(), Default::default());
                 for (k, v) in map.value {
-                    new_map.insert(K::try_from(k)?, v);
+                    new_map.insert(K::from(k), v);
                 }
                 Ok(new_map)
             }
@@ -309,7 +441,7 @@ impl TryFrom<Value> for std::string::String {
 
     fn try_from(value: Value) -> Result<Self> {
         match value {
-            Value::String(string) => Ok(string.value),
+            Value::String(string) => string.into_string(),
             _ => Err(ConversionError::FromValue(value).into()),
         }
     }
@@ -323,14 +455,13 @@ impl_try_from_value!(Path, Path);
 
 impl_try_from_value!(UnboundRelationship, UnboundRelationship);
 
+#[cfg(feature = "chrono")]
 impl TryFrom<Value> for NaiveDate {
     type Error = Error;
 
     fn try_from(value: Value) -> Result<Self> {
         match value {
-            Value::Date(date) => {
-                Ok(NaiveDate::from_ymd(1970, 1, 1) + chrono::Duration::days(date.days_since_epoch))
-            }
+            Value::Date(date) => date.naive_date(),
             _ => Err(ConversionError::FromValue(value).into()),
         }
     }
@@ -338,94 +469,99 @@ impl TryFrom<Value> for NaiveDate {
 
 impl_try_from_value!(Time, Time);
 
+#[cfg(feature = "chrono")]
 impl TryFrom<Value> for DateTime<FixedOffset> {
     type Error = Error;
 
     fn try_from(value: Value) -> Result<Self> {
         match value {
-            Value::DateTimeOffset(date_time_offset) => Ok(FixedOffset::east(
-                date_time_offset.offset_seconds,
-            )
-            .timestamp(
-                date_time_offset.epoch_seconds,
-                date_time_offset.nanos as u32,
-            )),
+            Value::DateTimeOffset(date_time_offset) => date_time_offset.date_time(),
             Value::DateTimeZoned(date_time_zoned) => {
-                // Time zone guaranteed to be valid in existing objects, ok to unwrap
-                let timezone: Tz = date_time_zoned.zone_id.parse().unwrap();
-                let timezone: FixedOffset = timezone
-                    // Get the fixed offset (e.g. Pacific Daylight vs. Pacific Standard) for the given point in time
-                    .offset_from_utc_datetime(
-                        &NaiveDateTime::from_timestamp_opt(date_time_zoned.epoch_seconds, 0)
-                            // epoch_seconds is guaranteed to be a valid timestamp, ok to unwrap
-                            .unwrap(),
-                    )
-                    .fix();
-                Ok(timezone
-                    .timestamp_opt(date_time_zoned.epoch_seconds, date_time_zoned.nanos as u32)
-                    // epoch_seconds and nanos are guaranteed to be valid in existing objects, ok to unwrap
-                    .unwrap())
+                let date_time = DateTime::<Tz>::try_from(Value::DateTimeZoned(date_time_zoned))?;
+                // The fixed offset (e.g. Pacific Daylight vs. Pacific Standard) at the given point in time
+                Ok(date_time.with_timezone(&date_time.offset().fix()))
             }
             _ => Err(ConversionError::FromValue(value).into()),
         }
     }
 }
 
+#[cfg(feature = "chrono")]
 impl TryFrom<Value> for DateTime<Tz> {
     type Error = Error;
 
     fn try_from(value: Value) -> Result<Self> {
         match value {
+            // Deserialized values can have an unknown zone id or out-of-range fields
             Value::DateTimeZoned(date_time_zoned) => {
-                // Time zone guaranteed to be valid in existing objects, ok to unwrap
-                let timezone: Tz = date_time_zoned.zone_id.parse().unwrap();
-                Ok(timezone
-                    .timestamp_opt(date_time_zoned.epoch_seconds, date_time_zoned.nanos as u32)
-                    // epoch_seconds and nanos are guaranteed to be valid in existing objects, ok to unwrap
-                    .unwrap())
+                let timezone = date_time_zoned.time_zone()?;
+                u32::try_from(date_time_zoned.nanos)
+                    .ok()
+                    .and_then(|nanos| {
+                        timezone
+                            .timestamp_opt(date_time_zoned.epoch_seconds, nanos)
+                            .single()
+                    })
+                    .ok_or_else(|| {
+                        ConversionError::InvalidTimestamp(date_time_zoned.epoch_seconds).into()
+                    })
             }
             _ => Err(ConversionError::FromValue(value).into()),
         }
     }
 }
 
+#[cfg(feature = "chrono")]
+impl TryFrom<Value> for DateTime<Utc> {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self> {
+        Ok(DateTime::<FixedOffset>::try_from(value)?.with_timezone(&Utc))
+    }
+}
+
+#[cfg(feature = "chrono")]
 impl TryFrom<Value> for NaiveTime {
     type Error = Error;
 
     fn try_from(value: Value) -> Result<Self> {
         match value {
-            Value::LocalTime(local_time) => {
-                let seconds = (local_time.nanos_since_midnight / 1_000_000_000) as u32;
-                let nanos = (local_time.nanos_since_midnight % 1_000_000_000) as u32;
-                // We created the LocalTime from a NaiveTime, so it can easily be converted back without worrying about
-                // a panic occurring
-                Ok(NaiveTime::from_num_seconds_from_midnight(seconds, nanos))
-            }
+            Value::LocalTime(local_time) => local_time.naive_time(),
             _ => Err(ConversionError::FromValue(value).into()),
         }
     }
 }
 
+#[cfg(feature = "chrono")]
 impl TryFrom<Value> for NaiveDateTime {
     type Error = Error;
 
     fn try_from(value: Value) -> Result<Self> {
         match value {
-            // We created the LocalDateTime from a NaiveDateTime, so it can easily be converted back without worrying
-            // about a panic occurring
-            Value::LocalDateTime(local_date_time) => Ok(NaiveDateTime::from_timestamp(
-                local_date_time.epoch_seconds,
-                local_date_time.nanos as u32,
-            )),
+            Value::LocalDateTime(local_date_time) => local_date_time.naive_date_time(),
             _ => Err(ConversionError::FromValue(value).into()),
         }
     }
 }
 
-// We cannot convert to std::time::Duration, since months are not well-defined in terms of seconds, and our Duration can
-// hold quantities that are impossible to hold in a std::time::Duration (like negative durations).
 impl_try_from_value!(Duration, Duration);
 
+// Only possible for durations without months that are not negative, see Duration::to_std
+impl TryFrom<Value> for std::time::Duration {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self> {
+        match &value {
+            Value::Duration(duration) => duration
+                .to_std()
+                .ok_or_else(|| ConversionError::FromValue(value).into()),
+            _ => Err(ConversionError::FromValue(value).into()),
+        }
+    }
+}
+
 impl_try_from_value!(Point2D, Point2D);
 
 impl_try_from_value!(Point3D, Point3D);
+
+impl_try_from_value!(Structure, Structure);
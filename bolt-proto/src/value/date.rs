@@ -1,23 +1,52 @@
+#[cfg(feature = "chrono")]
+use std::convert::TryFrom;
+
 use bolt_proto_derive::*;
+#[cfg(feature = "chrono")]
 use chrono::NaiveDate;
 
+#[cfg(feature = "chrono")]
+use crate::error::*;
+
 pub(crate) const MARKER: u8 = 0xB1;
 pub(crate) const SIGNATURE: u8 = 0x44;
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Date {
     pub(crate) days_since_epoch: i64,
 }
 
+impl Date {
+    /// The number of days since the Unix epoch, which is negative for dates before it.
+    pub fn days_since_epoch(&self) -> i64 {
+        self.days_since_epoch
+    }
+
+    /// Convert to a chrono date. Fails for dates outside of chrono's range, which is narrower than Bolt's.
+    #[cfg(feature = "chrono")]
+    pub fn naive_date(&self) -> Result<NaiveDate> {
+        // The Unix epoch is 719,163 days after 0001-01-01, which chrono counts as day 1
+        self.days_since_epoch
+            .checked_add(719_163)
+            .and_then(|days| i32::try_from(days).ok())
+            .and_then(NaiveDate::from_num_days_from_ce_opt)
+            .ok_or_else(|| ConversionError::InvalidDate(self.days_since_epoch).into())
+    }
+}
+
+#[cfg(feature = "chrono")]
 impl From<NaiveDate> for Date {
     fn from(naive_date: NaiveDate) -> Self {
         Self {
-            days_since_epoch: (naive_date - NaiveDate::from_ymd(1970, 1, 1)).num_days(),
+            // The Unix epoch is a valid date, ok to unwrap
+            days_since_epoch: (naive_date - NaiveDate::from_ymd_opt(1970, 1, 1).unwrap())
+                .num_days(),
         }
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "chrono"))]
 mod tests {
     use std::convert::TryFrom;
     use std::sync::{Arc, Mutex};
@@ -27,18 +56,41 @@ mod tests {
 
     use crate::serialization::*;
     use crate::value::integer::{MARKER_INT_16, MARKER_INT_32};
+    use crate::Value;
 
     use super::*;
 
+    #[test]
+    fn naive_date() {
+        let naive_date = NaiveDate::from_ymd_opt(1901, 12, 31).unwrap();
+        assert_eq!(Date::from(naive_date).naive_date().unwrap(), naive_date);
+        let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+        assert_eq!(Date::from(epoch).naive_date().unwrap(), epoch);
+    }
+
+    #[test]
+    fn out_of_range() {
+        for days in [i64::MAX, i64::MIN, 1_000_000_000] {
+            let date = Date {
+                days_since_epoch: days,
+            };
+            assert!(matches!(
+                date.naive_date(),
+                Err(Error::ConversionError(ConversionError::InvalidDate(d))) if d == days
+            ));
+            assert!(Value::Date(date).to_string().contains(&days.to_string()));
+        }
+    }
+
     #[test]
     fn get_marker() {
-        let date = Date::from(NaiveDate::from_ymd(2020, 1, 1));
+        let date = Date::from(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap());
         assert_eq!(date.get_marker().unwrap(), MARKER);
     }
 
     #[test]
     fn try_into_bytes() {
-        let date = Date::from(NaiveDate::from_ymd(1901, 12, 31));
+        let date = Date::from(NaiveDate::from_ymd_opt(1901, 12, 31).unwrap());
         assert_eq!(
             date.try_into_bytes().unwrap(),
             Bytes::from_static(&[MARKER, SIGNATURE, MARKER_INT_16, 0x9E, 0xFA])
@@ -47,9 +99,9 @@ mod tests {
 
     #[test]
     fn try_from_bytes() {
-        let past_date = Date::from(NaiveDate::from_ymd(1901, 12, 31));
+        let past_date = Date::from(NaiveDate::from_ymd_opt(1901, 12, 31).unwrap());
         let past_bytes = &[MARKER_INT_16, 0x9E, 0xFA];
-        let future_date = Date::from(NaiveDate::from_ymd(3000, 5, 23));
+        let future_date = Date::from(NaiveDate::from_ymd_opt(3000, 5, 23).unwrap());
         let future_bytes = &[MARKER_INT_32, 0x00, 0x05, 0xBE, 0x16];
         assert_eq!(
             Date::try_from(Arc::new(Mutex::new(Bytes::from_static(past_bytes)))).unwrap(),
@@ -1,17 +1,76 @@
-use chrono::{DateTime, Offset, TimeZone, Timelike};
+#[cfg(feature = "chrono")]
+use std::convert::TryFrom;
+
+use bytes::BufMut;
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, Offset, TimeZone, Timelike};
 
 use bolt_proto_derive::*;
 
+use crate::error::*;
+use crate::impl_try_into_bytes;
+use crate::serialization::*;
+
 pub(crate) const MARKER: u8 = 0xB3;
 pub(crate) const SIGNATURE: u8 = 0x46;
+pub(crate) const SIGNATURE_UTC: u8 = 0x49;
 
-#[derive(Debug, Clone, Hash, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+// The seconds are kept relative to UTC, as in the UTC structure, and converted to local time for the legacy structure
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Deserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DateTimeOffset {
     pub(crate) epoch_seconds: i64,
     pub(crate) nanos: i64,
     pub(crate) offset_seconds: i32,
 }
 
+impl DateTimeOffset {
+    /// Create a date-time from a local date and time, along with its offset from UTC.
+    #[cfg(feature = "chrono")]
+    pub fn new(date: NaiveDate, time: NaiveTime, offset: impl Offset) -> Self {
+        let offset = offset.fix();
+        // A fixed offset maps every local date-time to exactly one UTC date-time, so this does not panic
+        Self::from(offset.from_local_datetime(&date.and_time(time)).unwrap())
+    }
+
+    /// The number of seconds since the Unix epoch, in UTC.
+    pub fn epoch_seconds(&self) -> i64 {
+        self.epoch_seconds
+    }
+
+    pub fn nanos(&self) -> i64 {
+        self.nanos
+    }
+
+    /// The offset from UTC in seconds, which is positive east of UTC.
+    pub fn offset_seconds(&self) -> i32 {
+        self.offset_seconds
+    }
+
+    /// Convert to a chrono date-time. Fails for values outside of chrono's range, or with out-of-range fields, which
+    /// deserialized values can have.
+    #[cfg(feature = "chrono")]
+    pub fn date_time(&self) -> Result<DateTime<FixedOffset>> {
+        u32::try_from(self.nanos)
+            .ok()
+            .and_then(|nanos| {
+                self.offset()
+                    .ok()?
+                    .timestamp_opt(self.epoch_seconds, nanos)
+                    .single()
+            })
+            .ok_or_else(|| ConversionError::InvalidTimestamp(self.epoch_seconds).into())
+    }
+
+    /// Convert the offset to a chrono offset. Fails if it is a day or more, which deserialized values can have.
+    #[cfg(feature = "chrono")]
+    pub fn offset(&self) -> Result<FixedOffset> {
+        FixedOffset::east_opt(self.offset_seconds)
+            .ok_or_else(|| ConversionError::InvalidOffset(self.offset_seconds).into())
+    }
+}
+
+#[cfg(feature = "chrono")]
 impl<T: TimeZone> From<DateTime<T>> for DateTimeOffset {
     fn from(date_time: DateTime<T>) -> Self {
         Self {
@@ -22,26 +81,142 @@ impl<T: TimeZone> From<DateTime<T>> for DateTimeOffset {
     }
 }
 
-#[cfg(test)]
+impl DateTimeOffset {
+    // The seconds as sent in the structure with the given signature
+    fn wire_epoch_seconds(&self, signature: u8) -> Result<i64> {
+        if signature == SIGNATURE_UTC {
+            return Ok(self.epoch_seconds);
+        }
+        self.epoch_seconds
+            .checked_add(i64::from(self.offset_seconds))
+            .ok_or_else(|| ConversionError::InvalidTimestamp(self.epoch_seconds).into())
+    }
+
+    // Convert the fields of a legacy structure, whose seconds are in local time
+    pub(crate) fn convert_from_legacy(mut self) -> Result<Self> {
+        self.epoch_seconds = self
+            .epoch_seconds
+            .checked_sub(i64::from(self.offset_seconds))
+            .ok_or(ConversionError::InvalidTimestamp(self.epoch_seconds))?;
+        Ok(self)
+    }
+}
+
+impl Marker for DateTimeOffset {
+    fn get_marker(&self) -> Result<u8> {
+        Ok(MARKER)
+    }
+
+    // The seconds are converted to local time for the legacy structure, which can change how many bytes they take
+    fn serialized_len(&self) -> Result<usize> {
+        // Marker byte, signature byte, then the fields
+        Ok(std::mem::size_of::<u8>() * 2
+            + self.wire_epoch_seconds(self.get_signature())?.field_len()?
+            + self.nanos.field_len()?
+            + self.offset_seconds.field_len()?)
+    }
+}
+
+impl Signature for DateTimeOffset {
+    fn get_signature(&self) -> u8 {
+        if is_utc_encoding() {
+            SIGNATURE_UTC
+        } else {
+            SIGNATURE
+        }
+    }
+}
+
+impl Serialize for DateTimeOffset {
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()> {
+        let signature = self.get_signature();
+        let epoch_seconds = self.wire_epoch_seconds(signature)?;
+        buf.put_u8(MARKER);
+        buf.put_u8(signature);
+        epoch_seconds.serialize_field(buf)?;
+        self.nanos.serialize_field(buf)?;
+        self.offset_seconds.serialize_field(buf)
+    }
+}
+
+impl_try_into_bytes!(DateTimeOffset);
+
+#[cfg(all(test, feature = "chrono"))]
 mod tests {
     use std::convert::TryFrom;
     use std::sync::{Arc, Mutex};
 
     use bytes::Bytes;
-    use chrono::{DateTime, FixedOffset, NaiveDateTime};
 
     use crate::serialization::*;
     use crate::value::integer::MARKER_INT_16;
+    use crate::Value;
 
     use super::*;
 
     fn get_chrono_date_time() -> DateTime<FixedOffset> {
-        DateTime::from_utc(
-            NaiveDateTime::from_timestamp(2000, 1000),
-            FixedOffset::east(-1200),
+        DateTime::from_naive_utc_and_offset(
+            DateTime::from_timestamp(2000, 1000).unwrap().naive_utc(),
+            FixedOffset::east_opt(-1200).unwrap(),
         )
     }
 
+    #[test]
+    fn new() {
+        let date_time = get_chrono_date_time();
+        let local = date_time.naive_local();
+        let date_time_offset = DateTimeOffset::new(local.date(), local.time(), *date_time.offset());
+        assert_eq!(date_time_offset, DateTimeOffset::from(date_time));
+        assert_eq!(date_time_offset.date_time().unwrap(), date_time);
+        assert_eq!(
+            date_time_offset.offset().unwrap(),
+            FixedOffset::east_opt(-1200).unwrap()
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        let invalid_offset = DateTimeOffset {
+            epoch_seconds: 2000,
+            nanos: 0,
+            offset_seconds: 200_000,
+        };
+        assert!(matches!(
+            invalid_offset.offset(),
+            Err(Error::ConversionError(ConversionError::InvalidOffset(
+                200_000
+            )))
+        ));
+        assert!(invalid_offset.date_time().is_err());
+        assert!(Value::DateTimeOffset(invalid_offset)
+            .to_string()
+            .contains("200000"));
+
+        let invalid_nanos = DateTimeOffset {
+            epoch_seconds: 2000,
+            nanos: -1,
+            offset_seconds: 0,
+        };
+        assert!(invalid_nanos.date_time().is_err());
+        let invalid_seconds = DateTimeOffset {
+            epoch_seconds: i64::MAX,
+            nanos: 0,
+            offset_seconds: 0,
+        };
+        assert!(invalid_seconds.date_time().is_err());
+    }
+
+    #[test]
+    fn utc_date_time() {
+        let date_time = get_chrono_date_time();
+        let value = Value::DateTimeOffset(DateTimeOffset::from(date_time));
+        assert_eq!(
+            DateTime::<chrono::Utc>::try_from(value).unwrap(),
+            date_time.with_timezone(&chrono::Utc)
+        );
+        assert!(DateTime::<chrono::Utc>::try_from(Value::from(2000)).is_err());
+    }
+
     #[test]
     fn get_marker() {
         let time = DateTimeOffset::from(get_chrono_date_time());
@@ -50,13 +225,41 @@ mod tests {
 
     #[test]
     fn try_into_bytes() {
+        // The legacy structure has the local seconds, 2000 - 1200
         let date_time_offset = DateTimeOffset::from(get_chrono_date_time());
+        assert_eq!(
+            date_time_offset.serialized_len().unwrap(),
+            date_time_offset.clone().try_into_bytes().unwrap().len()
+        );
         assert_eq!(
             date_time_offset.try_into_bytes().unwrap(),
             Bytes::from_static(&[
                 MARKER,
                 SIGNATURE,
                 MARKER_INT_16,
+                0x03,
+                0x20,
+                MARKER_INT_16,
+                0x03,
+                0xE8,
+                MARKER_INT_16,
+                0xFB,
+                0x50,
+            ])
+        );
+    }
+
+    #[test]
+    fn try_into_utc_bytes() {
+        // The UTC structure has the seconds relative to UTC
+        let date_time_offset = DateTimeOffset::from(get_chrono_date_time());
+        let utc_bytes = encode_utc(|| date_time_offset.try_into_bytes().unwrap());
+        assert_eq!(
+            utc_bytes,
+            Bytes::from_static(&[
+                MARKER,
+                SIGNATURE_UTC,
+                MARKER_INT_16,
                 0x07,
                 0xD0,
                 MARKER_INT_16,
@@ -69,8 +272,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn legacy_round_trip() {
+        let date_time = get_chrono_date_time();
+        let value = Value::from(date_time);
+        let legacy_bytes = value.clone().try_into_bytes().unwrap();
+        let utc_bytes = encode_utc(|| value.clone().try_into_bytes().unwrap());
+        assert_ne!(legacy_bytes, utc_bytes);
+        for bytes in [legacy_bytes, utc_bytes] {
+            let decoded = Value::try_from(Arc::new(Mutex::new(bytes))).unwrap();
+            assert_eq!(decoded, value);
+            assert_eq!(
+                DateTime::<FixedOffset>::try_from(decoded).unwrap(),
+                date_time
+            );
+        }
+    }
+
     #[test]
     fn try_from_bytes() {
+        // The fields as sent in the UTC structure
         let date_time_offset = DateTimeOffset::from(get_chrono_date_time());
         let date_time_bytes = &[
             MARKER_INT_16,
@@ -88,5 +309,12 @@ mod tests {
                 .unwrap(),
             date_time_offset
         );
+        assert_eq!(
+            date_time_offset
+                .convert_from_legacy()
+                .unwrap()
+                .epoch_seconds,
+            2000 + 1200
+        );
     }
 }
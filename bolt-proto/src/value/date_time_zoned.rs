@@ -1,12 +1,22 @@
-use chrono::{NaiveDateTime, Timelike};
+use bytes::BufMut;
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, NaiveDateTime, Offset, TimeZone, Timelike};
+#[cfg(feature = "chrono")]
 use chrono_tz::Tz;
 
 use bolt_proto_derive::*;
 
+use crate::error::*;
+use crate::impl_try_into_bytes;
+use crate::serialization::*;
+
 pub(crate) const MARKER: u8 = 0xB3;
 pub(crate) const SIGNATURE: u8 = 0x66;
+pub(crate) const SIGNATURE_UTC: u8 = 0x69;
 
-#[derive(Debug, Clone, Hash, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+// The seconds are kept in local time, as in the legacy structure, and converted when using the UTC structure
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Deserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DateTimeZoned {
     pub(crate) epoch_seconds: i64,
     pub(crate) nanos: i64,
@@ -15,17 +25,126 @@ pub struct DateTimeZoned {
 
 // Can't impl<T: TimeZone> From<DateTime<T>> for DateTimeZoned, since we can't get a timezone name from an Offset
 // Provide separate conversion instead
+#[cfg(feature = "chrono")]
 impl From<(NaiveDateTime, Tz)> for DateTimeZoned {
     fn from(pair: (NaiveDateTime, Tz)) -> Self {
         Self {
-            epoch_seconds: pair.0.timestamp(),
+            epoch_seconds: pair.0.and_utc().timestamp(),
             nanos: pair.0.nanosecond() as i64,
             zone_id: pair.1.name().to_string(),
         }
     }
 }
 
-#[cfg(test)]
+impl DateTimeZoned {
+    /// The number of seconds since the Unix epoch, in the local time of the time zone.
+    pub fn epoch_seconds(&self) -> i64 {
+        self.epoch_seconds
+    }
+
+    pub fn nanos(&self) -> i64 {
+        self.nanos
+    }
+
+    /// The id of the time zone, e.g. `Europe/Paris`.
+    pub fn zone_id(&self) -> &str {
+        &self.zone_id
+    }
+
+    // The offset from UTC in the time zone at the local date-time. An ambiguous local date-time resolves to the earliest
+    // offset, and one in a gap to the offset in effect before the gap.
+    #[cfg(feature = "chrono")]
+    pub(crate) fn offset_seconds_at_local(&self) -> Result<i64> {
+        let tz = self.time_zone()?;
+        let local = DateTime::from_timestamp(self.epoch_seconds, 0)
+            .ok_or(ConversionError::InvalidTimestamp(self.epoch_seconds))?
+            .naive_utc();
+        let offset = match tz.offset_from_local_datetime(&local).earliest() {
+            Some(offset) => offset.fix(),
+            None => tz.offset_from_utc_datetime(&local).fix(),
+        };
+        Ok(offset.local_minus_utc() as i64)
+    }
+
+    // Convert the fields of a UTC structure, whose seconds are relative to UTC
+    #[cfg(feature = "chrono")]
+    pub(crate) fn convert_from_utc(mut self) -> Result<Self> {
+        let tz = self.time_zone()?;
+        let utc = DateTime::from_timestamp(self.epoch_seconds, 0)
+            .ok_or(ConversionError::InvalidTimestamp(self.epoch_seconds))?
+            .naive_utc();
+        self.epoch_seconds += tz.offset_from_utc_datetime(&utc).fix().local_minus_utc() as i64;
+        Ok(self)
+    }
+
+    #[cfg(feature = "chrono")]
+    pub(crate) fn time_zone(&self) -> Result<Tz> {
+        self.zone_id
+            .parse()
+            .map_err(|_| ConversionError::InvalidTimeZone(self.zone_id.clone()).into())
+    }
+
+    // Without time zone data, no offset can be found for a zone id, so the UTC structure is unsupported
+    #[cfg(not(feature = "chrono"))]
+    pub(crate) fn offset_seconds_at_local(&self) -> Result<i64> {
+        Err(ConversionError::InvalidTimeZone(self.zone_id.clone()).into())
+    }
+
+    #[cfg(not(feature = "chrono"))]
+    pub(crate) fn convert_from_utc(self) -> Result<Self> {
+        Err(ConversionError::InvalidTimeZone(self.zone_id).into())
+    }
+}
+
+impl Marker for DateTimeZoned {
+    fn get_marker(&self) -> Result<u8> {
+        Ok(MARKER)
+    }
+
+    // The seconds are converted to UTC for the UTC structure, which can change how many bytes they take
+    fn serialized_len(&self) -> Result<usize> {
+        let epoch_seconds = if self.get_signature() == SIGNATURE_UTC {
+            self.epoch_seconds - self.offset_seconds_at_local()?
+        } else {
+            self.epoch_seconds
+        };
+        // Marker byte, signature byte, then the fields
+        Ok(std::mem::size_of::<u8>() * 2
+            + epoch_seconds.field_len()?
+            + self.nanos.field_len()?
+            + self.zone_id.field_len()?)
+    }
+}
+
+impl Signature for DateTimeZoned {
+    fn get_signature(&self) -> u8 {
+        if is_utc_encoding() {
+            SIGNATURE_UTC
+        } else {
+            SIGNATURE
+        }
+    }
+}
+
+impl Serialize for DateTimeZoned {
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()> {
+        let signature = self.get_signature();
+        let epoch_seconds = if signature == SIGNATURE_UTC {
+            self.epoch_seconds - self.offset_seconds_at_local()?
+        } else {
+            self.epoch_seconds
+        };
+        buf.put_u8(MARKER);
+        buf.put_u8(signature);
+        epoch_seconds.serialize_field(buf)?;
+        self.nanos.serialize_field(buf)?;
+        self.zone_id.serialize_field(buf)
+    }
+}
+
+impl_try_into_bytes!(DateTimeZoned);
+
+#[cfg(all(test, feature = "chrono"))]
 mod tests {
     use std::convert::TryFrom;
     use std::sync::{Arc, Mutex};
@@ -36,12 +155,16 @@ mod tests {
     use crate::serialization::*;
     use crate::value::integer::{MARKER_INT_32, MARKER_INT_64};
     use crate::value::string;
+    use crate::Value;
 
     use super::*;
 
     fn get_date_time() -> DateTimeZoned {
         DateTimeZoned::from((
-            NaiveDate::from_ymd(3500, 7, 29).and_hms_nano(13, 5, 1, 123_456),
+            NaiveDate::from_ymd_opt(3500, 7, 29)
+                .unwrap()
+                .and_hms_nano_opt(13, 5, 1, 123_456)
+                .unwrap(),
             chrono_tz::Antarctica::Rothera,
         ))
     }
@@ -98,6 +221,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn utc_bytes() {
+        // Paris is at UTC+02:00 in the summer
+        let date_time = DateTimeZoned::from((
+            NaiveDate::from_ymd_opt(2021, 7, 1)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap(),
+            chrono_tz::Europe::Paris,
+        ));
+        let utc_bytes = encode_utc(|| date_time.clone().try_into_bytes().unwrap());
+        assert_eq!(&utc_bytes[..2], &[MARKER, SIGNATURE_UTC]);
+        let utc_fields =
+            DateTimeZoned::try_from(Arc::new(Mutex::new(utc_bytes.slice(2..)))).unwrap();
+        assert_eq!(utc_fields.epoch_seconds, date_time.epoch_seconds - 7200);
+        assert_eq!(utc_fields.convert_from_utc().unwrap(), date_time);
+    }
+
+    #[test]
+    fn invalid_fields() {
+        let unknown_zone = DateTimeZoned {
+            epoch_seconds: 2000,
+            nanos: 0,
+            zone_id: "Nope".to_string(),
+        };
+        let value = Value::DateTimeZoned(unknown_zone);
+        assert!(matches!(
+            DateTime::<Tz>::try_from(value.clone()),
+            Err(Error::ConversionError(ConversionError::InvalidTimeZone(zone_id))) if zone_id == "Nope"
+        ));
+        assert!(DateTime::<chrono::FixedOffset>::try_from(value.clone()).is_err());
+        assert!(value.to_string().contains("Nope"));
+
+        let invalid_seconds = Value::DateTimeZoned(DateTimeZoned {
+            epoch_seconds: i64::MAX,
+            nanos: -1,
+            zone_id: "Europe/Paris".to_string(),
+        });
+        assert!(DateTime::<Tz>::try_from(invalid_seconds.clone()).is_err());
+        assert!(invalid_seconds.to_string().contains("Europe/Paris"));
+    }
+
     #[test]
     fn try_from_bytes() {
         let date_time_offset = get_date_time();
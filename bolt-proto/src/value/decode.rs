@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+use std::mem;
+
+use bytes::{Buf, Bytes};
+
+use crate::error::*;
+use crate::serialization::*;
+use crate::value::*;
+
+// Values are deserialized with an explicit stack of the lists, maps, and structures being read, rather than by
+// recursion, so that deeply nested input is rejected by the depth limit instead of overflowing the call stack. The
+// limit is still needed, since most operations on a deserialized value (including dropping it) recurse.
+
+// A list, map, or structure whose elements are still being read
+struct Frame {
+    container: Container,
+    remaining: usize,
+}
+
+enum Container {
+    List(Vec<Value>),
+    // The entries read so far, and the key of the entry whose value is being read
+    Map(HashMap<std::string::String, Value>, std::string::String),
+    Structure(u8, Vec<Value>),
+}
+
+impl Frame {
+    // Adds the next element, then reads the key of the next entry of a map, if there is one
+    fn push(&mut self, value: Value, bytes: &mut Bytes) -> Result<()> {
+        self.remaining -= 1;
+        match &mut self.container {
+            Container::List(values) | Container::Structure(_, values) => values.push(value),
+            Container::Map(entries, key) => {
+                entries.insert(mem::take(key), value);
+                if self.remaining > 0 {
+                    *key = String::deserialize(bytes)?.into_string()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+enum Header {
+    Value(Value),
+    Container(Container, usize),
+}
+
+pub(crate) fn deserialize_value(bytes: &mut Bytes) -> Result<Value> {
+    let config = deserialize_config();
+    let input_len = bytes.len();
+    let mut stack: Vec<Frame> = Vec::new();
+    loop {
+        let header = read_header(bytes)?;
+        check_limit(
+            "total bytes",
+            config.max_total_bytes,
+            input_len - bytes.len(),
+        )?;
+        let mut value = match header {
+            Header::Value(value) => value,
+            Header::Container(container, size) => {
+                check_limit("depth", config.max_depth, stack.len() + 1)?;
+                if let Container::List(_) | Container::Map(..) = container {
+                    check_limit("collection length", config.max_collection_len, size)?;
+                }
+                if size == 0 {
+                    finish(container)?
+                } else {
+                    stack.push(start_frame(container, size, bytes)?);
+                    continue;
+                }
+            }
+        };
+        // Add the value to the innermost container, finishing each container that is now full
+        loop {
+            let mut frame = match stack.pop() {
+                Some(frame) => frame,
+                None => return Ok(value),
+            };
+            frame.push(value, bytes)?;
+            if frame.remaining > 0 {
+                stack.push(frame);
+                break;
+            }
+            value = finish(frame.container)?;
+        }
+    }
+}
+
+// Reads the key of the first entry of a map
+fn start_frame(mut container: Container, size: usize, bytes: &mut Bytes) -> Result<Frame> {
+    if let Container::Map(_, key) = &mut container {
+        *key = String::deserialize(bytes)?.into_string()?;
+    }
+    Ok(Frame {
+        container,
+        remaining: size,
+    })
+}
+
+// Reads a whole value if it can't contain other values, otherwise only its marker and size
+fn read_header(bytes: &mut Bytes) -> Result<Header> {
+    let marker = bytes.peek_u8("marker")?;
+    let value = match marker {
+        null::MARKER => {
+            bytes.advance(1);
+            Value::Null
+        }
+        boolean::MARKER_FALSE => {
+            bytes.advance(1);
+            Value::Boolean(Boolean::from(false))
+        }
+        boolean::MARKER_TRUE => {
+            bytes.advance(1);
+            Value::Boolean(Boolean::from(true))
+        }
+        // Tiny int
+        marker if (-16..=127).contains(&(marker as i8)) => {
+            bytes.advance(1);
+            Value::Integer(Integer::from(marker as i8))
+        }
+        // Other int types
+        integer::MARKER_INT_8
+        | integer::MARKER_INT_16
+        | integer::MARKER_INT_32
+        | integer::MARKER_INT_64 => Value::Integer(Integer::deserialize(bytes)?),
+        float::MARKER => Value::Float(Float::deserialize(bytes)?),
+        byte_array::MARKER_SMALL | byte_array::MARKER_MEDIUM | byte_array::MARKER_LARGE => {
+            Value::Bytes(ByteArray::deserialize(bytes)?)
+        }
+        // Tiny string
+        marker if (string::MARKER_TINY..=(string::MARKER_TINY | 0x0F)).contains(&marker) => {
+            Value::String(String::deserialize(bytes)?)
+        }
+        string::MARKER_SMALL | string::MARKER_MEDIUM | string::MARKER_LARGE => {
+            Value::String(String::deserialize(bytes)?)
+        }
+        // Tiny list
+        marker if (list::MARKER_TINY..=(list::MARKER_TINY | 0x0F)).contains(&marker) => {
+            bytes.advance(1);
+            let size = 0x0F & marker as usize;
+            return Ok(Header::Container(new_list(size, bytes), size));
+        }
+        list::MARKER_SMALL | list::MARKER_MEDIUM | list::MARKER_LARGE => {
+            bytes.advance(1);
+            let size = read_size(bytes, marker, list::MARKER_SMALL, list::MARKER_MEDIUM)?;
+            return Ok(Header::Container(new_list(size, bytes), size));
+        }
+        // Tiny map
+        marker if (map::MARKER_TINY..=(map::MARKER_TINY | 0x0F)).contains(&marker) => {
+            bytes.advance(1);
+            let size = 0x0F & marker as usize;
+            return Ok(Header::Container(new_map(size, bytes), size));
+        }
+        map::MARKER_SMALL | map::MARKER_MEDIUM | map::MARKER_LARGE => {
+            bytes.advance(1);
+            let size = read_size(bytes, marker, map::MARKER_SMALL, map::MARKER_MEDIUM)?;
+            return Ok(Header::Container(new_map(size, bytes), size));
+        }
+        // Tiny structure
+        marker if (STRUCT_MARKER_TINY..=(STRUCT_MARKER_TINY | 0x0F)).contains(&marker) => {
+            return read_structure_header(bytes);
+        }
+        STRUCT_MARKER_SMALL | STRUCT_MARKER_MEDIUM => return read_structure_header(bytes),
+        _ => return Err(DeserializationError::InvalidMarkerByte(marker).into()),
+    };
+    Ok(Header::Value(value))
+}
+
+// Reads an 8, 16, or 32-bit size following a marker that has already been consumed
+fn read_size(bytes: &mut Bytes, marker: u8, small: u8, medium: u8) -> Result<usize> {
+    if marker == small {
+        Ok(bytes.try_get_u8("size")? as usize)
+    } else if marker == medium {
+        Ok(bytes.try_get_u16("size")? as usize)
+    } else {
+        Ok(bytes.try_get_u32("size")? as usize)
+    }
+}
+
+// Every element takes at least one byte, so don't trust a size larger than the remaining input when allocating
+fn new_list(size: usize, bytes: &Bytes) -> Container {
+    Container::List(Vec::with_capacity(size.min(bytes.len())))
+}
+
+fn new_map(size: usize, bytes: &Bytes) -> Container {
+    Container::Map(
+        HashMap::with_capacity(size.min(bytes.len() / 2)),
+        std::string::String::new(),
+    )
+}
+
+fn read_structure_header(bytes: &mut Bytes) -> Result<Header> {
+    let (_marker, field_count, signature) = get_info_from_bytes(bytes)?;
+    Ok(Header::Container(
+        Container::Structure(signature, Vec::with_capacity(field_count)),
+        field_count,
+    ))
+}
+
+fn finish(container: Container) -> Result<Value> {
+    match container {
+        Container::List(values) => Ok(Value::List(List { value: values })),
+        Container::Map(entries, _) => Ok(Value::Map(Map { value: entries })),
+        Container::Structure(signature, fields) => finish_structure(signature, fields),
+    }
+}
+
+fn finish_structure(signature: u8, fields: Vec<Value>) -> Result<Value> {
+    if let Some(decoder) = extension::extension_decoder(signature) {
+        return decoder(Structure { signature, fields });
+    }
+    match signature {
+        node::SIGNATURE => Ok(Value::Node(Node::from_fields(fields)?)),
+        relationship::SIGNATURE => Ok(Value::Relationship(Relationship::from_fields(fields)?)),
+        path::SIGNATURE => Ok(Value::Path(Path::from_fields(fields)?)),
+        unbound_relationship::SIGNATURE => Ok(Value::UnboundRelationship(
+            UnboundRelationship::from_fields(fields)?,
+        )),
+        date::SIGNATURE => Ok(Value::Date(Date::from_fields(fields)?)),
+        time::SIGNATURE => Ok(Value::Time(Time::from_fields(fields)?)),
+        date_time_offset::SIGNATURE => Ok(Value::DateTimeOffset(
+            DateTimeOffset::from_fields(fields)?.convert_from_legacy()?,
+        )),
+        date_time_offset::SIGNATURE_UTC => {
+            Ok(Value::DateTimeOffset(DateTimeOffset::from_fields(fields)?))
+        }
+        date_time_zoned::SIGNATURE => Ok(Value::DateTimeZoned(DateTimeZoned::from_fields(fields)?)),
+        date_time_zoned::SIGNATURE_UTC => Ok(Value::DateTimeZoned(
+            DateTimeZoned::from_fields(fields)?.convert_from_utc()?,
+        )),
+        local_time::SIGNATURE => Ok(Value::LocalTime(LocalTime::from_fields(fields)?)),
+        local_date_time::SIGNATURE => Ok(Value::LocalDateTime(LocalDateTime::from_fields(fields)?)),
+        duration::SIGNATURE => Ok(Value::Duration(Duration::from_fields(fields)?)),
+        point_2d::SIGNATURE => Ok(Value::Point2D(Point2D::from_fields(fields)?)),
+        point_3d::SIGNATURE => Ok(Value::Point3D(Point3D::from_fields(fields)?)),
+        _ => Ok(Value::Structure(Structure { signature, fields })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::FromIterator;
+
+    use super::*;
+
+    fn depth(mut value: &Value) -> usize {
+        let mut depth = 0;
+        loop {
+            value = match value {
+                Value::List(list) => &list.value[0],
+                Value::Map(map) => map.value.values().next().unwrap(),
+                Value::Structure(structure) => &structure.fields[0],
+                _ => return depth,
+            };
+            depth += 1;
+        }
+    }
+
+    // Alternately nests lists, maps, and structures
+    fn nested_bytes(depth: usize) -> Bytes {
+        let mut bytes = Vec::with_capacity(depth * 3 + 1);
+        for i in 0..depth {
+            match i % 3 {
+                0 => bytes.push(list::MARKER_TINY | 1),
+                1 => {
+                    bytes.extend_from_slice(&[map::MARKER_TINY | 1, string::MARKER_TINY | 1, b'a'])
+                }
+                _ => bytes.extend_from_slice(&[STRUCT_MARKER_TINY | 1, 0x01]),
+            }
+        }
+        bytes.push(0x01);
+        Bytes::from(bytes)
+    }
+
+    #[test]
+    fn deeply_nested() {
+        let max_depth = DeserializeConfig::DEFAULT_MAX_DEPTH;
+        let value = Value::deserialize(&mut nested_bytes(max_depth)).unwrap();
+        assert_eq!(depth(&value), max_depth);
+        // Values within the default limit can be used (and dropped) without overflowing the stack
+        assert_eq!(value.clone(), value);
+        assert!(!value.to_string().is_empty());
+        assert!(value.try_into_bytes().is_ok());
+
+        assert!(is_limit_exceeded(
+            Value::deserialize(&mut nested_bytes(max_depth + 1)),
+            "depth"
+        ));
+        assert!(is_limit_exceeded(
+            Value::deserialize(&mut nested_bytes(1_000_000)),
+            "depth"
+        ));
+    }
+
+    #[test]
+    fn nested_structures() {
+        let node = Node::new(1, vec!["Person".to_string()], HashMap::<_, Value>::new())
+            .with_element_id("4:abc:1");
+        let value = Value::from(HashMap::from_iter(vec![
+            (
+                "nodes".to_string(),
+                Value::from(vec![Value::from(node.clone()), Value::Null]),
+            ),
+            ("empty".to_string(), Value::from(Vec::<Value>::new())),
+            (
+                "point".to_string(),
+                Value::from(Point2D::new(7203, 1.0, 2.0)),
+            ),
+        ]));
+        let mut bytes = value.clone().try_into_bytes().unwrap();
+        assert_eq!(Value::deserialize(&mut bytes).unwrap(), value);
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn truncated() {
+        let value = Value::from(vec![
+            Value::from("a string"),
+            Value::from(1_000_000),
+            Value::from(1.5),
+            Value::from(vec![1_u8, 2, 3]),
+            Value::from(Point2D::new(7203, 1.0, 2.0)),
+        ]);
+        let bytes = value.try_into_bytes().unwrap();
+        for len in 0..bytes.len() {
+            assert!(matches!(
+                Value::deserialize(&mut bytes.slice(..len)),
+                Err(Error::DeserializationError(
+                    DeserializationError::UnexpectedEnd { .. }
+                ))
+            ));
+        }
+    }
+
+    #[test]
+    fn invalid_field_count() {
+        // A node with only two fields
+        let mut bytes = Bytes::from_static(&[
+            STRUCT_MARKER_TINY | 2,
+            node::SIGNATURE,
+            0x01,
+            list::MARKER_TINY,
+        ]);
+        assert!(matches!(
+            Value::deserialize(&mut bytes),
+            Err(Error::InvalidFieldCount {
+                expected: 3,
+                actual: 2
+            })
+        ));
+    }
+
+    fn is_limit_exceeded(result: Result<Value>, expected_limit: &str) -> bool {
+        matches!(
+            result,
+            Err(Error::DeserializationError(DeserializationError::LimitExceeded { limit, .. }))
+                if limit == expected_limit
+        )
+    }
+
+    #[test]
+    fn limits() {
+        let value = Value::from(vec![
+            Value::from(vec![Value::from(vec![1, 2, 3])]),
+            Value::from(HashMap::<_, Value>::from_iter(vec![(
+                "a".to_string(),
+                Value::from(Point2D::new(7203, 1.0, 2.0)),
+            )])),
+        ]);
+        let bytes = value.clone().try_into_bytes().unwrap();
+        let deserialize = |config: DeserializeConfig| {
+            decode_with_config(config, || Value::deserialize(&mut bytes.clone()))
+        };
+
+        let exact = DeserializeConfig::new()
+            .with_max_depth(3)
+            .with_max_collection_len(3)
+            .with_max_total_bytes(bytes.len());
+        assert_eq!(deserialize(exact).unwrap(), value);
+        assert!(is_limit_exceeded(
+            deserialize(exact.with_max_depth(2)),
+            "depth"
+        ));
+        assert!(is_limit_exceeded(
+            deserialize(exact.with_max_collection_len(2)),
+            "collection length"
+        ));
+        assert!(is_limit_exceeded(
+            deserialize(exact.with_max_total_bytes(bytes.len() - 1)),
+            "total bytes"
+        ));
+
+        // Limits are checked before reading the elements of a collection
+        let mut huge_list = Bytes::from_static(&[list::MARKER_LARGE, 0xFF, 0xFF, 0xFF, 0xFF]);
+        assert!(is_limit_exceeded(
+            decode_with_config(
+                DeserializeConfig::new().with_max_collection_len(1000),
+                || { Value::deserialize(&mut huge_list) }
+            ),
+            "collection length"
+        ));
+    }
+}
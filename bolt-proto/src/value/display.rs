@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+use crate::value::*;
+
+// Values are displayed like Cypher literals, e.g. `{name: 'Alice', tags: ['a', 'b']}`. Maps and properties are sorted
+// by key so the output is stable.
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Value::Boolean(boolean) => write!(f, "{}", boolean.value),
+            Value::Integer(integer) => write!(f, "{}", integer.value),
+            // Debug keeps the decimal point of whole numbers, e.g. 1.0
+            Value::Float(float) => write!(f, "{:?}", float.value),
+            Value::Bytes(byte_array) => {
+                f.write_str("0x")?;
+                byte_array
+                    .value
+                    .iter()
+                    .try_for_each(|byte| write!(f, "{:02x}", byte))
+            }
+            Value::List(list) => write_list(f, &list.value),
+            Value::Map(map) => write_properties(f, &map.value),
+            Value::Null => f.write_str("null"),
+            Value::String(string) => write_string(f, &string.to_str_lossy()),
+            Value::Node(node) => node.fmt(f),
+            Value::Relationship(rel) => rel.fmt(f),
+            Value::Path(path) => path.fmt(f),
+            Value::UnboundRelationship(unbound_rel) => unbound_rel.fmt(f),
+            Value::Duration(duration) => write!(f, "duration('{}')", duration.to_iso_8601()),
+            Value::Point2D(point) => write!(
+                f,
+                "point({{srid: {}, x: {:?}, y: {:?}}})",
+                point.srid, point.x, point.y
+            ),
+            Value::Point3D(point) => write!(
+                f,
+                "point({{srid: {}, x: {:?}, y: {:?}, z: {:?}}})",
+                point.srid, point.x, point.y, point.z
+            ),
+            Value::Structure(structure) => {
+                write!(f, "structure<0x{:02x}>", structure.signature)?;
+                write_list(f, &structure.fields)
+            }
+            temporal => write_temporal(f, temporal),
+        }
+    }
+}
+
+// Deserialized values can be out of chrono's range or have invalid fields (e.g. an unknown zone id), in which case their
+// fields are shown instead, so that formatting never panics
+#[cfg(feature = "chrono")]
+fn write_temporal(f: &mut Formatter, value: &Value) -> fmt::Result {
+    let function = match value {
+        Value::Date(_) => "date",
+        Value::Time(_) => "time",
+        Value::LocalTime(_) => "localtime",
+        Value::LocalDateTime(_) => "localdatetime",
+        _ => "datetime",
+    };
+    match value.to_iso_8601() {
+        Some(iso_8601) => write!(f, "{}('{}')", function, iso_8601),
+        None => write!(f, "{:?}", value),
+    }
+}
+
+// Formatting temporal values as ISO 8601 requires chrono, so show their fields instead
+#[cfg(not(feature = "chrono"))]
+fn write_temporal(f: &mut Formatter, value: &Value) -> fmt::Result {
+    write!(f, "{:?}", value)
+}
+
+impl Display for Node {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("(")?;
+        self.labels
+            .iter()
+            .try_for_each(|label| write!(f, ":{}", format_key(label)))?;
+        if !self.properties.is_empty() {
+            if !self.labels.is_empty() {
+                f.write_str(" ")?;
+            }
+            write_properties(f, &self.properties)?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl Display for Relationship {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "({})-[:{}",
+            self.start_node_identity,
+            format_key(&self.rel_type)
+        )?;
+        if !self.properties.is_empty() {
+            f.write_str(" ")?;
+            write_properties(f, &self.properties)?;
+        }
+        write!(f, "]->({})", self.end_node_identity)
+    }
+}
+
+impl Display for UnboundRelationship {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "[:{}", format_key(&self.rel_type))?;
+        if !self.properties.is_empty() {
+            f.write_str(" ")?;
+            write_properties(f, &self.properties)?;
+        }
+        f.write_str("]")
+    }
+}
+
+// Relationships are shown in the direction they point, or undirected if the sequence of the path is invalid
+impl Display for Path {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.segments().collect::<Result<Vec<PathSegment>>>() {
+            Ok(segments) => {
+                if let Some(node) = self.start_node() {
+                    node.fmt(f)?;
+                }
+                for segment in segments {
+                    if segment.is_reversed() {
+                        write!(f, "<-{}-{}", segment.relationship(), segment.start_node())?;
+                    } else {
+                        write!(f, "-{}->{}", segment.relationship(), segment.end_node())?;
+                    }
+                }
+                Ok(())
+            }
+            Err(_) => {
+                let mut nodes = self.nodes.iter();
+                if let Some(node) = nodes.next() {
+                    node.fmt(f)?;
+                }
+                for (rel, node) in self.relationships.iter().zip(nodes) {
+                    write!(f, "-{}-{}", rel, node)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn write_list(f: &mut Formatter, values: &[Value]) -> fmt::Result {
+    f.write_str("[")?;
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        value.fmt(f)?;
+    }
+    f.write_str("]")
+}
+
+fn write_properties(
+    f: &mut Formatter,
+    properties: &HashMap<std::string::String, Value>,
+) -> fmt::Result {
+    let mut entries: Vec<(std::string::String, &Value)> = properties
+        .iter()
+        .map(|(key, value)| (format_key(key), value))
+        .collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    write_entries(f, entries)
+}
+
+fn write_entries(f: &mut Formatter, entries: Vec<(std::string::String, &Value)>) -> fmt::Result {
+    f.write_str("{")?;
+    for (i, (key, value)) in entries.into_iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{}: {}", key, value)?;
+    }
+    f.write_str("}")
+}
+
+fn write_string(f: &mut Formatter, string: &str) -> fmt::Result {
+    f.write_str("'")?;
+    for c in string.chars() {
+        match c {
+            '\'' => f.write_str("\\'")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("'")
+}
+
+// Keys, labels, and types that aren't plain identifiers are quoted with backticks, as in Cypher
+fn format_key(key: &str) -> std::string::String {
+    let mut chars = key.chars();
+    let is_identifier = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_');
+    if is_identifier {
+        key.to_string()
+    } else {
+        format!("`{}`", key.replace('`', "``"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::FromIterator;
+
+    use super::*;
+
+    #[test]
+    fn primitives() {
+        assert_eq!(Value::Null.to_string(), "null");
+        assert_eq!(Value::from(true).to_string(), "true");
+        assert_eq!(Value::from(-1).to_string(), "-1");
+        assert_eq!(Value::from(1.0).to_string(), "1.0");
+        assert_eq!(Value::from(vec![1_u8, 255]).to_string(), "0x01ff");
+        assert_eq!(Value::from("it's").to_string(), "'it\\'s'");
+    }
+
+    #[test]
+    fn collections() {
+        assert_eq!(
+            Value::from(vec![Value::from(1), Value::from("a"), Value::Null]).to_string(),
+            "[1, 'a', null]"
+        );
+        assert_eq!(
+            Value::from([("b", 2), ("a", 1), ("first name", 3)]).to_string(),
+            "{`first name`: 3, a: 1, b: 2}"
+        );
+        assert_eq!(Value::from(Vec::<i64>::new()).to_string(), "[]");
+    }
+
+    #[test]
+    fn graph_structures() {
+        let alice = Node::new(
+            1,
+            vec!["Person".to_string(), "Employee".to_string()],
+            HashMap::from_iter(vec![("name".to_string(), Value::from("Alice"))]),
+        );
+        let bob = Node::new(2, vec!["Person".to_string()], HashMap::<_, Value>::new());
+        assert_eq!(
+            Value::from(alice.clone()).to_string(),
+            "(:Person:Employee {name: 'Alice'})"
+        );
+        assert_eq!(bob.to_string(), "(:Person)");
+
+        let properties = HashMap::from_iter(vec![("since".to_string(), Value::from(2020))]);
+        let rel = Relationship::new(3, 1, 2, "KNOWS".to_string(), properties.clone());
+        assert_eq!(rel.to_string(), "(1)-[:KNOWS {since: 2020}]->(2)");
+
+        let unbound_rel = UnboundRelationship::new(3, "KNOWS".to_string(), properties);
+        let path = Path::new(vec![alice, bob], vec![unbound_rel], vec![-1, 1]);
+        assert_eq!(
+            path.to_string(),
+            "(:Person:Employee {name: 'Alice'})<-[:KNOWS {since: 2020}]-(:Person)"
+        );
+        let path = Path::new(
+            path.nodes().to_vec(),
+            path.relationships().to_vec(),
+            vec![5, 1],
+        );
+        assert_eq!(
+            path.to_string(),
+            "(:Person:Employee {name: 'Alice'})-[:KNOWS {since: 2020}]-(:Person)"
+        );
+    }
+
+    #[test]
+    fn other_structures() {
+        assert_eq!(
+            Value::from(Duration::new(1, 2, 3, 4)).to_string(),
+            "duration('P1M2DT3.000000004S')"
+        );
+        assert_eq!(
+            Value::from(Point2D::new(7203, 1.0, 2.5)).to_string(),
+            "point({srid: 7203, x: 1.0, y: 2.5})"
+        );
+        assert_eq!(
+            Value::from(Structure::new(0x01, vec![1])).to_string(),
+            "structure<0x01>[1]"
+        );
+        assert_eq!(
+            Value::from(Duration::new(0, 0, -1, 500_000_000)).to_string(),
+            "duration('P0M0DT-0.500000000S')"
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn temporal() {
+        let date = chrono::NaiveDate::from_ymd_opt(2020, 12, 25).unwrap();
+        assert_eq!(Value::from(date).to_string(), "date('2020-12-25')");
+        assert_eq!(
+            Value::from(date.and_hms_opt(10, 0, 0).unwrap()).to_string(),
+            "localdatetime('2020-12-25T10:00:00')"
+        );
+    }
+}
@@ -1,9 +1,13 @@
+use std::convert::TryFrom;
+use std::ops::{Add, Neg, Sub};
+
 use bolt_proto_derive::*;
 
 pub(crate) const MARKER: u8 = 0xB4;
 pub(crate) const SIGNATURE: u8 = 0x45;
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Duration {
     pub(crate) months: i64,
     pub(crate) days: i64,
@@ -36,6 +40,105 @@ impl Duration {
     pub fn nanos(&self) -> i32 {
         self.nanos
     }
+
+    /// Convert this into a [`std::time::Duration`], counting each day as 24 hours. Returns `None` if the conversion
+    /// would lose information, i.e. if this duration has months (which have no fixed length) or is negative.
+    pub fn to_std(&self) -> Option<std::time::Duration> {
+        if self.months != 0 {
+            return None;
+        }
+        let total_nanos = (i128::from(self.days) * SECONDS_PER_DAY + i128::from(self.seconds))
+            * NANOS_PER_SECOND
+            + i128::from(self.nanos);
+        let seconds = u64::try_from(total_nanos.div_euclid(NANOS_PER_SECOND)).ok()?;
+        // The remainder is in 0..1e9, which fits in a u32
+        let nanos = total_nanos.rem_euclid(NANOS_PER_SECOND) as u32;
+        Some(std::time::Duration::new(seconds, nanos))
+    }
+
+    /// Add two durations component-wise, carrying excess nanoseconds into seconds. Returns `None` on overflow.
+    pub fn checked_add(&self, other: &Duration) -> Option<Duration> {
+        let nanos = i64::from(self.nanos) + i64::from(other.nanos);
+        let seconds = self
+            .seconds
+            .checked_add(other.seconds)?
+            .checked_add(nanos.div_euclid(NANOS_PER_SECOND as i64))?;
+        Some(Duration {
+            months: self.months.checked_add(other.months)?,
+            days: self.days.checked_add(other.days)?,
+            seconds,
+            // The remainder is in 0..1e9, which fits in an i32
+            nanos: nanos.rem_euclid(NANOS_PER_SECOND as i64) as i32,
+        })
+    }
+
+    /// Subtract a duration from this one component-wise, see [`checked_add`](Duration::checked_add). Returns `None` on
+    /// overflow.
+    pub fn checked_sub(&self, other: &Duration) -> Option<Duration> {
+        self.checked_add(&other.checked_neg()?)
+    }
+
+    // Formats this duration as ISO 8601, e.g. P1M2DT-0.5S. The seconds and nanoseconds can have different signs, so
+    // they are combined before formatting.
+    pub(crate) fn to_iso_8601(&self) -> std::string::String {
+        let total_nanos = i128::from(self.seconds) * NANOS_PER_SECOND + i128::from(self.nanos);
+        let sign = if total_nanos < 0 { "-" } else { "" };
+        let total_nanos = total_nanos.abs();
+        format!(
+            "P{}M{}DT{}{}.{:09}S",
+            self.months,
+            self.days,
+            sign,
+            total_nanos / NANOS_PER_SECOND,
+            total_nanos % NANOS_PER_SECOND
+        )
+    }
+
+    /// Negate every component of this duration, borrowing from the seconds so that the nanoseconds stay positive like
+    /// in [`checked_add`](Duration::checked_add). Returns `None` on overflow.
+    pub fn checked_neg(&self) -> Option<Duration> {
+        let nanos = -i64::from(self.nanos);
+        let seconds = self
+            .seconds
+            .checked_neg()?
+            .checked_add(nanos.div_euclid(NANOS_PER_SECOND as i64))?;
+        Some(Duration {
+            months: self.months.checked_neg()?,
+            days: self.days.checked_neg()?,
+            seconds,
+            // The remainder is in 0..1e9, which fits in an i32
+            nanos: nanos.rem_euclid(NANOS_PER_SECOND as i64) as i32,
+        })
+    }
+}
+
+const SECONDS_PER_DAY: i128 = 3600 * 24;
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+
+impl Add for Duration {
+    type Output = Duration;
+
+    fn add(self, rhs: Duration) -> Duration {
+        self.checked_add(&rhs)
+            .expect("overflow when adding durations")
+    }
+}
+
+impl Sub for Duration {
+    type Output = Duration;
+
+    fn sub(self, rhs: Duration) -> Duration {
+        self.checked_sub(&rhs)
+            .expect("overflow when subtracting durations")
+    }
+}
+
+impl Neg for Duration {
+    type Output = Duration;
+
+    fn neg(self) -> Duration {
+        self.checked_neg().expect("overflow when negating duration")
+    }
 }
 
 impl From<std::time::Duration> for Duration {
@@ -59,7 +162,6 @@ impl From<std::time::Duration> for Duration {
 
 #[cfg(test)]
 mod tests {
-    use std::convert::TryFrom;
     use std::sync::{Arc, Mutex};
 
     use bytes::Bytes;
@@ -73,6 +175,68 @@ mod tests {
         Duration::new(7, 123_456_543, 54_213_945_693_251, 19287)
     }
 
+    #[test]
+    fn arithmetic() {
+        let a = Duration::new(1, 2, 3, 600_000_000);
+        let b = Duration::new(0, 1, 1, 500_000_000);
+        assert_eq!(a.clone() + b.clone(), Duration::new(1, 3, 5, 100_000_000));
+        assert_eq!(a.clone() - b.clone(), Duration::new(1, 1, 2, 100_000_000));
+        assert_eq!(
+            b.clone() - a.clone(),
+            Duration::new(-1, -1, -3, 900_000_000)
+        );
+        assert_eq!(-a.clone(), Duration::new(-1, -2, -4, 400_000_000));
+        assert_eq!(-a.clone(), Duration::new(0, 0, 0, 0) - a.clone());
+        assert_eq!(-(-a.clone()), a);
+        assert_eq!(
+            -Duration::new(0, 0, 1, -500_000_000),
+            Duration::new(0, 0, -1, 500_000_000)
+        );
+        assert_eq!(Duration::new(0, 0, i64::MIN, 0).checked_neg(), None);
+        assert_eq!(
+            Duration::new(i64::MAX, 0, 0, 0).checked_add(&Duration::new(1, 0, 0, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn to_iso_8601() {
+        assert_eq!(
+            Duration::new(1, 2, 3, 4).to_iso_8601(),
+            "P1M2DT3.000000004S"
+        );
+        assert_eq!(
+            Duration::new(0, 0, -1, 500_000_000).to_iso_8601(),
+            "P0M0DT-0.500000000S"
+        );
+        assert_eq!(
+            Duration::new(0, 0, -1, -500_000_000).to_iso_8601(),
+            "P0M0DT-1.500000000S"
+        );
+        assert_eq!(
+            Duration::new(-1, 2, 1, -500_000_000).to_iso_8601(),
+            "P-1M2DT0.500000000S"
+        );
+        assert_eq!(
+            Duration::new(0, 0, i64::MIN, -999_999_999).to_iso_8601(),
+            "P0M0DT-9223372036854775808.999999999S"
+        );
+    }
+
+    #[test]
+    fn to_std() {
+        let std_duration = std::time::Duration::new(200_000, 123);
+        assert_eq!(Duration::from(std_duration).to_std(), Some(std_duration));
+        assert_eq!(
+            Duration::new(0, 1, -1, 0).to_std(),
+            Some(std::time::Duration::from_secs(86_399))
+        );
+        // Months have no fixed length
+        assert_eq!(Duration::new(1, 0, 0, 0).to_std(), None);
+        // Negative durations
+        assert_eq!(Duration::new(0, 0, -1, 0).to_std(), None);
+    }
+
     #[test]
     fn get_marker() {
         let duration = get_duration();
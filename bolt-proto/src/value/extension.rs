@@ -0,0 +1,189 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::*;
+use crate::serialization::with_scoped;
+use crate::value::{Structure, Value};
+
+/// A structure type defined outside of this crate, e.g. a proprietary structure sent by a database vendor that speaks
+/// Bolt. Values of such a type are sent and received as a [`Structure`] with the given signature, see
+/// [`Structure::from_extension`] and [`Structure::try_into_extension`].
+pub trait StructureExtension: Sized {
+    const SIGNATURE: u8;
+
+    /// Create a value from the fields of a structure with this signature, returning an error if they are invalid.
+    fn from_fields(fields: Vec<Value>) -> Result<Self>;
+
+    fn into_fields(self) -> Vec<Value>;
+}
+
+/// Decodes a structure with a registered signature, see [`ExtensionRegistry::register`].
+pub type StructureDecoder = fn(Structure) -> Result<Value>;
+
+/// Custom decoders for structure signatures, used while deserializing within
+/// [`decode_with_extensions`](crate::decode_with_extensions).
+#[derive(Debug, Clone, Default)]
+pub struct ExtensionRegistry {
+    decoders: Arc<HashMap<u8, StructureDecoder>>,
+}
+
+impl ExtensionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode structures with the given signature using a custom decoder, which receives the structure with its
+    /// fields already deserialized. This takes precedence over the structures this crate knows about, so it can also
+    /// be used for vendors that reuse a signature for something else. Returns the decoder previously registered for
+    /// the signature, if any.
+    pub fn register(
+        &mut self,
+        signature: u8,
+        decoder: StructureDecoder,
+    ) -> Option<StructureDecoder> {
+        Arc::make_mut(&mut self.decoders).insert(signature, decoder)
+    }
+
+    /// Register the signature of a [`StructureExtension`], so that structures whose fields aren't valid for it fail to
+    /// deserialize. Valid ones are then deserialized as a [`Value::Structure`].
+    pub fn register_extension<T: StructureExtension>(&mut self) -> Option<StructureDecoder> {
+        self.register(T::SIGNATURE, validate_extension::<T>)
+    }
+
+    pub fn is_registered(&self, signature: u8) -> bool {
+        self.decoders.contains_key(&signature)
+    }
+}
+
+fn validate_extension<T: StructureExtension>(structure: Structure) -> Result<Value> {
+    Ok(Value::Structure(Structure::from_extension(T::from_fields(
+        structure.fields,
+    )?)))
+}
+
+thread_local! {
+    static EXTENSIONS: Cell<Option<ExtensionRegistry>> = const { Cell::new(None) };
+}
+
+/// Run the given closure with the decoders of a registry in use on the current thread, so that structures with a
+/// registered signature are decoded by them wherever they appear, including inside lists, maps, and other structures.
+/// The previously used registry (if any) is replaced until the closure returns.
+///
+/// # Example
+/// ```
+/// # use bytes::Bytes;
+/// # use bolt_proto::{decode_with_extensions, Deserialize, Value};
+/// # use bolt_proto::error::*;
+/// # use bolt_proto::value::{ExtensionRegistry, Structure};
+/// fn reject(_: Structure) -> Result<Value> {
+///     Err(ConversionError::FromValue(Value::Null).into())
+/// }
+///
+/// let mut registry = ExtensionRegistry::new();
+/// registry.register(0x01, reject);
+/// let mut bytes = Bytes::from_static(&[0xB1, 0x01, 0x01]);
+/// let result = decode_with_extensions(&registry, || Value::deserialize(&mut bytes));
+/// assert!(result.is_err());
+/// ```
+pub fn decode_with_extensions<T>(registry: &ExtensionRegistry, f: impl FnOnce() -> T) -> T {
+    with_scoped(&EXTENSIONS, Some(registry.clone()), f)
+}
+
+pub(crate) fn extension_decoder(signature: u8) -> Option<StructureDecoder> {
+    // The registry is moved out of the cell to look up the decoder, and put back before it is used
+    EXTENSIONS.with(|extensions| {
+        let registry = extensions.take();
+        let decoder = registry
+            .as_ref()
+            .and_then(|registry| registry.decoders.get(&signature).copied());
+        extensions.set(registry);
+        decoder
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::serialization::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Vector(Vec<f64>);
+
+    impl StructureExtension for Vector {
+        const SIGNATURE: u8 = 0x56;
+
+        fn from_fields(fields: Vec<Value>) -> Result<Self> {
+            match <[Value; 1]>::try_from(fields) {
+                Ok([list]) => Ok(Vector(Vec::try_from(list)?)),
+                Err(fields) => Err(ConversionError::FromValue(Value::from(fields)).into()),
+            }
+        }
+
+        fn into_fields(self) -> Vec<Value> {
+            vec![Value::from(self.0)]
+        }
+    }
+
+    fn deserialize(registry: &ExtensionRegistry, value: Value) -> Result<Value> {
+        let bytes = value.try_into_bytes().unwrap();
+        decode_with_extensions(registry, || Value::try_from(Arc::new(Mutex::new(bytes))))
+    }
+
+    #[test]
+    fn extension_round_trip() {
+        let structure = Structure::from_extension(Vector(vec![1.0, 2.0]));
+        assert_eq!(structure.signature(), 0x56);
+        assert_eq!(
+            structure.clone().try_into_extension::<Vector>().unwrap(),
+            Vector(vec![1.0, 2.0])
+        );
+        assert!(Structure::new(0x01, vec![Value::from(vec![1.0])])
+            .try_into_extension::<Vector>()
+            .is_err());
+
+        let mut registry = ExtensionRegistry::new();
+        assert!(registry.register_extension::<Vector>().is_none());
+        assert!(registry.is_registered(0x56));
+        let value = Value::from(vec![Value::from(structure.clone())]);
+        assert_eq!(deserialize(&registry, value.clone()).unwrap(), value);
+
+        let invalid = Value::from(Structure::new(0x56, vec![1, 2]));
+        assert!(deserialize(&registry, invalid.clone()).is_err());
+        // Without the registry, the structure is kept as it is
+        assert_eq!(
+            deserialize(&ExtensionRegistry::new(), invalid.clone()).unwrap(),
+            invalid
+        );
+    }
+
+    #[test]
+    fn override_known_signature() {
+        fn as_structure(structure: Structure) -> Result<Value> {
+            Ok(Value::Structure(structure))
+        }
+
+        let mut registry = ExtensionRegistry::new();
+        registry.register(crate::value::point_2d::SIGNATURE, as_structure);
+        let point = Value::from(crate::value::Point2D::new(7203, 1.0, 2.0));
+        assert_eq!(
+            deserialize(&registry, point.clone()).unwrap(),
+            Value::from(Structure::new(
+                crate::value::point_2d::SIGNATURE,
+                vec![Value::from(7203), Value::from(1.0), Value::from(2.0)]
+            ))
+        );
+        // The registry is only used within the closure
+        assert!(extension_decoder(crate::value::point_2d::SIGNATURE).is_none());
+        assert_eq!(
+            Value::try_from(Arc::new(Mutex::new(
+                point.clone().try_into_bytes().unwrap()
+            )))
+            .unwrap(),
+            point
+        );
+    }
+}
@@ -1,12 +1,12 @@
-use std::convert::{TryFrom, TryInto};
-use std::mem;
-use std::panic::catch_unwind;
-use std::sync::{Arc, Mutex};
+use std::hash::{Hash, Hasher};
 
-use bytes::{Buf, BufMut, Bytes, BytesMut};
+use bytes::{BufMut, Bytes};
 
 use crate::error::*;
+use crate::impl_try_from_shared_bytes;
+use crate::impl_try_into_bytes;
 use crate::serialization::*;
+use crate::value::hash_f64;
 
 pub(crate) const MARKER: u8 = 0xC1;
 
@@ -15,44 +15,43 @@ pub struct Float {
     pub(crate) value: f64,
 }
 
+impl Hash for Float {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f64(self.value, state);
+    }
+}
+
 impl Marker for Float {
     fn get_marker(&self) -> Result<u8> {
         Ok(MARKER)
     }
-}
-
-impl Serialize for Float {}
 
-impl TryInto<Bytes> for Float {
-    type Error = Error;
-
-    fn try_into(self) -> Result<Bytes> {
-        let mut bytes = BytesMut::with_capacity(mem::size_of::<u8>() + mem::size_of::<f64>());
-        bytes.put_u8(MARKER);
-        bytes.put_f64(self.value);
-        Ok(bytes.freeze())
+    fn serialized_len(&self) -> Result<usize> {
+        self.value.field_len()
     }
 }
 
-impl Deserialize for Float {}
+impl Serialize for Float {
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()> {
+        self.value.serialize_field(buf)
+    }
+}
 
-impl TryFrom<Arc<Mutex<Bytes>>> for Float {
-    type Error = Error;
+impl_try_into_bytes!(Float);
 
-    fn try_from(input_arc: Arc<Mutex<Bytes>>) -> Result<Self> {
-        catch_unwind(move || {
-            let mut input_bytes = input_arc.lock().unwrap();
-            let marker = input_bytes.get_u8();
+impl Deserialize for Float {
+    fn deserialize(bytes: &mut Bytes) -> Result<Self> {
+        let marker = bytes.try_get_u8("float marker")?;
 
-            match marker {
-                MARKER => Ok(Float::from(input_bytes.get_f64())),
-                _ => Err(DeserializationError::InvalidMarkerByte(marker).into()),
-            }
-        })
-        .map_err(|_| DeserializationError::Panicked)?
+        match marker {
+            MARKER => Ok(Float::from(bytes.try_get_f64("float")?)),
+            _ => Err(DeserializationError::InvalidMarkerByte(marker).into()),
+        }
     }
 }
 
+impl_try_from_shared_bytes!(Float);
+
 impl From<f64> for Float {
     fn from(float: f64) -> Self {
         Self { value: float }
@@ -70,7 +69,7 @@ mod tests {
 
     #[test]
     fn get_marker() {
-        let min = Float::from(std::f64::MIN_POSITIVE);
+        let min = Float::from(f64::MIN_POSITIVE);
         assert_eq!(min.get_marker().unwrap(), MARKER);
         let e = Float::from(std::f64::consts::E);
         assert_eq!(e.get_marker().unwrap(), MARKER);
@@ -92,7 +91,7 @@ mod tests {
             Float::try_from(Arc::new(Mutex::new(pi.clone().try_into_bytes().unwrap()))).unwrap(),
             pi
         );
-        let max = Float::from(std::f64::MAX);
+        let max = Float::from(f64::MAX);
         assert_eq!(
             Float::try_from(Arc::new(Mutex::new(max.clone().try_into_bytes().unwrap()))).unwrap(),
             max
@@ -0,0 +1,72 @@
+use crate::error::*;
+use crate::Value;
+
+// GeoJSON coordinates are always on WGS-84, so only geographic points can be converted. The points convert with these
+// helpers, passing their coordinates in order (longitude, latitude, and height if any).
+
+pub(crate) fn to_geojson<P>(
+    point: &P,
+    wgs_84: bool,
+    coordinates: &[f64],
+) -> Result<serde_json::Value>
+where
+    P: Clone + Into<Value>,
+{
+    if !wgs_84 {
+        return Err(ConversionError::FromValue(point.clone().into()).into());
+    }
+    Ok(serde_json::json!({"type": "Point", "coordinates": coordinates}))
+}
+
+pub(crate) fn from_geojson<const N: usize>(geometry: &serde_json::Value) -> Result<[f64; N]> {
+    let invalid = || ConversionError::FromJson(geometry.clone()).into();
+    if geometry.get("type").and_then(serde_json::Value::as_str) != Some("Point") {
+        return Err(invalid());
+    }
+    let coordinates = geometry
+        .get("coordinates")
+        .and_then(serde_json::Value::as_array)
+        .filter(|coordinates| coordinates.len() == N)
+        .ok_or_else(invalid)?;
+    let mut point = [0.0; N];
+    for (value, coordinate) in point.iter_mut().zip(coordinates) {
+        *value = coordinate.as_f64().ok_or_else(invalid)?;
+    }
+    Ok(point)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::value::{Point2D, Point3D};
+
+    #[test]
+    fn points() {
+        let point = Point2D::wgs_84(2.35, 48.85);
+        let geometry = json!({"type": "Point", "coordinates": [2.35, 48.85]});
+        assert_eq!(point.to_geojson().unwrap(), geometry);
+        assert_eq!(Point2D::from_geojson(&geometry).unwrap(), point);
+        assert!(Point3D::from_geojson(&geometry).is_err());
+
+        let point = Point3D::wgs_84(2.35, 48.85, 35.0);
+        let geometry = json!({"type": "Point", "coordinates": [2.35, 48.85, 35.0]});
+        assert_eq!(point.to_geojson().unwrap(), geometry);
+        assert_eq!(Point3D::from_geojson(&geometry).unwrap(), point);
+        assert!(Point2D::from_geojson(&geometry).is_err());
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(Point2D::cartesian(1.0, 2.0).to_geojson().is_err());
+        assert!(Point3D::cartesian(1.0, 2.0, 3.0).to_geojson().is_err());
+        assert!(Point2D::from_geojson(&json!({"type": "Point", "coordinates": [2.35]})).is_err());
+        assert!(
+            Point2D::from_geojson(&json!({"type": "Point", "coordinates": [2.35, "a"]})).is_err()
+        );
+        assert!(Point2D::from_geojson(
+            &json!({"type": "LineString", "coordinates": [[2.35, 48.85]]})
+        )
+        .is_err());
+    }
+}
@@ -1,11 +1,10 @@
-use std::convert::{TryFrom, TryInto};
 use std::mem;
-use std::panic::catch_unwind;
-use std::sync::{Arc, Mutex};
 
-use bytes::{Buf, BufMut, Bytes, BytesMut};
+use bytes::{BufMut, Bytes};
 
 use crate::error::*;
+use crate::impl_try_from_shared_bytes;
+use crate::impl_try_into_bytes;
 use crate::serialization::*;
 
 pub(crate) const MARKER_INT_8: u8 = 0xC8;
@@ -29,51 +28,61 @@ impl Marker for Integer {
             -16..=127 => Ok(self.value as u8),
         }
     }
-}
-
-impl Serialize for Integer {}
 
-impl TryInto<Bytes> for Integer {
-    type Error = Error;
+    fn serialized_len(&self) -> Result<usize> {
+        // Marker byte, then the value unless it is a tiny int
+        match self.get_marker()? {
+            MARKER_INT_64 => Ok(mem::size_of::<u8>() + mem::size_of::<i64>()),
+            MARKER_INT_32 => Ok(mem::size_of::<u8>() + mem::size_of::<i32>()),
+            MARKER_INT_16 => Ok(mem::size_of::<u8>() + mem::size_of::<i16>()),
+            MARKER_INT_8 => Ok(mem::size_of::<u8>() + mem::size_of::<i8>()),
+            _ => Ok(mem::size_of::<u8>()),
+        }
+    }
+}
 
-    fn try_into(self) -> Result<Bytes> {
-        let mut bytes = BytesMut::with_capacity(mem::size_of::<u8>() + mem::size_of::<i64>());
+impl Serialize for Integer {
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()> {
         let marker = self.get_marker()?;
-        bytes.put_u8(marker);
+        buf.put_u8(marker);
         match marker {
-            MARKER_INT_8 => bytes.put_i8(self.value as i8),
-            MARKER_INT_16 => bytes.put_i16(self.value as i16),
-            MARKER_INT_32 => bytes.put_i32(self.value as i32),
-            MARKER_INT_64 => bytes.put_i64(self.value),
+            MARKER_INT_8 => buf.put_i8(self.value as i8),
+            MARKER_INT_16 => buf.put_i16(self.value as i16),
+            MARKER_INT_32 => buf.put_i32(self.value as i32),
+            MARKER_INT_64 => buf.put_i64(self.value),
             _ => {} // tiny int, the marker IS the value
         }
-        Ok(bytes.freeze())
+        Ok(())
     }
 }
 
-impl Deserialize for Integer {}
-
-impl TryFrom<Arc<Mutex<Bytes>>> for Integer {
-    type Error = Error;
-
-    fn try_from(input_arc: Arc<Mutex<Bytes>>) -> Result<Self> {
-        catch_unwind(move || {
-            let mut input_bytes = input_arc.lock().unwrap();
-            let marker = input_bytes.get_u8();
-
-            match marker {
-                marker if (-16..=127).contains(&(marker as i8)) => Ok(Integer::from(marker as i8)),
-                MARKER_INT_8 => Ok(Integer::from(input_bytes.get_i8())),
-                MARKER_INT_16 => Ok(Integer::from(input_bytes.get_i16())),
-                MARKER_INT_32 => Ok(Integer::from(input_bytes.get_i32())),
-                MARKER_INT_64 => Ok(Integer::from(input_bytes.get_i64())),
-                _ => Err(DeserializationError::InvalidMarkerByte(marker).into()),
+impl_try_into_bytes!(Integer);
+
+impl Deserialize for Integer {
+    fn deserialize(bytes: &mut Bytes) -> Result<Self> {
+        let marker = bytes.try_get_u8("integer marker")?;
+
+        let integer = match marker {
+            marker if (-16..=127).contains(&(marker as i8)) => Integer::from(marker as i8),
+            MARKER_INT_8 => Integer::from(bytes.try_get_i8("integer")?),
+            MARKER_INT_16 => Integer::from(bytes.try_get_i16("integer")?),
+            MARKER_INT_32 => Integer::from(bytes.try_get_i32("integer")?),
+            MARKER_INT_64 => Integer::from(bytes.try_get_i64("integer")?),
+            _ => return Err(DeserializationError::InvalidMarkerByte(marker).into()),
+        };
+        if is_strict_decoding() && integer.get_marker()? != marker {
+            return Err(DeserializationError::NonCanonicalInteger {
+                value: integer.value,
+                marker,
             }
-        })
-        .map_err(|_| DeserializationError::Panicked)?
+            .into());
+        }
+        Ok(integer)
     }
 }
 
+impl_try_from_shared_bytes!(Integer);
+
 macro_rules! impl_from_primitives_for_integer {
     ($($T:ty),+) => {
         $(
@@ -89,6 +98,9 @@ impl_from_primitives_for_integer!(i8, i16, i32, i64);
 
 #[cfg(test)]
 mod tests {
+    use std::convert::TryFrom;
+    use std::sync::{Arc, Mutex};
+
     use bytes::Bytes;
 
     use super::*;
@@ -157,41 +169,61 @@ mod tests {
     fn try_from_bytes() {
         let tiny = Integer::from(-16_i8);
         assert_eq!(
-            Integer::try_from(Arc::new(Mutex::new(tiny.clone().try_into_bytes().unwrap())))
-                .unwrap(),
+            Integer::try_from(Arc::new(Mutex::new(tiny.try_into_bytes().unwrap()))).unwrap(),
             tiny
         );
         let small = Integer::from(-50_i8);
         assert_eq!(
-            Integer::try_from(Arc::new(Mutex::new(
-                small.clone().try_into_bytes().unwrap()
-            )))
-            .unwrap(),
+            Integer::try_from(Arc::new(Mutex::new(small.try_into_bytes().unwrap()))).unwrap(),
             small
         );
         let medium = Integer::from(-8000_i16);
         assert_eq!(
-            Integer::try_from(Arc::new(Mutex::new(
-                medium.clone().try_into_bytes().unwrap()
-            )))
-            .unwrap(),
+            Integer::try_from(Arc::new(Mutex::new(medium.try_into_bytes().unwrap()))).unwrap(),
             medium
         );
         let large = Integer::from(-1_000_000_000_i32);
         assert_eq!(
-            Integer::try_from(Arc::new(Mutex::new(
-                large.clone().try_into_bytes().unwrap()
-            )))
-            .unwrap(),
+            Integer::try_from(Arc::new(Mutex::new(large.try_into_bytes().unwrap()))).unwrap(),
             large
         );
         let very_large = Integer::from(-9_000_000_000_000_000_000_i64);
         assert_eq!(
-            Integer::try_from(Arc::new(Mutex::new(
-                very_large.clone().try_into_bytes().unwrap()
-            )))
-            .unwrap(),
+            Integer::try_from(Arc::new(Mutex::new(very_large.try_into_bytes().unwrap()))).unwrap(),
             very_large
         );
     }
+
+    #[test]
+    fn try_from_non_canonical_bytes() {
+        let int_16_five = Bytes::from_static(&[MARKER_INT_16, 0x00, 0x05]);
+        assert_eq!(
+            Integer::try_from(Arc::new(Mutex::new(int_16_five.clone()))).unwrap(),
+            Integer::from(5_i8)
+        );
+        assert!(matches!(
+            decode_strict(|| Integer::try_from(Arc::new(Mutex::new(int_16_five)))),
+            Err(Error::DeserializationError(
+                DeserializationError::NonCanonicalInteger {
+                    value: 5,
+                    marker: MARKER_INT_16
+                }
+            ))
+        ));
+
+        // Minimal encodings are accepted
+        for value in &[
+            5_i64,
+            -50,
+            -8000,
+            -1_000_000_000,
+            -9_000_000_000_000_000_000,
+        ] {
+            let bytes = Integer::from(*value).try_into_bytes().unwrap();
+            assert_eq!(
+                decode_strict(|| Integer::try_from(Arc::new(Mutex::new(bytes)))).unwrap(),
+                Integer::from(*value)
+            );
+        }
+    }
 }
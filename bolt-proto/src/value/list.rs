@@ -1,99 +1,104 @@
+use std::collections::HashSet;
 use std::convert::{TryFrom, TryInto};
-use std::mem;
-use std::panic::catch_unwind;
-use std::sync::{Arc, Mutex};
+use std::hash::Hash;
 
-use bytes::{Buf, BufMut, Bytes, BytesMut};
+use bytes::{BufMut, Bytes};
 
 use crate::error::*;
+use crate::impl_try_from_shared_bytes;
+use crate::impl_try_into_bytes;
 use crate::serialization::*;
+use crate::value::decode;
 use crate::Value;
 
 pub(crate) const MARKER_TINY: u8 = 0x90;
 pub(crate) const MARKER_SMALL: u8 = 0xD4;
 pub(crate) const MARKER_MEDIUM: u8 = 0xD5;
 pub(crate) const MARKER_LARGE: u8 = 0xD6;
+pub(crate) const MARKERS: [u8; 4] = [MARKER_TINY, MARKER_SMALL, MARKER_MEDIUM, MARKER_LARGE];
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub struct List {
     pub(crate) value: Vec<Value>,
 }
 
-impl Marker for List {
-    fn get_marker(&self) -> Result<u8> {
-        match self.value.len() {
-            0..=15 => Ok(MARKER_TINY | self.value.len() as u8),
-            16..=255 => Ok(MARKER_SMALL),
-            256..=65_535 => Ok(MARKER_MEDIUM),
-            65_536..=4_294_967_295 => Ok(MARKER_LARGE),
-            _ => Err(Error::ValueTooLarge(self.value.len())),
+impl List {
+    pub fn len(&self) -> usize {
+        self.value.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// Convert this list into an array of exactly `N` elements, returning an error if the list has a different length or
+    /// if any of its elements can't be converted.
+    pub fn try_into_array<T, const N: usize>(self) -> Result<[T; N]>
+    where
+        T: TryFrom<Value, Error = Error>,
+    {
+        if self.value.len() != N {
+            return Err(ConversionError::InvalidLength {
+                expected: N,
+                actual: self.value.len(),
+            }
+            .into());
         }
+        let elements = self
+            .value
+            .into_iter()
+            .map(T::try_from)
+            .collect::<Result<Vec<T>>>()?;
+        // The length was checked above
+        Ok(elements
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("list length changed during conversion")))
+    }
+
+    /// Convert this list into a set, e.g. for membership checks. Duplicate elements are collapsed. Returns an error if
+    /// any of the elements can't be converted.
+    pub fn try_into_set<T>(self) -> Result<HashSet<T>>
+    where
+        T: TryFrom<Value, Error = Error> + Eq + Hash,
+    {
+        self.value.into_iter().map(T::try_from).collect()
     }
 }
 
-impl Serialize for List {}
+impl Marker for List {
+    fn get_marker(&self) -> Result<u8> {
+        sized_marker(self.value.len(), MARKERS)
+    }
+
+    fn serialized_len(&self) -> Result<usize> {
+        self.value.field_len()
+    }
+}
 
-impl TryInto<Bytes> for List {
-    type Error = Error;
+impl Serialize for List {
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()> {
+        self.value.serialize_field(buf)
+    }
+}
 
-    fn try_into(self) -> Result<Bytes> {
-        let marker = self.get_marker()?;
-        let length = self.value.len();
+impl_try_into_bytes!(List);
 
-        let mut total_value_bytes: usize = 0;
-        let mut value_bytes_vec: Vec<Bytes> = Vec::with_capacity(length);
-        for value in self.value {
-            let value_bytes: Bytes = value.try_into()?;
-            total_value_bytes += value_bytes.len();
-            value_bytes_vec.push(value_bytes);
-        }
-        // Worst case is a large List, with marker byte, 32-bit size value, and all the Value bytes
-        let mut bytes = BytesMut::with_capacity(
-            mem::size_of::<u8>() + mem::size_of::<u32>() + total_value_bytes,
-        );
-        bytes.put_u8(marker);
-        match length {
-            0..=15 => {}
-            16..=255 => bytes.put_u8(length as u8),
-            256..=65_535 => bytes.put_u16(length as u16),
-            65_536..=4_294_967_295 => bytes.put_u32(length as u32),
-            _ => return Err(Error::ValueTooLarge(length)),
+impl Deserialize for List {
+    fn deserialize(bytes: &mut Bytes) -> Result<Self> {
+        let marker = bytes.peek_u8("list marker")?;
+        match marker {
+            marker if (MARKER_TINY..=(MARKER_TINY | 0x0F)).contains(&marker) => {}
+            MARKER_SMALL | MARKER_MEDIUM | MARKER_LARGE => {}
+            _ => return Err(DeserializationError::InvalidMarkerByte(marker).into()),
         }
-        for value_bytes in value_bytes_vec {
-            bytes.put(value_bytes);
+        match decode::deserialize_value(bytes)? {
+            Value::List(list) => Ok(list),
+            value => Err(ConversionError::FromValue(value).into()),
         }
-        Ok(bytes.freeze())
     }
 }
 
-impl Deserialize for List {}
-
-impl TryFrom<Arc<Mutex<Bytes>>> for List {
-    type Error = Error;
-
-    fn try_from(input_arc: Arc<Mutex<Bytes>>) -> Result<Self> {
-        catch_unwind(move || {
-            let marker = input_arc.lock().unwrap().get_u8();
-            let size = match marker {
-                marker if (MARKER_TINY..=(MARKER_TINY | 0x0F)).contains(&marker) => {
-                    0x0F & marker as usize
-                }
-                MARKER_SMALL => input_arc.lock().unwrap().get_u8() as usize,
-                MARKER_MEDIUM => input_arc.lock().unwrap().get_u16() as usize,
-                MARKER_LARGE => input_arc.lock().unwrap().get_u32() as usize,
-                _ => {
-                    return Err(DeserializationError::InvalidMarkerByte(marker).into());
-                }
-            };
-            let mut list: Vec<Value> = Vec::with_capacity(size);
-            for _ in 0..size {
-                list.push(Value::try_from(Arc::clone(&input_arc))?);
-            }
-            Ok(List::from(list))
-        })
-        .map_err(|_| DeserializationError::Panicked)?
-    }
-}
+impl_try_from_shared_bytes!(List);
 
 impl<T> From<Vec<T>> for List
 where
@@ -108,10 +113,64 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::convert::TryFrom;
+    use std::sync::{Arc, Mutex};
+
     use crate::value::*;
 
     use super::*;
 
+    #[test]
+    fn len() {
+        let empty_list: List = Vec::<i32>::new().into();
+        let list: List = vec![1, 2, 3].into();
+        assert_eq!(empty_list.len(), 0);
+        assert!(empty_list.is_empty());
+        assert_eq!(list.len(), 3);
+        assert!(!list.is_empty());
+    }
+
+    #[test]
+    fn try_into_array() {
+        let list: List = vec![1, 2, 3].into();
+        assert_eq!(list.try_into_array::<i32, 3>().unwrap(), [1, 2, 3]);
+
+        let list: List = vec![1, 2, 3].into();
+        assert!(matches!(
+            list.try_into_array::<i32, 2>(),
+            Err(Error::ConversionError(ConversionError::InvalidLength {
+                expected: 2,
+                actual: 3
+            }))
+        ));
+
+        let list: List = vec![Value::from(1), Value::from("two")].into();
+        assert!(matches!(
+            list.try_into_array::<i64, 2>(),
+            Err(Error::ConversionError(ConversionError::FromValue(_)))
+        ));
+    }
+
+    #[test]
+    fn try_into_set() {
+        let list: List = vec![1, 2, 3].into();
+        assert_eq!(
+            list.try_into_set::<i64>().unwrap(),
+            vec![1, 2, 3].into_iter().collect()
+        );
+
+        let list: List = vec![1, 2, 2, 3, 1].into();
+        let set = list.try_into_set::<i64>().unwrap();
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&2));
+
+        let list: List = vec![Value::from(1), Value::from("two")].into();
+        assert!(matches!(
+            list.try_into_set::<i64>(),
+            Err(Error::ConversionError(ConversionError::FromValue(_)))
+        ));
+    }
+
     #[test]
     fn get_marker() {
         let empty_list: List = Vec::<i32>::new().into();
@@ -147,7 +206,7 @@ mod tests {
         let tiny_list_item_bytes = Integer::from(100_000_000_000_i64).try_into_bytes().unwrap();
         let tiny_list_expected_bytes: Vec<u8> = vec![MARKER_TINY | 10]
             .into_iter()
-            .chain(tiny_list_item_bytes.repeat(10).into_iter())
+            .chain(tiny_list_item_bytes.repeat(10))
             .collect();
         assert_eq!(
             tiny_list.try_into_bytes().unwrap(),
@@ -156,7 +215,7 @@ mod tests {
         let small_list_item_bytes = String::from("item").try_into_bytes().unwrap();
         let small_list_expected_bytes: Vec<u8> = vec![MARKER_SMALL, 0x64] // marker, size
             .into_iter()
-            .chain(small_list_item_bytes.repeat(100).into_iter())
+            .chain(small_list_item_bytes.repeat(100))
             .collect();
         assert_eq!(
             small_list.try_into_bytes().unwrap(),
@@ -165,7 +224,7 @@ mod tests {
         let medium_list_item_bytes = Boolean::from(false).try_into_bytes().unwrap();
         let medium_list_expected_bytes: Vec<u8> = vec![MARKER_MEDIUM, 0x03, 0xE8] // marker, size
             .into_iter()
-            .chain(medium_list_item_bytes.repeat(1000).into_iter())
+            .chain(medium_list_item_bytes.repeat(1000))
             .collect();
         assert_eq!(
             medium_list.try_into_bytes().unwrap(),
@@ -180,7 +239,7 @@ mod tests {
         let large_list_item_bytes = Integer::from(1_i8).try_into_bytes().unwrap();
         let large_list_expected_bytes: Vec<u8> = vec![MARKER_LARGE, 0x00, 0x01, 0x86, 0xA0] // marker, size
             .into_iter()
-            .chain(large_list_item_bytes.repeat(100_000).into_iter())
+            .chain(large_list_item_bytes.repeat(100_000))
             .collect();
         assert_eq!(
             large_list.try_into_bytes().unwrap(),
@@ -1,25 +1,72 @@
+use std::convert::TryFrom;
+
 use bolt_proto_derive::*;
-use chrono::{NaiveDateTime, Timelike};
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, NaiveDateTime, Timelike};
+
+use crate::error::*;
+use crate::value::{Date, LocalTime};
 
 pub(crate) const MARKER: u8 = 0xB2;
 pub(crate) const SIGNATURE: u8 = 0x64;
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LocalDateTime {
     pub(crate) epoch_seconds: i64,
     pub(crate) nanos: i64,
 }
 
+impl LocalDateTime {
+    pub fn epoch_seconds(&self) -> i64 {
+        self.epoch_seconds
+    }
+
+    pub fn nanos(&self) -> i64 {
+        self.nanos
+    }
+
+    /// Convert to a chrono date-time. Fails for values outside of chrono's range, or with out-of-range nanoseconds,
+    /// which deserialized values can have.
+    #[cfg(feature = "chrono")]
+    pub fn naive_date_time(&self) -> Result<NaiveDateTime> {
+        u32::try_from(self.nanos)
+            .ok()
+            .and_then(|nanos| DateTime::from_timestamp(self.epoch_seconds, nanos))
+            .map(|date_time| date_time.naive_utc())
+            .ok_or_else(|| ConversionError::InvalidTimestamp(self.epoch_seconds).into())
+    }
+}
+
+// Fails if the date is too far from the Unix epoch for its seconds to fit in an i64
+impl TryFrom<(Date, LocalTime)> for LocalDateTime {
+    type Error = Error;
+
+    fn try_from(pair: (Date, LocalTime)) -> Result<Self> {
+        let (date, time) = pair;
+        let epoch_seconds = date
+            .days_since_epoch
+            .checked_mul(86_400)
+            .and_then(|seconds| seconds.checked_add(time.nanos_since_midnight / 1_000_000_000))
+            .ok_or(ConversionError::InvalidDate(date.days_since_epoch))?;
+        Ok(Self {
+            epoch_seconds,
+            nanos: time.nanos_since_midnight % 1_000_000_000,
+        })
+    }
+}
+
+#[cfg(feature = "chrono")]
 impl From<NaiveDateTime> for LocalDateTime {
     fn from(date_time: NaiveDateTime) -> Self {
         Self {
-            epoch_seconds: date_time.timestamp(),
+            epoch_seconds: date_time.and_utc().timestamp(),
             nanos: date_time.nanosecond() as i64,
         }
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "chrono"))]
 mod tests {
     use std::convert::TryFrom;
     use std::sync::{Arc, Mutex};
@@ -29,11 +76,80 @@ mod tests {
 
     use crate::serialization::*;
     use crate::value::integer::{MARKER_INT_16, MARKER_INT_64};
+    use crate::Value;
 
     use super::*;
 
     fn get_local_date_time() -> LocalDateTime {
-        LocalDateTime::from(NaiveDate::from_ymd(2050, 3, 15).and_hms_nano(13, 15, 5, 420))
+        LocalDateTime::from(
+            NaiveDate::from_ymd_opt(2050, 3, 15)
+                .unwrap()
+                .and_hms_nano_opt(13, 15, 5, 420)
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn from_date_and_local_time() {
+        let naive_date_time = NaiveDate::from_ymd_opt(2050, 3, 15)
+            .unwrap()
+            .and_hms_nano_opt(13, 15, 5, 420)
+            .unwrap();
+        let date = Date::from(naive_date_time.date());
+        let time = LocalTime::from(naive_date_time.time());
+        assert_eq!(
+            LocalDateTime::try_from((date, time)).unwrap(),
+            get_local_date_time()
+        );
+        assert_eq!(
+            get_local_date_time().naive_date_time().unwrap(),
+            naive_date_time
+        );
+
+        // Dates before the Unix epoch
+        let naive_date_time = NaiveDate::from_ymd_opt(1901, 12, 31)
+            .unwrap()
+            .and_hms_nano_opt(23, 59, 59, 999_999_999)
+            .unwrap();
+        let local_date_time = LocalDateTime::try_from((
+            Date::from(naive_date_time.date()),
+            LocalTime::from(naive_date_time.time()),
+        ))
+        .unwrap();
+        assert_eq!(local_date_time, LocalDateTime::from(naive_date_time));
+
+        let far_future = Date {
+            days_since_epoch: i64::MAX,
+        };
+        assert!(matches!(
+            LocalDateTime::try_from((far_future, LocalTime::from(naive_date_time.time()))),
+            Err(Error::ConversionError(ConversionError::InvalidDate(
+                i64::MAX
+            )))
+        ));
+    }
+
+    #[test]
+    fn out_of_range() {
+        let local_date_time = LocalDateTime {
+            epoch_seconds: i64::MAX,
+            nanos: 0,
+        };
+        assert!(matches!(
+            local_date_time.naive_date_time(),
+            Err(Error::ConversionError(ConversionError::InvalidTimestamp(
+                i64::MAX
+            )))
+        ));
+        assert!(Value::LocalDateTime(local_date_time)
+            .to_string()
+            .contains(&i64::MAX.to_string()));
+
+        let invalid_nanos = LocalDateTime {
+            epoch_seconds: 0,
+            nanos: -1,
+        };
+        assert!(invalid_nanos.naive_date_time().is_err());
     }
 
     #[test]
@@ -1,14 +1,46 @@
+#[cfg(feature = "chrono")]
+use std::convert::TryFrom;
+
 use bolt_proto_derive::*;
+#[cfg(feature = "chrono")]
 use chrono::{NaiveTime, Timelike};
 
+#[cfg(feature = "chrono")]
+use crate::error::*;
+
 pub(crate) const MARKER: u8 = 0xB1;
 pub(crate) const SIGNATURE: u8 = 0x74;
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LocalTime {
     pub(crate) nanos_since_midnight: i64,
 }
 
+impl LocalTime {
+    pub fn nanos_since_midnight(&self) -> i64 {
+        self.nanos_since_midnight
+    }
+
+    /// Convert to a chrono time. Fails if the time isn't within a day, which deserialized values can have.
+    #[cfg(feature = "chrono")]
+    pub fn naive_time(&self) -> Result<NaiveTime> {
+        naive_time_from_nanos(self.nanos_since_midnight)
+    }
+}
+
+#[cfg(feature = "chrono")]
+pub(crate) fn naive_time_from_nanos(nanos_since_midnight: i64) -> Result<NaiveTime> {
+    let invalid = || ConversionError::InvalidTime(nanos_since_midnight).into();
+    if nanos_since_midnight < 0 {
+        return Err(invalid());
+    }
+    let seconds = u32::try_from(nanos_since_midnight / 1_000_000_000).map_err(|_| invalid())?;
+    let nanos = (nanos_since_midnight % 1_000_000_000) as u32;
+    NaiveTime::from_num_seconds_from_midnight_opt(seconds, nanos).ok_or_else(invalid)
+}
+
+#[cfg(feature = "chrono")]
 impl From<NaiveTime> for LocalTime {
     fn from(naive_time: NaiveTime) -> Self {
         Self {
@@ -19,21 +51,21 @@ impl From<NaiveTime> for LocalTime {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "chrono"))]
 mod tests {
     use std::convert::TryFrom;
     use std::sync::{Arc, Mutex};
 
     use bytes::Bytes;
-    use chrono::NaiveTime;
 
     use crate::serialization::*;
     use crate::value::integer::MARKER_INT_64;
+    use crate::Value;
 
     use super::*;
 
     fn get_chrono_naive_time() -> NaiveTime {
-        NaiveTime::from_hms_nano(12, 34, 24, 1029)
+        NaiveTime::from_hms_nano_opt(12, 34, 24, 1029).unwrap()
     }
 
     #[test]
@@ -83,4 +115,33 @@ mod tests {
             time
         );
     }
+
+    #[test]
+    fn out_of_range() {
+        for nanos in [-1, 86_400 * 1_000_000_000, i64::MAX] {
+            let time = LocalTime {
+                nanos_since_midnight: nanos,
+            };
+            assert!(matches!(
+                time.naive_time(),
+                Err(Error::ConversionError(ConversionError::InvalidTime(n))) if n == nanos
+            ));
+            assert!(Value::LocalTime(time)
+                .to_string()
+                .contains(&nanos.to_string()));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let time = LocalTime::from(get_chrono_naive_time());
+        assert_eq!(time.naive_time().unwrap(), get_chrono_naive_time());
+
+        let bytes = Value::from(get_chrono_naive_time())
+            .try_into_bytes()
+            .unwrap();
+        let value = Value::try_from(Arc::new(Mutex::new(bytes))).unwrap();
+        assert_eq!(value, Value::LocalTime(time));
+        assert_eq!(NaiveTime::try_from(value).unwrap(), get_chrono_naive_time());
+    }
 }
@@ -1,109 +1,119 @@
 use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
-use std::convert::{TryFrom, TryInto};
-use std::mem;
-use std::panic::catch_unwind;
-use std::sync::{Arc, Mutex};
+use std::hash::{Hash, Hasher};
 
-use bytes::{Buf, BufMut, Bytes, BytesMut};
+use bytes::{BufMut, Bytes};
 
 use crate::error::*;
+use crate::impl_try_from_shared_bytes;
+use crate::impl_try_into_bytes;
 use crate::serialization::*;
+use crate::value::decode;
+use crate::value::hash_map;
 use crate::Value;
 
 pub(crate) const MARKER_TINY: u8 = 0xA0;
 pub(crate) const MARKER_SMALL: u8 = 0xD8;
 pub(crate) const MARKER_MEDIUM: u8 = 0xD9;
 pub(crate) const MARKER_LARGE: u8 = 0xDA;
+pub(crate) const MARKERS: [u8; 4] = [MARKER_TINY, MARKER_SMALL, MARKER_MEDIUM, MARKER_LARGE];
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Map {
-    pub(crate) value: HashMap<Value, Value>,
+    pub(crate) value: HashMap<std::string::String, Value>,
+}
+
+impl Map {
+    /// Create an empty map with space for at least `capacity` entries, to avoid reallocating while it is filled.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            value: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Insert an entry into this map, returning the previous value for the key if there was one.
+    pub fn insert(
+        &mut self,
+        key: impl Into<std::string::String>,
+        value: impl Into<Value>,
+    ) -> Option<Value> {
+        self.value.insert(key.into(), value.into())
+    }
+
+    pub fn len(&self) -> usize {
+        self.value.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.value.capacity()
+    }
+}
+
+impl Hash for Map {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_map(&self.value, state);
+    }
 }
 
 impl Marker for Map {
     fn get_marker(&self) -> Result<u8> {
-        match self.value.len() {
-            0..=15 => Ok(MARKER_TINY | self.value.len() as u8),
-            16..=255 => Ok(MARKER_SMALL),
-            256..=65_535 => Ok(MARKER_MEDIUM),
-            65_536..=4_294_967_295 => Ok(MARKER_LARGE),
-            _ => Err(Error::ValueTooLarge(self.value.len())),
-        }
+        sized_marker(self.value.len(), MARKERS)
+    }
+
+    fn serialized_len(&self) -> Result<usize> {
+        self.value.field_len()
     }
 }
 
-impl Serialize for Map {}
+impl Serialize for Map {
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()> {
+        serialize_entries(&self.value, buf)
+    }
+}
 
-impl TryInto<Bytes> for Map {
-    type Error = Error;
+impl_try_into_bytes!(Map);
 
-    fn try_into(self) -> Result<Bytes> {
-        let marker = self.get_marker()?;
-        let length = self.value.len();
+// Entries are written in key order if deterministic encoding is on, see encode_deterministic
+pub(crate) fn serialize_entries(
+    entries: &HashMap<std::string::String, Value>,
+    buf: &mut impl BufMut,
+) -> Result<()> {
+    put_sized_header(buf, entries.len(), MARKERS)?;
+    let mut entries: Vec<(&std::string::String, &Value)> = entries.iter().collect();
+    if is_deterministic_encoding() {
+        entries.sort_unstable_by_key(|(key, _)| *key);
+    }
+    for (key, value) in entries {
+        key.serialize_field(buf)?;
+        value.serialize_into(buf)?;
+    }
+    Ok(())
+}
 
-        let mut total_value_bytes: usize = 0;
-        let mut value_bytes_vec: Vec<Bytes> = Vec::with_capacity(length);
-        for (key, val) in self.value {
-            let key_bytes: Bytes = key.try_into()?;
-            let val_bytes: Bytes = val.try_into()?;
-            total_value_bytes += key_bytes.len() + val_bytes.len();
-            value_bytes_vec.push(key_bytes);
-            value_bytes_vec.push(val_bytes);
-        }
-        // Worst case is a large Map, with marker byte, 32-bit size value, and all the Value bytes
-        let mut bytes = BytesMut::with_capacity(
-            mem::size_of::<u8>() + mem::size_of::<u32>() + total_value_bytes,
-        );
-        bytes.put_u8(marker);
-        match length {
-            0..=15 => {}
-            16..=255 => bytes.put_u8(length as u8),
-            256..=65_535 => bytes.put_u16(length as u16),
-            65_536..=4_294_967_295 => bytes.put_u32(length as u32),
-            _ => return Err(Error::ValueTooLarge(length)),
+impl Deserialize for Map {
+    fn deserialize(bytes: &mut Bytes) -> Result<Self> {
+        let marker = bytes.peek_u8("map marker")?;
+        match marker {
+            marker if (MARKER_TINY..=(MARKER_TINY | 0x0F)).contains(&marker) => {}
+            MARKER_SMALL | MARKER_MEDIUM | MARKER_LARGE => {}
+            _ => return Err(DeserializationError::InvalidMarkerByte(marker).into()),
         }
-        for value_bytes in value_bytes_vec {
-            bytes.put(value_bytes);
+        match decode::deserialize_value(bytes)? {
+            Value::Map(map) => Ok(map),
+            value => Err(ConversionError::FromValue(value).into()),
         }
-        Ok(bytes.freeze())
     }
 }
 
-impl Deserialize for Map {}
-
-impl TryFrom<Arc<Mutex<Bytes>>> for Map {
-    type Error = Error;
-
-    fn try_from(input_arc: Arc<Mutex<Bytes>>) -> Result<Self> {
-        catch_unwind(move || {
-            let marker = input_arc.lock().unwrap().get_u8();
-            let size = match marker {
-                marker if (MARKER_TINY..=(MARKER_TINY | 0x0F)).contains(&marker) => {
-                    0x0F & marker as usize
-                }
-                MARKER_SMALL => input_arc.lock().unwrap().get_u8() as usize,
-                MARKER_MEDIUM => input_arc.lock().unwrap().get_u16() as usize,
-                MARKER_LARGE => input_arc.lock().unwrap().get_u32() as usize,
-                _ => {
-                    return Err(DeserializationError::InvalidMarkerByte(marker).into());
-                }
-            };
-            let mut hash_map: HashMap<Value, Value> = HashMap::with_capacity(size);
-            for _ in 0..size {
-                let key = Value::try_from(Arc::clone(&input_arc))?;
-                let value = Value::try_from(Arc::clone(&input_arc))?;
-                hash_map.insert(key, value);
-            }
-            Ok(Map::from(hash_map))
-        })
-        .map_err(|_| DeserializationError::Panicked)?
-    }
-}
+impl_try_from_shared_bytes!(Map);
 
 impl<K, V> From<HashMap<K, V>> for Map
 where
-    K: Into<Value>,
+    K: Into<std::string::String>,
     V: Into<Value>,
 {
     fn from(value: HashMap<K, V, RandomState>) -> Self {
@@ -128,6 +138,47 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn with_capacity() {
+        let mut map = Map::with_capacity(100);
+        assert!(map.is_empty());
+        assert!(map.capacity() >= 100);
+        for i in 0..100 {
+            assert_eq!(map.insert(format!("key{}", i), i), None);
+        }
+        assert_eq!(map.len(), 100);
+
+        let bytes = map.clone().try_into_bytes().unwrap();
+        // Marker, then an 8-bit size
+        assert_eq!(&bytes[..2], &[MARKER_SMALL, 100]);
+        assert_eq!(Map::try_from(Arc::new(Mutex::new(bytes))).unwrap(), map);
+    }
+
+    #[test]
+    fn deterministic_encoding() {
+        let keys: Vec<std::string::String> = (0..20).map(|i| format!("key{:02}", i)).collect();
+        let mut forward = Map::with_capacity(20);
+        keys.iter().for_each(|key| {
+            forward.insert(key.clone(), 1);
+        });
+        let mut backward = Map::with_capacity(100);
+        keys.iter().rev().for_each(|key| {
+            backward.insert(key.clone(), 1);
+        });
+
+        let bytes = encode_deterministic(|| forward.try_into_bytes()).unwrap();
+        assert_eq!(
+            encode_deterministic(|| backward.clone().try_into_bytes()).unwrap(),
+            bytes
+        );
+        // The first key after the marker and size
+        assert_eq!(&bytes[2..8], &[0x85, b'k', b'e', b'y', b'0', b'0']);
+        assert_eq!(
+            Map::try_from(Arc::new(Mutex::new(bytes))).unwrap(),
+            backward
+        );
+    }
+
     #[test]
     fn get_marker() {
         let empty_map: Map = HashMap::<&str, i8>::new().into();
@@ -145,7 +196,7 @@ mod tests {
         let empty_map: Map = HashMap::<&str, i8>::new().into();
         assert_eq!(
             empty_map.try_into_bytes().unwrap(),
-            Bytes::from_static(&[MARKER_TINY | 0 as u8])
+            Bytes::from_static(&[MARKER_TINY])
         );
         let tiny_map: Map = HashMap::<&str, i8>::from_iter(vec![("a", 1_i8)]).into();
         assert_eq!(
@@ -221,6 +272,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn try_from_bytes_with_non_string_key() {
+        // A tiny map whose only key is the tiny int 1
+        let bytes = Bytes::from_static(&[MARKER_TINY | 1, 0x01, 0x01]);
+        assert!(matches!(
+            Map::try_from(Arc::new(Mutex::new(bytes))),
+            Err(Error::DeserializationError(
+                DeserializationError::InvalidMarkerByte(0x01)
+            ))
+        ));
+    }
+
     #[test]
     fn deep_nested_map_is_ok() {
         let bytes = Bytes::from_static(&[
@@ -1,20 +1,50 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::convert::{TryFrom, TryInto};
+use std::hash::{Hash, Hasher};
+
+use bytes::{BufMut, Bytes};
 
 use bolt_proto_derive::*;
 
+use crate::error::*;
+use crate::impl_try_from_shared_bytes;
+use crate::impl_try_into_bytes;
+use crate::serialization::*;
+use crate::value::{
+    get_opt_property, get_property, hash_map, invalid_graph_structure, validate_properties,
+};
 use crate::Value;
 
 pub(crate) const MARKER: u8 = 0xB3;
+pub(crate) const MARKER_V5: u8 = 0xB4;
 pub(crate) const SIGNATURE: u8 = 0x4E;
 
-#[derive(Debug, Clone, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Signature)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Node {
     pub(crate) node_identity: i64,
     pub(crate) labels: Vec<String>,
     pub(crate) properties: HashMap<String, Value>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub(crate) element_id: Option<String>,
 }
 
 impl Node {
+    /// Start building a node with the given identity, validating its labels and properties when it is built.
+    pub fn builder(node_identity: i64) -> NodeBuilder {
+        NodeBuilder {
+            node: Node {
+                node_identity,
+                labels: Vec::new(),
+                properties: HashMap::new(),
+                element_id: None,
+            },
+        }
+    }
+
     pub fn new(
         node_identity: i64,
         labels: Vec<String>,
@@ -24,18 +54,312 @@ impl Node {
             node_identity,
             labels,
             properties: properties.into_iter().map(|(k, v)| (k, v.into())).collect(),
+            element_id: None,
         }
     }
 
+    /// Set the element id of this node, which makes it serialize in the Bolt v5 form.
+    pub fn with_element_id(mut self, element_id: impl Into<String>) -> Self {
+        self.element_id = Some(element_id.into());
+        self
+    }
+
+    /// The legacy numeric identity of this node. Servers using Bolt v5+ may send `-1` here, prefer
+    /// [`element_id`](Node::element_id) when it is available.
     pub fn node_identity(&self) -> i64 {
         self.node_identity
     }
 
+    /// The element id of this node, which is its primary identity in Bolt v5+. This is `None` for nodes received
+    /// using earlier protocol versions.
+    pub fn element_id(&self) -> Option<&str> {
+        self.element_id.as_deref()
+    }
+
     pub fn labels(&self) -> &[String] {
         &self.labels
     }
 
+    /// The labels of this node as a set, for checking several labels at once.
+    pub fn label_set(&self) -> HashSet<&str> {
+        self.labels.iter().map(String::as_str).collect()
+    }
+
+    pub fn has_label(&self, label: &str) -> bool {
+        self.labels.iter().any(|l| l == label)
+    }
+
     pub fn properties(&self) -> &HashMap<String, Value> {
         &self.properties
     }
+
+    /// Convert the property with the given key, without cloning the other properties. Returns
+    /// [`ConversionError::MissingProperty`] if there is no such property.
+    pub fn property<T>(&self, key: &str) -> Result<T>
+    where
+        T: TryFrom<Value, Error = Error>,
+    {
+        get_property(&self.properties, key)
+    }
+
+    /// Like [`property`](Node::property), but returns `None` if the property is missing or null.
+    pub fn opt_property<T>(&self, key: &str) -> Result<Option<T>>
+    where
+        T: TryFrom<Value, Error = Error>,
+    {
+        get_opt_property(&self.properties, key)
+    }
+
+    /// Combine the identity, labels, and properties of this node into a single map, with the identity under `"_id"`
+    /// and the labels under `"_labels"`. If present, the element id is included under `"_element_id"`. These keys
+    /// take precedence over properties with the same name.
+    pub fn to_map(&self) -> HashMap<String, Value> {
+        self.to_map_with_prefix("_")
+    }
+
+    /// Like [`to_map`](Node::to_map), but with the given prefix for the identity, labels, and element id keys instead
+    /// of `"_"`.
+    pub fn to_map_with_prefix(&self, prefix: &str) -> HashMap<String, Value> {
+        let mut map = self.properties.clone();
+        map.insert(format!("{}id", prefix), Value::from(self.node_identity));
+        map.insert(
+            format!("{}labels", prefix),
+            Value::from(self.labels.clone()),
+        );
+        if let Some(element_id) = &self.element_id {
+            map.insert(
+                format!("{}element_id", prefix),
+                Value::from(element_id.as_str()),
+            );
+        }
+        map
+    }
+}
+
+/// Builds a [`Node`], see [`Node::builder`].
+#[derive(Debug, Clone)]
+pub struct NodeBuilder {
+    node: Node,
+}
+
+impl NodeBuilder {
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.node.labels.push(label.into());
+        self
+    }
+
+    pub fn property(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.node.properties.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set the element id of the node, see [`Node::with_element_id`].
+    pub fn element_id(mut self, element_id: impl Into<String>) -> Self {
+        self.node.element_id = Some(element_id.into());
+        self
+    }
+
+    /// Build the node, returning [`ConversionError::InvalidGraphStructure`] if a label is empty or repeated, or a
+    /// property key is empty.
+    pub fn build(self) -> Result<Node> {
+        let labels = &self.node.labels;
+        for (i, label) in labels.iter().enumerate() {
+            if label.is_empty() {
+                return Err(invalid_graph_structure("node label is empty"));
+            }
+            if labels[..i].contains(label) {
+                return Err(invalid_graph_structure(format!(
+                    "node label {} is repeated",
+                    label
+                )));
+            }
+        }
+        validate_properties(&self.node.properties)?;
+        Ok(self.node)
+    }
+}
+
+impl Hash for Node {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.node_identity.hash(state);
+        self.labels.hash(state);
+        hash_map(&self.properties, state);
+        self.element_id.hash(state);
+    }
+}
+
+impl Marker for Node {
+    fn get_marker(&self) -> Result<u8> {
+        match self.element_id {
+            Some(_) => Ok(MARKER_V5),
+            None => Ok(MARKER),
+        }
+    }
+
+    fn serialized_len(&self) -> Result<usize> {
+        // Marker byte, signature byte, then the fields
+        let mut len = std::mem::size_of::<u8>() * 2
+            + self.node_identity.field_len()?
+            + self.labels.field_len()?
+            + self.properties.field_len()?;
+        if let Some(element_id) = &self.element_id {
+            len += element_id.field_len()?;
+        }
+        Ok(len)
+    }
+}
+
+impl Serialize for Node {
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()> {
+        buf.put_u8(self.get_marker()?);
+        buf.put_u8(SIGNATURE);
+        self.node_identity.serialize_field(buf)?;
+        self.labels.serialize_field(buf)?;
+        self.properties.serialize_field(buf)?;
+        if let Some(element_id) = &self.element_id {
+            element_id.serialize_field(buf)?;
+        }
+        Ok(())
+    }
+}
+
+impl_try_into_bytes!(Node);
+
+// Reads the fields of the legacy form only, the marker and signature have already been consumed at this point. The
+// element id of the Bolt v5 form is only read when deserializing a Value, which knows the field count.
+impl Deserialize for Node {
+    fn deserialize(bytes: &mut Bytes) -> Result<Self> {
+        Ok(Node {
+            node_identity: Value::deserialize(bytes)?.try_into()?,
+            labels: Value::deserialize(bytes)?.try_into()?,
+            properties: Value::deserialize(bytes)?.try_into()?,
+            element_id: None,
+        })
+    }
+}
+
+impl_try_from_shared_bytes!(Node);
+
+impl FromFields for Node {
+    fn from_fields(mut fields: Vec<Value>) -> Result<Self> {
+        let element_id = match fields.len() {
+            4 => Some(fields.pop().unwrap().try_into()?),
+            _ => None,
+        };
+        let [node_identity, labels, properties] = into_field_array(fields)?;
+        Ok(Node {
+            node_identity: node_identity.try_into()?,
+            labels: labels.try_into()?,
+            properties: properties.try_into()?,
+            element_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::FromIterator;
+
+    use super::*;
+
+    fn get_node() -> Node {
+        Node::new(
+            1,
+            vec!["Person".to_string(), "Employee".to_string()],
+            HashMap::from_iter(vec![
+                ("name".to_string(), Value::from("Alice")),
+                ("age".to_string(), Value::from(30)),
+            ]),
+        )
+    }
+
+    #[test]
+    fn property() {
+        let node = get_node();
+        assert_eq!(node.property::<String>("name").unwrap(), "Alice");
+        assert_eq!(node.property::<i64>("age").unwrap(), 30);
+        assert!(matches!(
+            node.property::<i64>("height"),
+            Err(Error::ConversionError(ConversionError::MissingProperty(key))) if key == "height"
+        ));
+        assert!(matches!(
+            node.property::<i64>("name"),
+            Err(Error::ConversionError(ConversionError::FromValue(_)))
+        ));
+
+        assert_eq!(node.opt_property::<i64>("age").unwrap(), Some(30));
+        assert_eq!(node.opt_property::<i64>("height").unwrap(), None);
+        assert!(node.opt_property::<i64>("name").is_err());
+    }
+
+    #[test]
+    fn labels() {
+        let node = get_node();
+        assert_eq!(
+            node.label_set(),
+            HashSet::from_iter(vec!["Employee", "Person"])
+        );
+        assert!(node.has_label("Person"));
+        assert!(!node.has_label("Manager"));
+    }
+
+    #[test]
+    fn builder() {
+        let node = Node::builder(1)
+            .label("Person")
+            .label("Employee")
+            .property("name", "Alice")
+            .property("age", 30)
+            .build()
+            .unwrap();
+        assert_eq!(node, get_node());
+        assert_eq!(
+            Node::builder(1).element_id("4:abc:1").build().unwrap(),
+            Node::new(1, vec![], HashMap::<_, Value>::new()).with_element_id("4:abc:1")
+        );
+
+        for builder in IntoIterator::into_iter([
+            Node::builder(1).label(""),
+            Node::builder(1).label("Person").label("Person"),
+            Node::builder(1).property("", 1),
+        ]) {
+            assert!(matches!(
+                builder.build(),
+                Err(Error::ConversionError(
+                    ConversionError::InvalidGraphStructure(_)
+                ))
+            ));
+        }
+    }
+
+    #[test]
+    fn to_map() {
+        assert_eq!(
+            get_node().to_map(),
+            HashMap::from_iter(vec![
+                ("_id".to_string(), Value::from(1)),
+                (
+                    "_labels".to_string(),
+                    Value::from(vec!["Person", "Employee"])
+                ),
+                ("name".to_string(), Value::from("Alice")),
+                ("age".to_string(), Value::from(30)),
+            ])
+        );
+    }
+
+    #[test]
+    fn to_map_with_prefix() {
+        let map = get_node()
+            .with_element_id("4:abc:1")
+            .to_map_with_prefix("node.");
+        assert_eq!(map.len(), 5);
+        assert_eq!(map.get("node.id"), Some(&Value::from(1)));
+        assert_eq!(
+            map.get("node.labels"),
+            Some(&Value::from(vec!["Person", "Employee"]))
+        );
+        assert_eq!(map.get("node.element_id"), Some(&Value::from("4:abc:1")));
+        assert_eq!(map.get("name"), Some(&Value::from("Alice")));
+    }
 }
@@ -1,10 +1,8 @@
-use std::convert::{TryFrom, TryInto};
-use std::panic::catch_unwind;
-use std::sync::{Arc, Mutex};
-
-use bytes::{Buf, Bytes};
+use bytes::{BufMut, Bytes};
 
 use crate::error::*;
+use crate::impl_try_from_shared_bytes;
+use crate::impl_try_into_bytes;
 use crate::serialization::*;
 
 pub(crate) const MARKER: u8 = 0xC0;
@@ -16,38 +14,34 @@ impl Marker for Null {
     fn get_marker(&self) -> Result<u8> {
         Ok(MARKER)
     }
-}
-
-impl Serialize for Null {}
-
-impl TryInto<Bytes> for Null {
-    type Error = Error;
 
-    fn try_into(self) -> Result<Bytes> {
-        Ok(Bytes::copy_from_slice(&[self.get_marker()?]))
+    fn serialized_len(&self) -> Result<usize> {
+        Ok(std::mem::size_of::<u8>())
     }
 }
 
-impl Deserialize for Null {}
+impl Serialize for Null {
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()> {
+        buf.put_u8(MARKER);
+        Ok(())
+    }
+}
 
-impl TryFrom<Arc<Mutex<Bytes>>> for Null {
-    type Error = Error;
+impl_try_into_bytes!(Null);
 
-    fn try_from(input_arc: Arc<Mutex<Bytes>>) -> Result<Self> {
-        catch_unwind(move || {
-            let mut input_bytes = input_arc.lock().unwrap();
-            let marker = input_bytes.get_u8();
-            debug_assert!(!input_bytes.has_remaining());
-            if marker == MARKER {
-                Ok(Null)
-            } else {
-                Err(DeserializationError::InvalidMarkerByte(marker).into())
-            }
-        })
-        .map_err(|_| DeserializationError::Panicked)?
+impl Deserialize for Null {
+    fn deserialize(bytes: &mut Bytes) -> Result<Self> {
+        let marker = bytes.try_get_u8("null marker")?;
+        if marker == MARKER {
+            Ok(Null)
+        } else {
+            Err(DeserializationError::InvalidMarkerByte(marker).into())
+        }
     }
 }
 
+impl_try_from_shared_bytes!(Null);
+
 #[cfg(test)]
 mod tests {
     use std::convert::TryFrom;
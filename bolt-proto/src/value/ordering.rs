@@ -0,0 +1,391 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::value::*;
+
+impl Value {
+    /// Compare two values following Cypher's rules for `ORDER BY`, so that results can be sorted or merged on the
+    /// client as the server would. This is a total order, in ascending order:
+    ///
+    /// map, node, relationship, unbound relationship, list, path, point, date-time (with an offset or time zone), local
+    /// date-time, date, time, local time, duration, byte array, string, boolean, number, other structure, null
+    ///
+    /// Values of the same type are compared by their contents, e.g. lists element by element, nodes by identity, and
+    /// date-times by the instant they represent. Integers and floats are compared by their numeric value, with NaN
+    /// greater than every other number.
+    pub fn compare(&self, other: &Value) -> Ordering {
+        rank(self)
+            .cmp(&rank(other))
+            .then_with(|| match (self, other) {
+                (Value::Map(map), Value::Map(other_map)) => {
+                    compare_maps(&map.value, &other_map.value)
+                }
+                (Value::Node(node), Value::Node(other_node)) => compare_nodes(node, other_node),
+                (Value::Relationship(rel), Value::Relationship(other_rel)) => rel
+                    .rel_identity
+                    .cmp(&other_rel.rel_identity)
+                    .then_with(|| rel.start_node_identity.cmp(&other_rel.start_node_identity))
+                    .then_with(|| rel.end_node_identity.cmp(&other_rel.end_node_identity))
+                    .then_with(|| rel.rel_type.cmp(&other_rel.rel_type))
+                    .then_with(|| rel.element_id().cmp(&other_rel.element_id()))
+                    .then_with(|| compare_maps(&rel.properties, &other_rel.properties)),
+                (Value::UnboundRelationship(rel), Value::UnboundRelationship(other_rel)) => {
+                    compare_unbound_relationships(rel, other_rel)
+                }
+                (Value::List(list), Value::List(other_list)) => {
+                    compare_lists(&list.value, &other_list.value)
+                }
+                (Value::Path(path), Value::Path(other_path)) => {
+                    compare_slices(&path.nodes, &other_path.nodes, compare_nodes)
+                        .then_with(|| {
+                            compare_slices(
+                                &path.relationships,
+                                &other_path.relationships,
+                                compare_unbound_relationships,
+                            )
+                        })
+                        .then_with(|| path.sequence.cmp(&other_path.sequence))
+                }
+                (point, other_point) if rank(point) == POINT_RANK => {
+                    let (srid, coordinates) = point_fields(point);
+                    let (other_srid, other_coordinates) = point_fields(other_point);
+                    srid.cmp(&other_srid).then_with(|| {
+                        compare_slices(&coordinates, &other_coordinates, |a, b| {
+                            compare_floats(*a, *b)
+                        })
+                    })
+                }
+                (date_time, other_date_time) if rank(date_time) == DATE_TIME_RANK => {
+                    compare_date_times(date_time, other_date_time)
+                }
+                (Value::LocalDateTime(date_time), Value::LocalDateTime(other_date_time)) => {
+                    (date_time.epoch_seconds, date_time.nanos)
+                        .cmp(&(other_date_time.epoch_seconds, other_date_time.nanos))
+                }
+                (Value::Date(date), Value::Date(other_date)) => {
+                    date.days_since_epoch.cmp(&other_date.days_since_epoch)
+                }
+                (Value::Time(time), Value::Time(other_time)) => {
+                    // Compare the times in UTC, then by offset
+                    let utc_nanos = |time: &Time| {
+                        time.nanos_since_midnight - time.zone_offset as i64 * 1_000_000_000
+                    };
+                    utc_nanos(time)
+                        .cmp(&utc_nanos(other_time))
+                        .then_with(|| time.zone_offset.cmp(&other_time.zone_offset))
+                }
+                (Value::LocalTime(time), Value::LocalTime(other_time)) => time
+                    .nanos_since_midnight
+                    .cmp(&other_time.nanos_since_midnight),
+                (Value::Duration(duration), Value::Duration(other_duration)) => {
+                    compare_durations(duration, other_duration)
+                }
+                (Value::Bytes(bytes), Value::Bytes(other_bytes)) => {
+                    bytes.value.cmp(&other_bytes.value)
+                }
+                (Value::String(string), Value::String(other_string)) => {
+                    string.as_bytes().cmp(other_string.as_bytes())
+                }
+                (Value::Boolean(boolean), Value::Boolean(other_boolean)) => {
+                    boolean.value.cmp(&other_boolean.value)
+                }
+                (Value::Integer(integer), Value::Integer(other_integer)) => {
+                    integer.value.cmp(&other_integer.value)
+                }
+                (Value::Float(float), Value::Float(other_float)) => {
+                    compare_floats(float.value, other_float.value)
+                }
+                // Integers come before floats of the same value, so that only equal values compare as equal
+                (Value::Integer(integer), Value::Float(float)) => {
+                    compare_integer_to_float(integer.value, float.value).then(Ordering::Less)
+                }
+                (Value::Float(float), Value::Integer(integer)) => {
+                    compare_integer_to_float(integer.value, float.value)
+                        .reverse()
+                        .then(Ordering::Greater)
+                }
+                (Value::Structure(structure), Value::Structure(other_structure)) => structure
+                    .signature
+                    .cmp(&other_structure.signature)
+                    .then_with(|| compare_lists(&structure.fields, &other_structure.fields)),
+                _ => Ordering::Equal,
+            })
+    }
+}
+
+// Values only compare as equal when they are equal, so with NaN, which isn't equal to itself, they are not ordered
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.compare(other) {
+            Ordering::Equal if self != other => None,
+            ordering => Some(ordering),
+        }
+    }
+}
+
+const POINT_RANK: u8 = 6;
+const DATE_TIME_RANK: u8 = 7;
+
+fn rank(value: &Value) -> u8 {
+    match value {
+        Value::Map(_) => 0,
+        Value::Node(_) => 1,
+        Value::Relationship(_) => 2,
+        Value::UnboundRelationship(_) => 3,
+        Value::List(_) => 4,
+        Value::Path(_) => 5,
+        Value::Point2D(_) | Value::Point3D(_) => POINT_RANK,
+        Value::DateTimeOffset(_) | Value::DateTimeZoned(_) => DATE_TIME_RANK,
+        Value::LocalDateTime(_) => 8,
+        Value::Date(_) => 9,
+        Value::Time(_) => 10,
+        Value::LocalTime(_) => 11,
+        Value::Duration(_) => 12,
+        Value::Bytes(_) => 13,
+        Value::String(_) => 14,
+        Value::Boolean(_) => 15,
+        Value::Integer(_) | Value::Float(_) => 16,
+        Value::Structure(_) => 17,
+        Value::Null => 18,
+    }
+}
+
+fn compare_slices<T>(a: &[T], b: &[T], compare: impl Fn(&T, &T) -> Ordering) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| compare(a, b))
+        .find(|ordering| *ordering != Ordering::Equal)
+        .unwrap_or_else(|| a.len().cmp(&b.len()))
+}
+
+fn compare_lists(a: &[Value], b: &[Value]) -> Ordering {
+    compare_slices(a, b, Value::compare)
+}
+
+// Maps are compared by their sorted keys, then by the values of those keys
+fn compare_maps(
+    a: &HashMap<std::string::String, Value>,
+    b: &HashMap<std::string::String, Value>,
+) -> Ordering {
+    fn sorted_entries(
+        map: &HashMap<std::string::String, Value>,
+    ) -> Vec<(&std::string::String, &Value)> {
+        let mut entries: Vec<(&std::string::String, &Value)> = map.iter().collect();
+        entries.sort_by_key(|(key, _)| *key);
+        entries
+    }
+
+    let entries = sorted_entries(a);
+    let other_entries = sorted_entries(b);
+    compare_slices(&entries, &other_entries, |(a, _), (b, _)| a.cmp(b))
+        .then_with(|| compare_slices(&entries, &other_entries, |(_, a), (_, b)| a.compare(b)))
+}
+
+fn compare_nodes(a: &Node, b: &Node) -> Ordering {
+    a.node_identity
+        .cmp(&b.node_identity)
+        .then_with(|| a.element_id.cmp(&b.element_id))
+        .then_with(|| a.labels.cmp(&b.labels))
+        .then_with(|| compare_maps(&a.properties, &b.properties))
+}
+
+fn compare_unbound_relationships(a: &UnboundRelationship, b: &UnboundRelationship) -> Ordering {
+    a.rel_identity
+        .cmp(&b.rel_identity)
+        .then_with(|| a.rel_type.cmp(&b.rel_type))
+        .then_with(|| a.element_id.cmp(&b.element_id))
+        .then_with(|| compare_maps(&a.properties, &b.properties))
+}
+
+fn point_fields(point: &Value) -> (i32, Vec<f64>) {
+    match point {
+        Value::Point2D(point) => (point.srid, vec![point.x, point.y]),
+        Value::Point3D(point) => (point.srid, vec![point.x, point.y, point.z]),
+        _ => unreachable!(),
+    }
+}
+
+// Date-times are compared by the instant they represent, then by their offset or time zone. Without time zone data,
+// date-times with a time zone are treated as UTC.
+fn compare_date_times(a: &Value, b: &Value) -> Ordering {
+    fn utc_instant(date_time: &Value) -> (i64, i64) {
+        match date_time {
+            Value::DateTimeOffset(date_time) => (date_time.epoch_seconds, date_time.nanos),
+            Value::DateTimeZoned(date_time) => (
+                date_time.epoch_seconds - date_time.offset_seconds_at_local().unwrap_or(0),
+                date_time.nanos,
+            ),
+            _ => unreachable!(),
+        }
+    }
+
+    utc_instant(a)
+        .cmp(&utc_instant(b))
+        .then_with(|| match (a, b) {
+            (Value::DateTimeOffset(a), Value::DateTimeOffset(b)) => {
+                a.offset_seconds.cmp(&b.offset_seconds)
+            }
+            (Value::DateTimeZoned(a), Value::DateTimeZoned(b)) => a.zone_id.cmp(&b.zone_id),
+            (Value::DateTimeOffset(_), _) => Ordering::Less,
+            _ => Ordering::Greater,
+        })
+}
+
+// Durations are compared by their approximate length, using the average length of a month, then by their fields
+fn compare_durations(a: &Duration, b: &Duration) -> Ordering {
+    const SECONDS_PER_MONTH: i128 = 2_629_746;
+    const SECONDS_PER_DAY: i128 = 86_400;
+
+    let approximate_nanos = |duration: &Duration| {
+        (duration.months as i128 * SECONDS_PER_MONTH
+            + duration.days as i128 * SECONDS_PER_DAY
+            + duration.seconds as i128)
+            * 1_000_000_000
+            + duration.nanos as i128
+    };
+    approximate_nanos(a)
+        .cmp(&approximate_nanos(b))
+        .then_with(|| {
+            (a.months, a.days, a.seconds, a.nanos).cmp(&(b.months, b.days, b.seconds, b.nanos))
+        })
+}
+
+fn compare_floats(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        // Does not panic, since neither is NaN
+        (false, false) => a.partial_cmp(&b).unwrap(),
+    }
+}
+
+// Compares exactly, since converting large integers to floats loses precision
+fn compare_integer_to_float(integer: i64, float: f64) -> Ordering {
+    const TWO_POW_63: f64 = 9_223_372_036_854_775_808.0;
+
+    if float.is_nan() || float >= TWO_POW_63 {
+        Ordering::Less
+    } else if float < -TWO_POW_63 {
+        Ordering::Greater
+    } else {
+        let truncated = float.trunc();
+        integer
+            .cmp(&(truncated as i64))
+            .then_with(|| compare_floats(truncated, float))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::FromIterator;
+
+    use super::*;
+
+    #[test]
+    fn compare_types() {
+        let node = Node::new(1, vec![], HashMap::<_, Value>::new());
+        let mut values = vec![
+            Value::Null,
+            Value::from(1),
+            Value::from(true),
+            Value::from("a"),
+            Value::from(Duration::new(0, 1, 0, 0)),
+            Value::from(Point2D::new(7203, 1.0, 2.0)),
+            Value::from(vec![1]),
+            Value::from(node.clone()),
+            Value::from([("a", 1)]),
+        ];
+        let sorted = values.iter().cloned().rev().collect::<Vec<_>>();
+        values.sort_by(Value::compare);
+        assert_eq!(values, sorted);
+    }
+
+    #[test]
+    fn compare_numbers() {
+        assert_eq!(Value::from(1).compare(&Value::from(1.5)), Ordering::Less);
+        assert_eq!(Value::from(2).compare(&Value::from(1.5)), Ordering::Greater);
+        assert_eq!(Value::from(-2).compare(&Value::from(-1.5)), Ordering::Less);
+        assert_eq!(Value::from(1).compare(&Value::from(1.0)), Ordering::Less);
+        assert_eq!(
+            Value::from(i64::MAX).compare(&Value::from(i64::MAX as f64)),
+            Ordering::Less
+        );
+        assert_eq!(
+            Value::from(f64::NAN).compare(&Value::from(f64::INFINITY)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            Value::from(f64::NAN).compare(&Value::from(i64::MAX)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            Value::from(0.0).compare(&Value::from(-0.0)),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn compare_collections() {
+        assert_eq!(
+            Value::from(vec![1, 2]).compare(&Value::from(vec![1, 2, 0])),
+            Ordering::Less
+        );
+        assert_eq!(
+            Value::from(vec![1, 3]).compare(&Value::from(vec![1, 2, 0])),
+            Ordering::Greater
+        );
+        assert_eq!(
+            Value::from([("a", 2)]).compare(&Value::from([("b", 1)])),
+            Ordering::Less
+        );
+        assert_eq!(
+            Value::from(HashMap::from_iter(vec![("a", 1), ("b", 2)]))
+                .compare(&Value::from(HashMap::from_iter(vec![("b", 2), ("a", 1)]))),
+            Ordering::Equal
+        );
+        assert_eq!(
+            Value::from(Duration::new(1, 0, 0, 0))
+                .compare(&Value::from(Duration::new(0, 40, 0, 0))),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn partial_cmp() {
+        assert!(Value::from("a") < Value::from("b"));
+        assert!(Value::from(1) < Value::Null);
+        assert_eq!(
+            Value::from(f64::NAN).partial_cmp(&Value::from(f64::NAN)),
+            None
+        );
+        assert_eq!(
+            Value::from(vec![1]).partial_cmp(&Value::from(vec![1])),
+            Some(Ordering::Equal)
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn compare_date_times() {
+        use chrono::{FixedOffset, NaiveDate, TimeZone};
+
+        let local = NaiveDate::from_ymd_opt(2020, 12, 25)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap();
+        let paris = Value::from((local, chrono_tz::Europe::Paris));
+        let utc = Value::from(
+            FixedOffset::east_opt(0)
+                .unwrap()
+                .from_local_datetime(&local)
+                .unwrap(),
+        );
+        // 10:00 in Paris is 09:00 UTC
+        assert_eq!(paris.compare(&utc), Ordering::Less);
+        assert_eq!(
+            Value::from(local.date()).compare(&Value::from(local)),
+            Ordering::Greater
+        );
+    }
+}
@@ -1,19 +1,27 @@
 use bolt_proto_derive::*;
 
+use crate::error::*;
 use crate::value::*;
 
 pub(crate) const MARKER: u8 = 0xB3;
 pub(crate) const SIGNATURE: u8 = 0x50;
 
-#[derive(Debug, Clone, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Path {
     pub(crate) nodes: Vec<Node>,
     pub(crate) relationships: Vec<UnboundRelationship>,
-    pub(crate) sequence: i64,
+    pub(crate) sequence: Vec<i64>,
 }
 
 impl Path {
-    pub fn new(nodes: Vec<Node>, relationships: Vec<UnboundRelationship>, sequence: i64) -> Self {
+    /// Create a path from its distinct nodes and relationships, and the sequence of indices describing how it
+    /// traverses them, see [`sequence`](Path::sequence).
+    pub fn new(
+        nodes: Vec<Node>,
+        relationships: Vec<UnboundRelationship>,
+        sequence: Vec<i64>,
+    ) -> Self {
         Self {
             nodes,
             relationships,
@@ -21,15 +29,358 @@ impl Path {
         }
     }
 
+    /// Like [`new`](Path::new), but returns [`ConversionError::InvalidPathSequence`] if an index in the sequence is
+    /// out of range, or [`ConversionError::InvalidGraphStructure`] if the path has no nodes.
+    pub fn try_new(
+        nodes: Vec<Node>,
+        relationships: Vec<UnboundRelationship>,
+        sequence: Vec<i64>,
+    ) -> Result<Self> {
+        if nodes.is_empty() {
+            return Err(invalid_graph_structure("path has no nodes"));
+        }
+        let path = Self::new(nodes, relationships, sequence);
+        for segment in path.segments() {
+            segment?;
+        }
+        Ok(path)
+    }
+
+    /// Start building a path at the given node, traversing relationships one at a time, so that the sequence of
+    /// indices is always valid.
+    pub fn builder(start_node: Node) -> PathBuilder {
+        PathBuilder {
+            path: Self::new(vec![start_node], Vec::new(), Vec::new()),
+        }
+    }
+
+    /// The distinct nodes of this path. The first node is where the path starts.
     pub fn nodes(&self) -> &[Node] {
         &self.nodes
     }
 
+    /// The distinct relationships of this path, in no particular order.
     pub fn relationships(&self) -> &[UnboundRelationship] {
         &self.relationships
     }
 
-    pub fn sequence(&self) -> i64 {
-        self.sequence
+    /// Pairs of indices describing each step of the path: a 1-based relationship index, which is negative if the
+    /// relationship is traversed against its direction, followed by a 0-based node index. Use
+    /// [`segments`](Path::segments) to resolve them.
+    pub fn sequence(&self) -> &[i64] {
+        &self.sequence
+    }
+
+    /// The number of segments (relationships traversed) in this path.
+    pub fn len(&self) -> usize {
+        self.sequence.len() / 2
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sequence.is_empty()
+    }
+
+    /// The node where this path starts, or `None` if it has no nodes.
+    pub fn start_node(&self) -> Option<&Node> {
+        self.nodes.first()
+    }
+
+    /// Iterate over the segments of this path in order, resolving the indices in its sequence. Yields an error for a
+    /// step whose indices are out of range, after which the iterator ends.
+    pub fn segments(&self) -> PathSegments<'_> {
+        PathSegments {
+            path: self,
+            current: self.nodes.first(),
+            position: 0,
+        }
+    }
+}
+
+/// Builds a [`Path`], see [`Path::builder`]. Nodes and relationships that are traversed more than once are only stored
+/// once.
+#[derive(Debug, Clone)]
+pub struct PathBuilder {
+    path: Path,
+}
+
+impl PathBuilder {
+    /// Traverse a relationship in its direction, from its start node to the given end node.
+    pub fn forward(self, relationship: UnboundRelationship, end_node: Node) -> Self {
+        self.traverse(relationship, end_node, false)
+    }
+
+    /// Traverse a relationship against its direction, from its end node to the given start node.
+    pub fn backward(self, relationship: UnboundRelationship, start_node: Node) -> Self {
+        self.traverse(relationship, start_node, true)
+    }
+
+    fn traverse(mut self, relationship: UnboundRelationship, node: Node, reversed: bool) -> Self {
+        let rel_index = index_of(&mut self.path.relationships, relationship) as i64 + 1;
+        let node_index = index_of(&mut self.path.nodes, node) as i64;
+        self.path
+            .sequence
+            .extend_from_slice(&[if reversed { -rel_index } else { rel_index }, node_index]);
+        self
+    }
+
+    /// Build the path, returning [`ConversionError::InvalidGraphStructure`] if the same relationship or node identity
+    /// is used for different values.
+    pub fn build(self) -> Result<Path> {
+        let path = self.path;
+        if has_conflicting_identity(&path.nodes, Node::node_identity)
+            || has_conflicting_identity(&path.relationships, UnboundRelationship::rel_identity)
+        {
+            return Err(invalid_graph_structure("path has conflicting identities"));
+        }
+        Ok(path)
+    }
+}
+
+// The index of a value in a list of distinct values, adding it if it is new
+fn index_of<T: PartialEq>(values: &mut Vec<T>, value: T) -> usize {
+    match values.iter().position(|v| *v == value) {
+        Some(index) => index,
+        None => {
+            values.push(value);
+            values.len() - 1
+        }
+    }
+}
+
+fn has_conflicting_identity<T>(values: &[T], identity: impl Fn(&T) -> i64) -> bool {
+    values
+        .iter()
+        .enumerate()
+        .any(|(i, value)| values[..i].iter().any(|v| identity(v) == identity(value)))
+}
+
+/// A relationship traversed by a [`Path`], along with the nodes it connects.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathSegment<'a> {
+    pub(crate) start_node: &'a Node,
+    pub(crate) relationship: &'a UnboundRelationship,
+    pub(crate) end_node: &'a Node,
+    pub(crate) reversed: bool,
+}
+
+impl<'a> PathSegment<'a> {
+    /// The start node of the relationship, which is where this segment ends if it is [reversed](Self::is_reversed).
+    pub fn start_node(&self) -> &'a Node {
+        self.start_node
+    }
+
+    pub fn relationship(&self) -> &'a UnboundRelationship {
+        self.relationship
+    }
+
+    /// The end node of the relationship, which is where this segment starts if it is [reversed](Self::is_reversed).
+    pub fn end_node(&self) -> &'a Node {
+        self.end_node
+    }
+
+    /// Whether the path traverses the relationship against its direction, from its end node to its start node.
+    pub fn is_reversed(&self) -> bool {
+        self.reversed
+    }
+}
+
+/// Iterator over the segments of a [`Path`], see [`Path::segments`].
+#[derive(Debug, Clone)]
+pub struct PathSegments<'a> {
+    path: &'a Path,
+    current: Option<&'a Node>,
+    position: usize,
+}
+
+impl<'a> Iterator for PathSegments<'a> {
+    type Item = Result<PathSegment<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sequence = &self.path.sequence;
+        if self.position >= sequence.len() {
+            return None;
+        }
+        let position = self.position;
+        // Stop after this step, whether or not it is valid
+        self.position = sequence.len();
+
+        let current = self.current?;
+        let rel_index = sequence[position];
+        let relationship = (rel_index.unsigned_abs() as usize)
+            .checked_sub(1)
+            .and_then(|i| self.path.relationships.get(i));
+        let next = sequence
+            .get(position + 1)
+            .and_then(|&i| usize::try_from(i).ok())
+            .and_then(|i| self.path.nodes.get(i));
+        let (relationship, next) = match (relationship, next) {
+            (Some(relationship), Some(next)) => (relationship, next),
+            _ => return Some(Err(ConversionError::InvalidPathSequence(position).into())),
+        };
+
+        self.position = position + 2;
+        self.current = Some(next);
+        let reversed = rel_index < 0;
+        let (start_node, end_node) = if reversed {
+            (next, current)
+        } else {
+            (current, next)
+        };
+        Some(Ok(PathSegment {
+            start_node,
+            relationship,
+            end_node,
+            reversed,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn get_node(identity: i64) -> Node {
+        Node::new(identity, vec![], HashMap::<_, Value>::new())
+    }
+
+    fn get_rel(identity: i64) -> UnboundRelationship {
+        UnboundRelationship::new(identity, "KNOWS".to_string(), HashMap::<_, Value>::new())
+    }
+
+    #[test]
+    fn segments() {
+        // (1)-[10]->(2)<-[11]-(3)
+        let path = Path::new(
+            vec![get_node(1), get_node(2), get_node(3)],
+            vec![get_rel(10), get_rel(11)],
+            vec![1, 1, -2, 2],
+        );
+        assert_eq!(path.len(), 2);
+        assert_eq!(path.start_node(), Some(&get_node(1)));
+
+        let segments: Vec<PathSegment> = path.segments().collect::<Result<_>>().unwrap();
+        let identities: Vec<(i64, i64, i64, bool)> = segments
+            .iter()
+            .map(|segment| {
+                (
+                    segment.start_node().node_identity(),
+                    segment.relationship().rel_identity(),
+                    segment.end_node().node_identity(),
+                    segment.is_reversed(),
+                )
+            })
+            .collect();
+        assert_eq!(identities, vec![(1, 10, 2, false), (3, 11, 2, true)]);
+
+        let empty_path = Path::new(vec![get_node(1)], vec![], vec![]);
+        assert!(empty_path.is_empty());
+        assert_eq!(empty_path.segments().count(), 0);
+    }
+
+    #[test]
+    fn builder() {
+        let rel = UnboundRelationship::builder(10, "KNOWS")
+            .property("since", 2020)
+            .build()
+            .unwrap();
+        assert!(UnboundRelationship::builder(11, "").build().is_err());
+
+        // (1)-[10]->(2)<-[11]-(3)
+        let path = Path::builder(get_node(1))
+            .forward(rel.clone(), get_node(2))
+            .backward(get_rel(11), get_node(3))
+            .build()
+            .unwrap();
+        assert_eq!(
+            path,
+            Path::new(
+                vec![get_node(1), get_node(2), get_node(3)],
+                vec![rel.clone(), get_rel(11)],
+                vec![1, 1, -2, 2],
+            )
+        );
+        // Nodes visited again are not added again
+        let path = Path::builder(get_node(1))
+            .forward(rel.clone(), get_node(2))
+            .backward(rel, get_node(1))
+            .build()
+            .unwrap();
+        assert_eq!(path.nodes().len(), 2);
+        assert_eq!(path.sequence(), &[1, 1, -1, 0]);
+
+        let conflicting_rel = Path::builder(get_node(1))
+            .forward(get_rel(10), get_node(2))
+            .forward(
+                UnboundRelationship::builder(10, "LIKES").build().unwrap(),
+                get_node(3),
+            );
+        assert!(matches!(
+            conflicting_rel.build(),
+            Err(Error::ConversionError(
+                ConversionError::InvalidGraphStructure(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn try_new() {
+        assert!(Path::try_new(
+            vec![get_node(1), get_node(2)],
+            vec![get_rel(10)],
+            vec![1, 1]
+        )
+        .is_ok());
+        assert!(matches!(
+            Path::try_new(
+                vec![get_node(1), get_node(2)],
+                vec![get_rel(10)],
+                vec![2, 1]
+            ),
+            Err(Error::ConversionError(
+                ConversionError::InvalidPathSequence(0)
+            ))
+        ));
+        assert!(matches!(
+            Path::try_new(vec![], vec![], vec![]),
+            Err(Error::ConversionError(
+                ConversionError::InvalidGraphStructure(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn invalid_segments() {
+        for &sequence in &[&[3, 1][..], &[0, 1], &[1, 5], &[1, -1], &[1]] {
+            let path = Path::new(
+                vec![get_node(1), get_node(2)],
+                vec![get_rel(10), get_rel(11)],
+                sequence.to_vec(),
+            );
+            let mut segments = path.segments();
+            assert!(matches!(
+                segments.next(),
+                Some(Err(Error::ConversionError(
+                    ConversionError::InvalidPathSequence(0)
+                )))
+            ));
+            assert!(segments.next().is_none());
+        }
+
+        let path = Path::new(
+            vec![get_node(1), get_node(2)],
+            vec![get_rel(10)],
+            vec![1, 1, 2],
+        );
+        let mut segments = path.segments();
+        assert!(matches!(segments.next(), Some(Ok(_))));
+        assert!(matches!(
+            segments.next(),
+            Some(Err(Error::ConversionError(
+                ConversionError::InvalidPathSequence(2)
+            )))
+        ));
+        assert!(segments.next().is_none());
     }
 }
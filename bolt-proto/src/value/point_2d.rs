@@ -1,9 +1,18 @@
+use std::hash::{Hash, Hasher};
+
 use bolt_proto_derive::*;
 
+#[cfg(feature = "json")]
+use crate::error::*;
+#[cfg(feature = "json")]
+use crate::value::geojson;
+use crate::value::hash_f64;
+
 pub(crate) const MARKER: u8 = 0xB3;
 pub(crate) const SIGNATURE: u8 = 0x58;
 
 #[derive(Debug, Clone, PartialEq, Signature, Marker, Serialize, Deserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Point2D {
     pub(crate) srid: i32,
     pub(crate) x: f64,
@@ -11,10 +20,25 @@ pub struct Point2D {
 }
 
 impl Point2D {
+    /// The SRID of geographic points on the WGS-84 ellipsoid, with longitude as x and latitude as y.
+    pub const WGS_84_SRID: i32 = 4326;
+    /// The SRID of points in a Cartesian plane.
+    pub const CARTESIAN_SRID: i32 = 7203;
+
     pub fn new(srid: i32, x: f64, y: f64) -> Self {
         Self { srid, x, y }
     }
 
+    /// Create a geographic point from its longitude and latitude in degrees.
+    pub fn wgs_84(longitude: f64, latitude: f64) -> Self {
+        Self::new(Self::WGS_84_SRID, longitude, latitude)
+    }
+
+    /// Create a point in a Cartesian plane.
+    pub fn cartesian(x: f64, y: f64) -> Self {
+        Self::new(Self::CARTESIAN_SRID, x, y)
+    }
+
     pub fn srid(&self) -> i32 {
         self.srid
     }
@@ -28,6 +52,30 @@ impl Point2D {
     }
 }
 
+#[cfg(feature = "json")]
+impl Point2D {
+    /// Convert this point into a GeoJSON `Point` geometry, with its longitude and latitude as coordinates. Returns
+    /// [`ConversionError::FromValue`] if the point is not on WGS-84.
+    pub fn to_geojson(&self) -> Result<serde_json::Value> {
+        geojson::to_geojson(self, self.srid == Self::WGS_84_SRID, &[self.x, self.y])
+    }
+
+    /// Create a point on WGS-84 from a GeoJSON `Point` geometry with 2 coordinates. Returns
+    /// [`ConversionError::FromJson`] for other geometries.
+    pub fn from_geojson(geometry: &serde_json::Value) -> Result<Self> {
+        let [x, y] = geojson::from_geojson(geometry)?;
+        Ok(Self::wgs_84(x, y))
+    }
+}
+
+impl Hash for Point2D {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.srid.hash(state);
+        hash_f64(self.x, state);
+        hash_f64(self.y, state);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::convert::TryFrom;
@@ -44,6 +92,16 @@ mod tests {
         Point2D::new(120, 5_421_394.569_325_1, 1.9287)
     }
 
+    #[test]
+    fn constructors() {
+        let point = Point2D::wgs_84(12.49, 41.89);
+        assert_eq!(point.srid(), 4326);
+        assert_eq!((point.x(), point.y()), (12.49, 41.89));
+        let point = Point2D::cartesian(1.0, -2.0);
+        assert_eq!(point.srid(), 7203);
+        assert_eq!((point.x(), point.y()), (1.0, -2.0));
+    }
+
     #[test]
     fn get_marker() {
         let point = get_point();
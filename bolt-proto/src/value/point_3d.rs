@@ -1,9 +1,18 @@
+use std::hash::{Hash, Hasher};
+
 use bolt_proto_derive::*;
 
+#[cfg(feature = "json")]
+use crate::error::*;
+#[cfg(feature = "json")]
+use crate::value::geojson;
+use crate::value::hash_f64;
+
 pub(crate) const MARKER: u8 = 0xB4;
 pub(crate) const SIGNATURE: u8 = 0x59;
 
 #[derive(Debug, Clone, PartialEq, Signature, Marker, Serialize, Deserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Point3D {
     pub(crate) srid: i32,
     pub(crate) x: f64,
@@ -12,10 +21,25 @@ pub struct Point3D {
 }
 
 impl Point3D {
+    /// The SRID of geographic points on the WGS-84 ellipsoid, with longitude as x, latitude as y, and height as z.
+    pub const WGS_84_SRID: i32 = 4979;
+    /// The SRID of points in a 3D Cartesian space.
+    pub const CARTESIAN_SRID: i32 = 9157;
+
     pub fn new(srid: i32, x: f64, y: f64, z: f64) -> Self {
         Self { srid, x, y, z }
     }
 
+    /// Create a geographic point from its longitude and latitude in degrees, and its height in meters.
+    pub fn wgs_84(longitude: f64, latitude: f64, height: f64) -> Self {
+        Self::new(Self::WGS_84_SRID, longitude, latitude, height)
+    }
+
+    /// Create a point in a 3D Cartesian space.
+    pub fn cartesian(x: f64, y: f64, z: f64) -> Self {
+        Self::new(Self::CARTESIAN_SRID, x, y, z)
+    }
+
     pub fn srid(&self) -> i32 {
         self.srid
     }
@@ -33,6 +57,35 @@ impl Point3D {
     }
 }
 
+#[cfg(feature = "json")]
+impl Point3D {
+    /// Convert this point into a GeoJSON `Point` geometry, with its longitude, latitude, and height as coordinates. Returns
+    /// [`ConversionError::FromValue`] if the point is not on WGS-84.
+    pub fn to_geojson(&self) -> Result<serde_json::Value> {
+        geojson::to_geojson(
+            self,
+            self.srid == Self::WGS_84_SRID,
+            &[self.x, self.y, self.z],
+        )
+    }
+
+    /// Create a point on WGS-84 from a GeoJSON `Point` geometry with 3 coordinates. Returns
+    /// [`ConversionError::FromJson`] for other geometries.
+    pub fn from_geojson(geometry: &serde_json::Value) -> Result<Self> {
+        let [x, y, z] = geojson::from_geojson(geometry)?;
+        Ok(Self::wgs_84(x, y, z))
+    }
+}
+
+impl Hash for Point3D {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.srid.hash(state);
+        hash_f64(self.x, state);
+        hash_f64(self.y, state);
+        hash_f64(self.z, state);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::convert::TryFrom;
@@ -49,6 +102,16 @@ mod tests {
         Point3D::new(7, 1234.56543, 5_421_394.569_325_1, 1.9287)
     }
 
+    #[test]
+    fn constructors() {
+        let point = Point3D::wgs_84(12.49, 41.89, 21.0);
+        assert_eq!(point.srid(), 4979);
+        assert_eq!((point.x(), point.y(), point.z()), (12.49, 41.89, 21.0));
+        let point = Point3D::cartesian(1.0, -2.0, 3.0);
+        assert_eq!(point.srid(), 9157);
+        assert_eq!((point.x(), point.y(), point.z()), (1.0, -2.0, 3.0));
+    }
+
     #[test]
     fn get_marker() {
         let point = get_point();
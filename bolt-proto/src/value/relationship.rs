@@ -1,22 +1,64 @@
 use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+use std::hash::{Hash, Hasher};
+
+use bytes::{BufMut, Bytes};
 
 use bolt_proto_derive::*;
 
+use crate::error::*;
+use crate::impl_try_from_shared_bytes;
+use crate::impl_try_into_bytes;
+use crate::serialization::*;
+use crate::value::{
+    get_opt_property, get_property, hash_map, invalid_graph_structure, validate_properties,
+};
 use crate::Value;
 
 pub(crate) const MARKER: u8 = 0xB5;
+pub(crate) const MARKER_V5: u8 = 0xB8;
 pub(crate) const SIGNATURE: u8 = 0x52;
 
-#[derive(Debug, Clone, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Signature)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Relationship {
     pub(crate) rel_identity: i64,
     pub(crate) start_node_identity: i64,
     pub(crate) end_node_identity: i64,
     pub(crate) rel_type: String,
     pub(crate) properties: HashMap<String, Value>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub(crate) element_ids: Option<Box<ElementIds>>,
+}
+
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct ElementIds {
+    pub(crate) element_id: String,
+    pub(crate) start_node_element_id: String,
+    pub(crate) end_node_element_id: String,
 }
 
 impl Relationship {
+    /// Start building a relationship between two nodes, validating its type and properties when it is built.
+    pub fn builder(
+        rel_identity: i64,
+        start_node_identity: i64,
+        end_node_identity: i64,
+        rel_type: impl Into<String>,
+    ) -> RelationshipBuilder {
+        RelationshipBuilder {
+            rel: Relationship {
+                rel_identity,
+                start_node_identity,
+                end_node_identity,
+                rel_type: rel_type.into(),
+                properties: HashMap::new(),
+                element_ids: None,
+            },
+        }
+    }
+
     pub fn new(
         rel_identity: i64,
         start_node_identity: i64,
@@ -30,21 +72,62 @@ impl Relationship {
             end_node_identity,
             rel_type,
             properties: properties.into_iter().map(|(k, v)| (k, v.into())).collect(),
+            element_ids: None,
         }
     }
 
+    /// Set the element ids of this relationship and its start and end nodes, which makes it serialize in the Bolt v5
+    /// form.
+    pub fn with_element_ids(
+        mut self,
+        element_id: impl Into<String>,
+        start_node_element_id: impl Into<String>,
+        end_node_element_id: impl Into<String>,
+    ) -> Self {
+        self.element_ids = Some(Box::new(ElementIds {
+            element_id: element_id.into(),
+            start_node_element_id: start_node_element_id.into(),
+            end_node_element_id: end_node_element_id.into(),
+        }));
+        self
+    }
+
+    /// The legacy numeric identity of this relationship. Servers using Bolt v5+ may send `-1` here, prefer
+    /// [`element_id`](Relationship::element_id) when it is available.
     pub fn rel_identity(&self) -> i64 {
         self.rel_identity
     }
 
+    /// The legacy numeric identity of this relationship's start node, which may be `-1` in Bolt v5+.
     pub fn start_node_identity(&self) -> i64 {
         self.start_node_identity
     }
 
+    /// The legacy numeric identity of this relationship's end node, which may be `-1` in Bolt v5+.
     pub fn end_node_identity(&self) -> i64 {
         self.end_node_identity
     }
 
+    /// The element id of this relationship, which is its primary identity in Bolt v5+. This is `None` for
+    /// relationships received using earlier protocol versions.
+    pub fn element_id(&self) -> Option<&str> {
+        self.element_ids.as_ref().map(|ids| ids.element_id.as_str())
+    }
+
+    /// The element id of this relationship's start node, see [`element_id`](Relationship::element_id).
+    pub fn start_node_element_id(&self) -> Option<&str> {
+        self.element_ids
+            .as_ref()
+            .map(|ids| ids.start_node_element_id.as_str())
+    }
+
+    /// The element id of this relationship's end node, see [`element_id`](Relationship::element_id).
+    pub fn end_node_element_id(&self) -> Option<&str> {
+        self.element_ids
+            .as_ref()
+            .map(|ids| ids.end_node_element_id.as_str())
+    }
+
     pub fn rel_type(&self) -> &str {
         &self.rel_type
     }
@@ -52,4 +135,158 @@ impl Relationship {
     pub fn properties(&self) -> &HashMap<String, Value> {
         &self.properties
     }
+
+    /// Convert the property with the given key, without cloning the other properties. Returns
+    /// [`ConversionError::MissingProperty`] if there is no such property.
+    pub fn property<T>(&self, key: &str) -> Result<T>
+    where
+        T: TryFrom<Value, Error = Error>,
+    {
+        get_property(&self.properties, key)
+    }
+
+    /// Like [`property`](Relationship::property), but returns `None` if the property is missing or null.
+    pub fn opt_property<T>(&self, key: &str) -> Result<Option<T>>
+    where
+        T: TryFrom<Value, Error = Error>,
+    {
+        get_opt_property(&self.properties, key)
+    }
+}
+
+/// Builds a [`Relationship`], see [`Relationship::builder`].
+#[derive(Debug, Clone)]
+pub struct RelationshipBuilder {
+    rel: Relationship,
+}
+
+impl RelationshipBuilder {
+    pub fn property(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.rel.properties.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set the element ids of the relationship and its nodes, see [`Relationship::with_element_ids`].
+    pub fn element_ids(
+        self,
+        element_id: impl Into<String>,
+        start_node_element_id: impl Into<String>,
+        end_node_element_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            rel: self
+                .rel
+                .with_element_ids(element_id, start_node_element_id, end_node_element_id),
+        }
+    }
+
+    /// Build the relationship, returning [`ConversionError::InvalidGraphStructure`] if its type or a property key is
+    /// empty.
+    pub fn build(self) -> Result<Relationship> {
+        if self.rel.rel_type.is_empty() {
+            return Err(invalid_graph_structure("relationship type is empty"));
+        }
+        validate_properties(&self.rel.properties)?;
+        Ok(self.rel)
+    }
+}
+
+impl Hash for Relationship {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rel_identity.hash(state);
+        self.start_node_identity.hash(state);
+        self.end_node_identity.hash(state);
+        self.rel_type.hash(state);
+        hash_map(&self.properties, state);
+        self.element_ids.hash(state);
+    }
+}
+
+impl Marker for Relationship {
+    fn get_marker(&self) -> Result<u8> {
+        match self.element_ids {
+            Some(_) => Ok(MARKER_V5),
+            None => Ok(MARKER),
+        }
+    }
+
+    fn serialized_len(&self) -> Result<usize> {
+        // Marker byte, signature byte, then the fields
+        let mut len = std::mem::size_of::<u8>() * 2
+            + self.rel_identity.field_len()?
+            + self.start_node_identity.field_len()?
+            + self.end_node_identity.field_len()?
+            + self.rel_type.field_len()?
+            + self.properties.field_len()?;
+        if let Some(ids) = &self.element_ids {
+            len += ids.element_id.field_len()?
+                + ids.start_node_element_id.field_len()?
+                + ids.end_node_element_id.field_len()?;
+        }
+        Ok(len)
+    }
+}
+
+impl Serialize for Relationship {
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()> {
+        buf.put_u8(self.get_marker()?);
+        buf.put_u8(SIGNATURE);
+        self.rel_identity.serialize_field(buf)?;
+        self.start_node_identity.serialize_field(buf)?;
+        self.end_node_identity.serialize_field(buf)?;
+        self.rel_type.serialize_field(buf)?;
+        self.properties.serialize_field(buf)?;
+        if let Some(ids) = &self.element_ids {
+            ids.element_id.serialize_field(buf)?;
+            ids.start_node_element_id.serialize_field(buf)?;
+            ids.end_node_element_id.serialize_field(buf)?;
+        }
+        Ok(())
+    }
+}
+
+impl_try_into_bytes!(Relationship);
+
+// Reads the fields of the legacy form only, the marker and signature have already been consumed at this point. The
+// element ids of the Bolt v5 form are only read when deserializing a Value, which knows the field count.
+impl Deserialize for Relationship {
+    fn deserialize(bytes: &mut Bytes) -> Result<Self> {
+        Ok(Relationship {
+            rel_identity: Value::deserialize(bytes)?.try_into()?,
+            start_node_identity: Value::deserialize(bytes)?.try_into()?,
+            end_node_identity: Value::deserialize(bytes)?.try_into()?,
+            rel_type: Value::deserialize(bytes)?.try_into()?,
+            properties: Value::deserialize(bytes)?.try_into()?,
+            element_ids: None,
+        })
+    }
+}
+
+impl_try_from_shared_bytes!(Relationship);
+
+impl FromFields for Relationship {
+    fn from_fields(mut fields: Vec<Value>) -> Result<Self> {
+        let element_ids = match fields.len() {
+            8 => {
+                let [element_id, start_node_element_id, end_node_element_id] =
+                    into_field_array(fields.split_off(5))?;
+                Some(Box::new(ElementIds {
+                    element_id: element_id.try_into()?,
+                    start_node_element_id: start_node_element_id.try_into()?,
+                    end_node_element_id: end_node_element_id.try_into()?,
+                }))
+            }
+            _ => None,
+        };
+        let [rel_identity, start_node_identity, end_node_identity, rel_type, properties] =
+            into_field_array(fields)?;
+        Ok(Relationship {
+            rel_identity: rel_identity.try_into()?,
+            start_node_identity: start_node_identity.try_into()?,
+            end_node_identity: end_node_identity.try_into()?,
+            rel_type: rel_type.try_into()?,
+            properties: properties.try_into()?,
+            element_ids,
+        })
+    }
 }
@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+use serde::de::{self, MapAccess, SeqAccess, Unexpected, Visitor};
+use serde::ser::{self, SerializeMap, SerializeSeq};
+use serde::{Deserializer, Serializer};
+
+#[cfg(feature = "json")]
+use crate::error::*;
+use crate::value::*;
+
+// Primitives, lists, and maps are serialized as their serde counterparts, while graph structures and other structures
+// are serialized as structs of their fields.
+impl serde::Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            Value::Boolean(boolean) => serializer.serialize_bool(boolean.value),
+            Value::Integer(integer) => serializer.serialize_i64(integer.value),
+            Value::Float(float) => serializer.serialize_f64(float.value),
+            Value::Bytes(byte_array) => serializer.serialize_bytes(&byte_array.value),
+            Value::List(list) => {
+                let mut seq = serializer.serialize_seq(Some(list.value.len()))?;
+                for value in &list.value {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+            Value::Map(map) => {
+                let mut serialize_map = serializer.serialize_map(Some(map.value.len()))?;
+                for (key, value) in &map.value {
+                    serialize_map.serialize_entry(key, value)?;
+                }
+                serialize_map.end()
+            }
+            Value::Null => serializer.serialize_unit(),
+            Value::String(string) => {
+                serializer.serialize_str(string.as_str().map_err(ser::Error::custom)?)
+            }
+            Value::Node(node) => node.serialize(serializer),
+            Value::Relationship(rel) => rel.serialize(serializer),
+            Value::Path(path) => path.serialize(serializer),
+            Value::UnboundRelationship(unbound_rel) => unbound_rel.serialize(serializer),
+            Value::Date(date) => date.serialize(serializer),
+            Value::Time(time) => time.serialize(serializer),
+            Value::DateTimeOffset(date_time_offset) => date_time_offset.serialize(serializer),
+            Value::DateTimeZoned(date_time_zoned) => date_time_zoned.serialize(serializer),
+            Value::LocalTime(local_time) => local_time.serialize(serializer),
+            Value::LocalDateTime(local_date_time) => local_date_time.serialize(serializer),
+            Value::Duration(duration) => duration.serialize(serializer),
+            Value::Point2D(point_2d) => point_2d.serialize(serializer),
+            Value::Point3D(point_3d) => point_3d.serialize(serializer),
+            Value::Structure(structure) => structure.serialize(serializer),
+        }
+    }
+}
+
+// Only primitives, lists, and maps can be deserialized, since a map of fields is indistinguishable from a graph
+// structure. Deserialize Node, Relationship, or Path directly where those are expected.
+impl<'de> serde::Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a Bolt-compatible value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> std::result::Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> std::result::Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> std::result::Result<Value, E> {
+        // Bolt integers are signed 64-bit, so larger values can't be represented
+        i64::try_from(v)
+            .map(Value::from)
+            .map_err(|_| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> std::result::Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_string<E: de::Error>(self, v: std::string::String) -> std::result::Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> std::result::Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> std::result::Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_none<E: de::Error>(self) -> std::result::Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Value, D::Error> {
+        serde::Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> std::result::Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<Value, A::Error> {
+        let mut list = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(value) = seq.next_element::<Value>()? {
+            list.push(value);
+        }
+        Ok(Value::List(List { value: list }))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Value, A::Error> {
+        let mut entries = HashMap::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some((key, value)) = map.next_entry::<std::string::String, Value>()? {
+            entries.insert(key, value);
+        }
+        Ok(Value::Map(Map { value: entries }))
+    }
+}
+
+#[cfg(feature = "json")]
+impl TryFrom<Value> for serde_json::Value {
+    type Error = Error;
+
+    /// Convert a value into JSON, the same way it is serialized with serde. Values without a JSON counterpart are
+    /// converted lossily:
+    /// - graph structures, temporal values, and points become objects of their fields (see
+    ///   [`Point2D::to_geojson`] and [`Point3D::to_geojson`] for GeoJSON instead),
+    /// - byte arrays become arrays of numbers,
+    /// - non-finite floats become null.
+    ///
+    /// Returns [`ConversionError::FromValue`] if the value can't be serialized, e.g. if it holds a string that isn't
+    /// valid UTF-8.
+    fn try_from(value: Value) -> Result<Self> {
+        serde_json::to_value(&value).map_err(|_| ConversionError::FromValue(value).into())
+    }
+}
+
+#[cfg(feature = "json")]
+impl TryFrom<serde_json::Value> for Value {
+    type Error = Error;
+
+    /// Convert JSON into a value, e.g. to use it as a query parameter. Objects always become maps, even if they have
+    /// the fields of a graph structure, temporal value, or point, so those aren't restored from the JSON they convert
+    /// into.
+    ///
+    /// Returns [`ConversionError::FromJson`] for integers that don't fit in an i64, which is the range of Bolt
+    /// integers.
+    fn try_from(json: serde_json::Value) -> Result<Self> {
+        serde::Deserialize::deserialize(&json).map_err(|_| ConversionError::FromJson(json).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::FromIterator;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn get_node() -> Node {
+        Node::new(
+            1,
+            vec!["Person".to_string()],
+            HashMap::from_iter(vec![("name".to_string(), Value::from("Alice"))]),
+        )
+    }
+
+    #[test]
+    fn serialize() {
+        let value = Value::from(vec![
+            Value::from(1),
+            Value::from(2.5),
+            Value::from(true),
+            Value::Null,
+            Value::from(HashMap::from_iter(vec![("key", "value")])),
+        ]);
+        assert_eq!(
+            serde_json::to_value(&value).unwrap(),
+            json!([1, 2.5, true, null, {"key": "value"}])
+        );
+    }
+
+    #[test]
+    fn serialize_graph_structures() {
+        let node = get_node();
+        assert_eq!(
+            serde_json::to_value(Value::from(node.clone())).unwrap(),
+            json!({"node_identity": 1, "labels": ["Person"], "properties": {"name": "Alice"}})
+        );
+        assert_eq!(
+            serde_json::to_value(node.clone().with_element_id("4:abc:1")).unwrap()["element_id"],
+            json!("4:abc:1")
+        );
+
+        let rel = Relationship::new(2, 1, 3, "KNOWS".to_string(), HashMap::<_, Value>::new())
+            .with_element_ids("5:abc:2", "4:abc:1", "4:abc:3");
+        assert_eq!(
+            serde_json::to_value(&rel).unwrap(),
+            json!({
+                "rel_identity": 2,
+                "start_node_identity": 1,
+                "end_node_identity": 3,
+                "rel_type": "KNOWS",
+                "properties": {},
+                "element_id": "5:abc:2",
+                "start_node_element_id": "4:abc:1",
+                "end_node_element_id": "4:abc:3",
+            })
+        );
+
+        let path = Path::new(vec![node], vec![], vec![]);
+        let json = serde_json::to_string(&path).unwrap();
+        assert_eq!(serde_json::from_str::<Path>(&json).unwrap(), path);
+        let json = serde_json::to_string(&rel).unwrap();
+        assert_eq!(serde_json::from_str::<Relationship>(&json).unwrap(), rel);
+        let rel = Relationship::new(2, 1, 3, "KNOWS".to_string(), HashMap::<_, Value>::new());
+        let json = serde_json::to_string(&rel).unwrap();
+        assert_eq!(serde_json::from_str::<Relationship>(&json).unwrap(), rel);
+    }
+
+    #[test]
+    fn deserialize() {
+        let value: Value =
+            serde_json::from_value(json!({"list": [1, -2, 3.5, "four", null, false]})).unwrap();
+        assert_eq!(
+            value,
+            Value::from(HashMap::from_iter(vec![(
+                "list",
+                Value::from(vec![
+                    Value::from(1),
+                    Value::from(-2),
+                    Value::from(3.5),
+                    Value::from("four"),
+                    Value::Null,
+                    Value::from(false),
+                ])
+            )]))
+        );
+        assert!(serde_json::from_value::<Value>(json!(u64::MAX)).is_err());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_conversions() {
+        let json =
+            json!({"name": "Alice", "tags": ["a", "b"], "age": 30, "score": 0.5, "manager": null});
+        let value = Value::try_from(json.clone()).unwrap();
+        assert_eq!(
+            value,
+            Value::from(HashMap::from_iter(vec![
+                ("name", Value::from("Alice")),
+                ("tags", Value::from(vec!["a", "b"])),
+                ("age", Value::from(30)),
+                ("score", Value::from(0.5)),
+                ("manager", Value::Null),
+            ]))
+        );
+        assert_eq!(serde_json::Value::try_from(value).unwrap(), json);
+
+        assert!(matches!(
+            Value::try_from(json!(u64::MAX)),
+            Err(Error::ConversionError(ConversionError::FromJson(_)))
+        ));
+        assert_eq!(
+            serde_json::Value::try_from(Value::from(vec![1u8, 2])).unwrap(),
+            json!([1, 2])
+        );
+        assert_eq!(
+            serde_json::Value::try_from(Value::from(f64::NAN)).unwrap(),
+            json!(null)
+        );
+    }
+}
@@ -1,102 +1,113 @@
-use std::convert::{TryFrom, TryInto};
-use std::mem;
-use std::panic::catch_unwind;
+use std::borrow::Cow;
+use std::fmt;
 use std::str;
-use std::sync::{Arc, Mutex};
 
-use bytes::{Buf, BufMut, Bytes, BytesMut};
+use bytes::{BufMut, Bytes};
 
 use crate::error::*;
+use crate::impl_try_from_shared_bytes;
+use crate::impl_try_into_bytes;
 use crate::serialization::*;
 
 pub(crate) const MARKER_TINY: u8 = 0x80;
 pub(crate) const MARKER_SMALL: u8 = 0xD0;
 pub(crate) const MARKER_MEDIUM: u8 = 0xD1;
 pub(crate) const MARKER_LARGE: u8 = 0xD2;
+pub(crate) const MARKERS: [u8; 4] = [MARKER_TINY, MARKER_SMALL, MARKER_MEDIUM, MARKER_LARGE];
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+// Backed by a slice of the buffer it was deserialized from, so large strings aren't copied. Deserialized bytes are only
+// validated as UTF-8 when they are read as a str, so strings that are never read (or are only compared, hashed, and
+// serialized, which work on the bytes) are never validated. Validity isn't cached, since interior mutability would make
+// values unsuitable as the keys of sets and maps.
+#[derive(Clone, Eq, PartialEq, Hash)]
 pub struct String {
-    pub(crate) value: std::string::String,
+    value: Bytes,
+}
+
+impl String {
+    /// Get the string as a str, returning [`DeserializationError::InvalidUTF8`] if it was deserialized from bytes that
+    /// aren't valid UTF-8.
+    pub(crate) fn as_str(&self) -> Result<&str> {
+        Ok(str::from_utf8(&self.value).map_err(DeserializationError::InvalidUTF8)?)
+    }
+
+    pub(crate) fn into_string(self) -> Result<std::string::String> {
+        Ok(self.as_str()?.to_string())
+    }
+
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.value
+    }
+
+    // For formatting, which can't fail, with invalid UTF-8 replaced
+    pub(crate) fn to_str_lossy(&self) -> Cow<'_, str> {
+        std::string::String::from_utf8_lossy(&self.value)
+    }
+}
+
+impl fmt::Debug for String {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("String")
+            .field("value", &self.to_str_lossy())
+            .finish()
+    }
 }
 
 impl Marker for String {
     fn get_marker(&self) -> Result<u8> {
-        match self.value.len() {
-            0..=15 => Ok(MARKER_TINY | self.value.len() as u8),
-            16..=255 => Ok(MARKER_SMALL),
-            256..=65_535 => Ok(MARKER_MEDIUM),
-            65_536..=4_294_967_295 => Ok(MARKER_LARGE),
-            _ => Err(Error::ValueTooLarge(self.value.len())),
-        }
+        sized_marker(self.value.len(), MARKERS)
+    }
+
+    fn serialized_len(&self) -> Result<usize> {
+        Ok(sized_header_len(self.value.len())? + self.value.len())
     }
 }
 
-impl Serialize for String {}
-
-impl TryInto<Bytes> for String {
-    type Error = Error;
-
-    fn try_into(self) -> Result<Bytes> {
-        let marker = self.get_marker()?;
-        let length = self.value.len();
-        // Worst case is a large string, with marker byte, 32-bit size value, and length
-        let mut bytes =
-            BytesMut::with_capacity(mem::size_of::<u8>() + mem::size_of::<u32>() + length);
-        bytes.put_u8(marker);
-        match length {
-            0..=15 => {}
-            16..=255 => bytes.put_u8(length as u8),
-            256..=65_535 => bytes.put_u16(length as u16),
-            65_536..=4_294_967_295 => bytes.put_u32(length as u32),
-            _ => return Err(Error::ValueTooLarge(length)),
-        }
-        bytes.put_slice(self.value.as_bytes());
-        Ok(bytes.freeze())
+impl Serialize for String {
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()> {
+        put_sized_header(buf, self.value.len(), MARKERS)?;
+        buf.put_slice(&self.value);
+        Ok(())
     }
 }
 
-impl Deserialize for String {}
-
-impl TryFrom<Arc<Mutex<Bytes>>> for String {
-    type Error = Error;
-
-    fn try_from(input_arc: Arc<Mutex<Bytes>>) -> Result<Self> {
-        catch_unwind(move || {
-            let mut input_bytes = input_arc.lock().unwrap();
-            let marker = input_bytes.get_u8();
-            let size = match marker {
-                // Lower-order nibble of tiny string marker
-                0x80..=0x8F => 0x0F & marker as usize,
-                MARKER_SMALL => input_bytes.get_u8() as usize,
-                MARKER_MEDIUM => input_bytes.get_u16() as usize,
-                MARKER_LARGE => input_bytes.get_u32() as usize,
-                _ => {
-                    return Err(DeserializationError::InvalidMarkerByte(marker).into());
-                }
-            };
-            let mut string_bytes = BytesMut::with_capacity(size);
-            // We resize here so that the length of string_bytes is nonzero, which allows us to use copy_to_slice
-            string_bytes.resize(size, 0);
-            input_bytes.copy_to_slice(&mut string_bytes);
-            Ok(String::from(
-                str::from_utf8(&string_bytes).map_err(DeserializationError::InvalidUTF8)?,
-            ))
+impl_try_into_bytes!(String);
+
+impl Deserialize for String {
+    fn deserialize(bytes: &mut Bytes) -> Result<Self> {
+        let marker = bytes.try_get_u8("string marker")?;
+        let size = match marker {
+            // Lower-order nibble of tiny string marker
+            0x80..=0x8F => 0x0F & marker as usize,
+            MARKER_SMALL => bytes.try_get_u8("string size")? as usize,
+            MARKER_MEDIUM => bytes.try_get_u16("string size")? as usize,
+            MARKER_LARGE => bytes.try_get_u32("string size")? as usize,
+            _ => {
+                return Err(DeserializationError::InvalidMarkerByte(marker).into());
+            }
+        };
+        bytes.ensure_remaining(size, "string")?;
+        Ok(String {
+            value: bytes.split_to(size),
         })
-        .map_err(|_| DeserializationError::Panicked)?
     }
 }
 
+impl_try_from_shared_bytes!(String);
+
 impl From<&str> for String {
     fn from(value: &str) -> Self {
         Self {
-            value: value.to_string(),
+            value: Bytes::copy_from_slice(value.as_bytes()),
         }
     }
 }
 
 impl From<std::string::String> for String {
     fn from(value: std::string::String) -> Self {
-        Self { value }
+        Self {
+            value: Bytes::from(value),
+        }
     }
 }
 
@@ -111,7 +122,7 @@ mod tests {
 
     #[test]
     fn get_marker() {
-        let tiny = String::from("string".repeat(1));
+        let tiny = String::from("string".to_string());
         assert_eq!(
             tiny.get_marker().unwrap(),
             MARKER_TINY | tiny.value.len() as u8
@@ -150,9 +161,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn serialize_str_field() {
+        for value in &["", "a", &"string".repeat(1000)] {
+            let mut field = Vec::new();
+            value.serialize_field(&mut field).unwrap();
+            assert_eq!(field.len(), value.field_len().unwrap());
+            assert_eq!(field, &String::from(*value).try_into_bytes().unwrap()[..]);
+        }
+    }
+
     #[test]
     fn try_from_bytes() {
-        let tiny = String::from("string".repeat(1));
+        let tiny = String::from("string".to_string());
         assert_eq!(
             String::try_from(Arc::new(Mutex::new(tiny.clone().try_into_bytes().unwrap()))).unwrap(),
             tiny
@@ -190,4 +211,32 @@ mod tests {
             special
         );
     }
+
+    #[test]
+    fn deserialize_without_copying() {
+        let input = String::from("string".repeat(100)).try_into_bytes().unwrap();
+        let string = String::deserialize(&mut input.clone()).unwrap();
+        assert_eq!(string.as_str().unwrap(), "string".repeat(100));
+        assert_eq!(string.as_str().unwrap().as_ptr(), input[3..].as_ptr());
+    }
+
+    #[test]
+    fn lazy_validation() {
+        // Invalid UTF-8 is only reported when the string is read
+        let input = Bytes::from_static(&[MARKER_TINY | 2, 0xC3, 0x28]);
+        let invalid = String::deserialize(&mut input.clone()).unwrap();
+        assert!(matches!(
+            invalid.as_str(),
+            Err(Error::DeserializationError(
+                DeserializationError::InvalidUTF8(_)
+            ))
+        ));
+        assert!(invalid.clone().into_string().is_err());
+        assert_eq!(invalid.to_str_lossy(), "\u{FFFD}(");
+        // The bytes can still be serialized as they were received
+        assert_eq!(invalid.clone().try_into_bytes().unwrap(), input);
+        let value = crate::Value::String(invalid);
+        assert_eq!(value.as_str(), None);
+        assert!(std::string::String::try_from(value).is_err());
+    }
 }
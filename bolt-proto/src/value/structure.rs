@@ -0,0 +1,173 @@
+use std::mem;
+
+use bytes::{BufMut, Bytes};
+
+use crate::error::*;
+use crate::impl_try_from_shared_bytes;
+use crate::impl_try_into_bytes;
+use crate::serialization::*;
+use crate::value::StructureExtension;
+use crate::Value;
+
+/// A structure with a signature this crate doesn't recognize, e.g. one added by a newer protocol version. Its fields
+/// are kept as they were received, so it can be serialized again without losing anything.
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Structure {
+    pub(crate) signature: u8,
+    pub(crate) fields: Vec<Value>,
+}
+
+impl Structure {
+    pub fn new(signature: u8, fields: Vec<impl Into<Value>>) -> Self {
+        Self {
+            signature,
+            fields: fields.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn signature(&self) -> u8 {
+        self.signature
+    }
+
+    pub fn fields(&self) -> &[Value] {
+        &self.fields
+    }
+
+    pub fn into_fields(self) -> Vec<Value> {
+        self.fields
+    }
+
+    pub fn from_extension<T: StructureExtension>(value: T) -> Self {
+        Self {
+            signature: T::SIGNATURE,
+            fields: value.into_fields(),
+        }
+    }
+
+    /// Convert this structure to a [`StructureExtension`], returning [`ConversionError::FromValue`] if its signature
+    /// doesn't match, or the error from [`StructureExtension::from_fields`] if its fields are invalid.
+    pub fn try_into_extension<T: StructureExtension>(self) -> Result<T> {
+        if self.signature != T::SIGNATURE {
+            return Err(ConversionError::FromValue(Value::Structure(self)).into());
+        }
+        T::from_fields(self.fields)
+    }
+
+    // Reads the given number of fields, the marker and signature have already been consumed at this point
+    pub(crate) fn read_fields(
+        signature: u8,
+        field_count: usize,
+        bytes: &mut Bytes,
+    ) -> Result<Self> {
+        let mut fields = Vec::with_capacity(field_count);
+        for _ in 0..field_count {
+            fields.push(Value::deserialize(bytes)?);
+        }
+        Ok(Self { signature, fields })
+    }
+}
+
+impl Marker for Structure {
+    fn get_marker(&self) -> Result<u8> {
+        match self.fields.len() {
+            0..=15 => Ok(STRUCT_MARKER_TINY | self.fields.len() as u8),
+            16..=255 => Ok(STRUCT_MARKER_SMALL),
+            256..=65_535 => Ok(STRUCT_MARKER_MEDIUM),
+            _ => Err(Error::ValueTooLarge(self.fields.len())),
+        }
+    }
+
+    fn serialized_len(&self) -> Result<usize> {
+        // Marker byte, then an 8 or 16-bit size if it isn't tiny, then the signature byte and the fields
+        let size_len = match self.get_marker()? {
+            STRUCT_MARKER_SMALL => mem::size_of::<u8>(),
+            STRUCT_MARKER_MEDIUM => mem::size_of::<u16>(),
+            _ => 0,
+        };
+        self.fields
+            .iter()
+            .try_fold(mem::size_of::<u8>() * 2 + size_len, |len, field| {
+                Ok(len + field.serialized_len()?)
+            })
+    }
+}
+
+impl Serialize for Structure {
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()> {
+        buf.put_u8(self.get_marker()?);
+        match self.fields.len() {
+            0..=15 => {}
+            16..=255 => buf.put_u8(self.fields.len() as u8),
+            _ => buf.put_u16(self.fields.len() as u16),
+        }
+        buf.put_u8(self.signature);
+        for field in &self.fields {
+            field.serialize_into(buf)?;
+        }
+        Ok(())
+    }
+}
+
+impl_try_into_bytes!(Structure);
+
+// Unlike the known structures, this reads the whole structure including its header, since the field count is needed
+impl Deserialize for Structure {
+    fn deserialize(bytes: &mut Bytes) -> Result<Self> {
+        let (_marker, field_count, signature) = get_info_from_bytes(bytes)?;
+        Structure::read_fields(signature, field_count, bytes)
+    }
+}
+
+impl_try_from_shared_bytes!(Structure);
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[test]
+    fn get_marker() {
+        assert_eq!(
+            Structure::new(0x01, vec![1, 2]).get_marker().unwrap(),
+            STRUCT_MARKER_TINY | 2
+        );
+        assert_eq!(
+            Structure::new(0x01, vec![0; 16]).get_marker().unwrap(),
+            STRUCT_MARKER_SMALL
+        );
+        assert_eq!(
+            Structure::new(0x01, vec![0; 256]).get_marker().unwrap(),
+            STRUCT_MARKER_MEDIUM
+        );
+        assert!(matches!(
+            Structure::new(0x01, vec![0; 65_536]).get_marker(),
+            Err(Error::ValueTooLarge(65_536))
+        ));
+    }
+
+    #[test]
+    fn try_into_bytes() {
+        assert_eq!(
+            Structure::new(0x01, vec![Value::from(1), Value::from("a")])
+                .try_into_bytes()
+                .unwrap(),
+            Bytes::from_static(&[STRUCT_MARKER_TINY | 2, 0x01, 0x01, 0x81, b'a'])
+        );
+        let bytes = Structure::new(0x01, vec![0; 16]).try_into_bytes().unwrap();
+        assert_eq!(&bytes[..3], &[STRUCT_MARKER_SMALL, 16, 0x01]);
+        assert_eq!(bytes.len(), 19);
+    }
+
+    #[test]
+    fn try_from_bytes() {
+        let structure = Structure::new(0x01, vec![Value::from(1), Value::from("a")]);
+        let bytes = Bytes::from_static(&[STRUCT_MARKER_TINY | 2, 0x01, 0x01, 0x81, b'a']);
+        assert_eq!(
+            Structure::try_from(Arc::new(Mutex::new(bytes))).unwrap(),
+            structure
+        );
+    }
+}
@@ -1,30 +1,49 @@
+#[cfg(feature = "chrono")]
 use chrono::{FixedOffset, NaiveTime, Offset, Timelike};
 
 use bolt_proto_derive::*;
 
+#[cfg(feature = "chrono")]
+use crate::error::*;
+#[cfg(feature = "chrono")]
+use crate::value::local_time::naive_time_from_nanos;
+
 pub(crate) const MARKER: u8 = 0xB2;
 pub(crate) const SIGNATURE: u8 = 0x54;
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Time {
     pub(crate) nanos_since_midnight: i64,
     pub(crate) zone_offset: i32,
 }
 
 impl Time {
-    pub fn naive_time(&self) -> NaiveTime {
-        let seconds = (self.nanos_since_midnight / 1_000_000_000) as u32;
-        let nanos = (self.nanos_since_midnight % 1_000_000_000) as u32;
-        // Does not panic since seconds and nanos came from a NaiveTime already
-        NaiveTime::from_num_seconds_from_midnight(seconds, nanos)
+    pub fn nanos_since_midnight(&self) -> i64 {
+        self.nanos_since_midnight
+    }
+
+    /// The offset from UTC in seconds, which is positive east of UTC.
+    pub fn offset_seconds(&self) -> i32 {
+        self.zone_offset
+    }
+
+    /// Convert the time of day to a chrono time. Fails if it isn't within a day, which deserialized values can have.
+    #[cfg(feature = "chrono")]
+    pub fn naive_time(&self) -> Result<NaiveTime> {
+        naive_time_from_nanos(self.nanos_since_midnight)
     }
 
-    pub fn offset(&self) -> FixedOffset {
-        FixedOffset::east(self.zone_offset)
+    /// Convert the offset to a chrono offset. Fails if it is a day or more, which deserialized values can have.
+    #[cfg(feature = "chrono")]
+    pub fn offset(&self) -> Result<FixedOffset> {
+        FixedOffset::east_opt(self.zone_offset)
+            .ok_or_else(|| ConversionError::InvalidOffset(self.zone_offset).into())
     }
 }
 
 // No timezone-aware time in chrono, so provide separate conversion instead
+#[cfg(feature = "chrono")]
 impl<O: Offset> From<(NaiveTime, O)> for Time {
     fn from(pair: (NaiveTime, O)) -> Self {
         Self {
@@ -36,7 +55,7 @@ impl<O: Offset> From<(NaiveTime, O)> for Time {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "chrono"))]
 mod tests {
     use std::convert::TryFrom;
     use std::sync::{Arc, Mutex};
@@ -45,13 +64,14 @@ mod tests {
 
     use crate::serialization::*;
     use crate::value::integer::{MARKER_INT_16, MARKER_INT_64};
+    use crate::Value;
 
     use super::*;
 
     fn get_time() -> Time {
         Time::from((
-            NaiveTime::from_hms_nano(1, 16, 40, 123),
-            FixedOffset::east(3600),
+            NaiveTime::from_hms_nano_opt(1, 16, 40, 123).unwrap(),
+            FixedOffset::east_opt(3600).unwrap(),
         ))
     }
 
@@ -110,7 +130,31 @@ mod tests {
     #[test]
     fn accessors() {
         let time = get_time();
-        assert_eq!(time.naive_time(), NaiveTime::from_hms_nano(1, 16, 40, 123));
-        assert_eq!(time.offset(), FixedOffset::east(3600));
+        assert_eq!(
+            time.naive_time().unwrap(),
+            NaiveTime::from_hms_nano_opt(1, 16, 40, 123).unwrap()
+        );
+        assert_eq!(time.offset().unwrap(), FixedOffset::east_opt(3600).unwrap());
+    }
+
+    #[test]
+    fn out_of_range() {
+        let time = Time {
+            nanos_since_midnight: -1,
+            zone_offset: 200_000,
+        };
+        assert!(matches!(
+            time.naive_time(),
+            Err(Error::ConversionError(ConversionError::InvalidTime(-1)))
+        ));
+        assert!(matches!(
+            time.offset(),
+            Err(Error::ConversionError(ConversionError::InvalidOffset(
+                200_000
+            )))
+        ));
+        let value = Value::Time(time);
+        assert_eq!(value.to_iso_8601(), None);
+        assert!(value.to_string().contains("200000"));
     }
 }
@@ -1,20 +1,50 @@
 use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+use std::hash::{Hash, Hasher};
+
+use bytes::{BufMut, Bytes};
 
 use bolt_proto_derive::*;
 
+use crate::error::*;
+use crate::impl_try_from_shared_bytes;
+use crate::impl_try_into_bytes;
+use crate::serialization::*;
+use crate::value::{
+    get_opt_property, get_property, hash_map, invalid_graph_structure, validate_properties,
+};
 use crate::Value;
 
 pub(crate) const MARKER: u8 = 0xB3;
+pub(crate) const MARKER_V5: u8 = 0xB4;
 pub(crate) const SIGNATURE: u8 = 0x72;
 
-#[derive(Debug, Clone, Eq, PartialEq, Signature, Marker, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Signature)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnboundRelationship {
     pub(crate) rel_identity: i64,
     pub(crate) rel_type: String,
     pub(crate) properties: HashMap<String, Value>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub(crate) element_id: Option<String>,
 }
 
 impl UnboundRelationship {
+    /// Start building a relationship, validating its type and properties when it is built.
+    pub fn builder(rel_identity: i64, rel_type: impl Into<String>) -> UnboundRelationshipBuilder {
+        UnboundRelationshipBuilder {
+            rel: UnboundRelationship {
+                rel_identity,
+                rel_type: rel_type.into(),
+                properties: HashMap::new(),
+                element_id: None,
+            },
+        }
+    }
+
     pub fn new(
         rel_identity: i64,
         rel_type: String,
@@ -24,13 +54,28 @@ impl UnboundRelationship {
             rel_identity,
             rel_type,
             properties: properties.into_iter().map(|(k, v)| (k, v.into())).collect(),
+            element_id: None,
         }
     }
 
+    /// Set the element id of this relationship, which makes it serialize in the Bolt v5 form.
+    pub fn with_element_id(mut self, element_id: impl Into<String>) -> Self {
+        self.element_id = Some(element_id.into());
+        self
+    }
+
+    /// The legacy numeric identity of this relationship. Servers using Bolt v5+ may send `-1` here, prefer
+    /// [`element_id`](UnboundRelationship::element_id) when it is available.
     pub fn rel_identity(&self) -> i64 {
         self.rel_identity
     }
 
+    /// The element id of this relationship, which is its primary identity in Bolt v5+. This is `None` for
+    /// relationships received using earlier protocol versions.
+    pub fn element_id(&self) -> Option<&str> {
+        self.element_id.as_deref()
+    }
+
     pub fn rel_type(&self) -> &str {
         &self.rel_type
     }
@@ -38,4 +83,127 @@ impl UnboundRelationship {
     pub fn properties(&self) -> &HashMap<String, Value> {
         &self.properties
     }
+
+    /// Convert the property with the given key, without cloning the other properties. Returns
+    /// [`ConversionError::MissingProperty`] if there is no such property.
+    pub fn property<T>(&self, key: &str) -> Result<T>
+    where
+        T: TryFrom<Value, Error = Error>,
+    {
+        get_property(&self.properties, key)
+    }
+
+    /// Like [`property`](UnboundRelationship::property), but returns `None` if the property is missing or null.
+    pub fn opt_property<T>(&self, key: &str) -> Result<Option<T>>
+    where
+        T: TryFrom<Value, Error = Error>,
+    {
+        get_opt_property(&self.properties, key)
+    }
+}
+
+/// Builds an [`UnboundRelationship`], see [`UnboundRelationship::builder`].
+#[derive(Debug, Clone)]
+pub struct UnboundRelationshipBuilder {
+    rel: UnboundRelationship,
+}
+
+impl UnboundRelationshipBuilder {
+    pub fn property(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.rel.properties.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set the element id of the relationship, see [`UnboundRelationship::with_element_id`].
+    pub fn element_id(mut self, element_id: impl Into<String>) -> Self {
+        self.rel.element_id = Some(element_id.into());
+        self
+    }
+
+    /// Build the relationship, returning [`ConversionError::InvalidGraphStructure`] if its type or a property key is
+    /// empty.
+    pub fn build(self) -> Result<UnboundRelationship> {
+        if self.rel.rel_type.is_empty() {
+            return Err(invalid_graph_structure("relationship type is empty"));
+        }
+        validate_properties(&self.rel.properties)?;
+        Ok(self.rel)
+    }
+}
+
+impl Hash for UnboundRelationship {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rel_identity.hash(state);
+        self.rel_type.hash(state);
+        hash_map(&self.properties, state);
+        self.element_id.hash(state);
+    }
+}
+
+impl Marker for UnboundRelationship {
+    fn get_marker(&self) -> Result<u8> {
+        match self.element_id {
+            Some(_) => Ok(MARKER_V5),
+            None => Ok(MARKER),
+        }
+    }
+
+    fn serialized_len(&self) -> Result<usize> {
+        // Marker byte, signature byte, then the fields
+        let mut len = std::mem::size_of::<u8>() * 2
+            + self.rel_identity.field_len()?
+            + self.rel_type.field_len()?
+            + self.properties.field_len()?;
+        if let Some(element_id) = &self.element_id {
+            len += element_id.field_len()?;
+        }
+        Ok(len)
+    }
+}
+
+impl Serialize for UnboundRelationship {
+    fn serialize_into(&self, buf: &mut impl BufMut) -> Result<()> {
+        buf.put_u8(self.get_marker()?);
+        buf.put_u8(SIGNATURE);
+        self.rel_identity.serialize_field(buf)?;
+        self.rel_type.serialize_field(buf)?;
+        self.properties.serialize_field(buf)?;
+        if let Some(element_id) = &self.element_id {
+            element_id.serialize_field(buf)?;
+        }
+        Ok(())
+    }
+}
+
+impl_try_into_bytes!(UnboundRelationship);
+
+// Reads the fields of the legacy form only, the marker and signature have already been consumed at this point. The
+// element id of the Bolt v5 form is only read when deserializing a Value, which knows the field count.
+impl Deserialize for UnboundRelationship {
+    fn deserialize(bytes: &mut Bytes) -> Result<Self> {
+        Ok(UnboundRelationship {
+            rel_identity: Value::deserialize(bytes)?.try_into()?,
+            rel_type: Value::deserialize(bytes)?.try_into()?,
+            properties: Value::deserialize(bytes)?.try_into()?,
+            element_id: None,
+        })
+    }
+}
+
+impl_try_from_shared_bytes!(UnboundRelationship);
+
+impl FromFields for UnboundRelationship {
+    fn from_fields(mut fields: Vec<Value>) -> Result<Self> {
+        let element_id = match fields.len() {
+            4 => Some(fields.pop().unwrap().try_into()?),
+            _ => None,
+        };
+        let [rel_identity, rel_type, properties] = into_field_array(fields)?;
+        Ok(UnboundRelationship {
+            rel_identity: rel_identity.try_into()?,
+            rel_type: rel_type.try_into()?,
+            properties: properties.try_into()?,
+            element_id,
+        })
+    }
 }
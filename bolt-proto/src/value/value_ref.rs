@@ -0,0 +1,237 @@
+use std::borrow::Cow;
+use std::str;
+
+use bytes::Bytes;
+
+use crate::error::*;
+use crate::serialization::{
+    Deserialize, STRUCT_MARKER_MEDIUM, STRUCT_MARKER_SMALL, STRUCT_MARKER_TINY,
+};
+use crate::value::{boolean, byte_array, float, integer, list, map, null, string};
+use crate::Value;
+
+/// A [`Value`] decoded from a byte slice that may borrow from it.
+///
+/// Strings are borrowed from the source bytes instead of being copied, which avoids allocating when they are only
+/// inspected briefly. All other values are decoded as usual, from a copy of only the bytes they were serialized as.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueRef<'a> {
+    String(Cow<'a, str>),
+    Value(Value),
+}
+
+impl<'a> ValueRef<'a> {
+    /// Decode a single value from the start of the given bytes. Returns the value along with the number of bytes that
+    /// were consumed.
+    pub fn from_slice(bytes: &'a [u8]) -> Result<(Self, usize)> {
+        let (header_len, size) = match read_sized_header(bytes, string::MARKERS)? {
+            Some(header) => header,
+            None => {
+                let len = serialized_len(bytes)?;
+                let value = Value::deserialize(&mut Bytes::copy_from_slice(&bytes[..len]))?;
+                return Ok((ValueRef::Value(value), len));
+            }
+        };
+        let end = header_len + size;
+        let string_bytes = bytes.get(header_len..end).ok_or_else(eof)?;
+        let string = str::from_utf8(string_bytes).map_err(DeserializationError::InvalidUTF8)?;
+        Ok((ValueRef::String(Cow::Borrowed(string)), end))
+    }
+
+    /// Get the string held by this value, if it is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ValueRef::String(string) => Some(string),
+            ValueRef::Value(Value::String(string)) => string.as_str().ok(),
+            ValueRef::Value(_) => None,
+        }
+    }
+
+    /// Convert this into an owned [`Value`], copying any borrowed data.
+    pub fn into_owned(self) -> Value {
+        match self {
+            ValueRef::String(string) => Value::from(string.into_owned()),
+            ValueRef::Value(value) => value,
+        }
+    }
+}
+
+impl From<ValueRef<'_>> for Value {
+    fn from(value: ValueRef<'_>) -> Self {
+        value.into_owned()
+    }
+}
+
+fn eof() -> Error {
+    std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into()
+}
+
+// Reads the header length and size of a value with one of the given tiny, small, medium, and large markers, or returns
+// None if it has another marker.
+fn read_sized_header(
+    bytes: &[u8],
+    [tiny, small, medium, large]: [u8; 4],
+) -> Result<Option<(usize, usize)>> {
+    let marker = *bytes.first().ok_or_else(eof)?;
+    let header = match marker {
+        marker if (tiny..=(tiny | 0x0F)).contains(&marker) => (1, 0x0F & marker as usize),
+        marker if marker == small => (2, read_size(bytes, 1)?),
+        marker if marker == medium => (3, read_size(bytes, 2)?),
+        marker if marker == large => (5, read_size(bytes, 4)?),
+        _ => return Ok(None),
+    };
+    Ok(Some(header))
+}
+
+// The length of the value at the start of the given bytes, from the markers and sizes alone. Values nested in lists,
+// maps, and structures are counted and skipped in turn rather than recursed into.
+fn serialized_len(bytes: &[u8]) -> Result<usize> {
+    let mut len = 0;
+    // Values left to skip, each of which takes at least one byte
+    let mut pending: usize = 1;
+    while pending > 0 {
+        if pending > bytes.len() - len {
+            return Err(eof());
+        }
+        pending -= 1;
+        let value = &bytes[len..];
+        let marker = value[0];
+        // The length of the header and of any data following it, and the number of nested values
+        let (header_len, data_len, nested) = match marker {
+            null::MARKER | boolean::MARKER_FALSE | boolean::MARKER_TRUE => (1, 0, 0),
+            // Tiny int
+            marker if (-16..=127).contains(&(marker as i8)) => (1, 0, 0),
+            integer::MARKER_INT_8 => (2, 0, 0),
+            integer::MARKER_INT_16 => (3, 0, 0),
+            integer::MARKER_INT_32 => (5, 0, 0),
+            integer::MARKER_INT_64 | float::MARKER => (9, 0, 0),
+            byte_array::MARKER_SMALL => (2, read_size(value, 1)?, 0),
+            byte_array::MARKER_MEDIUM => (3, read_size(value, 2)?, 0),
+            byte_array::MARKER_LARGE => (5, read_size(value, 4)?, 0),
+            marker if (STRUCT_MARKER_TINY..=(STRUCT_MARKER_TINY | 0x0F)).contains(&marker) => {
+                (2, 0, 0x0F & marker as usize)
+            }
+            STRUCT_MARKER_SMALL => (3, 0, read_size(value, 1)?),
+            STRUCT_MARKER_MEDIUM => (4, 0, read_size(value, 2)?),
+            _ => {
+                if let Some((header_len, size)) = read_sized_header(value, string::MARKERS)? {
+                    (header_len, size, 0)
+                } else if let Some((header_len, size)) = read_sized_header(value, list::MARKERS)? {
+                    (header_len, 0, size)
+                } else if let Some((header_len, size)) = read_sized_header(value, map::MARKERS)? {
+                    (header_len, 0, size.saturating_mul(2))
+                } else {
+                    return Err(DeserializationError::InvalidMarkerByte(marker).into());
+                }
+            }
+        };
+        len = len
+            .checked_add(header_len + data_len)
+            .filter(|&len| len <= bytes.len())
+            .ok_or_else(eof)?;
+        pending = pending.saturating_add(nested);
+    }
+    Ok(len)
+}
+
+// Reads a big-endian size of `len` bytes following the marker byte.
+fn read_size(bytes: &[u8], len: usize) -> Result<usize> {
+    let size_bytes = bytes.get(1..=len).ok_or_else(eof)?;
+    Ok(size_bytes
+        .iter()
+        .fold(0, |size, &byte| (size << 8) | byte as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::iter::FromIterator;
+
+    use crate::serialization::*;
+    use crate::value::Node;
+
+    use super::*;
+
+    #[test]
+    fn borrowed_string() {
+        for string in &["short", &"medium".repeat(10), &"long".repeat(100)] {
+            let bytes = Value::from(*string).try_into_bytes().unwrap();
+            let (value, consumed) = ValueRef::from_slice(&bytes).unwrap();
+            assert_eq!(consumed, bytes.len());
+            match &value {
+                ValueRef::String(Cow::Borrowed(borrowed)) => {
+                    assert_eq!(borrowed, string);
+                    // The borrowed string points into the source buffer
+                    let source = bytes.as_ptr_range();
+                    assert!(source.contains(&borrowed.as_ptr()));
+                }
+                other => panic!("expected a borrowed string, got {:?}", other),
+            }
+            assert_eq!(value.as_str(), Some(*string));
+            assert_eq!(value.into_owned(), Value::from(*string));
+        }
+    }
+
+    #[test]
+    fn owned_value() {
+        let bytes = Value::from(vec![1, 2, 3]).try_into_bytes().unwrap();
+        let mut trailing = bytes.to_vec();
+        trailing.push(0xC0);
+        let (value, consumed) = ValueRef::from_slice(&trailing).unwrap();
+        assert_eq!(consumed, bytes.len());
+        assert_eq!(value.as_str(), None);
+        assert_eq!(Value::from(value), Value::from(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn serialized_len() {
+        let node = Node::new(
+            1,
+            vec!["Person".to_string()],
+            HashMap::from_iter(vec![("name".to_string(), Value::from("Alice"))]),
+        );
+        let values = vec![
+            Value::Null,
+            Value::from(true),
+            Value::from(-16),
+            Value::from(1_000),
+            Value::from(i64::MAX),
+            Value::from(1.5),
+            Value::from(vec![0_u8; 300]),
+            Value::from(vec![Value::from(1), Value::from("a"), Value::Null]),
+            Value::from(HashMap::from_iter(vec![("a", vec!["b".repeat(100)])])),
+            Value::from(node),
+        ];
+        for value in values {
+            let bytes = value.clone().try_into_bytes().unwrap();
+            assert_eq!(super::serialized_len(&bytes).unwrap(), bytes.len());
+            // Only the value is decoded, not what follows it
+            let mut trailing = bytes.to_vec();
+            trailing.extend_from_slice(&[0xC7; 100]);
+            let (decoded, consumed) = ValueRef::from_slice(&trailing).unwrap();
+            assert_eq!(consumed, bytes.len());
+            assert_eq!(decoded.into_owned(), value);
+            assert!(super::serialized_len(&bytes[..bytes.len() - 1]).is_err());
+        }
+        assert!(matches!(
+            ValueRef::from_slice(&[list::MARKER_TINY | 2, 0x01, 0xC7]),
+            Err(Error::DeserializationError(
+                DeserializationError::InvalidMarkerByte(0xC7)
+            ))
+        ));
+        // A size larger than the input is rejected before anything is skipped
+        assert!(ValueRef::from_slice(&[list::MARKER_LARGE, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]).is_err());
+    }
+
+    #[test]
+    fn invalid_string() {
+        assert!(ValueRef::from_slice(&[]).is_err());
+        assert!(ValueRef::from_slice(&[string::MARKER_TINY | 3, b'a']).is_err());
+        assert!(matches!(
+            ValueRef::from_slice(&[string::MARKER_TINY | 1, 0xFF]),
+            Err(Error::DeserializationError(
+                DeserializationError::InvalidUTF8(_)
+            ))
+        ));
+    }
+}
@@ -0,0 +1,182 @@
+use std::fmt;
+
+/// A version of the Bolt protocol, as agreed upon in the handshake between a client and a server.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Version {
+    pub(crate) major: u8,
+    pub(crate) minor: u8,
+}
+
+impl Version {
+    /// Offered in a handshake to negotiate the version using a manifest, which lists all versions supported by the
+    /// server instead of just the one it picked. Servers supporting Bolt v5.7+ understand it.
+    pub const HANDSHAKE_MANIFEST_V1: Version = Version::new(0xFF, 1);
+
+    pub const fn new(major: u8, minor: u8) -> Self {
+        Self { major, minor }
+    }
+
+    pub fn major(&self) -> u8 {
+        self.major
+    }
+
+    pub fn minor(&self) -> u8 {
+        self.minor
+    }
+
+    /// Encode this version as the server's response to a handshake.
+    pub fn to_bytes(self) -> [u8; 4] {
+        [0, 0, self.minor, self.major]
+    }
+
+    /// Decode the version chosen by the server in response to a handshake. Returns `None` if no version was chosen
+    /// (i.e. the response is all zeros), or if the reserved bytes are set.
+    pub fn from_bytes(bytes: [u8; 4]) -> Option<Self> {
+        match bytes {
+            [0, 0, 0, 0] => None,
+            [0, 0, minor, major] => Some(Self::new(major, minor)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// An entry of a handshake proposal: a version, optionally along with a number of preceding minor versions of the same
+/// major version. For example, `VersionRange::new(Version::new(4, 4), 2)` offers Bolt v4.4, v4.3, and v4.2 in a single
+/// entry, which leaves room for other major versions in the proposal.
+///
+/// Ranges are only understood by servers supporting Bolt v4.3+, so older versions should be offered in entries of their
+/// own.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct VersionRange {
+    pub(crate) newest: Version,
+    pub(crate) older_minors: u8,
+}
+
+impl VersionRange {
+    /// Create a range of versions, from the given version down to `older_minors` minor versions before it. The range
+    /// doesn't extend past minor version 0.
+    pub const fn new(newest: Version, older_minors: u8) -> Self {
+        let older_minors = if older_minors < newest.minor {
+            older_minors
+        } else {
+            newest.minor
+        };
+        Self {
+            newest,
+            older_minors,
+        }
+    }
+
+    pub fn newest(&self) -> Version {
+        self.newest
+    }
+
+    pub fn oldest(&self) -> Version {
+        Version::new(self.newest.major, self.newest.minor - self.older_minors)
+    }
+
+    pub fn contains(&self, version: Version) -> bool {
+        self.oldest() <= version && version <= self.newest
+    }
+
+    /// The newest version contained in both this range and the other one, if any.
+    pub fn newest_common(&self, other: &VersionRange) -> Option<Version> {
+        let newest = self.newest.min(other.newest);
+        if self.contains(newest) && other.contains(newest) {
+            Some(newest)
+        } else {
+            None
+        }
+    }
+
+    /// Encode this range as an entry of a handshake proposal.
+    pub fn to_bytes(self) -> [u8; 4] {
+        [0, self.older_minors, self.newest.minor, self.newest.major]
+    }
+
+    /// Decode an entry of a handshake proposal, or of a server's handshake manifest. The reserved byte is ignored.
+    pub fn from_bytes(bytes: [u8; 4]) -> Self {
+        let [_, older_minors, minor, major] = bytes;
+        Self::new(Version::new(major, minor), older_minors)
+    }
+}
+
+impl From<Version> for VersionRange {
+    fn from(version: Version) -> Self {
+        Self::new(version, 0)
+    }
+}
+
+impl fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.older_minors == 0 {
+            write!(f, "{}", self.newest)
+        } else {
+            write!(f, "{}-{}", self.oldest(), self.newest)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_bytes() {
+        let version = Version::new(4, 3);
+        assert_eq!(version.to_bytes(), [0, 0, 3, 4]);
+        assert_eq!(Version::from_bytes([0, 0, 3, 4]), Some(version));
+        assert_eq!(Version::from_bytes([0, 0, 0, 1]), Some(Version::new(1, 0)));
+        assert_eq!(Version::from_bytes([0, 0, 0, 0]), None);
+        assert_eq!(Version::from_bytes([0x48, 0x54, 0x54, 0x50]), None);
+        assert_eq!(version.to_string(), "4.3");
+        assert!(Version::new(4, 4) > Version::new(4, 3));
+        assert!(Version::new(5, 0) > Version::new(4, 4));
+    }
+
+    #[test]
+    fn range() {
+        let range = VersionRange::new(Version::new(4, 4), 2);
+        assert_eq!(range.to_bytes(), [0, 2, 4, 4]);
+        assert_eq!(range.oldest(), Version::new(4, 2));
+        assert!(range.contains(Version::new(4, 3)));
+        assert!(!range.contains(Version::new(4, 1)));
+        assert!(!range.contains(Version::new(3, 3)));
+        assert_eq!(range.to_string(), "4.2-4.4");
+
+        let range = VersionRange::from(Version::new(3, 0));
+        assert_eq!(range.to_bytes(), [0, 0, 0, 3]);
+        assert!(range.contains(Version::new(3, 0)));
+        assert_eq!(range.to_string(), "3.0");
+
+        // Ranges stop at minor version 0
+        let range = VersionRange::new(Version::new(4, 1), 5);
+        assert_eq!(range.to_bytes(), [0, 1, 1, 4]);
+        assert_eq!(range.oldest(), Version::new(4, 0));
+        assert_eq!(VersionRange::from_bytes([0, 5, 1, 4]), range);
+    }
+
+    #[test]
+    fn newest_common() {
+        let range = VersionRange::new(Version::new(5, 4), 4);
+        assert_eq!(
+            range.newest_common(&VersionRange::new(Version::new(5, 8), 6)),
+            Some(Version::new(5, 4))
+        );
+        assert_eq!(
+            range.newest_common(&VersionRange::new(Version::new(5, 2), 2)),
+            Some(Version::new(5, 2))
+        );
+        assert_eq!(range.newest_common(&Version::new(5, 7).into()), None);
+        assert_eq!(
+            range.newest_common(&VersionRange::new(Version::new(4, 4), 4)),
+            None
+        );
+    }
+}